@@ -0,0 +1,117 @@
+/*
+Benchmarks for the per-report hot paths of the telemetry pipeline and mission state: telemetry
+parsing and enrichment, zone string conversion, geofence checks and state emission. Run with
+`cargo bench` and compare against the previous run (criterion keeps its baseline in target/).
+*/
+use std::hint::black_box;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+use interface_lib::bench::{
+    broadcast, convert_zone_format, convert_zone_to_json, current_geofence, distance_to_polygon,
+    record_position, set_geometry, Coordinate, GeoCoordinateStruct, LocalProjection, MissionGeometry,
+    TelemetryData, TelemetryState,
+};
+
+const ORIGIN: GeoCoordinateStruct = GeoCoordinateStruct {
+    lat: 33.932573934575075,
+    long: -117.63059569114814,
+};
+
+// Regular polygon of `vertices` points, `radius_m` around a point `offset_m` east of the origin
+fn polygon(vertices: usize, radius_m: f64, offset_m: f64) -> Vec<GeoCoordinateStruct> {
+    let projection = LocalProjection::new(&ORIGIN);
+    (0..vertices)
+        .map(|i| {
+            let angle = i as f64 / vertices as f64 * std::f64::consts::TAU;
+            projection.to_coord(offset_m + radius_m * angle.cos(), radius_m * angle.sin())
+        })
+        .collect()
+}
+
+fn report(vehicle_id: &str) -> TelemetryData {
+    let mut data = TelemetryData {
+        vehicle_id: vehicle_id.to_string(),
+        signal_strength: -62,
+        pitch: 1.5,
+        yaw: 270.0,
+        roll: -0.5,
+        speed: 15.0,
+        altitude: 60.0,
+        battery_life: 80,
+        vehicle_status: "Connected".to_string(),
+        ..Default::default()
+    };
+    data.current_position.latitude = ORIGIN.lat;
+    data.current_position.longitude = ORIGIN.long;
+    data
+}
+
+fn telemetry(c: &mut Criterion) {
+    let payload = serde_json::to_vec(&report("mea")).unwrap();
+    c.bench_function("telemetry/parse", |b| {
+        b.iter(|| serde_json::from_slice::<TelemetryData>(black_box(&payload)).unwrap())
+    });
+
+    // Geometry done for every report in the enrich stage
+    set_geometry(MissionGeometry {
+        mission_id: None,
+        keep_in: vec![polygon(64, 5000.0, 0.0)],
+        keep_out: (0..4).map(|i| polygon(24, 150.0, 1000.0 + i as f64 * 400.0)).collect(),
+    });
+    let position = Coordinate { latitude: ORIGIN.lat, longitude: ORIGIN.long };
+    c.bench_function("telemetry/enrich_geometry", |b| {
+        b.iter(|| {
+            let violations = current_geofence().violations_for(black_box(&ORIGIN), 60.0);
+            record_position("mea", position.clone(), 60.0);
+            violations
+        })
+    });
+}
+
+fn zones(c: &mut Criterion) {
+    let zone = polygon(64, 500.0, 0.0);
+    let json = serde_json::to_string(&zone).unwrap();
+    let stored = convert_zone_format(&json);
+    c.bench_function("zones/to_db_format", |b| b.iter(|| convert_zone_format(black_box(&json))));
+    c.bench_function("zones/from_db_format", |b| b.iter(|| convert_zone_to_json(black_box(&stored))));
+}
+
+fn geofence(c: &mut Criterion) {
+    let keep_in = polygon(64, 2000.0, 0.0);
+    let inside = ORIGIN;
+    let outside = LocalProjection::new(&ORIGIN).to_coord(3000.0, 0.0);
+    c.bench_function("geofence/distance_inside", |b| {
+        b.iter(|| distance_to_polygon(black_box(&inside), black_box(&keep_in)))
+    });
+    c.bench_function("geofence/distance_outside", |b| {
+        b.iter(|| distance_to_polygon(black_box(&outside), black_box(&keep_in)))
+    });
+}
+
+fn emission(c: &mut Criterion) {
+    let state = TelemetryState::default();
+    c.bench_function("emit/state_update", |b| {
+        b.iter_batched(
+            || report("eru"),
+            |data| {
+                state.set(data);
+                state.snapshot()
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    // Serialization done by the Tauri event, plus the fan-out to WebSocket clients
+    let _subscriber = broadcast::subscribe();
+    let snapshot = state.snapshot();
+    c.bench_function("emit/serialize_and_broadcast", |b| {
+        b.iter(|| {
+            let payload = serde_json::to_value(black_box(&*snapshot)).unwrap();
+            broadcast::publish(Some("eru"), snapshot.clone());
+            payload
+        })
+    });
+}
+
+criterion_group!(benches, telemetry, zones, geofence, emission);
+criterion_main!(benches);
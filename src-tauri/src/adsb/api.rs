@@ -0,0 +1,84 @@
+/*
+Define the ADS-B API: the current traffic picture, the traffic_updated event, and the SBS feed
+reader task.
+*/
+use std::time::Duration;
+use tauri::AppHandle;
+use taurpc::{procedures, resolvers};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::{interval, timeout};
+
+use crate::config;
+use crate::logs;
+use crate::supervisor::{self, RestartPolicy};
+use super::{sbs, TrafficPicture};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// How often proximity is re-evaluated and the picture emitted
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[procedures(event_trigger = AdsbEventTrigger, export_to = "../src/lib/bindings.ts", path = "adsb")]
+pub trait AdsbApi {
+    #[taurpc(event)]
+    async fn on_traffic_updated(traffic: TrafficPicture);
+
+    async fn get_traffic() -> TrafficPicture;
+}
+
+#[derive(Clone, Default)]
+pub struct AdsbApiImpl;
+
+impl AdsbApiImpl {
+    // Read the SBS feed when adsb_feed_addr is set; reconnects with backoff through the supervisor
+    pub fn start_feed(&self, app_handle: AppHandle) {
+        let addr = config::get().adsb_feed_addr;
+        if addr.is_empty() {
+            return;
+        }
+        logs::info("adsb", format!("Reading ADS-B traffic from {}", addr));
+        supervisor::spawn("adsb_feed", RestartPolicy::Always, move || {
+            run_feed(app_handle.clone(), addr.clone())
+        });
+    }
+}
+
+async fn run_feed(app_handle: AppHandle, addr: String) -> Result<(), String> {
+    let stream = timeout(CONNECT_TIMEOUT, TcpStream::connect(&addr))
+        .await
+        .map_err(|_| format!("Timed out connecting to ADS-B feed {}", addr))?
+        .map_err(|e| format!("Failed to connect to ADS-B feed {}: {}", addr, e))?;
+    super::set_feed_connected(true);
+    logs::info("adsb", format!("Connected to ADS-B feed {}", addr));
+
+    let mut lines = BufReader::new(stream).lines();
+    let mut ticker = interval(REFRESH_INTERVAL);
+    let result = loop {
+        tokio::select! {
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => {
+                    if let Some(message) = sbs::parse_line(&line) {
+                        super::ingest(message);
+                    }
+                }
+                Ok(None) => break Err(format!("ADS-B feed {} closed the connection", addr)),
+                Err(e) => break Err(format!("Failed to read ADS-B feed {}: {}", addr, e)),
+            },
+            _ = ticker.tick() => {
+                let traffic = super::refresh();
+                if let Err(e) = AdsbEventTrigger::new(app_handle.clone()).on_traffic_updated(traffic) {
+                    logs::error("adsb", format!("Failed to emit traffic update: {}", e));
+                }
+            }
+        }
+    };
+    super::set_feed_connected(false);
+    result
+}
+
+#[resolvers]
+impl AdsbApi for AdsbApiImpl {
+    async fn get_traffic(self) -> TrafficPicture {
+        super::refresh()
+    }
+}
@@ -0,0 +1,242 @@
+/*
+ADS-B traffic awareness. Manned aircraft are tracked from a dump1090 SBS feed
+(`adsb_feed_addr`, e.g. localhost:30003) and checked against our vehicles' latest positions
+and the active mission's keep-in zones. An AirspaceIntrusion alert is raised while an aircraft
+is within `adsb_alert_distance_m` horizontally and `adsb_alert_altitude_m` vertically of a
+vehicle, or within the alert distance of a keep-in zone, so operators can pause the mission
+before a conflict. Aircraft not heard from for a minute are dropped.
+
+Aircraft altitudes are barometric and vehicle altitudes are GPS, so vertical distances are
+approximate; an aircraft without a reported altitude is treated as vertically close.
+*/
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::config;
+use crate::geofence;
+use crate::geometry::distance_to_polygon;
+use crate::missions::types::{GeoCoordinateStruct, GeofenceType};
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use crate::telemetry::geos::{harversine_distance, Coordinate};
+use crate::telemetry::separation;
+
+pub mod api;
+pub mod sbs;
+
+pub use api::{AdsbApi, AdsbApiImpl, AdsbEventTrigger};
+
+const TRACK_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct TrackedAircraft {
+    pub icao: String,
+    pub callsign: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub altitude_m: Option<f64>,
+    pub ground_speed_ms: Option<f64>,
+    pub track_deg: Option<f64>,
+    pub vertical_rate_ms: Option<f64>,
+    pub squawk: Option<String>,
+    pub on_ground: bool,
+    pub last_seen: String,
+    // Closest vehicle with a recent position and the distances to it
+    pub nearest_vehicle: Option<String>,
+    pub horizontal_distance_m: Option<f64>,
+    pub vertical_distance_m: Option<f64>,
+    // Distance to the nearest keep-in zone, 0 when inside it
+    pub keep_in_distance_m: Option<f64>,
+    pub intrusion: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct TrafficPicture {
+    pub feed_connected: bool,
+    // Aircraft with an intrusion first, then by distance to the nearest vehicle
+    pub aircraft: Vec<TrackedAircraft>,
+    pub intrusion_count: u32,
+    pub alert_distance_m: f64,
+    pub alert_altitude_m: f64,
+    pub updated_at: String,
+}
+
+struct Track {
+    aircraft: TrackedAircraft,
+    updated: Instant,
+}
+
+lazy_static! {
+    static ref TRACKS: Mutex<HashMap<String, Track>> = Mutex::new(HashMap::new());
+    // Notification keys currently raised, so they can be cleared when the conflict ends
+    static ref ACTIVE_ALERTS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+    static ref FEED_CONNECTED: AtomicBool = AtomicBool::new(false);
+}
+
+pub fn set_feed_connected(connected: bool) {
+    FEED_CONNECTED.store(connected, Ordering::Relaxed);
+}
+
+// Merge one SBS record into the aircraft's track
+pub fn ingest(message: sbs::SbsMessage) {
+    let mut tracks = TRACKS.lock().unwrap();
+    let track = tracks.entry(message.icao.clone()).or_insert_with(|| Track {
+        aircraft: TrackedAircraft {
+            icao: message.icao.clone(),
+            callsign: None,
+            latitude: None,
+            longitude: None,
+            altitude_m: None,
+            ground_speed_ms: None,
+            track_deg: None,
+            vertical_rate_ms: None,
+            squawk: None,
+            on_ground: false,
+            last_seen: String::new(),
+            nearest_vehicle: None,
+            horizontal_distance_m: None,
+            vertical_distance_m: None,
+            keep_in_distance_m: None,
+            intrusion: false,
+        },
+        updated: Instant::now(),
+    });
+
+    let aircraft = &mut track.aircraft;
+    aircraft.callsign = message.callsign.or(aircraft.callsign.take());
+    aircraft.altitude_m = message.altitude_m.or(aircraft.altitude_m);
+    aircraft.ground_speed_ms = message.ground_speed_ms.or(aircraft.ground_speed_ms);
+    aircraft.track_deg = message.track_deg.or(aircraft.track_deg);
+    aircraft.latitude = message.latitude.or(aircraft.latitude);
+    aircraft.longitude = message.longitude.or(aircraft.longitude);
+    aircraft.vertical_rate_ms = message.vertical_rate_ms.or(aircraft.vertical_rate_ms);
+    aircraft.squawk = message.squawk.or(aircraft.squawk.take());
+    aircraft.on_ground = message.on_ground.unwrap_or(aircraft.on_ground);
+    aircraft.last_seen = chrono::Utc::now().to_rfc3339();
+    track.updated = Instant::now();
+}
+
+fn label(aircraft: &TrackedAircraft) -> String {
+    match &aircraft.callsign {
+        Some(callsign) => format!("{} ({})", callsign, aircraft.icao.to_uppercase()),
+        None => aircraft.icao.to_uppercase(),
+    }
+}
+
+// Update each aircraft's proximity to vehicles and keep-in zones, then raise or clear alerts
+fn evaluate(tracks: &mut HashMap<String, Track>) {
+    let config = config::get();
+    let vehicles = separation::recent_positions();
+    let geometry = geofence::current();
+    let keep_in_zones: Vec<&GeofenceType> = geometry.keep_in.iter().filter(|zone| zone.len() >= 3).collect();
+    // The keep-in airspace extends up to the highest vehicle plus the vertical alert band
+    let ceiling = vehicles
+        .iter()
+        .map(|(_, _, altitude)| *altitude)
+        .reduce(f64::max)
+        .map(|highest| highest + config.adsb_alert_altitude_m);
+
+    let mut alerts: Vec<(String, NotificationSeverity, String)> = vec![];
+    for track in tracks.values_mut() {
+        let aircraft = &mut track.aircraft;
+        aircraft.nearest_vehicle = None;
+        aircraft.horizontal_distance_m = None;
+        aircraft.vertical_distance_m = None;
+        aircraft.keep_in_distance_m = None;
+        aircraft.intrusion = false;
+        let (Some(latitude), Some(longitude)) = (aircraft.latitude, aircraft.longitude) else {
+            continue;
+        };
+        let position = Coordinate { latitude, longitude };
+
+        for (vehicle_id, vehicle_position, vehicle_altitude) in &vehicles {
+            let horizontal = harversine_distance(&position, vehicle_position);
+            let vertical = aircraft.altitude_m.map(|a| (a - vehicle_altitude).abs());
+            if aircraft.horizontal_distance_m.map_or(true, |d| horizontal < d) {
+                aircraft.nearest_vehicle = Some(vehicle_id.clone());
+                aircraft.horizontal_distance_m = Some(horizontal);
+                aircraft.vertical_distance_m = vertical;
+            }
+            if !aircraft.on_ground
+                && horizontal < config.adsb_alert_distance_m
+                && vertical.map_or(true, |v| v < config.adsb_alert_altitude_m)
+            {
+                aircraft.intrusion = true;
+                alerts.push((
+                    format!("{}-{}", aircraft.icao, vehicle_id),
+                    NotificationSeverity::Critical,
+                    format!(
+                        "Aircraft {} is {:.0} m from {}{}",
+                        label(aircraft),
+                        horizontal,
+                        vehicle_id.to_uppercase(),
+                        vertical.map(|v| format!(", {:.0} m vertically", v)).unwrap_or_default()
+                    ),
+                ));
+            }
+        }
+
+        let point = GeoCoordinateStruct { lat: latitude, long: longitude };
+        aircraft.keep_in_distance_m = keep_in_zones
+            .iter()
+            .map(|zone| distance_to_polygon(&point, zone))
+            .reduce(f64::min);
+        let below_ceiling = match (aircraft.altitude_m, ceiling) {
+            (Some(altitude), Some(ceiling)) => altitude < ceiling,
+            _ => true,
+        };
+        if let Some(distance) = aircraft.keep_in_distance_m {
+            if !aircraft.on_ground && below_ceiling && distance < config.adsb_alert_distance_m {
+                aircraft.intrusion = true;
+                alerts.push((
+                    format!("{}-keep-in", aircraft.icao),
+                    NotificationSeverity::Warning,
+                    if distance == 0.0 {
+                        format!("Aircraft {} is inside the keep-in zone", label(aircraft))
+                    } else {
+                        format!("Aircraft {} is {:.0} m from the keep-in zone", label(aircraft), distance)
+                    },
+                ));
+            }
+        }
+    }
+
+    let mut active = ACTIVE_ALERTS.lock().unwrap();
+    let current: HashSet<String> = alerts.iter().map(|(key, _, _)| key.clone()).collect();
+    for key in active.difference(&current) {
+        notifications::clear(NotificationCategory::AirspaceIntrusion, key);
+    }
+    for (key, severity, message) in alerts {
+        notifications::raise(NotificationCategory::AirspaceIntrusion, severity, &key, message);
+    }
+    *active = current;
+}
+
+// Drop stale aircraft, re-evaluate proximity, and return the current picture
+pub fn refresh() -> TrafficPicture {
+    let config = config::get();
+    let mut tracks = TRACKS.lock().unwrap();
+    tracks.retain(|_, track| track.updated.elapsed() <= TRACK_TIMEOUT);
+    evaluate(&mut tracks);
+
+    let mut aircraft: Vec<TrackedAircraft> = tracks.values().map(|t| t.aircraft.clone()).collect();
+    aircraft.sort_by(|a, b| {
+        b.intrusion.cmp(&a.intrusion).then(
+            a.horizontal_distance_m
+                .unwrap_or(f64::INFINITY)
+                .total_cmp(&b.horizontal_distance_m.unwrap_or(f64::INFINITY)),
+        )
+    });
+    TrafficPicture {
+        feed_connected: FEED_CONNECTED.load(Ordering::Relaxed),
+        intrusion_count: aircraft.iter().filter(|a| a.intrusion).count() as u32,
+        aircraft,
+        alert_distance_m: config.adsb_alert_distance_m,
+        alert_altitude_m: config.adsb_alert_altitude_m,
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
@@ -0,0 +1,68 @@
+/*
+Parser for the SBS-1 (BaseStation) text format served by dump1090 on port 30003. Each line is
+a comma-separated MSG record carrying a subset of an aircraft's state; fields missing from a
+record are left as None so they can be merged into the existing track.
+*/
+
+const FEET_TO_M: f64 = 0.3048;
+const KNOTS_TO_MS: f64 = 0.514444;
+const FPM_TO_MS: f64 = 0.00508;
+
+#[derive(Debug, Clone, Default)]
+pub struct SbsMessage {
+    // 24-bit ICAO address in lowercase hex
+    pub icao: String,
+    pub callsign: Option<String>,
+    // Barometric altitude, converted from feet
+    pub altitude_m: Option<f64>,
+    pub ground_speed_ms: Option<f64>,
+    pub track_deg: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub vertical_rate_ms: Option<f64>,
+    pub squawk: Option<String>,
+    pub on_ground: Option<bool>,
+}
+
+fn field<'a>(fields: &[&'a str], index: usize) -> Option<&'a str> {
+    fields.get(index).map(|f| f.trim()).filter(|f| !f.is_empty())
+}
+
+fn number(fields: &[&str], index: usize) -> Option<f64> {
+    field(fields, index)
+        .and_then(|f| f.parse::<f64>().ok())
+        .filter(|v| v.is_finite())
+}
+
+// Parse one MSG line; other record types (SEL, ID, AIR, STA, CLK) and malformed lines give None
+pub fn parse_line(line: &str) -> Option<SbsMessage> {
+    let fields: Vec<&str> = line.trim().split(',').collect();
+    if field(&fields, 0)? != "MSG" {
+        return None;
+    }
+    let icao = field(&fields, 4)?;
+    if icao.len() != 6 || !icao.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let latitude = number(&fields, 14).filter(|v| (-90.0..=90.0).contains(v));
+    let longitude = number(&fields, 15).filter(|v| (-180.0..=180.0).contains(v));
+    // A position is only usable with both halves
+    let (latitude, longitude) = match (latitude, longitude) {
+        (Some(lat), Some(lon)) => (Some(lat), Some(lon)),
+        _ => (None, None),
+    };
+    Some(SbsMessage {
+        icao: icao.to_lowercase(),
+        callsign: field(&fields, 10).map(str::to_string),
+        altitude_m: number(&fields, 11).map(|ft| ft * FEET_TO_M),
+        ground_speed_ms: number(&fields, 12).map(|kt| kt * KNOTS_TO_MS),
+        track_deg: number(&fields, 13),
+        latitude,
+        longitude,
+        vertical_rate_ms: number(&fields, 16).map(|fpm| fpm * FPM_TO_MS),
+        squawk: field(&fields, 17).map(str::to_string),
+        // dump1090 writes -1 for true; some decoders write 1
+        on_ground: field(&fields, 21).map(|f| f != "0"),
+    })
+}
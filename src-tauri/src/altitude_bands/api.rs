@@ -0,0 +1,114 @@
+/*
+Define the altitude bands API: list, assign and clear a mission's per-vehicle altitude bands,
+notifying every window when they change.
+*/
+use sqlx::PgPool;
+use tauri::{AppHandle, Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, current_operator, OperatorRole};
+use crate::init_db::lazy_pool;
+use crate::logs;
+use super::sql::{delete_band, select_bands};
+use super::{assign_band, load, set_bands, AltitudeBand};
+
+#[procedures(
+    event_trigger = AltitudeBandsEventTrigger,
+    export_to = "../src/lib/bindings.ts",
+    path = "altitude_bands"
+)]
+pub trait AltitudeBandsApi {
+    #[taurpc(event)]
+    async fn on_bands_changed(mission_id: i32, bands: Vec<AltitudeBand>);
+
+    async fn get_altitude_bands(mission_id: i32) -> Result<Vec<AltitudeBand>, String>;
+    // Assign or replace the vehicle's band; returns the mission's bands
+    async fn set_altitude_band(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        band: AltitudeBand,
+    ) -> Result<Vec<AltitudeBand>, String>;
+    async fn clear_altitude_band(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        vehicle_id: String,
+    ) -> Result<Vec<AltitudeBand>, String>;
+}
+
+#[derive(Clone)]
+pub struct AltitudeBandsApiImpl {
+    db: PgPool,
+}
+
+impl AltitudeBandsApiImpl {
+    pub async fn new() -> Self {
+        let api = Self { db: lazy_pool(2) };
+        // Warm the cache used by the telemetry checks
+        match select_bands(api.db.clone(), None).await {
+            Ok(bands) => {
+                let mut missions: Vec<i32> = bands.iter().map(|band| band.mission_id).collect();
+                missions.dedup();
+                for mission_id in missions {
+                    set_bands(
+                        mission_id,
+                        bands.iter().filter(|band| band.mission_id == mission_id).cloned().collect(),
+                    );
+                }
+            }
+            Err(e) => logs::warn("altitude_bands", format!("Failed to load altitude bands: {}", e)),
+        }
+        api
+    }
+
+}
+
+pub fn emit_bands_changed(app_handle: AppHandle<impl Runtime>, mission_id: i32, bands: &[AltitudeBand]) {
+    if let Err(e) = AltitudeBandsEventTrigger::new(app_handle).on_bands_changed(mission_id, bands.to_vec()) {
+        logs::warn("altitude_bands", format!("Failed to emit altitude band change: {}", e));
+    }
+}
+
+#[resolvers]
+impl AltitudeBandsApi for AltitudeBandsApiImpl {
+    async fn get_altitude_bands(self, mission_id: i32) -> Result<Vec<AltitudeBand>, String> {
+        load(self.db.clone(), mission_id).await
+    }
+
+    async fn set_altitude_band(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        band: AltitudeBand,
+    ) -> Result<Vec<AltitudeBand>, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let mission_id = band.mission_id;
+            let bands = assign_band(self.db.clone(), band, &current_operator()).await?;
+            emit_bands_changed(app_handle, mission_id, &bands);
+            Ok(bands)
+        })
+        .await
+    }
+
+    async fn clear_altitude_band(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        vehicle_id: String,
+    ) -> Result<Vec<AltitudeBand>, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let removed = delete_band(self.db.clone(), mission_id, &vehicle_id.to_lowercase())
+                .await
+                .map_err(|e| format!("Failed to clear altitude band: {}", e))?;
+            if !removed {
+                return Err(format!("{} has no altitude band in mission {}", vehicle_id.to_uppercase(), mission_id));
+            }
+
+            let bands = load(self.db.clone(), mission_id).await?;
+            emit_bands_changed(app_handle, mission_id, &bands);
+            Ok(bands)
+        })
+        .await
+    }
+}
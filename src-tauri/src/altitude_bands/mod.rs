@@ -0,0 +1,176 @@
+/*
+Altitude band deconfliction. Each vehicle in a mission can be assigned a band of altitudes
+(e.g. MRA 100–120 m, ERU 60–80 m), and the bands of one mission may not overlap. While the
+mission is active every telemetry report is checked against the vehicle's band and against the
+latest altitude of the other banded vehicles: an AltitudeBand alert is raised when a vehicle
+leaves its band, or when two banded vehicles come within the minimum vertical separation.
+*/
+use std::collections::HashMap;
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::PgPool;
+
+use crate::logs;
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use crate::settings;
+use crate::telemetry::separation;
+use crate::vehicles::{self, VEHICLE_IDS};
+
+pub mod api;
+pub mod sql;
+
+pub use api::{emit_bands_changed, AltitudeBandsApi, AltitudeBandsApiImpl};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct AltitudeBand {
+    pub mission_id: i32,
+    pub vehicle_id: String,
+    pub min_altitude_m: f64,
+    pub max_altitude_m: f64,
+}
+
+impl AltitudeBand {
+    pub fn validate(&self) -> Result<(), String> {
+        if !VEHICLE_IDS.contains(&self.vehicle_id.to_lowercase().as_str()) {
+            return Err(format!("Unknown vehicle {}", self.vehicle_id));
+        }
+        if !self.min_altitude_m.is_finite() || !self.max_altitude_m.is_finite() || self.min_altitude_m < 0.0 {
+            return Err("Altitude band limits must be non-negative altitudes".into());
+        }
+        if self.max_altitude_m <= self.min_altitude_m {
+            return Err("Altitude band maximum must be above its minimum".into());
+        }
+        Ok(())
+    }
+
+    fn contains(&self, altitude: f64) -> bool {
+        (self.min_altitude_m..=self.max_altitude_m).contains(&altitude)
+    }
+
+    fn overlaps(&self, other: &AltitudeBand) -> bool {
+        self.min_altitude_m < other.max_altitude_m && other.min_altitude_m < self.max_altitude_m
+    }
+}
+
+lazy_static! {
+    // Bands by mission, vehicle ids lowercase
+    static ref BANDS: RwLock<HashMap<i32, Vec<AltitudeBand>>> = RwLock::new(HashMap::new());
+}
+
+pub fn bands_for(mission_id: i32) -> Vec<AltitudeBand> {
+    BANDS.read().unwrap().get(&mission_id).cloned().unwrap_or_default()
+}
+
+fn set_bands(mission_id: i32, bands: Vec<AltitudeBand>) {
+    BANDS.write().unwrap().insert(mission_id, bands);
+}
+
+// Reject a band that overlaps another vehicle's band in the same mission
+fn check_overlap(bands: &[AltitudeBand], band: &AltitudeBand) -> Result<(), String> {
+    match bands
+        .iter()
+        .find(|other| other.vehicle_id != band.vehicle_id && other.overlaps(band))
+    {
+        Some(other) => Err(format!(
+            "Band overlaps {}'s band of {:.0}–{:.0} m",
+            vehicles::display_name(&other.vehicle_id),
+            other.min_altitude_m,
+            other.max_altitude_m
+        )),
+        None => Ok(()),
+    }
+}
+
+// Read the mission's bands from the database, refreshing the cache
+pub async fn load(db: PgPool, mission_id: i32) -> Result<Vec<AltitudeBand>, String> {
+    let bands = sql::select_bands(db, Some(mission_id))
+        .await
+        .map_err(|e| format!("Failed to load altitude bands: {}", e))?;
+    set_bands(mission_id, bands.clone());
+    Ok(bands)
+}
+
+// Assign or replace the vehicle's band; returns the mission's bands
+pub async fn assign_band(db: PgPool, band: AltitudeBand, operator: &str) -> Result<Vec<AltitudeBand>, String> {
+    band.validate()?;
+    let band = AltitudeBand {
+        vehicle_id: band.vehicle_id.to_lowercase(),
+        ..band
+    };
+    check_overlap(&load(db.clone(), band.mission_id).await?, &band)?;
+
+    sql::upsert_band(db.clone(), &band, operator)
+        .await
+        .map_err(|e| format!("Failed to save altitude band: {}", e))?;
+    logs::info(
+        "altitude_bands",
+        format!(
+            "{} assigned {:.0}–{:.0} m in mission {} by {}",
+            band.vehicle_id.to_uppercase(),
+            band.min_altitude_m,
+            band.max_altitude_m,
+            band.mission_id,
+            operator
+        ),
+    );
+    load(db, band.mission_id).await
+}
+
+fn pair_key(a: &str, b: &str) -> String {
+    if a <= b {
+        format!("{}-{}", a, b)
+    } else {
+        format!("{}-{}", b, a)
+    }
+}
+
+// Check a telemetry altitude against the active mission's bands; without an active mission or
+// a band for the vehicle any earlier alert is cleared
+pub fn check_altitude(mission_id: Option<i32>, vehicle_id: &str, altitude: f64) {
+    let vehicle_id = vehicle_id.to_lowercase();
+    let bands = mission_id.map(bands_for).unwrap_or_default();
+    let own = bands.iter().find(|band| band.vehicle_id == vehicle_id);
+
+    notifications::track(
+        own.is_some_and(|band| !band.contains(altitude)),
+        NotificationCategory::AltitudeBand,
+        NotificationSeverity::Warning,
+        &vehicle_id,
+        || {
+            let band = own.unwrap();
+            format!(
+                "{} is at {:.0} m, outside its {:.0}–{:.0} m band",
+                vehicles::display_name(&vehicle_id),
+                altitude,
+                band.min_altitude_m,
+                band.max_altitude_m
+            )
+        },
+    );
+
+    let min_vertical_m = settings::current().alerts.min_vertical_separation_m;
+    let recent = separation::recent_positions();
+    for other in VEHICLE_IDS.iter().filter(|other| **other != vehicle_id) {
+        let gap = recent
+            .iter()
+            .find(|(id, _, _)| id.as_str() == *other)
+            .filter(|_| own.is_some() && bands.iter().any(|band| band.vehicle_id == *other))
+            .map(|(_, _, other_altitude)| (altitude - other_altitude).abs());
+        notifications::track(
+            gap.is_some_and(|gap| gap < min_vertical_m),
+            NotificationCategory::AltitudeBand,
+            NotificationSeverity::Warning,
+            &pair_key(&vehicle_id, other),
+            || {
+                format!(
+                    "{} and {} are converging in altitude: {:.0} m apart",
+                    vehicles::display_name(&vehicle_id),
+                    vehicles::display_name(other),
+                    gap.unwrap_or_default()
+                )
+            },
+        );
+    }
+}
@@ -0,0 +1,66 @@
+/*
+Define all altitude band database functions.
+*/
+use sqlx::postgres::PgRow;
+use sqlx::{query, PgPool, Row};
+
+use super::AltitudeBand;
+
+fn to_band(row: PgRow) -> AltitudeBand {
+    AltitudeBand {
+        mission_id: row.get("mission_id"),
+        vehicle_id: row.get("vehicle_id"),
+        min_altitude_m: row.get("min_altitude"),
+        max_altitude_m: row.get("max_altitude"),
+    }
+}
+
+// With a mission id: that mission's bands; otherwise every mission's
+pub async fn select_bands(
+    db_conn: PgPool,
+    mission_id: Option<i32>,
+) -> Result<Vec<AltitudeBand>, sqlx::Error> {
+    let rows = query(
+        "SELECT mission_id, vehicle_id, min_altitude, max_altitude
+        FROM altitude_bands
+        WHERE $1::INTEGER IS NULL OR mission_id = $1
+        ORDER BY mission_id, vehicle_id",
+    )
+    .bind(mission_id)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_band).collect())
+}
+
+pub async fn upsert_band(
+    db_conn: PgPool,
+    band: &AltitudeBand,
+    updated_by: &str,
+) -> Result<(), sqlx::Error> {
+    query("
+        INSERT INTO altitude_bands(mission_id, vehicle_id, min_altitude, max_altitude, updated_by, updated_at)
+        VALUES ($1, $2, $3, $4, $5, NOW())
+        ON CONFLICT (mission_id, vehicle_id) DO UPDATE
+        SET min_altitude = EXCLUDED.min_altitude, max_altitude = EXCLUDED.max_altitude,
+            updated_by = EXCLUDED.updated_by, updated_at = NOW()
+    ")
+    .bind(band.mission_id)
+    .bind(&band.vehicle_id)
+    .bind(band.min_altitude_m)
+    .bind(band.max_altitude_m)
+    .bind(updated_by)
+    .execute(&db_conn)
+    .await?;
+    Ok(())
+}
+
+// Returns whether a band was removed
+pub async fn delete_band(db_conn: PgPool, mission_id: i32, vehicle_id: &str) -> Result<bool, sqlx::Error> {
+    let result = query("DELETE FROM altitude_bands WHERE mission_id = $1 AND vehicle_id = $2")
+        .bind(mission_id)
+        .bind(vehicle_id)
+        .execute(&db_conn)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
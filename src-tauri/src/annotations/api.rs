@@ -0,0 +1,129 @@
+/*
+Define the annotations API: create, edit, delete and list map annotations, with a change
+event so every open map redraws its markers.
+*/
+use sqlx::PgPool;
+use tauri::{AppHandle, Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, current_operator, OperatorRole};
+use crate::init_db::lazy_pool;
+use crate::logs;
+use super::sql::{delete_annotation, insert_annotation, select_annotations, update_annotation};
+use super::{AnnotationAction, AnnotationChange, AnnotationInput, MapAnnotation};
+
+#[procedures(
+    event_trigger = AnnotationsEventTrigger,
+    export_to = "../src/lib/bindings.ts",
+    path = "annotations"
+)]
+pub trait AnnotationsApi {
+    #[taurpc(event)]
+    async fn on_annotation_changed(change: AnnotationChange);
+
+    async fn list_annotations(mission_id: Option<i32>) -> Result<Vec<MapAnnotation>, String>;
+    async fn create_annotation(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        input: AnnotationInput,
+    ) -> Result<MapAnnotation, String>;
+    async fn update_annotation(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        annotation_id: i32,
+        input: AnnotationInput,
+    ) -> Result<MapAnnotation, String>;
+    async fn delete_annotation(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        annotation_id: i32,
+    ) -> Result<(), String>;
+}
+
+#[derive(Clone)]
+pub struct AnnotationsApiImpl {
+    db: PgPool,
+}
+
+impl AnnotationsApiImpl {
+    pub async fn new() -> Self {
+        Self { db: lazy_pool(2) }
+    }
+
+    fn emit_change(
+        &self,
+        app_handle: AppHandle<impl Runtime>,
+        action: AnnotationAction,
+        annotation: &MapAnnotation,
+    ) {
+        let change = AnnotationChange {
+            action,
+            annotation: annotation.clone(),
+        };
+        if let Err(e) = AnnotationsEventTrigger::new(app_handle).on_annotation_changed(change) {
+            logs::warn("annotations", format!("Failed to emit annotation change: {}", e));
+        }
+    }
+}
+
+#[resolvers]
+impl AnnotationsApi for AnnotationsApiImpl {
+    async fn list_annotations(self, mission_id: Option<i32>) -> Result<Vec<MapAnnotation>, String> {
+        select_annotations(self.db.clone(), mission_id)
+            .await
+            .map_err(|e| format!("Failed to load annotations: {}", e))
+    }
+
+    async fn create_annotation(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        input: AnnotationInput,
+    ) -> Result<MapAnnotation, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            input.validate()?;
+            let annotation = insert_annotation(self.db.clone(), &input, &current_operator())
+                .await
+                .map_err(|e| format!("Failed to save annotation: {}", e))?;
+            self.emit_change(app_handle, AnnotationAction::Created, &annotation);
+            Ok(annotation)
+        })
+        .await
+    }
+
+    async fn update_annotation(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        annotation_id: i32,
+        input: AnnotationInput,
+    ) -> Result<MapAnnotation, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            input.validate()?;
+            let annotation = update_annotation(self.db.clone(), annotation_id, &input)
+                .await
+                .map_err(|e| format!("Failed to update annotation: {}", e))?
+                .ok_or(format!("Annotation {} not found", annotation_id))?;
+            self.emit_change(app_handle, AnnotationAction::Updated, &annotation);
+            Ok(annotation)
+        })
+        .await
+    }
+
+    async fn delete_annotation(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        annotation_id: i32,
+    ) -> Result<(), String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let annotation = delete_annotation(self.db.clone(), annotation_id)
+                .await
+                .map_err(|e| format!("Failed to delete annotation: {}", e))?
+                .ok_or(format!("Annotation {} not found", annotation_id))?;
+            self.emit_change(app_handle, AnnotationAction::Deleted, &annotation);
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,94 @@
+/*
+Map annotations: points of interest, hazards and casualty sightings placed on the map by
+operators. Annotations are persisted so they survive restarts and are visible to every GCS
+station sharing the database; they can be tied to a mission or left global.
+*/
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+pub mod api;
+pub mod sql;
+
+pub use api::{AnnotationsApi, AnnotationsApiImpl};
+
+const MAX_LABEL_LENGTH: usize = 120;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum AnnotationKind {
+    PointOfInterest,
+    Hazard,
+    CasualtySighting,
+}
+
+impl AnnotationKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnnotationKind::PointOfInterest => "PointOfInterest",
+            AnnotationKind::Hazard => "Hazard",
+            AnnotationKind::CasualtySighting => "CasualtySighting",
+        }
+    }
+
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "Hazard" => AnnotationKind::Hazard,
+            "CasualtySighting" => AnnotationKind::CasualtySighting,
+            _ => AnnotationKind::PointOfInterest,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct MapAnnotation {
+    pub annotation_id: i32,
+    // None for annotations shown regardless of the selected mission
+    pub mission_id: Option<i32>,
+    pub kind: AnnotationKind,
+    pub label: String,
+    pub description: String,
+    pub lat: f64,
+    pub long: f64,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// Fields an operator can set when creating or editing an annotation
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct AnnotationInput {
+    pub mission_id: Option<i32>,
+    pub kind: AnnotationKind,
+    pub label: String,
+    pub description: String,
+    pub lat: f64,
+    pub long: f64,
+}
+
+impl AnnotationInput {
+    pub fn validate(&self) -> Result<(), String> {
+        let label = self.label.trim();
+        if label.is_empty() {
+            return Err("Annotation label is required".into());
+        }
+        if label.chars().count() > MAX_LABEL_LENGTH {
+            return Err(format!("Annotation labels are limited to {} characters", MAX_LABEL_LENGTH));
+        }
+        if !(-90.0..=90.0).contains(&self.lat) || !(-180.0..=180.0).contains(&self.long) {
+            return Err("Annotation position must be a valid latitude/longitude".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum AnnotationAction {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct AnnotationChange {
+    pub action: AnnotationAction,
+    pub annotation: MapAnnotation,
+}
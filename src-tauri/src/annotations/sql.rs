@@ -0,0 +1,112 @@
+/*
+Define all map annotation database functions.
+*/
+use sqlx::postgres::PgRow;
+use sqlx::{query, PgPool, Row};
+
+use super::{AnnotationInput, AnnotationKind, MapAnnotation};
+
+const ANNOTATION_COLUMNS: &str = "
+    annotation_id, mission_id, kind, label, description, lat, long, created_by,
+    to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS created_at,
+    to_char(updated_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS updated_at
+";
+
+fn to_annotation(row: PgRow) -> MapAnnotation {
+    MapAnnotation {
+        annotation_id: row.get("annotation_id"),
+        mission_id: row.get("mission_id"),
+        kind: AnnotationKind::parse(row.get("kind")),
+        label: row.get("label"),
+        description: row.get("description"),
+        lat: row.get("lat"),
+        long: row.get("long"),
+        created_by: row.get("created_by"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+pub async fn insert_annotation(
+    db_conn: PgPool,
+    input: &AnnotationInput,
+    created_by: &str,
+) -> Result<MapAnnotation, sqlx::Error> {
+    let row = query(&format!(
+        "INSERT INTO annotations(mission_id, kind, label, description, lat, long, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {}",
+        ANNOTATION_COLUMNS
+    ))
+    .bind(input.mission_id)
+    .bind(input.kind.name())
+    .bind(input.label.trim())
+    .bind(&input.description)
+    .bind(input.lat)
+    .bind(input.long)
+    .bind(created_by)
+    .fetch_one(&db_conn)
+    .await?;
+
+    Ok(to_annotation(row))
+}
+
+pub async fn update_annotation(
+    db_conn: PgPool,
+    annotation_id: i32,
+    input: &AnnotationInput,
+) -> Result<Option<MapAnnotation>, sqlx::Error> {
+    let row = query(&format!(
+        "UPDATE annotations
+        SET mission_id = $2, kind = $3, label = $4, description = $5, lat = $6, long = $7,
+            updated_at = NOW()
+        WHERE annotation_id = $1
+        RETURNING {}",
+        ANNOTATION_COLUMNS
+    ))
+    .bind(annotation_id)
+    .bind(input.mission_id)
+    .bind(input.kind.name())
+    .bind(input.label.trim())
+    .bind(&input.description)
+    .bind(input.lat)
+    .bind(input.long)
+    .fetch_optional(&db_conn)
+    .await?;
+
+    Ok(row.map(to_annotation))
+}
+
+pub async fn delete_annotation(
+    db_conn: PgPool,
+    annotation_id: i32,
+) -> Result<Option<MapAnnotation>, sqlx::Error> {
+    let row = query(&format!(
+        "DELETE FROM annotations WHERE annotation_id = $1 RETURNING {}",
+        ANNOTATION_COLUMNS
+    ))
+    .bind(annotation_id)
+    .fetch_optional(&db_conn)
+    .await?;
+
+    Ok(row.map(to_annotation))
+}
+
+// With a mission id: that mission's annotations plus global ones; otherwise everything
+pub async fn select_annotations(
+    db_conn: PgPool,
+    mission_id: Option<i32>,
+) -> Result<Vec<MapAnnotation>, sqlx::Error> {
+    let rows = query(&format!(
+        "SELECT {}
+        FROM annotations
+        WHERE $1::INTEGER IS NULL OR mission_id = $1 OR mission_id IS NULL
+        ORDER BY annotation_id",
+        ANNOTATION_COLUMNS
+    ))
+    .bind(mission_id)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_annotation).collect())
+}
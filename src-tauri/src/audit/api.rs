@@ -0,0 +1,35 @@
+/*
+Define the audit API: reading back the audit log.
+*/
+use sqlx::PgPool;
+use taurpc::{procedures, resolvers};
+
+use crate::init_db::lazy_pool;
+use super::sql::select_entries;
+use super::AuditEntry;
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "audit")]
+pub trait AuditApi {
+    // Newest first; without a mission id the whole log
+    async fn list_audit_log(mission_id: Option<i32>) -> Result<Vec<AuditEntry>, String>;
+}
+
+#[derive(Clone)]
+pub struct AuditApiImpl {
+    db: PgPool,
+}
+
+impl AuditApiImpl {
+    pub async fn new() -> Self {
+        Self { db: lazy_pool(1) }
+    }
+}
+
+#[resolvers]
+impl AuditApi for AuditApiImpl {
+    async fn list_audit_log(self, mission_id: Option<i32>) -> Result<Vec<AuditEntry>, String> {
+        select_entries(self.db.clone(), mission_id)
+            .await
+            .map_err(|e| format!("Failed to load audit log: {}", e))
+    }
+}
@@ -0,0 +1,110 @@
+/*
+Audit log of safety-relevant changes made during a mission. Removing a zone or stage from the
+active mission is refused unless the operator holds the mission-commander role and gives a
+justification; the justification is recorded here with the operator and what was removed.
+Changes the backend makes on its own, such as patient status set from a payload sensor or
+mission progress repaired at startup, are recorded under AUTOMATIC_OPERATOR.
+*/
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::PgPool;
+
+use crate::auth::current_operator;
+use crate::logs;
+
+pub mod api;
+pub mod sql;
+
+pub use api::{AuditApi, AuditApiImpl};
+
+const MAX_JUSTIFICATION_LENGTH: usize = 2000;
+// Operator named in entries for changes no operator made
+pub const AUTOMATIC_OPERATOR: &str = "system";
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum AuditAction {
+    ZoneDeleted,
+    StageDeleted,
+    PatientStatusChanged,
+    // Mission progress repaired at startup after an unclean shutdown
+    StateReconciled,
+    // The whole database replaced from a backup file
+    BackupRestored,
+}
+
+impl AuditAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AuditAction::ZoneDeleted => "ZoneDeleted",
+            AuditAction::StageDeleted => "StageDeleted",
+            AuditAction::PatientStatusChanged => "PatientStatusChanged",
+            AuditAction::StateReconciled => "StateReconciled",
+            AuditAction::BackupRestored => "BackupRestored",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ZoneDeleted" => Some(AuditAction::ZoneDeleted),
+            "StageDeleted" => Some(AuditAction::StageDeleted),
+            "PatientStatusChanged" => Some(AuditAction::PatientStatusChanged),
+            "StateReconciled" => Some(AuditAction::StateReconciled),
+            "BackupRestored" => Some(AuditAction::BackupRestored),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct AuditEntry {
+    pub entry_id: i32,
+    // None for an action name this build doesn't know
+    pub action: Option<AuditAction>,
+    pub mission_id: Option<i32>,
+    pub operator: String,
+    pub justification: String,
+    // What the action touched, e.g. "KeepOut zone 2"
+    pub details: String,
+    pub recorded_at: String,
+}
+
+// Trimmed justification, or an error when it is missing or too long
+pub fn require_justification(justification: Option<&str>) -> Result<String, String> {
+    let justification = justification.unwrap_or_default().trim();
+    if justification.is_empty() {
+        return Err("A justification is required for this change during an active mission".into());
+    }
+    if justification.chars().count() > MAX_JUSTIFICATION_LENGTH {
+        return Err(format!("Justifications are limited to {} characters", MAX_JUSTIFICATION_LENGTH));
+    }
+    Ok(justification.to_string())
+}
+
+// Record `action` for the current operator; callers make the change only once this succeeded
+pub async fn record(
+    db: PgPool,
+    action: AuditAction,
+    mission_id: Option<i32>,
+    justification: &str,
+    details: &str,
+) -> Result<AuditEntry, String> {
+    record_as(db, &current_operator(), action, mission_id, justification, details).await
+}
+
+pub async fn record_as(
+    db: PgPool,
+    operator: &str,
+    action: AuditAction,
+    mission_id: Option<i32>,
+    justification: &str,
+    details: &str,
+) -> Result<AuditEntry, String> {
+    let entry = sql::insert_entry(db, action, mission_id, operator, justification, details)
+        .await
+        .map_err(|e| format!("Failed to write audit log: {}", e))?;
+    logs::warn(
+        "audit",
+        format!("{} by {} ({}): {}", action.name(), operator, details, justification),
+    );
+    Ok(entry)
+}
@@ -0,0 +1,62 @@
+/*
+Define all audit log database functions. Actions are stored by name.
+*/
+use sqlx::postgres::PgRow;
+use sqlx::{query, PgPool, Row};
+
+use super::{AuditAction, AuditEntry};
+
+const ENTRY_COLUMNS: &str = "
+    entry_id, action, mission_id, operator, justification, details,
+    to_char(recorded_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS recorded_at
+";
+
+fn to_entry(row: PgRow) -> AuditEntry {
+    AuditEntry {
+        entry_id: row.get("entry_id"),
+        action: AuditAction::parse(row.get("action")),
+        mission_id: row.get("mission_id"),
+        operator: row.get("operator"),
+        justification: row.get("justification"),
+        details: row.get("details"),
+        recorded_at: row.get("recorded_at"),
+    }
+}
+
+pub async fn insert_entry(
+    db_conn: PgPool,
+    action: AuditAction,
+    mission_id: Option<i32>,
+    operator: &str,
+    justification: &str,
+    details: &str,
+) -> Result<AuditEntry, sqlx::Error> {
+    let row = query(&format!(
+        "INSERT INTO audit_log(action, mission_id, operator, justification, details)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING {}",
+        ENTRY_COLUMNS
+    ))
+    .bind(action.name())
+    .bind(mission_id)
+    .bind(operator)
+    .bind(justification)
+    .bind(details)
+    .fetch_one(&db_conn)
+    .await?;
+    Ok(to_entry(row))
+}
+
+// Newest first, optionally only the entries of one mission
+pub async fn select_entries(db_conn: PgPool, mission_id: Option<i32>) -> Result<Vec<AuditEntry>, sqlx::Error> {
+    let rows = query(&format!(
+        "SELECT {} FROM audit_log
+        WHERE $1::INTEGER IS NULL OR mission_id = $1
+        ORDER BY recorded_at DESC, entry_id DESC",
+        ENTRY_COLUMNS
+    ))
+    .bind(mission_id)
+    .fetch_all(&db_conn)
+    .await?;
+    Ok(rows.into_iter().map(to_entry).collect())
+}
@@ -0,0 +1,168 @@
+/*
+Define the authentication API: operator login/logout, session resume after a frontend reload,
+and operator registration against the local credential store. Sessions belong to the window
+that logs in.
+*/
+use std::env;
+use sqlx::PgPool;
+use tauri::{Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::init_db::lazy_pool;
+use crate::logs;
+use super::credentials::{generate_salt, hash_password, needs_rehash, verify_password};
+use super::roles::{require_role, OperatorRole};
+use super::session::{as_window, end_session, resume_session, session_for, start_session, OperatorSession};
+use super::sql::{count_operators, select_operator, insert_operator, update_operator_password};
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "auth")]
+pub trait AuthApi {
+    async fn login(
+        window: Window<impl Runtime>,
+        username: String,
+        password: String,
+    ) -> Result<OperatorSession, String>;
+    async fn resume_session(window: Window<impl Runtime>, token: String) -> Result<OperatorSession, String>;
+    async fn logout(window: Window<impl Runtime>) -> Result<(), String>;
+    async fn get_session(window: Window<impl Runtime>) -> Option<OperatorSession>;
+    // The first operator can be registered freely; afterwards a mission commander is required.
+    // Existing usernames are refused rather than overwritten.
+    async fn register_operator(
+        window: Window<impl Runtime>,
+        username: String,
+        password: String,
+        role: OperatorRole,
+    ) -> Result<(), String>;
+}
+
+#[derive(Clone)]
+pub struct AuthApiImpl {
+    db: PgPool,
+}
+
+impl AuthApiImpl {
+    pub async fn new() -> Self {
+        Self { db: lazy_pool(2) }
+    }
+}
+
+// Credential backend, chosen with GCS_AUTH_BACKEND ("local" by default)
+fn check_backend() -> Result<(), String> {
+    match env::var("GCS_AUTH_BACKEND")
+        .unwrap_or_else(|_| "local".to_string())
+        .to_lowercase()
+        .as_str()
+    {
+        "local" => Ok(()),
+        "ldap" => Err("LDAP authentication is not available in this build; use GCS_AUTH_BACKEND=local".into()),
+        other => Err(format!("Unknown authentication backend: {}", other)),
+    }
+}
+
+// (salt, hash) of a new password
+async fn hash_off_runtime(password: String) -> Result<(String, String), String> {
+    tokio::task::spawn_blocking(move || {
+        let salt = generate_salt();
+        let password_hash = hash_password(&password, &salt);
+        (salt, password_hash)
+    })
+    .await
+    .map_err(|e| format!("Failed to hash password: {}", e))
+}
+
+#[resolvers]
+impl AuthApi for AuthApiImpl {
+    async fn login(
+        self,
+        window: Window<impl Runtime>,
+        username: String,
+        password: String,
+    ) -> Result<OperatorSession, String> {
+        check_backend()?;
+        let username = username.trim().to_string();
+        let record = select_operator(self.db.clone(), &username)
+            .await
+            .map_err(|e| format!("Failed to look up operator: {}", e))?;
+
+        // Same error for unknown users and wrong passwords
+        let verified = match &record {
+            Some(r) => {
+                let (password, salt, hash) = (password.clone(), r.salt.clone(), r.password_hash.clone());
+                tokio::task::spawn_blocking(move || verify_password(&password, &salt, &hash))
+                    .await
+                    .map_err(|e| format!("Failed to check password: {}", e))?
+            }
+            None => false,
+        };
+        let record = record.filter(|_| verified).ok_or_else(|| {
+            println!("Failed login attempt for operator '{}'", username);
+            "Invalid username or password".to_string()
+        })?;
+        let role = OperatorRole::parse(&record.role)
+            .ok_or(format!("Operator '{}' has an invalid role", record.username))?;
+
+        // Hashes from an older scheme are replaced while the password is at hand
+        if needs_rehash(&record.password_hash) {
+            let (salt, password_hash) = hash_off_runtime(password).await?;
+            if let Err(e) = update_operator_password(self.db.clone(), &record.username, &password_hash, &salt).await {
+                logs::warn("auth", format!("Failed to upgrade the password hash of '{}': {}", record.username, e));
+            }
+        }
+
+        println!("Operator '{}' logged in as {}", record.username, role.name());
+        Ok(start_session(window.label(), &record.username, role))
+    }
+
+    async fn resume_session(
+        self,
+        window: Window<impl Runtime>,
+        token: String,
+    ) -> Result<OperatorSession, String> {
+        resume_session(window.label(), &token)
+    }
+
+    async fn logout(self, window: Window<impl Runtime>) -> Result<(), String> {
+        let session = end_session(window.label()).ok_or("Not logged in")?;
+        println!("Operator '{}' logged out", session.username);
+        Ok(())
+    }
+
+    async fn get_session(self, window: Window<impl Runtime>) -> Option<OperatorSession> {
+        session_for(window.label())
+    }
+
+    async fn register_operator(
+        self,
+        window: Window<impl Runtime>,
+        username: String,
+        password: String,
+        role: OperatorRole,
+    ) -> Result<(), String> {
+        check_backend()?;
+        let existing = count_operators(self.db.clone())
+            .await
+            .map_err(|e| format!("Failed to count operators: {}", e))?;
+        if existing > 0 {
+            as_window(&window, async { require_role(OperatorRole::MissionCommander) }).await?;
+        }
+
+        let username = username.trim();
+        if username.is_empty() {
+            return Err("Username is required".into());
+        }
+        if password.len() < MIN_PASSWORD_LENGTH {
+            return Err(format!("Password must be at least {} characters", MIN_PASSWORD_LENGTH));
+        }
+
+        let (salt, password_hash) = hash_off_runtime(password).await?;
+        let inserted = insert_operator(self.db.clone(), username, &password_hash, &salt, role.name())
+            .await
+            .map_err(|e| format!("Failed to save operator: {}", e))?;
+        if !inserted {
+            return Err(format!("Operator '{}' already exists", username));
+        }
+        Ok(())
+    }
+}
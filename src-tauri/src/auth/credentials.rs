@@ -0,0 +1,76 @@
+/*
+Local credential store: salted PBKDF2-HMAC-SHA256 password hashes kept in the operators table,
+stored as "pbkdf2-sha256$<iterations>$<hex>". Hashes from before PBKDF2 (iterated SHA-256, no
+prefix) still verify and are replaced on the operator's next login.
+*/
+use pbkdf2::pbkdf2_hmac;
+use sha2::{Digest, Sha256};
+
+const SCHEME: &str = "pbkdf2-sha256";
+// OWASP's recommendation for PBKDF2-HMAC-SHA256
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const LEGACY_ITERATIONS: u32 = 100_000;
+
+pub fn generate_salt() -> String {
+    to_hex(&rand::random::<[u8; 16]>())
+}
+
+// Slow on purpose; call it off the async runtime
+pub fn hash_password(password: &str, salt: &str) -> String {
+    format!("{}${}${}", SCHEME, PBKDF2_ITERATIONS, pbkdf2_hex(password, salt, PBKDF2_ITERATIONS))
+}
+
+// Slow on purpose; call it off the async runtime
+pub fn verify_password(password: &str, salt: &str, expected_hash: &str) -> bool {
+    let actual = match parse(expected_hash) {
+        Some(iterations) => pbkdf2_hex(password, salt, iterations),
+        None => legacy_hash(password, salt),
+    };
+    let expected = expected_hash.rsplit('$').next().unwrap_or_default();
+    constant_time_eq(&actual, expected)
+}
+
+// Hashes in an older scheme or with fewer iterations than now
+pub fn needs_rehash(stored_hash: &str) -> bool {
+    parse(stored_hash).is_none_or(|iterations| iterations < PBKDF2_ITERATIONS)
+}
+
+// Iteration count of a PBKDF2 hash; None for legacy hashes
+fn parse(stored_hash: &str) -> Option<u32> {
+    let mut parts = stored_hash.split('$');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(SCHEME), Some(iterations), Some(_), None) => iterations.parse().ok().filter(|i| *i > 0),
+        _ => None,
+    }
+}
+
+fn pbkdf2_hex(password: &str, salt: &str, iterations: u32) -> String {
+    let mut derived = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(password.as_bytes(), salt.as_bytes(), iterations, &mut derived);
+    to_hex(&derived)
+}
+
+fn legacy_hash(password: &str, salt: &str) -> String {
+    let mut digest = Sha256::digest(format!("{}:{}", salt, password).as_bytes());
+    for _ in 1..LEGACY_ITERATIONS {
+        let mut hasher = Sha256::new();
+        hasher.update(digest);
+        hasher.update(salt.as_bytes());
+        digest = hasher.finalize();
+    }
+    to_hex(&digest)
+}
+
+// Compare without short-circuiting so timing does not reveal how much of the hash matched
+fn constant_time_eq(actual: &str, expected: &str) -> bool {
+    actual.len() == expected.len()
+        && actual
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
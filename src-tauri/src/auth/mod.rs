@@ -0,0 +1,13 @@
+/*
+Declares the operator access-control submodules (roles, login sessions, credential store)
+and the authentication API.
+*/
+pub mod api;
+pub mod credentials;
+pub mod roles;
+pub mod session;
+pub mod sql;
+
+pub use api::{AuthApi, AuthApiImpl};
+pub use roles::{current_role, require_role, OperatorRole};
+pub use session::{as_window, authorized, current_operator};
@@ -0,0 +1,70 @@
+/*
+Operator roles and permission checks. Roles are ordered: an observer can only view data,
+an operator can command vehicles and edit the mission plan, and a mission commander can
+additionally start, abort and delete missions.
+*/
+use std::env;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::session::{active_session, auth_required};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Type)]
+pub enum OperatorRole {
+    Observer,
+    Operator,
+    MissionCommander,
+}
+
+impl OperatorRole {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "observer" => Some(OperatorRole::Observer),
+            "operator" => Some(OperatorRole::Operator),
+            "missioncommander" | "commander" => Some(OperatorRole::MissionCommander),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OperatorRole::Observer => "observer",
+            OperatorRole::Operator => "operator",
+            OperatorRole::MissionCommander => "mission commander",
+        }
+    }
+}
+
+// Role of this GCS station, set with GCS_OPERATOR_ROLE; stations without one keep full access
+pub fn station_role() -> OperatorRole {
+    match env::var("GCS_OPERATOR_ROLE") {
+        Ok(value) => OperatorRole::parse(&value).unwrap_or_else(|| {
+            eprintln!("Unknown GCS_OPERATOR_ROLE '{}', falling back to observer", value);
+            OperatorRole::Observer
+        }),
+        Err(_) => OperatorRole::MissionCommander,
+    }
+}
+
+// Effective role: the logged-in operator's role, capped by the station role. None when
+// authentication is required and nobody is logged in.
+pub fn current_role() -> Option<OperatorRole> {
+    match active_session() {
+        Some(session) => Some(session.role.min(station_role())),
+        None if auth_required() => None,
+        None => Some(station_role()),
+    }
+}
+
+pub fn require_role(required: OperatorRole) -> Result<(), String> {
+    let role = current_role().ok_or("Not logged in")?;
+    if role >= required {
+        Ok(())
+    } else {
+        Err(format!(
+            "Permission denied: requires {} role (current role: {})",
+            required.name(),
+            role.name()
+        ))
+    }
+}
@@ -0,0 +1,162 @@
+/*
+Operator sessions. A login creates a session token for the window it was made in, so operators
+logged in to different windows of the station act under their own names. Procedures that check
+permissions or attribute actions run in the calling window's scope (see as_window), and every
+check looks up that window's session. A token can only be resumed by the window it was issued
+to. Sessions expire after SESSION_TTL and end when their window closes.
+*/
+use std::collections::HashMap;
+use std::env;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{Runtime, Window};
+
+use super::credentials::to_hex;
+use super::roles::{require_role, OperatorRole};
+
+pub const SESSION_TTL: Duration = Duration::from_secs(12 * 60 * 60);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct OperatorSession {
+    pub token: String,
+    pub username: String,
+    pub role: OperatorRole,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Clone)]
+struct StoredSession {
+    session: OperatorSession,
+    // Label of the window the session was created in
+    window: String,
+    created_at: Instant,
+}
+
+impl StoredSession {
+    fn is_expired(&self) -> bool {
+        self.created_at.elapsed() > SESSION_TTL
+    }
+}
+
+#[derive(Debug, Default)]
+struct SessionStore {
+    sessions: HashMap<String, StoredSession>,
+    // Session token by window label
+    windows: HashMap<String, String>,
+}
+
+impl SessionStore {
+    fn prune(&mut self) {
+        self.sessions.retain(|_, s| !s.is_expired());
+        let sessions = &self.sessions;
+        self.windows.retain(|_, token| sessions.contains_key(token));
+    }
+}
+
+lazy_static! {
+    static ref SESSIONS: Mutex<SessionStore> = Mutex::new(SessionStore::default());
+}
+
+tokio::task_local! {
+    // Label of the window whose call is running
+    static CALLER: String;
+}
+
+// Run `f` on behalf of `window`: permission checks and attribution inside it use the session of
+// that window. Tasks spawned from `f` run without a caller.
+pub async fn as_window<R: Runtime, F: Future>(window: &Window<R>, f: F) -> F::Output {
+    CALLER.scope(window.label().to_string(), f).await
+}
+
+// Run `f` for the operator of `window` once they hold at least `role`
+pub async fn authorized<R: Runtime, T, F>(window: &Window<R>, role: OperatorRole, f: F) -> Result<T, String>
+where
+    F: Future<Output = Result<T, String>>,
+{
+    as_window(window, async move {
+        require_role(role)?;
+        f.await
+    })
+    .await
+}
+
+// With GCS_AUTH_REQUIRED=true every permission check needs a logged-in operator
+pub fn auth_required() -> bool {
+    env::var("GCS_AUTH_REQUIRED")
+        .unwrap_or_default()
+        .to_lowercase()
+        == "true"
+}
+
+// Create a session for an authenticated operator, replacing the window's previous one
+pub fn start_session(window: &str, username: &str, role: OperatorRole) -> OperatorSession {
+    let expires_at = chrono::Utc::now()
+        + chrono::Duration::from_std(SESSION_TTL).unwrap_or_default();
+    let session = OperatorSession {
+        token: to_hex(&rand::random::<[u8; 32]>()),
+        username: username.to_string(),
+        role,
+        expires_at: expires_at.to_rfc3339(),
+    };
+
+    let mut store = SESSIONS.lock().unwrap();
+    if let Some(previous) = store.windows.remove(window) {
+        store.sessions.remove(&previous);
+    }
+    store.prune();
+    store.sessions.insert(
+        session.token.clone(),
+        StoredSession {
+            session: session.clone(),
+            window: window.to_string(),
+            created_at: Instant::now(),
+        },
+    );
+    store.windows.insert(window.to_string(), session.token.clone());
+    session
+}
+
+// Re-attach a window's session after the frontend reloads; a window keeps its label across
+// reloads, and a token issued to another window is refused
+pub fn resume_session(window: &str, token: &str) -> Result<OperatorSession, String> {
+    let mut store = SESSIONS.lock().unwrap();
+    store.prune();
+    let session = store
+        .sessions
+        .get(token)
+        .filter(|s| s.window == window)
+        .map(|s| s.session.clone())
+        .ok_or("Session expired or invalid")?;
+    store.windows.insert(window.to_string(), token.to_string());
+    Ok(session)
+}
+
+// End the window's session, returning it if there was one
+pub fn end_session(window: &str) -> Option<OperatorSession> {
+    let mut store = SESSIONS.lock().unwrap();
+    let token = store.windows.remove(window)?;
+    store.sessions.remove(&token).map(|s| s.session)
+}
+
+pub fn session_for(window: &str) -> Option<OperatorSession> {
+    let mut store = SESSIONS.lock().unwrap();
+    store.prune();
+    let token = store.windows.get(window)?;
+    store.sessions.get(token).map(|s| s.session.clone())
+}
+
+// Session of the window whose call is running; None outside a call or when nobody is logged in there
+pub fn active_session() -> Option<OperatorSession> {
+    CALLER.try_with(|window| session_for(window)).ok().flatten()
+}
+
+// Operator attributed in the command history and audit log
+pub fn current_operator() -> String {
+    active_session()
+        .map(|s| s.username)
+        .unwrap_or_else(|| env::var("GCS_OPERATOR").unwrap_or_else(|_| "local".to_string()))
+}
@@ -0,0 +1,74 @@
+/*
+Define all operator credential database functions.
+*/
+use sqlx::{query, PgPool, Row};
+
+pub struct OperatorRecord {
+    pub username: String,
+    pub password_hash: String,
+    pub salt: String,
+    pub role: String,
+}
+
+pub async fn select_operator(
+    db_conn: PgPool,
+    username: &str,
+) -> Result<Option<OperatorRecord>, sqlx::Error> {
+    let row = query("SELECT username, password_hash, salt, role FROM operators WHERE username = $1")
+        .bind(username)
+        .fetch_optional(&db_conn)
+        .await?;
+
+    Ok(row.map(|row| OperatorRecord {
+        username: row.get("username"),
+        password_hash: row.get("password_hash"),
+        salt: row.get("salt"),
+        role: row.get("role"),
+    }))
+}
+
+pub async fn count_operators(db_conn: PgPool) -> Result<i64, sqlx::Error> {
+    let row = query("SELECT COUNT(*) AS count FROM operators")
+        .fetch_one(&db_conn)
+        .await?;
+    Ok(row.get("count"))
+}
+
+// Returns false, changing nothing, when the username is taken
+pub async fn insert_operator(
+    db_conn: PgPool,
+    username: &str,
+    password_hash: &str,
+    salt: &str,
+    role: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = query("
+        INSERT INTO operators(username, password_hash, salt, role)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (username) DO NOTHING
+    ")
+    .bind(username)
+    .bind(password_hash)
+    .bind(salt)
+    .bind(role)
+    .execute(&db_conn)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn update_operator_password(
+    db_conn: PgPool,
+    username: &str,
+    password_hash: &str,
+    salt: &str,
+) -> Result<(), sqlx::Error> {
+    query("UPDATE operators SET password_hash = $2, salt = $3 WHERE username = $1")
+        .bind(username)
+        .bind(password_hash)
+        .bind(salt)
+        .execute(&db_conn)
+        .await?;
+
+    Ok(())
+}
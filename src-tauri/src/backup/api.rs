@@ -0,0 +1,138 @@
+/*
+Define the backup API: snapshot the GCS database to a file and restore it from one.
+*/
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use sqlx::PgPool;
+use tauri::{AppHandle, Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::audit::{self, AuditAction};
+use crate::auth::{authorized, current_operator, OperatorRole};
+use crate::init_db::{lazy_pool, REQUIRED_TABLES};
+use crate::logs;
+use crate::missions::api::MissionApiImpl;
+use crate::missions::types::MissionStageStatusEnum;
+use super::sql::{replace_tables, select_tables};
+use super::{BackupFile, BackupSummary, FORMAT_VERSION};
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "backup")]
+pub trait BackupApi {
+    // Writes every GCS table to the JSON file at `path`
+    async fn create_backup(window: Window<impl Runtime>, path: String) -> Result<BackupSummary, String>;
+    // Replaces the whole database with the backup at `path`; refused while a mission runs
+    async fn restore_backup(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        path: String,
+    ) -> Result<BackupSummary, String>;
+}
+
+#[derive(Clone)]
+pub struct BackupApiImpl {
+    db: PgPool,
+    missions: MissionApiImpl,
+}
+
+impl BackupApiImpl {
+    pub fn new(missions: MissionApiImpl) -> Self {
+        Self { db: lazy_pool(1), missions }
+    }
+}
+
+#[resolvers]
+impl BackupApi for BackupApiImpl {
+    async fn create_backup(
+        self,
+        window: Window<impl Runtime>,
+        path: String,
+    ) -> Result<BackupSummary, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            if path.trim().is_empty() {
+                return Err("A backup file is required".into());
+            }
+            let mut tables = BTreeMap::new();
+            for (name, rows) in select_tables(self.db.clone())
+                .await
+                .map_err(|e| format!("Failed to read the database: {}", e))?
+            {
+                let rows = serde_json::from_str(&rows).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+                tables.insert(name, rows);
+            }
+            let backup = BackupFile {
+                format_version: FORMAT_VERSION,
+                created_at: chrono::Utc::now().to_rfc3339(),
+                created_by: current_operator(),
+                tables,
+            };
+
+            let file = Path::new(&path);
+            if let Some(parent) = file.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            let contents = serde_json::to_vec(&backup).map_err(|e| format!("Failed to serialize backup: {}", e))?;
+            fs::write(file, contents).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+            let summary = backup.summary(&path);
+            let rows: u32 = summary.tables.iter().map(|t| t.rows).sum();
+            logs::info("backup", format!("Backed up {} rows to {} for {}", rows, path, backup.created_by));
+            Ok(summary)
+        })
+        .await
+    }
+
+    async fn restore_backup(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        path: String,
+    ) -> Result<BackupSummary, String> {
+        authorized(&window, OperatorRole::MissionCommander, async move {
+            let running = self.missions.snapshot().await.missions.iter().any(|m| {
+                matches!(m.mission_status, MissionStageStatusEnum::Active | MissionStageStatusEnum::Paused)
+            });
+            if running {
+                return Err("Cannot restore a backup while a mission is active or paused".into());
+            }
+
+            let contents = fs::read(&path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+            let backup: BackupFile =
+                serde_json::from_slice(&contents).map_err(|e| format!("{} is not a GCS backup: {}", path, e))?;
+            if backup.format_version != FORMAT_VERSION {
+                return Err(format!(
+                    "Unsupported backup format version {} (expected {})",
+                    backup.format_version, FORMAT_VERSION
+                ));
+            }
+            if let Some((name, _)) = backup.tables.iter().find(|(_, rows)| !rows.is_array()) {
+                return Err(format!("{} has no rows for table {}", path, name));
+            }
+            for name in backup.tables.keys().filter(|name| !REQUIRED_TABLES.contains(&name.as_str())) {
+                logs::warn("backup", format!("Skipping unknown table {} in {}", name, path));
+            }
+
+            replace_tables(self.db.clone(), &backup.tables)
+                .await
+                .map_err(|e| format!("Failed to restore {}: {}", path, e))?;
+            let summary = backup.summary(&path);
+            // The restored audit log replaced the old one, so the restore is recorded in the new one
+            let details = format!("{} taken {} by {}", path, backup.created_at, backup.created_by);
+            let recorded = audit::record(
+                self.db.clone(),
+                AuditAction::BackupRestored,
+                None,
+                "Database restored from backup",
+                &details,
+            )
+            .await;
+            if let Err(e) = recorded {
+                logs::error("backup", e);
+            }
+            self.missions.reload_state(&app_handle).await?;
+            Ok(summary)
+        })
+        .await
+    }
+}
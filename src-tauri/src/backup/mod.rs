@@ -0,0 +1,63 @@
+/*
+Backup and restore of the GCS database, so field teams can snapshot it before risky schema or
+mission changes and roll back afterwards. A backup is one JSON file holding every row of every
+GCS table (init_db::REQUIRED_TABLES), taken from a single consistent snapshot. It is written by
+the app itself rather than pg_dump, so field laptops don't need the PostgreSQL client tools,
+and a backup restores into a newer schema: columns added since keep their defaults.
+
+Restoring replaces the contents of every GCS table, including operators and the audit log, in
+one transaction; a backup that fails to restore leaves the database untouched. It is refused
+while a mission is running.
+*/
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+pub mod api;
+pub mod sql;
+
+pub use api::{BackupApi, BackupApiImpl};
+
+// Bumped when the file layout changes; newer backups are refused rather than half restored
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BackupFile {
+    pub format_version: u32,
+    pub created_at: String,
+    pub created_by: String,
+    // Array of row objects by table name
+    pub tables: BTreeMap<String, serde_json::Value>,
+}
+
+impl BackupFile {
+    pub fn summary(&self, path: &str) -> BackupSummary {
+        BackupSummary {
+            path: path.to_string(),
+            created_at: self.created_at.clone(),
+            created_by: self.created_by.clone(),
+            tables: self
+                .tables
+                .iter()
+                .map(|(name, rows)| BackupTable {
+                    name: name.clone(),
+                    rows: rows.as_array().map_or(0, |rows| rows.len() as u32),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct BackupTable {
+    pub name: String,
+    pub rows: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct BackupSummary {
+    pub path: String,
+    pub created_at: String,
+    pub created_by: String,
+    pub tables: Vec<BackupTable>,
+}
@@ -0,0 +1,85 @@
+/*
+Define the backup database functions: reading every GCS table as JSON and writing it back.
+*/
+use std::collections::BTreeMap;
+use sqlx::{query, query_scalar, PgPool, Postgres, Transaction};
+
+use crate::init_db::REQUIRED_TABLES;
+
+// Rows of every GCS table as a JSON array, by table name, all read from one snapshot
+pub async fn select_tables(db_conn: PgPool) -> Result<BTreeMap<String, String>, sqlx::Error> {
+    let mut transaction = db_conn.begin().await?;
+    query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *transaction)
+        .await?;
+    let mut tables = BTreeMap::new();
+    for table in REQUIRED_TABLES {
+        let rows: String = query_scalar(&format!(
+            "SELECT COALESCE(json_agg(t), '[]'::json)::text FROM {} t",
+            table
+        ))
+        .fetch_one(&mut *transaction)
+        .await?;
+        tables.insert(table.to_string(), rows);
+    }
+    transaction.commit().await?;
+    Ok(tables)
+}
+
+async fn select_columns(
+    transaction: &mut Transaction<'_, Postgres>,
+    table: &str,
+    serial_only: bool,
+) -> Result<Vec<String>, sqlx::Error> {
+    query_scalar(
+        "SELECT column_name::text FROM information_schema.columns
+        WHERE table_schema = current_schema() AND table_name = $1
+            AND (NOT $2 OR column_default LIKE 'nextval(%')
+        ORDER BY ordinal_position",
+    )
+    .bind(table)
+    .bind(serial_only)
+    .fetch_all(&mut **transaction)
+    .await
+}
+
+// Replace the contents of every GCS table with `tables` (JSON arrays of row objects by table
+// name); tables missing from it are left empty. Tables are filled in REQUIRED_TABLES order,
+// which lists referenced tables first, and serial sequences continue after the restored ids.
+pub async fn replace_tables(
+    db_conn: PgPool,
+    tables: &BTreeMap<String, serde_json::Value>,
+) -> Result<(), sqlx::Error> {
+    let mut transaction = db_conn.begin().await?;
+    query(&format!("TRUNCATE {} RESTART IDENTITY CASCADE", REQUIRED_TABLES.join(", ")))
+        .execute(&mut *transaction)
+        .await?;
+
+    for table in REQUIRED_TABLES {
+        let Some(rows) = tables.get(table) else { continue };
+        let Some(first) = rows.as_array().and_then(|rows| rows.first()) else { continue };
+        // Columns in both the backup and this schema; ones added since keep their defaults
+        let columns = select_columns(&mut transaction, table, false)
+            .await?
+            .into_iter()
+            .filter(|column| first.get(column).is_some())
+            .map(|column| format!("\"{}\"", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        query(&format!(
+            "INSERT INTO {table} ({columns}) SELECT {columns} FROM json_populate_recordset(NULL::{table}, $1::json)",
+        ))
+        .bind(rows.to_string())
+        .execute(&mut *transaction)
+        .await?;
+
+        for column in select_columns(&mut transaction, table, true).await? {
+            query(&format!(
+                "SELECT setval(pg_get_serial_sequence('{table}', '{column}'), COALESCE(MAX(\"{column}\"), 0) + 1, false) FROM {table}",
+            ))
+            .execute(&mut *transaction)
+            .await?;
+        }
+    }
+    transaction.commit().await
+}
@@ -0,0 +1,14 @@
+/*
+Hot paths reachable from the criterion benchmarks in benches/. Not part of the application API;
+anything re-exported here must stay free of Tauri, broker and database handles so a benchmark
+runs on its own.
+*/
+pub use crate::geofence::{current as current_geofence, set_geometry, MissionGeometry};
+pub use crate::geometry::{distance_to_polygon, LocalProjection};
+pub use crate::missions::api::zones::{convert_zone_format, convert_zone_to_json};
+pub use crate::missions::types::GeoCoordinateStruct;
+pub use crate::telemetry::broadcast;
+pub use crate::telemetry::geos::Coordinate;
+pub use crate::telemetry::separation::record_position;
+pub use crate::telemetry::state::TelemetryState;
+pub use crate::telemetry::types::{TelemetryData, VehicleTelemetryData};
@@ -0,0 +1,23 @@
+/*
+Time source for timeouts and schedulers (heartbeat monitor, command retry queue, supervisor
+restarts). It is tokio's clock, so a test can freeze time with tokio::time::pause() and step it
+with tokio::time::advance() (the test-util feature) to check timeout and backoff logic
+deterministically instead of sleeping. Latency measurements keep using std::time::Instant,
+since they have to see real time.
+*/
+use std::time::Duration;
+
+pub use tokio::time::{Instant, Interval};
+
+pub fn now() -> Instant {
+    Instant::now()
+}
+
+// Ticks every `period`; the first tick completes immediately
+pub fn interval(period: Duration) -> Interval {
+    tokio::time::interval(period)
+}
+
+pub async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
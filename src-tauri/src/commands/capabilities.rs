@@ -0,0 +1,281 @@
+/*
+Vehicle capability model: which commands each vehicle accepts and its command limits.
+Used by the commands module to reject unsupported commands before they are sent. Each vehicle's
+capabilities come from its protocol adapter (see protocols) until the vehicle announces its own:
+vehicles send their supported commands and limits in the first telemetry report after they
+connect, and the announcement replaces the adapter's model for that vehicle until it disconnects.
+The emergency stop is always accepted, whatever a vehicle announces.
+*/
+use std::collections::HashMap;
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Deserializer, Serialize};
+use specta::Type;
+
+use crate::config;
+use crate::logs;
+use crate::protocols;
+
+// Every vehicle the GCS can command, from the configured roster
+pub fn known_vehicles() -> Vec<String> {
+    config::get().vehicles.iter().map(|v| v.to_uppercase()).collect()
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Type)]
+pub enum CommandType {
+    EmergencyStop,
+    MissionUpdate,
+    ZoneUpdate,
+    Takeoff,
+    Land,
+    HoldPosition,
+    ReturnToHome,
+    Waypoints,
+    ApproveRequest,
+    DenyRequest,
+    // Stick input streamed from a gamepad; see ManualControlInput
+    ManualControl,
+    Arm,
+    Disarm,
+    // Link round-trip probe; see ping.rs
+    Ping,
+}
+
+impl CommandType {
+    // Wire-level commandID; zone updates carry the zone kind instead (2 keep-in, 3 keep-out, 4 search area)
+    pub fn command_id(&self) -> Option<i32> {
+        match self {
+            CommandType::EmergencyStop => Some(1),
+            CommandType::Takeoff => Some(5),
+            CommandType::Land => Some(6),
+            CommandType::HoldPosition => Some(7),
+            CommandType::ReturnToHome => Some(8),
+            CommandType::Waypoints => Some(9),
+            CommandType::ApproveRequest => Some(10),
+            CommandType::DenyRequest => Some(11),
+            CommandType::ManualControl => Some(12),
+            CommandType::Arm => Some(13),
+            CommandType::Disarm => Some(14),
+            CommandType::Ping => Some(15),
+            CommandType::MissionUpdate | CommandType::ZoneUpdate => None,
+        }
+    }
+
+    // Inverse of command_id; the zone kinds are all ZoneUpdate
+    pub fn from_command_id(command_id: i32) -> Option<CommandType> {
+        match command_id {
+            2..=4 => Some(CommandType::ZoneUpdate),
+            id => [
+                CommandType::EmergencyStop,
+                CommandType::Takeoff,
+                CommandType::Land,
+                CommandType::HoldPosition,
+                CommandType::ReturnToHome,
+                CommandType::Waypoints,
+                CommandType::ApproveRequest,
+                CommandType::DenyRequest,
+                CommandType::ManualControl,
+                CommandType::Arm,
+                CommandType::Disarm,
+                CommandType::Ping,
+            ]
+            .into_iter()
+            .find(|command| command.command_id() == Some(id)),
+        }
+    }
+
+    // Emergency and safing commands, still sent while the mission is on hold. Manual control and
+    // zone changes are not: a held mission is neither flown by hand nor reshaped.
+    pub fn allowed_during_hold(&self) -> bool {
+        matches!(
+            self,
+            CommandType::EmergencyStop
+                | CommandType::Land
+                | CommandType::HoldPosition
+                | CommandType::ReturnToHome
+                | CommandType::Ping
+        )
+    }
+
+    // Commands the operator must explicitly confirm before they are sent
+    pub fn requires_confirmation(&self) -> bool {
+        matches!(
+            self,
+            CommandType::Takeoff
+                | CommandType::Land
+                | CommandType::ReturnToHome
+                | CommandType::Arm
+                | CommandType::Disarm
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct VehicleCapabilities {
+    pub vehicle_id: String,
+    pub is_aircraft: bool,
+    pub supported_commands: Vec<CommandType>,
+    pub max_zone_vertices: i32,
+    // True once the vehicle announced its capabilities, false while they are the adapter's defaults
+    pub announced: bool,
+}
+
+impl VehicleCapabilities {
+    pub fn supports(&self, command: CommandType) -> bool {
+        self.supported_commands.contains(&command)
+    }
+
+    pub fn require(&self, command: CommandType) -> Result<(), String> {
+        if self.supports(command) {
+            return Ok(());
+        }
+        let source = if self.announced { "as announced by the vehicle" } else { "in its default capability model" };
+        Err(format!("{} does not support {:?} ({})", self.vehicle_id, command, source))
+    }
+}
+
+// What a vehicle reports it supports; limits it leaves out keep the adapter's value
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct CapabilityAnnouncement {
+    #[serde(deserialize_with = "known_commands")]
+    pub supported_commands: Vec<CommandType>,
+    #[serde(default)]
+    pub max_zone_vertices: Option<i32>,
+}
+
+// Vehicle firmware may be newer than the GCS: commands it does not know are skipped, not a reason
+// to drop the whole report
+fn known_commands<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<CommandType>, D::Error> {
+    let entries = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| match serde_json::from_value::<CommandType>(entry.clone()) {
+            Ok(command) => Some(command),
+            Err(_) => {
+                logs::warn("commands", format!("Ignoring unknown command {} in a capability announcement", entry));
+                None
+            }
+        })
+        .collect())
+}
+
+lazy_static! {
+    // Latest announcement by uppercase vehicle id
+    static ref ANNOUNCED: RwLock<HashMap<String, CapabilityAnnouncement>> = RwLock::new(HashMap::new());
+}
+
+// Record a vehicle's announcement; true when it differs from the one it made before
+pub fn announce(vehicle_id: &str, announcement: CapabilityAnnouncement) -> bool {
+    let vehicle_id = vehicle_id.to_uppercase();
+    if announcement.max_zone_vertices.is_some_and(|max| max < 3) {
+        logs::warn(
+            "commands",
+            format!("Ignoring capabilities from {}: a zone needs at least 3 vertices", vehicle_id),
+        );
+        return false;
+    }
+    let mut announced = ANNOUNCED.write().unwrap();
+    if announced.get(&vehicle_id) == Some(&announcement) {
+        return false;
+    }
+    logs::info(
+        "commands",
+        format!("{} announced support for {:?}", vehicle_id, announcement.supported_commands),
+    );
+    announced.insert(vehicle_id, announcement);
+    true
+}
+
+// Drop a vehicle's announcement when it disconnects; it announces again in its first report
+// after reconnecting, possibly on different firmware
+pub fn forget(vehicle_id: &str) {
+    ANNOUNCED.write().unwrap().remove(&vehicle_id.to_uppercase());
+}
+
+pub fn capabilities_for(vehicle_id: &str) -> Option<VehicleCapabilities> {
+    let mut capabilities = protocols::adapter_for(vehicle_id)?.capabilities(vehicle_id)?;
+    if let Some(announcement) = ANNOUNCED.read().unwrap().get(&capabilities.vehicle_id) {
+        capabilities.supported_commands = announcement.supported_commands.clone();
+        // An announcement can narrow what the vehicle accepts, but never takes away the emergency stop
+        if !capabilities.supports(CommandType::EmergencyStop) {
+            capabilities.supported_commands.insert(0, CommandType::EmergencyStop);
+        }
+        if let Some(max_zone_vertices) = announcement.max_zone_vertices {
+            capabilities.max_zone_vertices = max_zone_vertices;
+        }
+        capabilities.announced = true;
+    }
+    Some(capabilities)
+}
+
+// Check a command against the target's capabilities; "ALL" goes to every vehicle that supports it
+pub fn require_supported(vehicle_id: &str, command: CommandType) -> Result<(), String> {
+    if vehicle_id.eq_ignore_ascii_case("ALL") {
+        return Ok(());
+    }
+    capabilities_for(vehicle_id)
+        .ok_or(format!("Unknown vehicle: {}", vehicle_id))?
+        .require(command)
+}
+
+// Capability model of the NGCP vehicles; ERU is a ground vehicle and cannot take off or land
+pub fn ngcp_capabilities(vehicle_id: &str) -> Option<VehicleCapabilities> {
+    let aircraft_commands = vec![
+        CommandType::EmergencyStop,
+        CommandType::MissionUpdate,
+        CommandType::ZoneUpdate,
+        CommandType::Takeoff,
+        CommandType::Land,
+        CommandType::HoldPosition,
+        CommandType::ReturnToHome,
+        CommandType::Waypoints,
+        CommandType::ApproveRequest,
+        CommandType::DenyRequest,
+        CommandType::ManualControl,
+        CommandType::Arm,
+        CommandType::Disarm,
+        CommandType::Ping,
+    ];
+
+    let max_zone_vertices = config::get().max_zone_vertices;
+    match vehicle_id.to_lowercase().as_str() {
+        "mea" | "mra" | "fra" => Some(VehicleCapabilities {
+            vehicle_id: vehicle_id.to_uppercase(),
+            is_aircraft: true,
+            supported_commands: aircraft_commands,
+            max_zone_vertices,
+            announced: false,
+        }),
+        "eru" => Some(VehicleCapabilities {
+            vehicle_id: vehicle_id.to_uppercase(),
+            is_aircraft: false,
+            supported_commands: vec![
+                CommandType::EmergencyStop,
+                CommandType::MissionUpdate,
+                CommandType::ZoneUpdate,
+                CommandType::HoldPosition,
+                CommandType::ReturnToHome,
+                CommandType::Waypoints,
+                CommandType::ApproveRequest,
+                CommandType::DenyRequest,
+                CommandType::ManualControl,
+                CommandType::Arm,
+                CommandType::Disarm,
+                CommandType::Ping,
+            ],
+            max_zone_vertices,
+            announced: false,
+        }),
+        _ => None,
+    }
+}
+
+// Zones are sent to every vehicle, so they must fit the smallest budget of the roster
+pub fn zone_vertex_budget() -> i32 {
+    known_vehicles()
+        .iter()
+        .filter_map(|v| capabilities_for(v))
+        .map(|c| c.max_zone_vertices)
+        .min()
+        .unwrap_or(config::get().max_zone_vertices)
+}
@@ -0,0 +1,126 @@
+/*
+Two-person confirmation for destructive actions (emergency stop, mission abort, keep-out
+deletion during an active mission). When enabled, the backend refuses these actions unless
+they carry either a one-time token issued by a second operator or the typed confirmation phrase.
+*/
+use std::env;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::types::ConfirmationToken;
+
+// Tokens must be used shortly after they are issued
+pub const CONFIRMATION_TOKEN_TTL: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum DestructiveAction {
+    EmergencyStop,
+    MissionAbort,
+    KeepOutDeletion,
+}
+
+impl DestructiveAction {
+    // Phrase an operator can type instead of presenting a token
+    pub fn phrase(&self) -> &'static str {
+        match self {
+            DestructiveAction::EmergencyStop => "CONFIRM EMERGENCY STOP",
+            DestructiveAction::MissionAbort => "CONFIRM MISSION ABORT",
+            DestructiveAction::KeepOutDeletion => "CONFIRM KEEP-OUT DELETION",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IssuedToken {
+    token: String,
+    action: DestructiveAction,
+    issued_by: String,
+    issued_at: Instant,
+}
+
+#[derive(Debug, Default)]
+pub struct ConfirmationGuard {
+    enabled: bool,
+    tokens: Vec<IssuedToken>,
+}
+
+impl ConfirmationGuard {
+    // Enabled with GCS_TWO_PERSON_CONFIRMATION=true
+    pub fn from_env() -> Self {
+        Self {
+            enabled: env::var("GCS_TWO_PERSON_CONFIRMATION")
+                .unwrap_or_default()
+                .to_lowercase()
+                == "true",
+            tokens: Vec::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // Issue a one-time token for `action` on behalf of the confirming (second) operator
+    pub fn issue(&mut self, action: DestructiveAction, operator: &str) -> Result<ConfirmationToken, String> {
+        let operator = operator.trim();
+        if operator.is_empty() {
+            return Err("Confirming operator is required".into());
+        }
+        self.tokens.retain(|t| t.issued_at.elapsed() <= CONFIRMATION_TOKEN_TTL);
+
+        let token = format!("{:06}", rand::random::<u32>() % 1_000_000);
+        self.tokens.push(IssuedToken {
+            token: token.clone(),
+            action,
+            issued_by: operator.to_string(),
+            issued_at: Instant::now(),
+        });
+
+        let expires_at = chrono::Utc::now()
+            + chrono::Duration::from_std(CONFIRMATION_TOKEN_TTL).unwrap_or_default();
+        Ok(ConfirmationToken {
+            token,
+            action,
+            issued_by: operator.to_string(),
+            expires_at: expires_at.to_rfc3339(),
+        })
+    }
+
+    // Check the confirmation for `action` requested by `requester`; a matching token is consumed
+    pub fn verify(
+        &mut self,
+        action: DestructiveAction,
+        confirmation: Option<&str>,
+        requester: &str,
+    ) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let confirmation = confirmation
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .ok_or_else(|| {
+                format!(
+                    "Two-person confirmation required: provide a second operator's token or type \"{}\"",
+                    action.phrase()
+                )
+            })?;
+
+        if confirmation == action.phrase() {
+            return Ok(());
+        }
+
+        self.tokens.retain(|t| t.issued_at.elapsed() <= CONFIRMATION_TOKEN_TTL);
+        let index = self
+            .tokens
+            .iter()
+            .position(|t| t.token == confirmation && t.action == action)
+            .ok_or("Invalid or expired confirmation token")?;
+        if self.tokens[index].issued_by == requester {
+            return Err("Confirmation token must be issued by a different operator".into());
+        }
+        self.tokens.remove(index);
+        Ok(())
+    }
+}
@@ -1,4 +1,13 @@
+pub mod capabilities;
 pub mod commands;
+pub mod confirmation;
+pub mod ping;
+pub mod queue;
+pub mod rate_limit;
+pub mod sequence;
+pub mod serial;
+pub mod sql;
+pub mod types;
 
 pub use commands::{CommandsApi, CommandsApiImpl};
 // pub use telem::TelemApiImpl; 
\ No newline at end of file
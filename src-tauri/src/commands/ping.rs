@@ -0,0 +1,72 @@
+/*
+Command link round-trip measurement. Every PING_INTERVAL each connected vehicle is sent a Ping
+(commandID 15) through the normal command path; the vehicle echoes its sequence number in the
+ping_reply field of its next telemetry. The smoothed round trip is shown as link_latency_ms in
+the vehicle's telemetry, so operators can tell how stale a manual command will be on arrival.
+While a ping is outstanding for longer than the smoothed round trip, its age is shown instead,
+since the link is at least that slow.
+*/
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+
+pub const PING_INTERVAL: Duration = Duration::from_secs(5);
+
+// Unanswered pings kept per vehicle; older ones are treated as lost
+const OUTSTANDING_LIMIT: usize = 8;
+// Weight of a new sample in the smoothed round trip
+const SMOOTHING: f64 = 0.25;
+
+#[derive(Default)]
+struct Link {
+    // (sequence, sent at), oldest first
+    outstanding: VecDeque<(u32, Instant)>,
+    smoothed_ms: Option<f64>,
+}
+
+lazy_static! {
+    // By lowercase vehicle id
+    static ref LINKS: Mutex<HashMap<String, Link>> = Mutex::new(HashMap::new());
+}
+
+pub fn sent(vehicle_id: &str, sequence: u32) {
+    let mut links = LINKS.lock().unwrap();
+    let link = links.entry(vehicle_id.to_lowercase()).or_default();
+    link.outstanding.push_back((sequence, Instant::now()));
+    if link.outstanding.len() > OUTSTANDING_LIMIT {
+        link.outstanding.pop_front();
+    }
+}
+
+// Record a reply; a repeated or unknown sequence is ignored. Pings sent before the answered one
+// went unanswered and are dropped.
+pub fn replied(vehicle_id: &str, sequence: u32) {
+    let mut links = LINKS.lock().unwrap();
+    let Some(link) = links.get_mut(&vehicle_id.to_lowercase()) else { return };
+    let Some(position) = link.outstanding.iter().position(|(s, _)| *s == sequence) else { return };
+    let (_, sent_at) = link.outstanding[position];
+    link.outstanding.drain(..=position);
+    let rtt_ms = sent_at.elapsed().as_secs_f64() * 1000.0;
+    link.smoothed_ms = Some(match link.smoothed_ms {
+        Some(smoothed) => smoothed + SMOOTHING * (rtt_ms - smoothed),
+        None => rtt_ms,
+    });
+}
+
+// None until the vehicle has answered a ping
+pub fn latency_ms(vehicle_id: &str) -> Option<f64> {
+    let links = LINKS.lock().unwrap();
+    let link = links.get(&vehicle_id.to_lowercase())?;
+    let smoothed = link.smoothed_ms?;
+    let waiting = link
+        .outstanding
+        .front()
+        .map_or(0.0, |(_, sent_at)| sent_at.elapsed().as_secs_f64() * 1000.0);
+    Some(smoothed.max(waiting).round())
+}
+
+// Forget a vehicle's pings when it disconnects, so the next link starts from fresh samples
+pub fn reset(vehicle_id: &str) {
+    LINKS.lock().unwrap().remove(&vehicle_id.to_lowercase());
+}
@@ -0,0 +1,325 @@
+/*
+Offline command queue: holds commands for vehicles that are disconnected (per heartbeat)
+or that could not be published, and retries them with exponential backoff until they
+are delivered or expire.
+*/
+
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+
+use crate::auth::current_operator;
+use crate::clock::{self, Instant};
+use super::commands::CommandsStruct;
+
+// Commands older than this are dropped instead of being sent late
+pub const COMMAND_MAX_AGE: Duration = Duration::from_secs(300);
+pub const COMMAND_RETRY_BASE: Duration = Duration::from_millis(500);
+pub const COMMAND_RETRY_MAX: Duration = Duration::from_secs(30);
+pub const QUEUE_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone)]
+pub struct QueuedCommand {
+    pub command: CommandsStruct,
+    // Operator the command is attributed to in the history
+    pub issued_by: String,
+    pub queued_at: Instant,
+    pub attempts: u32,
+    pub next_attempt: Instant,
+}
+
+impl QueuedCommand {
+    pub fn new(command: CommandsStruct) -> Self {
+        let now = clock::now();
+        Self {
+            command,
+            issued_by: current_operator(),
+            queued_at: now,
+            attempts: 0,
+            next_attempt: now,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.queued_at.elapsed() > COMMAND_MAX_AGE
+    }
+
+    pub fn is_due(&self) -> bool {
+        clock::now() >= self.next_attempt
+    }
+
+    // Schedule the next attempt: base * 2^attempts, capped at COMMAND_RETRY_MAX
+    pub fn schedule_retry(&mut self) {
+        self.attempts += 1;
+        let backoff = COMMAND_RETRY_BASE
+            .checked_mul(2u32.saturating_pow(self.attempts.min(16)))
+            .unwrap_or(COMMAND_RETRY_MAX)
+            .min(COMMAND_RETRY_MAX);
+        self.next_attempt = clock::now() + backoff;
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CommandQueue {
+    entries: VecDeque<QueuedCommand>,
+    // Uppercase ids of vehicles with a send under way, whether taken from the queue or sent
+    // directly; their entries are out of `entries` until it ends, but still count as pending
+    in_flight: HashSet<String>,
+    // In-flight vehicles whose commands an emergency stop cancelled; what hasn't gone out yet
+    // must not go out after the stop
+    cancelled: HashSet<String>,
+}
+
+impl CommandQueue {
+    pub fn push(&mut self, command: CommandsStruct) {
+        self.entries.push_back(QueuedCommand::new(command));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Whether a new command for the vehicle has to queue behind others, waiting or being sent
+    pub fn has_pending(&self, vehicle_id: &str) -> bool {
+        self.in_flight.contains(&vehicle_id.to_uppercase())
+            || self.entries.iter().any(|entry| entry.command.vehicle_id.eq_ignore_ascii_case(vehicle_id))
+    }
+
+    // Mark a direct send to the vehicle as under way; end it with finish_sending
+    pub fn start_sending(&mut self, vehicle_id: &str) {
+        self.in_flight.insert(vehicle_id.to_uppercase());
+    }
+
+    pub fn is_cancelled(&self, vehicle_id: &str) -> bool {
+        self.cancelled.contains(&vehicle_id.to_uppercase())
+    }
+
+    // End the sends to these vehicles, putting back the entries that have to be retried.
+    // Retries for a vehicle cancelled meanwhile are returned instead so the caller can report them.
+    pub fn finish_sending<'a>(
+        &mut self,
+        vehicle_ids: impl IntoIterator<Item = &'a str>,
+        retries: Vec<QueuedCommand>,
+    ) -> Vec<QueuedCommand> {
+        let (cancelled, retries): (Vec<_>, Vec<_>) =
+            retries.into_iter().partition(|entry| self.is_cancelled(&entry.command.vehicle_id));
+        self.requeue_front(retries);
+        for vehicle_id in vehicle_ids {
+            let vehicle_id = vehicle_id.to_uppercase();
+            self.in_flight.remove(&vehicle_id);
+            self.cancelled.remove(&vehicle_id);
+        }
+        cancelled
+    }
+
+    // Remove and return expired entries so the caller can report them
+    pub fn drain_expired(&mut self) -> Vec<QueuedCommand> {
+        let (expired, kept): (Vec<_>, Vec<_>) =
+            self.entries.drain(..).partition(|entry| entry.is_expired());
+        self.entries = kept.into();
+        expired
+    }
+
+    // Take every entry due for another attempt that `sendable` lets through, marking their
+    // vehicles as in flight until finish_sending. An entry stays queued while an older entry for
+    // the same vehicle is still backing off, held back or being sent, so per-vehicle order is
+    // preserved.
+    pub fn take_due(&mut self, sendable: impl Fn(&QueuedCommand) -> bool) -> Vec<QueuedCommand> {
+        let mut waiting_vehicles: HashSet<String> = self.in_flight.clone();
+        let mut due = Vec::new();
+        let mut waiting = VecDeque::new();
+        for entry in self.entries.drain(..) {
+            let vehicle_id = entry.command.vehicle_id.to_uppercase();
+            if entry.is_due() && sendable(&entry) && !waiting_vehicles.contains(&vehicle_id) {
+                due.push(entry);
+            } else {
+                waiting_vehicles.insert(vehicle_id);
+                waiting.push_back(entry);
+            }
+        }
+        self.entries = waiting;
+        self.in_flight.extend(due.iter().map(|entry| entry.command.vehicle_id.to_uppercase()));
+        due
+    }
+
+    // Take every entry and cancel the sends under way
+    pub fn take_all(&mut self) -> Vec<QueuedCommand> {
+        self.cancelled.extend(self.in_flight.iter().cloned());
+        self.entries.drain(..).collect()
+    }
+
+    // Take the vehicle's entries and cancel its send under way, if any
+    pub fn take_for(&mut self, vehicle_id: &str) -> Vec<QueuedCommand> {
+        let upper = vehicle_id.to_uppercase();
+        if self.in_flight.contains(&upper) {
+            self.cancelled.insert(upper);
+        }
+        let (taken, kept): (Vec<_>, Vec<_>) = self
+            .entries
+            .drain(..)
+            .partition(|entry| entry.command.vehicle_id.eq_ignore_ascii_case(vehicle_id));
+        self.entries = kept.into();
+        taken
+    }
+
+    // Put entries back at the front so they keep their original ordering
+    pub fn requeue_front(&mut self, entries: Vec<QueuedCommand>) {
+        for entry in entries.into_iter().rev() {
+            self.entries.push_front(entry);
+        }
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &QueuedCommand> {
+        self.entries.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::advance;
+
+    fn command(vehicle_id: &str, command_id: i32) -> CommandsStruct {
+        CommandsStruct { vehicle_id: vehicle_id.to_string(), commandID: command_id, ..Default::default() }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_back_off_exponentially_up_to_the_cap() {
+        let mut entry = QueuedCommand::new(command("MEA", 7));
+        assert!(entry.is_due());
+
+        let mut expected = vec![];
+        let mut actual = vec![];
+        for attempt in 1..=8u32 {
+            expected.push((COMMAND_RETRY_BASE * 2u32.pow(attempt)).min(COMMAND_RETRY_MAX));
+            entry.schedule_retry();
+            actual.push(entry.next_attempt - clock::now());
+        }
+        assert_eq!(actual, expected);
+        assert_eq!(actual.last(), Some(&COMMAND_RETRY_MAX));
+        assert_eq!(entry.attempts, 8);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_stays_capped_after_many_attempts() {
+        let mut entry = QueuedCommand::new(command("MEA", 7));
+        entry.attempts = u32::MAX - 1;
+        entry.schedule_retry();
+        assert_eq!(entry.next_attempt - clock::now(), COMMAND_RETRY_MAX);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_retry_is_due_once_its_backoff_has_passed() {
+        let mut entry = QueuedCommand::new(command("MEA", 7));
+        entry.schedule_retry();
+        assert!(!entry.is_due());
+
+        advance(COMMAND_RETRY_BASE * 2 - Duration::from_millis(1)).await;
+        assert!(!entry.is_due());
+        advance(Duration::from_millis(1)).await;
+        assert!(entry.is_due());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn commands_expire_after_the_max_age() {
+        let mut queue = CommandQueue::default();
+        queue.push(command("MEA", 7));
+        advance(COMMAND_MAX_AGE).await;
+        queue.push(command("ERU", 7));
+        assert!(queue.drain_expired().is_empty());
+
+        advance(Duration::from_millis(1)).await;
+        let expired = queue.drain_expired();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].command.vehicle_id, "MEA");
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn take_due_waits_behind_an_older_entry_backing_off() {
+        let mut queue = CommandQueue::default();
+        queue.push(command("MEA", 7));
+        queue.push(command("MEA", 8));
+        queue.push(command("ERU", 7));
+        let mut first = queue.take_due(|_| true);
+        assert_eq!(first.len(), 3);
+
+        // The first MEA command failed again; the second must not overtake it
+        first[0].schedule_retry();
+        assert!(queue.finish_sending(["MEA", "ERU"], first).is_empty());
+        let due = queue.take_due(|_| true);
+        assert_eq!(due.iter().map(|e| e.command.vehicle_id.as_str()).collect::<Vec<_>>(), vec!["ERU"]);
+        assert_eq!(queue.len(), 2);
+
+        advance(COMMAND_RETRY_BASE * 2).await;
+        let due = queue.take_due(|_| true);
+        assert_eq!(due.iter().map(|e| e.command.commandID).collect::<Vec<_>>(), vec![7, 8]);
+        assert!(queue.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn take_due_keeps_held_back_entries_and_what_follows_them() {
+        let mut queue = CommandQueue::default();
+        queue.push(command("MEA", 9));
+        queue.push(command("MEA", 8));
+        queue.push(command("ERU", 8));
+
+        // Waypoints (9) are held back; MEA's return to home waits behind them, ERU's goes
+        let due = queue.take_due(|entry| entry.command.commandID != 9);
+        assert_eq!(due.iter().map(|e| e.command.vehicle_id.as_str()).collect::<Vec<_>>(), vec!["ERU"]);
+        assert_eq!(queue.len(), 2);
+        queue.finish_sending(["ERU"], vec![]);
+
+        let due = queue.take_due(|_| true);
+        assert_eq!(due.iter().map(|e| e.command.commandID).collect::<Vec<_>>(), vec![9, 8]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_vehicle_with_a_send_in_flight_has_pending_commands() {
+        let mut queue = CommandQueue::default();
+        queue.push(command("MEA", 9));
+        let taken = queue.take_due(|_| true);
+        assert_eq!(taken.len(), 1);
+        // Taken out of the queue, but a newer command must still wait behind it
+        assert!(queue.has_pending("mea"));
+        assert!(!queue.has_pending("ERU"));
+
+        // A command queued meanwhile is not taken while the send is under way
+        queue.push(command("MEA", 8));
+        assert!(queue.take_due(|_| true).is_empty());
+
+        // Failed: the entry goes back in front of the newer one
+        assert!(queue.finish_sending(["MEA"], taken).is_empty());
+        assert_eq!(queue.take_due(|_| true).iter().map(|e| e.command.commandID).collect::<Vec<_>>(), vec![9, 8]);
+
+        queue.finish_sending(["MEA"], vec![]);
+        assert!(!queue.has_pending("MEA"));
+
+        queue.start_sending("ERU");
+        assert!(queue.has_pending("ERU"));
+        queue.finish_sending(["ERU"], vec![]);
+        assert!(!queue.has_pending("ERU"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn an_emergency_stop_cancels_the_sends_under_way() {
+        let mut queue = CommandQueue::default();
+        queue.push(command("MEA", 9));
+        queue.push(command("ERU", 9));
+        let mut taken = queue.take_due(|_| true);
+
+        assert_eq!(queue.take_for("MEA").len(), 0);
+        assert!(queue.is_cancelled("mea"));
+        assert!(!queue.is_cancelled("ERU"));
+
+        // A failed MEA send is not retried after the stop; ERU's is
+        taken.iter_mut().for_each(QueuedCommand::schedule_retry);
+        let cancelled = queue.finish_sending(["MEA", "ERU"], taken);
+        assert_eq!(cancelled.iter().map(|e| e.command.vehicle_id.as_str()).collect::<Vec<_>>(), vec!["MEA"]);
+        assert!(!queue.is_cancelled("MEA"));
+        assert_eq!(queue.entries().map(|e| e.command.vehicle_id.as_str()).collect::<Vec<_>>(), vec!["ERU"]);
+    }
+}
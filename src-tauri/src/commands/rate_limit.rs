@@ -0,0 +1,107 @@
+/*
+Outgoing command throttling: per-vehicle minimum spacing between messages, and coalescing
+of zone geometry so rapid edits while drawing only transmit the latest shape per zone
+at most once per COALESCE_WINDOW.
+*/
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::commands::GeoCoordinate;
+
+pub const COALESCE_WINDOW: Duration = Duration::from_secs(1);
+pub const VEHICLE_MIN_INTERVAL: Duration = Duration::from_millis(50);
+
+// (vehicle_id, zone key)
+type ZoneKey = (String, String);
+
+#[derive(Debug, Clone)]
+pub struct PendingZone {
+    pub vehicle_id: String,
+    pub command_id: i32,
+    pub coordinates: Vec<GeoCoordinate>,
+}
+
+#[derive(Debug, Default)]
+pub struct ZoneCoalescer {
+    last_sent: HashMap<ZoneKey, Instant>,
+    pending: HashMap<ZoneKey, PendingZone>,
+}
+
+impl ZoneCoalescer {
+    // Returns the zone back if it may be sent now; otherwise it replaces whatever was pending
+    // for the same zone and will be released by `take_ready`.
+    pub fn offer(&mut self, zone_key: &str, zone: PendingZone) -> Option<PendingZone> {
+        let key = (zone.vehicle_id.clone(), zone_key.to_string());
+        let recently_sent = self
+            .last_sent
+            .get(&key)
+            .map(|sent| sent.elapsed() < COALESCE_WINDOW)
+            .unwrap_or(false);
+
+        if recently_sent {
+            self.pending.insert(key, zone);
+            None
+        } else {
+            self.last_sent.insert(key, Instant::now());
+            Some(zone)
+        }
+    }
+
+    // Pending zones whose window has elapsed
+    pub fn take_ready(&mut self) -> Vec<PendingZone> {
+        let ready_keys: Vec<ZoneKey> = self
+            .pending
+            .keys()
+            .filter(|key| {
+                self.last_sent
+                    .get(*key)
+                    .map(|sent| sent.elapsed() >= COALESCE_WINDOW)
+                    .unwrap_or(true)
+            })
+            .cloned()
+            .collect();
+
+        let now = Instant::now();
+        ready_keys
+            .into_iter()
+            .filter_map(|key| {
+                self.last_sent.insert(key.clone(), now);
+                self.pending.remove(&key)
+            })
+            .collect()
+    }
+
+    pub fn clear_pending(&mut self) -> usize {
+        let count = self.pending.len();
+        self.pending.clear();
+        count
+    }
+
+    pub fn clear_pending_for(&mut self, vehicle_id: &str) -> usize {
+        let count = self.pending.len();
+        self.pending.retain(|(vehicle, _), _| !vehicle.eq_ignore_ascii_case(vehicle_id));
+        count - self.pending.len()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct VehicleRateLimiter {
+    next_slot: HashMap<String, Instant>,
+}
+
+impl VehicleRateLimiter {
+    // Reserve the next send slot for a vehicle and return how long to wait for it
+    pub fn reserve(&mut self, vehicle_id: &str) -> Duration {
+        let now = Instant::now();
+        let slot = self
+            .next_slot
+            .get(&vehicle_id.to_uppercase())
+            .copied()
+            .filter(|slot| *slot > now)
+            .unwrap_or(now);
+        self.next_slot
+            .insert(vehicle_id.to_uppercase(), slot + VEHICLE_MIN_INTERVAL);
+        slot - now
+    }
+}
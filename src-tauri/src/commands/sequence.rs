@@ -0,0 +1,80 @@
+/*
+Replay protection for outgoing commands. Every command carries the id of this GCS session and a
+sequence number that only increases, per vehicle, within the session. A vehicle should drop a
+command whose sequence is not above the last one it executed from the same session. Numbers are
+given when a command is first published, so they follow the order commands leave the station;
+a queued command keeps its number across retries, so a duplicate delivery shows up as a replay
+rather than a new command. A new session id after a restart tells the vehicle that numbering
+starts over.
+*/
+use std::collections::HashMap;
+use std::sync::Mutex;
+use lazy_static::lazy_static;
+
+use crate::auth::credentials::to_hex;
+use super::capabilities::known_vehicles;
+use super::commands::CommandsStruct;
+
+lazy_static! {
+    static ref SESSION_ID: String = to_hex(&rand::random::<[u8; 8]>());
+    // Last sequence number given for each vehicle, by uppercase id
+    static ref LAST_SEQUENCE: Mutex<HashMap<String, u32>> = Mutex::new(HashMap::new());
+}
+
+pub fn session_id() -> &'static str {
+    &SESSION_ID
+}
+
+// Gives the command a session id and the vehicle's next sequence number, unless it already has
+// them. Every vehicle executes a command for "ALL", so it gets a number above each vehicle's last
+// and what follows it for any vehicle is numbered above it.
+pub fn stamp(command: &mut CommandsStruct) {
+    if command.sequence.is_some() {
+        return;
+    }
+    let mut last = LAST_SEQUENCE.lock().unwrap();
+    let sequence = if command.vehicle_id.eq_ignore_ascii_case("ALL") {
+        for vehicle_id in known_vehicles() {
+            last.entry(vehicle_id).or_insert(0);
+        }
+        let sequence = last.values().copied().max().unwrap_or(0) + 1;
+        last.values_mut().for_each(|s| *s = sequence);
+        sequence
+    } else {
+        let sequence = last.entry(command.vehicle_id.to_uppercase()).or_insert(0);
+        *sequence += 1;
+        *sequence
+    };
+    command.session_id = Some(session_id().to_string());
+    command.sequence = Some(sequence);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamped(vehicle_id: &str) -> u32 {
+        let mut command = CommandsStruct { vehicle_id: vehicle_id.to_string(), ..Default::default() };
+        stamp(&mut command);
+        command.sequence.unwrap()
+    }
+
+    #[test]
+    fn sequences_are_per_vehicle_and_all_is_above_every_vehicle() {
+        let first = stamped("seq-a");
+        assert_eq!(stamped("SEQ-A"), first + 1);
+        // Another vehicle's commands don't use up this one's numbers
+        let other = stamped("seq-b");
+        assert_eq!(stamped("seq-a"), first + 2);
+
+        let all = stamped("ALL");
+        assert!(all > first + 2 && all > other);
+        assert!(stamped("seq-a") > all);
+        assert!(stamped("seq-b") > all);
+
+        // A command that already has a number keeps it
+        let mut retry = CommandsStruct { vehicle_id: "seq-a".to_string(), sequence: Some(first), ..Default::default() };
+        stamp(&mut retry);
+        assert_eq!(retry.sequence, Some(first));
+    }
+}
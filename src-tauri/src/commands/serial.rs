@@ -0,0 +1,148 @@
+/*
+Serial radio fallback for commands, e.g. an XBee in transparent mode. When `serial_port` is set
+and a command can't be published through the broker (AMQP, or MQTT for its vehicles), it is
+written to the radio instead: the vehicle's protocol encoding followed by a newline, one command
+per line. After a broker failure commands go straight to the radio for BROKER_RETRY_INTERVAL
+instead of each waiting out another connection attempt, then the broker is tried again. The
+transport in use is part of the system health.
+*/
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serialport::SerialPort;
+use specta::Type;
+
+use crate::config;
+use crate::logs;
+use crate::protocols;
+use super::commands::CommandsStruct;
+
+pub const BROKER_RETRY_INTERVAL: Duration = Duration::from_secs(15);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum CommandTransport {
+    Broker,
+    Serial,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct CommandTransportHealth {
+    pub active: CommandTransport,
+    // Failure that moved commands to the radio, while they are on it
+    pub broker_error: Option<String>,
+    // None when no serial port is configured
+    pub serial_port: Option<String>,
+    pub serial_error: Option<String>,
+    // Sent over the radio since startup
+    pub serial_commands: u32,
+}
+
+#[derive(Default)]
+struct Transport {
+    broker_failed_at: Option<Instant>,
+    broker_error: Option<String>,
+    serial_error: Option<String>,
+    serial_commands: u32,
+}
+
+lazy_static! {
+    static ref TRANSPORT: Mutex<Transport> = Mutex::new(Transport::default());
+    // Opened on first use and again after a write error
+    static ref PORT: Mutex<Option<Box<dyn SerialPort>>> = Mutex::new(None);
+}
+
+pub fn configured() -> bool {
+    !config::get().serial_port.is_empty()
+}
+
+// Whether commands skip the broker because it failed within the last BROKER_RETRY_INTERVAL
+pub fn broker_suspended() -> bool {
+    configured()
+        && TRANSPORT
+            .lock()
+            .unwrap()
+            .broker_failed_at
+            .is_some_and(|failed| failed.elapsed() < BROKER_RETRY_INTERVAL)
+}
+
+pub fn broker_ok() {
+    let mut transport = TRANSPORT.lock().unwrap();
+    if transport.broker_failed_at.take().is_some() {
+        logs::info("commands", "Broker reachable again; commands back on the broker");
+    }
+    transport.broker_error = None;
+}
+
+pub fn broker_failed(error: &str) {
+    let mut transport = TRANSPORT.lock().unwrap();
+    if transport.broker_failed_at.is_none() {
+        logs::warn(
+            "commands",
+            format!("Broker unreachable ({}); sending commands over {}", error, config::get().serial_port),
+        );
+    }
+    transport.broker_failed_at = Some(Instant::now());
+    transport.broker_error = Some(error.to_string());
+}
+
+fn write_line(path: &str, baud_rate: u32, line: &[u8]) -> Result<(), String> {
+    let mut port = PORT.lock().unwrap();
+    if port.is_none() {
+        *port = Some(
+            serialport::new(path, baud_rate)
+                .timeout(WRITE_TIMEOUT)
+                .open()
+                .map_err(|e| format!("Failed to open serial port {}: {}", path, e))?,
+        );
+    }
+    let written = port
+        .as_mut()
+        .map_or(Ok(()), |p| p.write_all(line).and_then(|_| p.flush()));
+    if let Err(e) = written {
+        // Reopen on the next command, e.g. after the radio was unplugged and plugged back in
+        *port = None;
+        return Err(format!("Failed to write to serial port {}: {}", path, e));
+    }
+    Ok(())
+}
+
+pub async fn send(command: &CommandsStruct) -> Result<(), String> {
+    let config = config::get();
+    if config.serial_port.is_empty() {
+        return Err("No serial port configured".into());
+    }
+    let mut line = protocols::encode_command(command)?;
+    line.push(b'\n');
+    let (path, baud_rate) = (config.serial_port.clone(), config.serial_baud_rate);
+    let result = tokio::task::spawn_blocking(move || write_line(&path, baud_rate, &line))
+        .await
+        .map_err(|e| format!("Serial write task failed: {}", e))
+        .and_then(|written| written);
+
+    let mut transport = TRANSPORT.lock().unwrap();
+    match &result {
+        Ok(()) => {
+            transport.serial_commands += 1;
+            transport.serial_error = None;
+            println!("Sent command {} to {} over {}", command.commandID, command.vehicle_id, config.serial_port);
+        }
+        Err(e) => transport.serial_error = Some(e.clone()),
+    }
+    result
+}
+
+pub fn health() -> CommandTransportHealth {
+    let serial_port = Some(config::get().serial_port).filter(|port| !port.is_empty());
+    let suspended = broker_suspended();
+    let transport = TRANSPORT.lock().unwrap();
+    CommandTransportHealth {
+        active: if suspended { CommandTransport::Serial } else { CommandTransport::Broker },
+        broker_error: transport.broker_error.clone().filter(|_| suspended),
+        serial_port,
+        serial_error: transport.serial_error.clone(),
+        serial_commands: transport.serial_commands,
+    }
+}
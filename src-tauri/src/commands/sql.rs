@@ -0,0 +1,101 @@
+/*
+Define all command-related database functions (command history logging and queries).
+*/
+use sqlx::{query, PgPool, Row};
+
+use super::types::{CommandRecord, TimeRange};
+
+pub async fn insert_command_record(
+    db_conn: PgPool,
+    vehicle_id: &str,
+    command_type: i32,
+    payload: &str,
+    operator: &str,
+    mission_id: Option<i32>,
+    result: &str,
+) -> Result<(), sqlx::Error> {
+    query("
+        INSERT INTO commands(vehicle_id, command_type, payload, operator, mission_id, result)
+        VALUES ($1, $2, $3, $4, $5, $6)
+    ")
+    .bind(vehicle_id)
+    .bind(command_type)
+    .bind(payload)
+    .bind(operator)
+    .bind(mission_id)
+    .bind(result)
+    .execute(&db_conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn select_command_history(
+    db_conn: PgPool,
+    vehicle_id: Option<String>,
+    mission_id: Option<i32>,
+    range: Option<TimeRange>,
+) -> Result<Vec<CommandRecord>, sqlx::Error> {
+    let (from, to) = range.map(|r| (r.from, r.to)).unwrap_or((None, None));
+
+    let rows = query("
+        SELECT
+            command_id,
+            vehicle_id,
+            command_type,
+            payload,
+            operator,
+            mission_id,
+            result,
+            to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS.MS\"Z\"') AS created_at
+        FROM commands
+        WHERE ($1::TEXT IS NULL OR UPPER(vehicle_id) = UPPER($1))
+          AND ($2::INTEGER IS NULL OR mission_id = $2)
+          AND ($3::TEXT IS NULL OR created_at >= $3::TIMESTAMPTZ)
+          AND ($4::TEXT IS NULL OR created_at <= $4::TIMESTAMPTZ)
+        ORDER BY created_at, command_id
+    ")
+    .bind(vehicle_id)
+    .bind(mission_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| CommandRecord {
+            command_id: row.get("command_id"),
+            vehicle_id: row.get("vehicle_id"),
+            command_type: row.get("command_type"),
+            payload: row.get("payload"),
+            operator: row.get("operator"),
+            mission_id: row.get("mission_id"),
+            result: row.get("result"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+// Re-insert a history entry from a mission bundle under the imported mission's id
+pub async fn insert_imported_command(
+    db_conn: PgPool,
+    record: &CommandRecord,
+    mission_id: i32,
+) -> Result<(), sqlx::Error> {
+    query("
+        INSERT INTO commands(vehicle_id, command_type, payload, operator, mission_id, result, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7::TIMESTAMPTZ)
+    ")
+    .bind(&record.vehicle_id)
+    .bind(record.command_type)
+    .bind(&record.payload)
+    .bind(&record.operator)
+    .bind(mission_id)
+    .bind(&record.result)
+    .bind(&record.created_at)
+    .execute(&db_conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+/*
+Define command-related data types shared with the frontend (command history records, query ranges).
+*/
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::confirmation::DestructiveAction;
+
+// Inclusive time window; timestamps are ISO-8601 strings (e.g. "2025-04-12T14:32:00Z")
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct TimeRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct CommandRecord {
+    pub command_id: i32,
+    pub vehicle_id: String,
+    pub command_type: i32,
+    pub payload: Option<String>,
+    pub operator: Option<String>,
+    pub mission_id: Option<i32>,
+    pub result: String,
+    pub created_at: String,
+}
+
+// A command held in the offline queue until its vehicle reconnects
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct PendingCommand {
+    pub command_type: i32,
+    pub queued_secs: f64,
+    pub attempts: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct EmergencyStopReport {
+    pub stopped_vehicles: Vec<String>,
+    pub failed_vehicles: Vec<String>,
+    pub skipped_vehicles: Vec<String>, // disconnected at the time of the stop
+    pub cancelled_commands: i32,
+    pub triggered_at: String,
+}
+
+// One-time token issued by a second operator to confirm a destructive action
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct ConfirmationToken {
+    pub token: String,
+    pub action: DestructiveAction,
+    pub issued_by: String,
+    pub expires_at: String,
+}
+
+// One manual-control frame, each channel normalised to -1..1: positive roll is right, pitch
+// forward, yaw clockwise and throttle up. Ground vehicles use yaw to steer and pitch as throttle.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Type)]
+pub struct ManualControlInput {
+    pub roll: f64,
+    pub pitch: f64,
+    pub yaw: f64,
+    pub throttle: f64,
+}
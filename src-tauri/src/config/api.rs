@@ -0,0 +1,50 @@
+/*
+Define the config API: read the effective configuration and tune runtime values.
+*/
+use tauri::{Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, OperatorRole};
+use super::{ConfigUpdate, GcsConfig};
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "config")]
+pub trait ConfigApi {
+    async fn get_config() -> GcsConfig;
+    // Only geofence, zone, coverage, routing, terrain clearance, weather limit, ADS-B alert, lost-link and telemetry trace settings can change at runtime; the rest needs a restart
+    async fn set_config(window: Window<impl Runtime>, update: ConfigUpdate) -> Result<GcsConfig, String>;
+}
+
+#[derive(Clone, Default)]
+pub struct ConfigApiImpl;
+
+#[resolvers]
+impl ConfigApi for ConfigApiImpl {
+    async fn get_config(self) -> GcsConfig {
+        super::get()
+    }
+
+    async fn set_config(
+        self,
+        window: Window<impl Runtime>,
+        update: ConfigUpdate,
+    ) -> Result<GcsConfig, String> {
+        authorized(&window, OperatorRole::MissionCommander, async move {
+            let config = super::update(update)?;
+            println!(
+                "Configuration updated: geofence warning distance {} m, max zone vertices {}, sensor footprint {} m, route clearance {} m, terrain clearance {} m, wind {} m/s, gusts {} m/s, precipitation {} mm, ADS-B alert {} m / {} m",
+                config.geofence_warning_distance_m,
+                config.max_zone_vertices,
+                config.sensor_footprint_width_m,
+                config.route_clearance_m,
+                config.min_terrain_clearance_m,
+                config.max_wind_speed_ms,
+                config.max_wind_gust_ms,
+                config.max_precipitation_mm,
+                config.adsb_alert_distance_m,
+                config.adsb_alert_altitude_m
+            );
+            Ok(config)
+        })
+        .await
+    }
+}
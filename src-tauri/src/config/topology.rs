@@ -0,0 +1,150 @@
+/*
+AMQP topology for vehicle telemetry and commands, built from the amqp_* settings. With no
+exchange configured every vehicle gets its own telemetry_<vehicle> queue on the default
+exchange and commands go to the vehicle_commands queue, as before. With an exchange set, the
+GCS declares it, declares its telemetry queues from amqp_telemetry_queue and binds them with
+amqp_telemetry_routing_key, and publishes commands with amqp_command_routing_key, so it can
+join a broker laid out by another team. "{vehicle}" in a template is replaced by the lowercase
+vehicle id; vehicles whose templates expand to the same queue share one consumer.
+*/
+use lapin::{options::*, types::FieldTable, Channel, ExchangeKind, Result as LapinResult};
+
+use super::GcsConfig;
+
+// A telemetry queue and the routing keys of the vehicles that report through it
+#[derive(Debug, Clone)]
+pub struct TelemetryQueue {
+    pub queue: String,
+    pub routing_keys: Vec<String>,
+}
+
+pub fn expand(template: &str, vehicle_id: &str) -> String {
+    template.replace("{vehicle}", &vehicle_id.to_lowercase())
+}
+
+pub fn exchange_kind(name: &str) -> Option<ExchangeKind> {
+    match name.to_lowercase().as_str() {
+        "direct" => Some(ExchangeKind::Direct),
+        "fanout" => Some(ExchangeKind::Fanout),
+        "topic" => Some(ExchangeKind::Topic),
+        "headers" => Some(ExchangeKind::Headers),
+        _ => None,
+    }
+}
+
+// Problems with the amqp_* settings, for the startup configuration check
+pub fn validate(config: &GcsConfig) -> Vec<String> {
+    let mut problems = vec![];
+    if config.amqp_telemetry_queue.is_empty() {
+        problems.push("amqp_telemetry_queue must not be empty".to_string());
+    }
+    if config.amqp_command_routing_key.is_empty() {
+        problems.push("amqp_command_routing_key must not be empty".to_string());
+    }
+    for (exchange, kind, setting) in [
+        (&config.amqp_telemetry_exchange, &config.amqp_telemetry_exchange_type, "amqp_telemetry_exchange_type"),
+        (&config.amqp_command_exchange, &config.amqp_command_exchange_type, "amqp_command_exchange_type"),
+    ] {
+        if !exchange.is_empty() && exchange_kind(kind).is_none() {
+            problems.push(format!("{} must be direct, fanout, topic or headers", setting));
+        }
+    }
+    // On the default exchange a queue only receives messages routed by its own name
+    if config.amqp_telemetry_exchange.is_empty() && !config.amqp_telemetry_queue.contains("{vehicle}") {
+        problems.push("amqp_telemetry_queue must contain {vehicle} without a telemetry exchange".to_string());
+    }
+    problems
+}
+
+// Telemetry queues for the given vehicles, deduplicated by name
+pub fn telemetry_queues<'a>(
+    config: &GcsConfig,
+    vehicles: impl IntoIterator<Item = &'a String>,
+) -> Vec<TelemetryQueue> {
+    let mut queues: Vec<TelemetryQueue> = vec![];
+    for vehicle_id in vehicles {
+        let queue = expand(&config.amqp_telemetry_queue, vehicle_id);
+        let routing_key = expand(&config.amqp_telemetry_routing_key, vehicle_id);
+        match queues.iter_mut().find(|q| q.queue == queue) {
+            Some(existing) if !existing.routing_keys.contains(&routing_key) => {
+                existing.routing_keys.push(routing_key)
+            }
+            Some(_) => {}
+            None => queues.push(TelemetryQueue { queue, routing_keys: vec![routing_key] }),
+        }
+    }
+    queues
+}
+
+async fn declare_exchange(channel: &Channel, exchange: &str, kind: &str) -> LapinResult<()> {
+    channel
+        .exchange_declare(
+            exchange,
+            exchange_kind(kind).unwrap_or(ExchangeKind::Topic),
+            ExchangeDeclareOptions { durable: true, ..Default::default() },
+            FieldTable::default(),
+        )
+        .await
+}
+
+// Declare a telemetry queue and, with a telemetry exchange, the exchange and its bindings
+pub async fn declare_telemetry_queue(
+    channel: &Channel,
+    config: &GcsConfig,
+    queue: &TelemetryQueue,
+) -> LapinResult<()> {
+    channel
+        .queue_declare(
+            &queue.queue,
+            QueueDeclareOptions {
+                durable: true,
+                auto_delete: false,
+                exclusive: false,
+                ..Default::default()
+            },
+            FieldTable::default(),
+        )
+        .await?;
+    if config.amqp_telemetry_exchange.is_empty() {
+        return Ok(());
+    }
+
+    declare_exchange(channel, &config.amqp_telemetry_exchange, &config.amqp_telemetry_exchange_type).await?;
+    for routing_key in &queue.routing_keys {
+        channel
+            .queue_bind(
+                &queue.queue,
+                &config.amqp_telemetry_exchange,
+                routing_key,
+                QueueBindOptions::default(),
+                FieldTable::default(),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+// Exchange and routing key a command for the vehicle is published with
+pub fn command_route(config: &GcsConfig, vehicle_id: &str) -> (String, String) {
+    (
+        config.amqp_command_exchange.clone(),
+        expand(&config.amqp_command_routing_key, vehicle_id),
+    )
+}
+
+// Declare what a command for the vehicle is published to: the command exchange, or on the
+// default exchange the queue named by the routing key
+pub async fn declare_command_route(channel: &Channel, config: &GcsConfig, vehicle_id: &str) -> LapinResult<()> {
+    let (exchange, routing_key) = command_route(config, vehicle_id);
+    if !exchange.is_empty() {
+        return declare_exchange(channel, &exchange, &config.amqp_command_exchange_type).await;
+    }
+    channel
+        .queue_declare(
+            &routing_key,
+            QueueDeclareOptions { durable: true, ..Default::default() },
+            FieldTable::default(),
+        )
+        .await
+        .map(|_| ())
+}
@@ -0,0 +1,60 @@
+/*
+Define the coordinates API: conversions between latitude/longitude, UTM, MGRS and DMS strings,
+and parsing of pasted coordinate lists into zone geometry.
+*/
+use taurpc::{procedures, resolvers};
+
+use crate::telemetry::types::Coordinate;
+use super::{bulk, dms, mgrs, utm, validate_lat_lon, CoordinateList, UtmCoordinate};
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "coordinates")]
+pub trait CoordinatesApi {
+    async fn to_utm(coordinate: Coordinate) -> Result<UtmCoordinate, String>;
+    async fn from_utm(utm: UtmCoordinate) -> Result<Coordinate, String>;
+    // `precision` is digits per axis, 1 (10 km) to 5 (1 m)
+    async fn to_mgrs(coordinate: Coordinate, precision: u8) -> Result<String, String>;
+    async fn from_mgrs(reference: String) -> Result<Coordinate, String>;
+    async fn format_dms(coordinate: Coordinate) -> Result<String, String>;
+    async fn parse_dms(text: String) -> Result<Coordinate, String>;
+    // One vertex per line; see coordinates::bulk for the accepted forms
+    async fn parse_coordinate_list(text: String) -> CoordinateList;
+}
+
+#[derive(Clone, Default)]
+pub struct CoordinatesApiImpl;
+
+fn coordinate((latitude, longitude): (f64, f64)) -> Coordinate {
+    Coordinate { latitude, longitude }
+}
+
+#[resolvers]
+impl CoordinatesApi for CoordinatesApiImpl {
+    async fn to_utm(self, coordinate: Coordinate) -> Result<UtmCoordinate, String> {
+        utm::to_utm(coordinate.latitude, coordinate.longitude)
+    }
+
+    async fn from_utm(self, utm: UtmCoordinate) -> Result<Coordinate, String> {
+        utm::from_utm(&utm).map(coordinate)
+    }
+
+    async fn to_mgrs(self, coordinate: Coordinate, precision: u8) -> Result<String, String> {
+        mgrs::to_mgrs(coordinate.latitude, coordinate.longitude, precision)
+    }
+
+    async fn from_mgrs(self, reference: String) -> Result<Coordinate, String> {
+        mgrs::parse_mgrs(&reference).map(coordinate)
+    }
+
+    async fn format_dms(self, coordinate: Coordinate) -> Result<String, String> {
+        validate_lat_lon(coordinate.latitude, coordinate.longitude)?;
+        Ok(dms::format_dms(coordinate.latitude, coordinate.longitude))
+    }
+
+    async fn parse_dms(self, text: String) -> Result<Coordinate, String> {
+        dms::parse_dms(&text).map(coordinate)
+    }
+
+    async fn parse_coordinate_list(self, text: String) -> CoordinateList {
+        bulk::parse_coordinate_list(&text)
+    }
+}
@@ -0,0 +1,69 @@
+/*
+Coordinate lists pasted from briefs, one vertex per line, in any of the forms below (formats
+may be mixed within a list). Blank lines and lines starting with '#' are skipped.
+  33.93258, -117.63059           (decimal degrees, comma, tab or space separated)
+  33°55'57.3"N 117°37'50.1"W     (any form parse_dms accepts)
+  11S MT 12345 67890             (MGRS; the south-west corner of the square)
+*/
+use crate::geometry::{open_ring, validate_polygon};
+use crate::missions::types::GeoCoordinateStruct;
+use super::{dms, mgrs, CoordinateLineError, CoordinateList};
+
+// More than any zone drawn by hand; longer pastes are almost certainly the wrong text
+const MAX_LINES: usize = 1000;
+
+// Zone digits followed by the band and the two square letters, e.g. "11SMT"
+fn is_mgrs(line: &str) -> bool {
+    let compact: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    let zone_len = compact.iter().take_while(|c| c.is_ascii_digit()).count();
+    (1..=2).contains(&zone_len)
+        && compact.len() >= zone_len + 3
+        && compact[zone_len..zone_len + 3].iter().all(|c| c.is_ascii_alphabetic())
+}
+
+fn parse_line(line: &str) -> Result<GeoCoordinateStruct, String> {
+    let (lat, long) = if is_mgrs(line) {
+        mgrs::parse_mgrs(line)?
+    } else {
+        dms::parse_dms(line)?
+    };
+    Ok(GeoCoordinateStruct { lat, long })
+}
+
+// A line that can't be read is reported and left out of the polygon, so the rest can still be
+// reviewed on the map and the bad lines fixed in place
+pub fn parse_coordinate_list(text: &str) -> CoordinateList {
+    let mut list = CoordinateList::default();
+    let lines: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    if lines.len() > MAX_LINES {
+        list.polygon_error =
+            Some(format!("At most {} coordinates can be pasted at once, got {}", MAX_LINES, lines.len()));
+        return list;
+    }
+
+    for (number, line) in lines {
+        match parse_line(line) {
+            Ok(coordinate) => {
+                list.polygon.push(coordinate);
+                list.source_lines.push(number as u32);
+            }
+            Err(error) => list.errors.push(CoordinateLineError {
+                line: number as u32,
+                text: line.to_string(),
+                error,
+            }),
+        }
+    }
+    let vertices = open_ring(&list.polygon).len();
+    list.polygon_error = if vertices < 3 {
+        Some(format!("A zone needs at least 3 vertices, got {}", vertices))
+    } else {
+        validate_polygon(&list.polygon).err()
+    };
+    list
+}
@@ -0,0 +1,186 @@
+/*
+Degrees-minutes-seconds parsing and formatting. Accepts the forms that show up in briefs:
+  33°55'57.3"N 117°37'50.1"W
+  N33 55 57.3, W117 37 50.1
+  33 55.955 N 117 37.835 W   (degrees and decimal minutes)
+  33.93258, -117.63059       (signed decimal degrees)
+*/
+
+struct Component {
+    value: f64,
+    hemisphere: Option<char>,
+}
+
+// Format as 33°55'57.3"N 117°37'50.1"W, seconds to one decimal place
+pub fn format_dms(latitude: f64, longitude: f64) -> String {
+    format!(
+        "{} {}",
+        format_component(latitude, if latitude < 0.0 { 'S' } else { 'N' }),
+        format_component(longitude, if longitude < 0.0 { 'W' } else { 'E' })
+    )
+}
+
+fn format_component(value: f64, hemisphere: char) -> String {
+    // Round once in tenths of a second so 59.96" carries into the minutes
+    let tenths = (value.abs() * 36000.0).round() as u64;
+    let degrees = tenths / 36000;
+    let minutes = (tenths % 36000) / 600;
+    let seconds = (tenths % 600) as f64 / 10.0;
+    format!("{}°{:02}'{:04.1}\"{}", degrees, minutes, seconds, hemisphere)
+}
+
+// Parse a latitude/longitude pair; with hemisphere letters the two may come in either order
+pub fn parse_dms(text: &str) -> Result<(f64, f64), String> {
+    let invalid = |reason: &str| format!("Invalid coordinate '{}': {}", text.trim(), reason);
+    let components = split_components(text).map_err(|reason| invalid(&reason))?;
+    if components.len() != 2 {
+        return Err(invalid("expected a latitude and a longitude"));
+    }
+
+    let (first, second) = (&components[0], &components[1]);
+    let is_longitude = |c: &Component| matches!(c.hemisphere, Some('E') | Some('W'));
+    let (lat, lon) = if is_longitude(first) && !is_longitude(second) {
+        (second, first)
+    } else {
+        (first, second)
+    };
+    if matches!(lat.hemisphere, Some('E') | Some('W')) || matches!(lon.hemisphere, Some('N') | Some('S')) {
+        return Err(invalid("hemisphere letters do not match"));
+    }
+
+    let latitude = signed(lat);
+    let longitude = signed(lon);
+    if !(-90.0..=90.0).contains(&latitude) {
+        return Err(invalid("latitude out of range"));
+    }
+    if !(-180.0..=180.0).contains(&longitude) {
+        return Err(invalid("longitude out of range"));
+    }
+    Ok((latitude, longitude))
+}
+
+fn signed(component: &Component) -> f64 {
+    match component.hemisphere {
+        Some('S') | Some('W') => -component.value.abs(),
+        _ => component.value,
+    }
+}
+
+enum Token {
+    Number(String),
+    Hemisphere(char),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut number = String::new();
+    for ch in text.to_uppercase().chars() {
+        match ch {
+            '0'..='9' | '.' => number.push(ch),
+            '-' | '+' if number.is_empty() => number.push(ch),
+            'N' | 'S' | 'E' | 'W' => {
+                if !number.is_empty() {
+                    tokens.push(Token::Number(std::mem::take(&mut number)));
+                }
+                tokens.push(Token::Hemisphere(ch));
+            }
+            // Degree, minute and second marks (ASCII and typographic) and list separators
+            '°' | '\'' | '"' | '′' | '″' | '’' | '”' | 'º' | ',' | ';' | ':' => {
+                if !number.is_empty() {
+                    tokens.push(Token::Number(std::mem::take(&mut number)));
+                }
+            }
+            c if c.is_whitespace() => {
+                if !number.is_empty() {
+                    tokens.push(Token::Number(std::mem::take(&mut number)));
+                }
+            }
+            c => return Err(format!("unexpected character '{}'", c)),
+        }
+    }
+    if !number.is_empty() {
+        tokens.push(Token::Number(number));
+    }
+    Ok(tokens)
+}
+
+fn split_components(text: &str) -> Result<Vec<Component>, String> {
+    let tokens = tokenize(text)?;
+    let has_hemispheres = tokens.iter().any(|t| matches!(t, Token::Hemisphere(_)));
+
+    let mut groups: Vec<(Vec<String>, Option<char>)> = Vec::new();
+    if has_hemispheres {
+        // A letter either closes the numbers before it (33 55 N) or opens the next group (N33 55)
+        let mut numbers: Vec<String> = Vec::new();
+        let mut prefix: Option<char> = None;
+        for token in tokens {
+            match token {
+                Token::Number(n) => numbers.push(n),
+                Token::Hemisphere(h) => {
+                    if numbers.is_empty() {
+                        if prefix.is_some() {
+                            return Err("two hemisphere letters in a row".into());
+                        }
+                        prefix = Some(h);
+                    } else if let Some(p) = prefix.take() {
+                        groups.push((std::mem::take(&mut numbers), Some(p)));
+                        prefix = Some(h);
+                    } else {
+                        groups.push((std::mem::take(&mut numbers), Some(h)));
+                    }
+                }
+            }
+        }
+        if !numbers.is_empty() {
+            groups.push((numbers, prefix));
+        } else if prefix.is_some() {
+            return Err("hemisphere letter without a value".into());
+        }
+    } else {
+        // Without letters the numbers must split evenly into latitude and longitude
+        let numbers: Vec<String> = tokens
+            .into_iter()
+            .filter_map(|t| match t {
+                Token::Number(n) => Some(n),
+                Token::Hemisphere(_) => None,
+            })
+            .collect();
+        if numbers.is_empty() || numbers.len() % 2 != 0 {
+            return Err("expected the same number of fields for latitude and longitude".into());
+        }
+        let half = numbers.len() / 2;
+        groups.push((numbers[..half].to_vec(), None));
+        groups.push((numbers[half..].to_vec(), None));
+    }
+
+    groups
+        .into_iter()
+        .map(|(numbers, hemisphere)| {
+            Ok(Component { value: combine(&numbers)?, hemisphere })
+        })
+        .collect()
+}
+
+// Degrees, optional minutes and optional seconds; only the last field may be fractional
+fn combine(fields: &[String]) -> Result<f64, String> {
+    if fields.is_empty() || fields.len() > 3 {
+        return Err("expected degrees, minutes and seconds".into());
+    }
+    let values = fields
+        .iter()
+        .map(|f| f.parse::<f64>().map_err(|_| format!("'{}' is not a number", f)))
+        .collect::<Result<Vec<f64>, String>>()?;
+
+    let negative = values[0].is_sign_negative();
+    let mut total = values[0].abs();
+    for (i, value) in values.iter().enumerate().skip(1) {
+        if *value < 0.0 || *value >= 60.0 {
+            return Err("minutes and seconds must be between 0 and 60".into());
+        }
+        if values[i - 1].fract() != 0.0 {
+            return Err("only the last field may have a fractional part".into());
+        }
+        total += value / 60f64.powi(i as i32);
+    }
+    Ok(if negative { -total } else { total })
+}
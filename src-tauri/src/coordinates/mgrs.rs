@@ -0,0 +1,143 @@
+/*
+MGRS grid references, built on top of UTM: zone, latitude band, 100 km square letters and
+an even number of easting/northing digits, e.g. "11S MT 12345 67890". Uses the standard
+(AA) lettering scheme of WGS84.
+*/
+use super::utm::{self, MAX_LATITUDE, MIN_LATITUDE};
+use super::{Hemisphere, UtmCoordinate};
+
+const LATITUDE_BANDS: &[u8] = b"CDEFGHJKLMNPQRSTUVWX";
+// 100 km column letters repeat every three zones
+const COLUMN_LETTERS: [&[u8]; 3] = [b"ABCDEFGH", b"JKLMNPQR", b"STUVWXYZ"];
+const ROW_LETTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUV";
+// Row letters of even zones are shifted by five
+const EVEN_ZONE_ROW_OFFSET: usize = 5;
+const SQUARE_SIZE: f64 = 100000.0;
+const ROW_CYCLE: f64 = 2000000.0;
+pub const MAX_PRECISION: u8 = 5;
+const BAND_TOLERANCE_DEG: f64 = 0.5;
+
+fn band_letter(latitude: f64) -> u8 {
+    // Band X is stretched to 12° to cover up to 84°N
+    let index = (((latitude - MIN_LATITUDE) / 8.0).floor() as usize).min(LATITUDE_BANDS.len() - 1);
+    LATITUDE_BANDS[index]
+}
+
+fn row_offset(zone: u8) -> usize {
+    if zone % 2 == 0 {
+        EVEN_ZONE_ROW_OFFSET
+    } else {
+        0
+    }
+}
+
+// `precision` is the number of digits per axis: 5 = 1 m, 4 = 10 m ... 1 = 10 km
+pub fn to_mgrs(latitude: f64, longitude: f64, precision: u8) -> Result<String, String> {
+    if !(1..=MAX_PRECISION).contains(&precision) {
+        return Err(format!("MGRS precision must be between 1 and {} digits", MAX_PRECISION));
+    }
+    let utm = utm::to_utm(latitude, longitude)?;
+
+    let column = (utm.easting / SQUARE_SIZE).floor() as usize;
+    let columns = COLUMN_LETTERS[(utm.zone as usize - 1) % 3];
+    if !(1..=columns.len()).contains(&column) {
+        return Err(format!("Easting {} is outside the MGRS grid", utm.easting));
+    }
+    let row = (utm.northing / SQUARE_SIZE).floor() as usize % ROW_LETTERS.len();
+    let row_letter = ROW_LETTERS[(row + row_offset(utm.zone)) % ROW_LETTERS.len()];
+
+    // Truncate rather than round, as grid references name the square the point lies in
+    let divisor = 10f64.powi((MAX_PRECISION - precision) as i32);
+    let easting = ((utm.easting % SQUARE_SIZE) / divisor).floor() as u32;
+    let northing = ((utm.northing % SQUARE_SIZE) / divisor).floor() as u32;
+    let width = precision as usize;
+
+    Ok(format!(
+        "{}{} {}{} {:0width$} {:0width$}",
+        utm.zone,
+        band_letter(latitude) as char,
+        columns[column - 1] as char,
+        row_letter as char,
+        easting,
+        northing,
+        width = width
+    ))
+}
+
+// Parse a grid reference, with or without spaces; returns the south-west corner of the square
+pub fn parse_mgrs(text: &str) -> Result<(f64, f64), String> {
+    let cleaned: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    let invalid = || format!("Invalid MGRS reference: {}", text.trim());
+    let bytes = cleaned.as_bytes();
+
+    let zone_len = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    if !(1..=2).contains(&zone_len) || bytes.len() < zone_len + 3 {
+        return Err(invalid());
+    }
+    let zone: u8 = cleaned[..zone_len].parse().map_err(|_| invalid())?;
+    if !(1..=60).contains(&zone) {
+        return Err(format!("UTM zone {} is out of range", zone));
+    }
+
+    let band = bytes[zone_len];
+    let band_index = LATITUDE_BANDS
+        .iter()
+        .position(|&b| b == band)
+        .ok_or_else(|| format!("Unknown latitude band '{}'", band as char))?;
+    let column_letter = bytes[zone_len + 1];
+    let row_letter = bytes[zone_len + 2];
+
+    let digits = &cleaned[zone_len + 3..];
+    if digits.len() % 2 != 0
+        || digits.len() > 2 * MAX_PRECISION as usize
+        || !digits.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let precision = digits.len() / 2;
+    let scale = 10f64.powi((MAX_PRECISION as usize - precision) as i32);
+    let parse_axis = |part: &str| -> Result<f64, String> {
+        if part.is_empty() {
+            Ok(0.0)
+        } else {
+            part.parse::<f64>().map(|v| v * scale).map_err(|_| invalid())
+        }
+    };
+    let easting_in_square = parse_axis(&digits[..precision])?;
+    let northing_in_square = parse_axis(&digits[precision..])?;
+
+    let column = COLUMN_LETTERS[(zone as usize - 1) % 3]
+        .iter()
+        .position(|&b| b == column_letter)
+        .ok_or_else(|| format!("Column letter '{}' is not used in zone {}", column_letter as char, zone))?;
+    let row = ROW_LETTERS
+        .iter()
+        .position(|&b| b == row_letter)
+        .ok_or_else(|| format!("Unknown row letter '{}'", row_letter as char))?;
+    let row = (row + ROW_LETTERS.len() - row_offset(zone)) % ROW_LETTERS.len();
+
+    let hemisphere = if band >= b'N' { Hemisphere::North } else { Hemisphere::South };
+    let easting = (column as f64 + 1.0) * SQUARE_SIZE + easting_in_square;
+    let mut northing = row as f64 * SQUARE_SIZE + northing_in_square;
+
+    // Row letters repeat every 2000 km; pick the cycle that falls inside the latitude band.
+    // The band's southern edge on the central meridian is close to its lowest northing.
+    let band_south = MIN_LATITUDE + band_index as f64 * 8.0;
+    let band_min_northing = utm::project(band_south, utm::central_meridian(zone), zone)?.northing
+        - SQUARE_SIZE;
+    while northing < band_min_northing {
+        northing += ROW_CYCLE;
+    }
+
+    let (latitude, longitude) = utm::from_utm(&UtmCoordinate { zone, hemisphere, easting, northing })?;
+    // Coarse squares on a band edge can have their corner just across it
+    let band_north = if band == b'X' { MAX_LATITUDE } else { band_south + 8.0 };
+    if latitude < band_south - BAND_TOLERANCE_DEG || latitude > band_north + BAND_TOLERANCE_DEG {
+        return Err(format!("MGRS reference {} does not lie in band {}", text.trim(), band as char));
+    }
+    Ok((latitude, longitude))
+}
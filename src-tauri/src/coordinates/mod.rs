@@ -0,0 +1,62 @@
+/*
+Coordinate conversion utilities: latitude/longitude to and from UTM and MGRS on the WGS84
+ellipsoid, plus parsing and formatting of degrees-minutes-seconds strings. Range safety briefs
+give grid references in MGRS; these let operators enter them directly instead of converting
+by hand, and whole coordinate lists can be pasted as zone geometry. Polar regions (UPS) are
+not supported.
+*/
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::missions::types::GeofenceType;
+
+pub mod api;
+pub mod bulk;
+pub mod dms;
+pub mod mgrs;
+pub mod utm;
+
+pub use api::{CoordinatesApi, CoordinatesApiImpl};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum Hemisphere {
+    North,
+    South,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct UtmCoordinate {
+    pub zone: u8,
+    pub hemisphere: Hemisphere,
+    pub easting: f64,
+    pub northing: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct CoordinateLineError {
+    // 1-based, counting blank and comment lines
+    pub line: u32,
+    pub text: String,
+    pub error: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct CoordinateList {
+    // Vertices of the lines that parsed, in order
+    pub polygon: GeofenceType,
+    // Line each vertex came from
+    pub source_lines: Vec<u32>,
+    pub errors: Vec<CoordinateLineError>,
+    // Why the vertices don't make a valid zone yet (too few, crossing edges...)
+    pub polygon_error: Option<String>,
+}
+
+pub fn validate_lat_lon(latitude: f64, longitude: f64) -> Result<(), String> {
+    if !latitude.is_finite() || !(-90.0..=90.0).contains(&latitude) {
+        return Err(format!("Latitude {} is out of range", latitude));
+    }
+    if !longitude.is_finite() || !(-180.0..=180.0).contains(&longitude) {
+        return Err(format!("Longitude {} is out of range", longitude));
+    }
+    Ok(())
+}
@@ -0,0 +1,163 @@
+/*
+Latitude/longitude <-> UTM on WGS84 using the transverse Mercator series from Snyder,
+"Map Projections: A Working Manual" (USGS 1395). Accurate to well under a metre inside a zone.
+*/
+use super::{validate_lat_lon, Hemisphere, UtmCoordinate};
+
+const WGS84_A: f64 = 6378137.0;
+const WGS84_F: f64 = 1.0 / 298.257223563;
+const SCALE_FACTOR: f64 = 0.9996;
+const FALSE_EASTING: f64 = 500000.0;
+const FALSE_NORTHING_SOUTH: f64 = 10000000.0;
+// UTM covers 80°S to 84°N; beyond that MGRS switches to UPS
+pub const MIN_LATITUDE: f64 = -80.0;
+pub const MAX_LATITUDE: f64 = 84.0;
+
+fn eccentricity_squared() -> f64 {
+    WGS84_F * (2.0 - WGS84_F)
+}
+
+// Standard zone, including the Norway and Svalbard exceptions
+pub fn zone_for(latitude: f64, longitude: f64) -> u8 {
+    if (56.0..64.0).contains(&latitude) && (3.0..12.0).contains(&longitude) {
+        return 32;
+    }
+    if (72.0..=MAX_LATITUDE).contains(&latitude) && (0.0..42.0).contains(&longitude) {
+        return match longitude {
+            lon if lon < 9.0 => 31,
+            lon if lon < 21.0 => 33,
+            lon if lon < 33.0 => 35,
+            _ => 37,
+        };
+    }
+    (((longitude + 180.0) / 6.0).floor() as i32 + 1).clamp(1, 60) as u8
+}
+
+pub fn central_meridian(zone: u8) -> f64 {
+    (zone as f64 - 1.0) * 6.0 - 180.0 + 3.0
+}
+
+// Project into the zone the point falls in
+pub fn to_utm(latitude: f64, longitude: f64) -> Result<UtmCoordinate, String> {
+    validate_lat_lon(latitude, longitude)?;
+    project(latitude, longitude, zone_for(latitude, longitude))
+}
+
+// Project into a specific zone, e.g. to keep a whole search area on one grid
+pub fn project(latitude: f64, longitude: f64, zone: u8) -> Result<UtmCoordinate, String> {
+    validate_lat_lon(latitude, longitude)?;
+    if !(MIN_LATITUDE..=MAX_LATITUDE).contains(&latitude) {
+        return Err(format!(
+            "Latitude {} is outside UTM coverage ({}° to {}°)",
+            latitude, MIN_LATITUDE, MAX_LATITUDE
+        ));
+    }
+    if !(1..=60).contains(&zone) {
+        return Err(format!("UTM zone {} is out of range", zone));
+    }
+
+    let e2 = eccentricity_squared();
+    let ep2 = e2 / (1.0 - e2);
+    let phi = latitude.to_radians();
+    let mut delta_lon = longitude - central_meridian(zone);
+    if delta_lon > 180.0 {
+        delta_lon -= 360.0;
+    } else if delta_lon < -180.0 {
+        delta_lon += 360.0;
+    }
+
+    let (sin_phi, cos_phi) = phi.sin_cos();
+    let n = WGS84_A / (1.0 - e2 * sin_phi * sin_phi).sqrt();
+    let t = phi.tan().powi(2);
+    let c = ep2 * cos_phi * cos_phi;
+    let a = cos_phi * delta_lon.to_radians();
+    let m = meridian_arc(phi, e2);
+
+    let easting = SCALE_FACTOR
+        * n
+        * (a + (1.0 - t + c) * a.powi(3) / 6.0
+            + (5.0 - 18.0 * t + t * t + 72.0 * c - 58.0 * ep2) * a.powi(5) / 120.0)
+        + FALSE_EASTING;
+    let mut northing = SCALE_FACTOR
+        * (m + n
+            * phi.tan()
+            * (a * a / 2.0
+                + (5.0 - t + 9.0 * c + 4.0 * c * c) * a.powi(4) / 24.0
+                + (61.0 - 58.0 * t + t * t + 600.0 * c - 330.0 * ep2) * a.powi(6) / 720.0));
+
+    let hemisphere = if latitude < 0.0 {
+        northing += FALSE_NORTHING_SOUTH;
+        Hemisphere::South
+    } else {
+        Hemisphere::North
+    };
+
+    Ok(UtmCoordinate { zone, hemisphere, easting, northing })
+}
+
+pub fn from_utm(utm: &UtmCoordinate) -> Result<(f64, f64), String> {
+    if !(1..=60).contains(&utm.zone) {
+        return Err(format!("UTM zone {} is out of range", utm.zone));
+    }
+    if !utm.easting.is_finite() || !(0.0..=1000000.0).contains(&utm.easting) {
+        return Err(format!("Easting {} is out of range", utm.easting));
+    }
+    if !utm.northing.is_finite() || !(0.0..=FALSE_NORTHING_SOUTH).contains(&utm.northing) {
+        return Err(format!("Northing {} is out of range", utm.northing));
+    }
+
+    let e2 = eccentricity_squared();
+    let ep2 = e2 / (1.0 - e2);
+    let northing = match utm.hemisphere {
+        Hemisphere::North => utm.northing,
+        Hemisphere::South => utm.northing - FALSE_NORTHING_SOUTH,
+    };
+
+    let m = northing / SCALE_FACTOR;
+    let mu = m / (WGS84_A * (1.0 - e2 / 4.0 - 3.0 * e2 * e2 / 64.0 - 5.0 * e2.powi(3) / 256.0));
+    let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
+    let phi1 = mu
+        + (3.0 * e1 / 2.0 - 27.0 * e1.powi(3) / 32.0) * (2.0 * mu).sin()
+        + (21.0 * e1 * e1 / 16.0 - 55.0 * e1.powi(4) / 32.0) * (4.0 * mu).sin()
+        + (151.0 * e1.powi(3) / 96.0) * (6.0 * mu).sin()
+        + (1097.0 * e1.powi(4) / 512.0) * (8.0 * mu).sin();
+
+    let (sin_phi1, cos_phi1) = phi1.sin_cos();
+    let n1 = WGS84_A / (1.0 - e2 * sin_phi1 * sin_phi1).sqrt();
+    let t1 = phi1.tan().powi(2);
+    let c1 = ep2 * cos_phi1 * cos_phi1;
+    let r1 = WGS84_A * (1.0 - e2) / (1.0 - e2 * sin_phi1 * sin_phi1).powf(1.5);
+    let d = (utm.easting - FALSE_EASTING) / (n1 * SCALE_FACTOR);
+
+    let phi = phi1
+        - (n1 * phi1.tan() / r1)
+            * (d * d / 2.0
+                - (5.0 + 3.0 * t1 + 10.0 * c1 - 4.0 * c1 * c1 - 9.0 * ep2) * d.powi(4) / 24.0
+                + (61.0 + 90.0 * t1 + 298.0 * c1 + 45.0 * t1 * t1 - 252.0 * ep2 - 3.0 * c1 * c1)
+                    * d.powi(6)
+                    / 720.0);
+    let lambda = (d - (1.0 + 2.0 * t1 + c1) * d.powi(3) / 6.0
+        + (5.0 - 2.0 * c1 + 28.0 * t1 - 3.0 * c1 * c1 + 8.0 * ep2 + 24.0 * t1 * t1) * d.powi(5)
+            / 120.0)
+        / cos_phi1;
+
+    let latitude = phi.to_degrees();
+    let mut longitude = central_meridian(utm.zone) + lambda.to_degrees();
+    if longitude > 180.0 {
+        longitude -= 360.0;
+    } else if longitude < -180.0 {
+        longitude += 360.0;
+    }
+    Ok((latitude, longitude))
+}
+
+// Distance along the meridian from the equator to latitude `phi` (radians)
+fn meridian_arc(phi: f64, e2: f64) -> f64 {
+    let e4 = e2 * e2;
+    let e6 = e4 * e2;
+    WGS84_A
+        * ((1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0) * phi
+            - (3.0 * e2 / 8.0 + 3.0 * e4 / 32.0 + 45.0 * e6 / 1024.0) * (2.0 * phi).sin()
+            + (15.0 * e4 / 256.0 + 45.0 * e6 / 1024.0) * (4.0 * phi).sin()
+            - (35.0 * e6 / 3072.0) * (6.0 * phi).sin())
+}
@@ -0,0 +1,34 @@
+/*
+Define the database maintenance API: run the scheduled maintenance on demand.
+*/
+use sqlx::PgPool;
+use tauri::{Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, current_operator, OperatorRole};
+use crate::init_db::lazy_pool;
+use super::MaintenanceRun;
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "db_maintenance")]
+pub trait DbMaintenanceApi {
+    // Refused while a mission is active or paused, or while a run is already going
+    async fn run_database_maintenance(window: Window<impl Runtime>) -> Result<MaintenanceRun, String>;
+}
+
+#[derive(Clone)]
+pub struct DbMaintenanceApiImpl {
+    db: PgPool,
+}
+
+impl DbMaintenanceApiImpl {
+    pub async fn new() -> Self {
+        Self { db: lazy_pool(1) }
+    }
+}
+
+#[resolvers]
+impl DbMaintenanceApi for DbMaintenanceApiImpl {
+    async fn run_database_maintenance(self, window: Window<impl Runtime>) -> Result<MaintenanceRun, String> {
+        authorized(&window, OperatorRole::Operator, super::run(self.db.clone(), &current_operator())).await
+    }
+}
@@ -0,0 +1,214 @@
+/*
+Scheduled database maintenance, since the field laptops' databases are never maintained by
+hand: the high-write tables are vacuumed and analyzed and, optionally, their indexes rebuilt,
+and audit log entries past the retention period are deleted. The schedule is part of the
+operator settings (database_maintenance); runs wait while a mission is active or paused, so
+they never compete with live telemetry writes. The last run is kept in the settings table, so
+the interval carries over restarts, and is reported in the system health.
+*/
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::PgPool;
+
+use crate::audit::AUTOMATIC_OPERATOR;
+use crate::init_db::lazy_pool;
+use crate::logs;
+use crate::settings::{self, DatabaseMaintenanceSchedule};
+use crate::settings::sql::{select_setting, upsert_setting};
+use crate::supervisor::{self, RestartPolicy};
+
+pub mod api;
+pub mod sql;
+
+pub use api::{DbMaintenanceApi, DbMaintenanceApiImpl};
+
+// Tables written on every telemetry report or command
+const HIGH_WRITE_TABLES: [&str; 4] = ["telemetry", "commands", "notifications", "weather_readings"];
+const LAST_RUN_KEY: &str = "db_maintenance_last_run";
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct MaintenanceStep {
+    // e.g. "vacuum telemetry"
+    pub step: String,
+    pub duration_ms: f64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct MaintenanceRun {
+    pub started_at: String,
+    pub finished_at: String,
+    pub steps: Vec<MaintenanceStep>,
+    pub audit_entries_pruned: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct DatabaseMaintenanceHealth {
+    pub enabled: bool,
+    pub running: bool,
+    // A run is due but waits for the active or paused mission to end
+    pub deferred: bool,
+    pub last_run: Option<MaintenanceRun>,
+    // None while maintenance is off
+    pub next_run_at: Option<String>,
+}
+
+lazy_static! {
+    static ref LAST_RUN: RwLock<Option<MaintenanceRun>> = RwLock::new(None);
+    // Held for the length of a run, so a manual run doesn't overlap a scheduled one
+    static ref RUNNING: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+static DEFERRED: AtomicBool = AtomicBool::new(false);
+
+// Now when maintenance has never run
+fn next_run(schedule: &DatabaseMaintenanceSchedule) -> Option<DateTime<Utc>> {
+    if !schedule.enabled {
+        return None;
+    }
+    let last_finished = LAST_RUN
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|run| DateTime::parse_from_rfc3339(&run.finished_at).ok())
+        .map(|finished| finished.with_timezone(&Utc));
+    Some(match last_finished {
+        Some(finished) => finished + chrono::Duration::hours(schedule.interval_hours as i64),
+        None => Utc::now(),
+    })
+}
+
+async fn timed<T>(
+    steps: &mut Vec<MaintenanceStep>,
+    step: String,
+    work: impl std::future::Future<Output = Result<T, sqlx::Error>>,
+) -> Option<T> {
+    let started = Instant::now();
+    let result = work.await;
+    let error = result.as_ref().err().map(|e| e.to_string());
+    if let Some(e) = &error {
+        logs::warn("db_maintenance", format!("Failed to {}: {}", step, e));
+    }
+    steps.push(MaintenanceStep {
+        step,
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        error,
+    });
+    result.ok()
+}
+
+// Run every maintenance step now; a failed step is recorded and the rest still run
+pub async fn run(db: PgPool, operator: &str) -> Result<MaintenanceRun, String> {
+    let _running = RUNNING.try_lock().map_err(|_| "Database maintenance is already running")?;
+    if sql::mission_running(db.clone())
+        .await
+        .map_err(|e| format!("Failed to check for a running mission: {}", e))?
+    {
+        return Err("Database maintenance waits until no mission is active or paused".into());
+    }
+    let schedule = settings::current().database_maintenance;
+    let started_at = Utc::now().to_rfc3339();
+    let mut steps = vec![];
+
+    // Pruned first, so the vacuum reclaims the space
+    let mut audit_entries_pruned = 0;
+    if schedule.audit_retention_days > 0 {
+        let step = format!("prune audit log entries older than {} days", schedule.audit_retention_days);
+        let pruned = timed(&mut steps, step, sql::prune_audit_log(db.clone(), schedule.audit_retention_days)).await;
+        audit_entries_pruned = pruned.unwrap_or(0) as u32;
+    }
+    for table in HIGH_WRITE_TABLES {
+        timed(&mut steps, format!("vacuum {}", table), sql::vacuum_analyze(db.clone(), table)).await;
+    }
+    if schedule.reindex {
+        for table in HIGH_WRITE_TABLES {
+            timed(&mut steps, format!("reindex {}", table), sql::reindex(db.clone(), table)).await;
+        }
+    }
+
+    let run = MaintenanceRun {
+        started_at,
+        finished_at: Utc::now().to_rfc3339(),
+        steps,
+        audit_entries_pruned,
+    };
+    *LAST_RUN.write().unwrap() = Some(run.clone());
+    DEFERRED.store(false, Ordering::SeqCst);
+    let value = serde_json::to_string(&run).map_err(|e| e.to_string())?;
+    if let Err(e) = upsert_setting(db, LAST_RUN_KEY, &value, operator).await {
+        logs::warn("db_maintenance", format!("Failed to save the maintenance run: {}", e));
+    }
+
+    let failed = run.steps.iter().filter(|s| s.error.is_some()).count();
+    logs::info(
+        "db_maintenance",
+        format!(
+            "Database maintenance by {} finished: {} steps, {} failed, {} audit entries pruned",
+            operator,
+            run.steps.len(),
+            failed,
+            audit_entries_pruned
+        ),
+    );
+    Ok(run)
+}
+
+async fn load_last_run(db: &PgPool) {
+    let stored = match select_setting(db.clone(), LAST_RUN_KEY).await {
+        Ok(stored) => stored,
+        Err(e) => {
+            logs::warn("db_maintenance", format!("Failed to load the last maintenance run: {}", e));
+            return;
+        }
+    };
+    if let Some(value) = stored {
+        match serde_json::from_str(&value) {
+            Ok(run) => *LAST_RUN.write().unwrap() = Some(run),
+            Err(e) => logs::warn("db_maintenance", format!("Stored maintenance run is invalid: {}", e)),
+        }
+    }
+}
+
+async fn run_scheduler(db: PgPool) -> Result<(), String> {
+    load_last_run(&db).await;
+    let mut ticker = tokio::time::interval(SCHEDULE_CHECK_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let due = next_run(&settings::current().database_maintenance).is_some_and(|next| next <= Utc::now());
+        if !due {
+            DEFERRED.store(false, Ordering::SeqCst);
+            continue;
+        }
+        // run refuses this too; checked here so a run put off for a mission isn't logged every minute
+        if sql::mission_running(db.clone()).await.unwrap_or(true) {
+            DEFERRED.store(true, Ordering::SeqCst);
+            continue;
+        }
+        if let Err(e) = run(db.clone(), AUTOMATIC_OPERATOR).await {
+            logs::warn("db_maintenance", format!("Scheduled database maintenance did not run: {}", e));
+        }
+    }
+}
+
+pub fn health() -> DatabaseMaintenanceHealth {
+    let schedule = settings::current().database_maintenance;
+    DatabaseMaintenanceHealth {
+        enabled: schedule.enabled,
+        running: RUNNING.try_lock().is_err(),
+        deferred: DEFERRED.load(Ordering::SeqCst),
+        last_run: LAST_RUN.read().unwrap().clone(),
+        next_run_at: next_run(&schedule).map(|next| next.to_rfc3339()),
+    }
+}
+
+// Check the schedule until the app exits
+pub fn start() {
+    let db = lazy_pool(1);
+    supervisor::spawn("db_maintenance", RestartPolicy::OnFailure, move || run_scheduler(db.clone()));
+}
@@ -0,0 +1,31 @@
+/*
+Define the database maintenance statements. VACUUM and REINDEX CONCURRENTLY can't run inside a
+transaction, so they are sent as plain statements on the pool.
+*/
+use sqlx::{query, query_scalar, raw_sql, PgPool};
+
+pub async fn vacuum_analyze(db_conn: PgPool, table: &str) -> Result<(), sqlx::Error> {
+    raw_sql(&format!("VACUUM (ANALYZE) {}", table)).execute(&db_conn).await?;
+    Ok(())
+}
+
+// Rebuilt alongside the live indexes, so telemetry writes aren't blocked while it runs
+pub async fn reindex(db_conn: PgPool, table: &str) -> Result<(), sqlx::Error> {
+    raw_sql(&format!("REINDEX TABLE CONCURRENTLY {}", table)).execute(&db_conn).await?;
+    Ok(())
+}
+
+// Number of entries deleted
+pub async fn prune_audit_log(db_conn: PgPool, retention_days: u32) -> Result<u64, sqlx::Error> {
+    let result = query("DELETE FROM audit_log WHERE recorded_at < NOW() - make_interval(days => $1)")
+        .bind(retention_days as i32)
+        .execute(&db_conn)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn mission_running(db_conn: PgPool) -> Result<bool, sqlx::Error> {
+    query_scalar("SELECT EXISTS(SELECT 1 FROM missions WHERE status IN ('Active', 'Paused'))")
+        .fetch_one(&db_conn)
+        .await
+}
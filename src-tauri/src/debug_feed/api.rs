@@ -0,0 +1,42 @@
+/*
+Define the debug feed API: turn the telemetry debug feed on or off, set its rate cap and
+subscribe to its lines.
+*/
+use taurpc::{procedures, resolvers};
+
+use super::{DebugFeedLine, DebugFeedSettings};
+
+#[procedures(
+    event_trigger = DebugFeedEventTrigger,
+    export_to = "../src/lib/bindings.ts",
+    path = "debug_feed"
+)]
+pub trait DebugFeedApi {
+    #[taurpc(event)]
+    async fn on_debug_line(line: DebugFeedLine);
+
+    async fn get_debug_feed() -> DebugFeedSettings;
+    // None keeps the current rate cap
+    async fn set_debug_feed(
+        enabled: bool,
+        max_lines_per_sec: Option<u32>,
+    ) -> Result<DebugFeedSettings, String>;
+}
+
+#[derive(Clone, Default)]
+pub struct DebugFeedApiImpl;
+
+#[resolvers]
+impl DebugFeedApi for DebugFeedApiImpl {
+    async fn get_debug_feed(self) -> DebugFeedSettings {
+        super::settings()
+    }
+
+    async fn set_debug_feed(
+        self,
+        enabled: bool,
+        max_lines_per_sec: Option<u32>,
+    ) -> Result<DebugFeedSettings, String> {
+        super::configure(enabled, max_lines_per_sec)
+    }
+}
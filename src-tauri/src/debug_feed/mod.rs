@@ -0,0 +1,130 @@
+/*
+Debug feed for the frontend dev console: a one-line summary of every telemetry message and
+every payload that failed to parse, streamed through the on_debug_line event. It is off by
+default and capped at a number of lines per second while on, so a busy broker can't flood the
+console; lines over the cap are dropped and counted on the next line that goes out. Summaries
+are only formatted while the feed is on, so it costs the consumer nothing otherwise.
+*/
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+
+pub mod api;
+
+pub use api::{DebugFeedApi, DebugFeedApiImpl, DebugFeedEventTrigger};
+
+const DEFAULT_MAX_LINES_PER_SEC: u32 = 20;
+const MAX_LINES_PER_SEC: u32 = 500;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct DebugFeedSettings {
+    pub enabled: bool,
+    pub max_lines_per_sec: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct DebugFeedLine {
+    pub timestamp: String,
+    // Vehicle the message came from, or "telemetry" for payloads that didn't parse
+    pub source: String,
+    pub summary: String,
+    // Lines dropped by the rate cap since the previous line
+    pub dropped: u32,
+}
+
+#[derive(Debug)]
+struct RateWindow {
+    max_lines_per_sec: u32,
+    started: Instant,
+    sent: u32,
+    dropped: u32,
+}
+
+impl RateWindow {
+    // True when a line may go out now
+    fn admit(&mut self) -> bool {
+        if self.started.elapsed() >= Duration::from_secs(1) {
+            self.started = Instant::now();
+            self.sent = 0;
+        }
+        if self.sent >= self.max_lines_per_sec {
+            self.dropped = self.dropped.saturating_add(1);
+            return false;
+        }
+        self.sent += 1;
+        true
+    }
+}
+
+// Checked before anything else, so a disabled feed takes no lock
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+lazy_static! {
+    static ref WINDOW: Mutex<RateWindow> = Mutex::new(RateWindow {
+        max_lines_per_sec: DEFAULT_MAX_LINES_PER_SEC,
+        started: Instant::now(),
+        sent: 0,
+        dropped: 0,
+    });
+}
+
+pub fn set_app_handle(app_handle: AppHandle) {
+    let _ = APP_HANDLE.set(app_handle);
+}
+
+pub fn settings() -> DebugFeedSettings {
+    DebugFeedSettings {
+        enabled: ENABLED.load(Ordering::Relaxed),
+        max_lines_per_sec: WINDOW.lock().unwrap().max_lines_per_sec,
+    }
+}
+
+pub fn configure(enabled: bool, max_lines_per_sec: Option<u32>) -> Result<DebugFeedSettings, String> {
+    if let Some(max) = max_lines_per_sec {
+        if max == 0 || max > MAX_LINES_PER_SEC {
+            return Err(format!("The debug feed rate must be 1 to {} lines per second", MAX_LINES_PER_SEC));
+        }
+    }
+    {
+        let mut window = WINDOW.lock().unwrap();
+        if let Some(max) = max_lines_per_sec {
+            window.max_lines_per_sec = max;
+        }
+        // A re-enabled feed doesn't report what was dropped before it was turned off
+        window.dropped = 0;
+    }
+    ENABLED.store(enabled, Ordering::Relaxed);
+    Ok(settings())
+}
+
+// `summary` is only called when the line goes out
+pub fn record(source: &str, summary: impl FnOnce() -> String) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let Some(app_handle) = APP_HANDLE.get() else {
+        return;
+    };
+    let dropped = {
+        let mut window = WINDOW.lock().unwrap();
+        if !window.admit() {
+            return;
+        }
+        std::mem::take(&mut window.dropped)
+    };
+    let line = DebugFeedLine {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        source: source.to_string(),
+        summary: summary(),
+        dropped,
+    };
+    // Not logged through logs::record() so a failing feed doesn't fill the log buffer
+    if let Err(e) = DebugFeedEventTrigger::new(app_handle.clone()).on_debug_line(line) {
+        eprintln!("Failed to emit debug feed line: {}", e);
+    }
+}
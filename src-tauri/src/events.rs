@@ -0,0 +1,239 @@
+/*
+Event emission to the frontend behind the EventSink trait, so telemetry processing and mission
+helpers run without an AppHandle (headless startup, unit tests). AppHandle forwards to the
+TauRPC event triggers, scoped to the windows subscribed to the event's channel (see
+window_routing); NullEventSink drops everything and stands in until the app is up.
+*/
+use std::sync::Arc;
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+
+use crate::missions::api::MissionEventTrigger;
+use crate::missions::types::{
+    EmergencyStopEvent, MissionHold, MissionNote, MissionStageStatusEnum, MissionStruct,
+    MissionsStruct, PatientStatusChange, StageStruct, VehicleEnum,
+};
+use crate::telemetry::rabbitmq::TelemetryEventTrigger;
+use crate::telemetry::signal::SignalTrendChange;
+use crate::telemetry::types::{CoordinateRequest, VehicleTelemetryData};
+use crate::vehicles::VehicleAlias;
+use crate::window_routing::{self, EventChannel};
+
+pub trait EventSink: Send + Sync {
+    fn telemetry_updated(&self, data: Arc<VehicleTelemetryData>) -> Result<(), String>;
+    fn coordinate_request(&self, request: CoordinateRequest) -> Result<(), String>;
+    fn signal_trend(&self, change: SignalTrendChange) -> Result<(), String>;
+    fn missions_updated(&self, state: Arc<MissionsStruct>) -> Result<(), String>;
+    fn mission_updated(&self, mission_id: i32, mission: MissionStruct) -> Result<(), String>;
+    fn mission_deleted(&self, mission_id: i32) -> Result<(), String>;
+    fn stage_changed(
+        &self,
+        mission_id: i32,
+        vehicle_name: VehicleEnum,
+        alias: VehicleAlias,
+        stage: StageStruct,
+    ) -> Result<(), String>;
+    fn mission_started(&self, mission_id: i32) -> Result<(), String>;
+    fn mission_completed(&self, mission_id: i32, status: MissionStageStatusEnum) -> Result<(), String>;
+    fn stage_transitioned(
+        &self,
+        mission_id: i32,
+        vehicle_name: VehicleEnum,
+        stage_id: i32,
+        previous_status: MissionStageStatusEnum,
+        status: MissionStageStatusEnum,
+    ) -> Result<(), String>;
+    fn emergency_stop(&self, event: EmergencyStopEvent) -> Result<(), String>;
+    fn note_added(&self, note: MissionNote) -> Result<(), String>;
+    fn hold_changed(&self, mission_id: i32, hold: Option<MissionHold>) -> Result<(), String>;
+    fn patient_status_changed(&self, change: PatientStatusChange) -> Result<(), String>;
+    // Plain Tauri event outside the TauRPC bindings (e.g. telemetry_error), routed as telemetry
+    fn emit_json(&self, event: &str, payload: Value) -> Result<(), String>;
+}
+
+impl<R: Runtime> EventSink for AppHandle<R> {
+    fn telemetry_updated(&self, data: Arc<VehicleTelemetryData>) -> Result<(), String> {
+        telemetry_trigger(self).on_updated(data).map_err(|e| e.to_string())
+    }
+
+    fn coordinate_request(&self, request: CoordinateRequest) -> Result<(), String> {
+        telemetry_trigger(self)
+            .on_coordinate_request(request)
+            .map_err(|e| e.to_string())
+    }
+
+    fn signal_trend(&self, change: SignalTrendChange) -> Result<(), String> {
+        telemetry_trigger(self)
+            .on_signal_trend(change)
+            .map_err(|e| e.to_string())
+    }
+
+    fn missions_updated(&self, state: Arc<MissionsStruct>) -> Result<(), String> {
+        mission_trigger(self).on_updated(state).map_err(|e| e.to_string())
+    }
+
+    fn mission_updated(&self, mission_id: i32, mission: MissionStruct) -> Result<(), String> {
+        mission_trigger(self)
+            .on_mission_updated(mission_id, mission)
+            .map_err(|e| e.to_string())
+    }
+
+    fn mission_deleted(&self, mission_id: i32) -> Result<(), String> {
+        mission_trigger(self)
+            .on_mission_deleted(mission_id)
+            .map_err(|e| e.to_string())
+    }
+
+    fn stage_changed(
+        &self,
+        mission_id: i32,
+        vehicle_name: VehicleEnum,
+        alias: VehicleAlias,
+        stage: StageStruct,
+    ) -> Result<(), String> {
+        mission_trigger(self)
+            .on_stage_changed(mission_id, vehicle_name, alias, stage)
+            .map_err(|e| e.to_string())
+    }
+
+    fn mission_started(&self, mission_id: i32) -> Result<(), String> {
+        mission_trigger(self)
+            .on_mission_started(mission_id)
+            .map_err(|e| e.to_string())
+    }
+
+    fn mission_completed(&self, mission_id: i32, status: MissionStageStatusEnum) -> Result<(), String> {
+        mission_trigger(self)
+            .on_mission_completed(mission_id, status)
+            .map_err(|e| e.to_string())
+    }
+
+    fn stage_transitioned(
+        &self,
+        mission_id: i32,
+        vehicle_name: VehicleEnum,
+        stage_id: i32,
+        previous_status: MissionStageStatusEnum,
+        status: MissionStageStatusEnum,
+    ) -> Result<(), String> {
+        mission_trigger(self)
+            .on_stage_transitioned(mission_id, vehicle_name, stage_id, previous_status, status)
+            .map_err(|e| e.to_string())
+    }
+
+    fn emergency_stop(&self, event: EmergencyStopEvent) -> Result<(), String> {
+        // Every window, whatever it subscribed to
+        MissionEventTrigger::new(self.clone())
+            .on_emergency_stop(event)
+            .map_err(|e| e.to_string())
+    }
+
+    fn note_added(&self, note: MissionNote) -> Result<(), String> {
+        mission_trigger(self).on_note_added(note).map_err(|e| e.to_string())
+    }
+
+    fn hold_changed(&self, mission_id: i32, hold: Option<MissionHold>) -> Result<(), String> {
+        mission_trigger(self)
+            .on_hold_changed(mission_id, hold)
+            .map_err(|e| e.to_string())
+    }
+
+    fn patient_status_changed(&self, change: PatientStatusChange) -> Result<(), String> {
+        mission_trigger(self)
+            .on_patient_status_changed(change)
+            .map_err(|e| e.to_string())
+    }
+
+    fn emit_json(&self, event: &str, payload: Value) -> Result<(), String> {
+        window_routing::emit(self, EventChannel::Telemetry, event, payload).map_err(|e| e.to_string())
+    }
+}
+
+fn telemetry_trigger<R: Runtime>(app_handle: &AppHandle<R>) -> TelemetryEventTrigger<R> {
+    TelemetryEventTrigger::new(app_handle.clone())
+        .send_to(window_routing::scope(app_handle, EventChannel::Telemetry))
+}
+
+fn mission_trigger<R: Runtime>(app_handle: &AppHandle<R>) -> MissionEventTrigger<R> {
+    MissionEventTrigger::new(app_handle.clone())
+        .send_to(window_routing::scope(app_handle, EventChannel::Missions))
+}
+
+// Discards every event
+#[derive(Clone, Copy, Default)]
+pub struct NullEventSink;
+
+impl EventSink for NullEventSink {
+    fn telemetry_updated(&self, _data: Arc<VehicleTelemetryData>) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn coordinate_request(&self, _request: CoordinateRequest) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn signal_trend(&self, _change: SignalTrendChange) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn missions_updated(&self, _state: Arc<MissionsStruct>) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn mission_updated(&self, _mission_id: i32, _mission: MissionStruct) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn mission_deleted(&self, _mission_id: i32) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn stage_changed(
+        &self,
+        _mission_id: i32,
+        _vehicle_name: VehicleEnum,
+        _alias: VehicleAlias,
+        _stage: StageStruct,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn mission_started(&self, _mission_id: i32) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn mission_completed(&self, _mission_id: i32, _status: MissionStageStatusEnum) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn stage_transitioned(
+        &self,
+        _mission_id: i32,
+        _vehicle_name: VehicleEnum,
+        _stage_id: i32,
+        _previous_status: MissionStageStatusEnum,
+        _status: MissionStageStatusEnum,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn emergency_stop(&self, _event: EmergencyStopEvent) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn note_added(&self, _note: MissionNote) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn hold_changed(&self, _mission_id: i32, _hold: Option<MissionHold>) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn patient_status_changed(&self, _change: PatientStatusChange) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn emit_json(&self, _event: &str, _payload: Value) -> Result<(), String> {
+        Ok(())
+    }
+}
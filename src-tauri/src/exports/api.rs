@@ -0,0 +1,110 @@
+/*
+Define the exports API: write a mission's flight logs to disk and choose where the live event
+log is written. The event log directory is kept in the settings table.
+*/
+use std::path::PathBuf;
+use sqlx::PgPool;
+use tauri::{Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, current_operator, OperatorRole};
+use crate::init_db::lazy_pool;
+use crate::logs;
+use crate::settings::sql::{select_setting, upsert_setting};
+use crate::telemetry::sql::select_mission_telemetry;
+use super::event_log;
+use super::flight_logs::write_flight_logs;
+use super::FlightLogFormat;
+
+const EVENT_LOG_DIR_KEY: &str = "event_log_dir";
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "exports")]
+pub trait ExportsApi {
+    // `path` is a directory; one file per vehicle is written into it
+    async fn export_flight_logs(
+        mission_id: i32,
+        format: FlightLogFormat,
+        path: String,
+    ) -> Result<Vec<String>, String>;
+    // Directory the live event log is appended to; None while the log is off
+    async fn get_event_log_dir() -> Option<String>;
+    async fn set_event_log_dir(
+        window: Window<impl Runtime>,
+        path: Option<String>,
+    ) -> Result<Option<String>, String>;
+}
+
+#[derive(Clone)]
+pub struct ExportsApiImpl {
+    db: PgPool,
+}
+
+impl ExportsApiImpl {
+    pub async fn new() -> Self {
+        let api = Self { db: lazy_pool(2) };
+        if let Err(e) = api.load_event_log_dir().await {
+            logs::warn("exports", format!("Live event log is off: {}", e));
+        }
+        api
+    }
+
+    async fn load_event_log_dir(&self) -> Result<(), String> {
+        let stored = select_setting(self.db.clone(), EVENT_LOG_DIR_KEY)
+            .await
+            .map_err(|e| format!("Failed to load event log directory: {}", e))?;
+        let directory: Option<String> = match stored {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Stored event log directory is invalid: {}", e))?,
+            None => None,
+        };
+        event_log::set_directory(directory.map(PathBuf::from))
+    }
+}
+
+#[resolvers]
+impl ExportsApi for ExportsApiImpl {
+    async fn export_flight_logs(
+        self,
+        mission_id: i32,
+        format: FlightLogFormat,
+        path: String,
+    ) -> Result<Vec<String>, String> {
+        if path.trim().is_empty() {
+            return Err("An export directory is required".into());
+        }
+        let records = select_mission_telemetry(self.db.clone(), mission_id)
+            .await
+            .map_err(|e| format!("Failed to load telemetry: {}", e))?;
+        let written = write_flight_logs(mission_id, records, format, &PathBuf::from(path))?;
+        logs::info(
+            "exports",
+            format!("Exported {} {:?} flight logs for mission {}", written.len(), format, mission_id),
+        );
+        Ok(written)
+    }
+    async fn get_event_log_dir(self) -> Option<String> {
+        event_log::directory().map(|directory| directory.display().to_string())
+    }
+
+    async fn set_event_log_dir(
+        self,
+        window: Window<impl Runtime>,
+        path: Option<String>,
+    ) -> Result<Option<String>, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+            event_log::set_directory(path.clone().map(PathBuf::from))?;
+
+            let value = serde_json::to_string(&path).map_err(|e| e.to_string())?;
+            upsert_setting(self.db.clone(), EVENT_LOG_DIR_KEY, &value, &current_operator())
+                .await
+                .map_err(|e| format!("Failed to save event log directory: {}", e))?;
+            match &path {
+                Some(path) => logs::info("exports", format!("Live event log written to {} by {}", path, current_operator())),
+                None => logs::info("exports", format!("Live event log turned off by {}", current_operator())),
+            }
+            Ok(path)
+        })
+        .await
+    }
+}
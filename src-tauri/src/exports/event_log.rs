@@ -0,0 +1,96 @@
+/*
+Live event log: every alert, stage transition and command of the active mission is appended to
+mission_{id}_events.csv in the chosen directory as it happens, so the mission's timeline stays
+readable even if the GCS crashes later. Each line is written and closed on its own; nothing is
+buffered in memory. Without a directory the log is off.
+*/
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use chrono::SecondsFormat;
+use lazy_static::lazy_static;
+
+use crate::logs;
+use super::csv_field;
+
+const HEADER: &str = "timestamp,event,vehicle_id,detail,message\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Alert,
+    StageTransition,
+    Command,
+}
+
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Alert => "Alert",
+            EventKind::StageTransition => "StageTransition",
+            EventKind::Command => "Command",
+        }
+    }
+}
+
+#[derive(Default)]
+struct EventLog {
+    directory: Option<PathBuf>,
+    active_mission: Option<i32>,
+}
+
+lazy_static! {
+    // Also serializes writers, so lines of concurrent events never interleave
+    static ref EVENT_LOG: Mutex<EventLog> = Mutex::new(EventLog::default());
+}
+
+pub fn directory() -> Option<PathBuf> {
+    EVENT_LOG.lock().unwrap().directory.clone()
+}
+
+// None turns the log off; the directory is created when missing
+pub fn set_directory(directory: Option<PathBuf>) -> Result<(), String> {
+    if let Some(directory) = &directory {
+        fs::create_dir_all(directory)
+            .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+    }
+    EVENT_LOG.lock().unwrap().directory = directory;
+    Ok(())
+}
+
+// -1 for no mission; events outside a mission are not logged
+pub fn set_active_mission(mission_id: i32) {
+    EVENT_LOG.lock().unwrap().active_mission = (mission_id != -1).then_some(mission_id);
+}
+
+pub fn active_mission() -> Option<i32> {
+    EVENT_LOG.lock().unwrap().active_mission
+}
+
+fn append(file: &Path, line: &str) -> std::io::Result<()> {
+    let mut out = OpenOptions::new().create(true).append(true).open(file)?;
+    if out.metadata()?.len() == 0 {
+        out.write_all(HEADER.as_bytes())?;
+    }
+    out.write_all(line.as_bytes())
+}
+
+// Append one event to the active mission's log
+pub fn record(kind: EventKind, vehicle_id: Option<&str>, detail: &str, message: &str) {
+    let log = EVENT_LOG.lock().unwrap();
+    let (Some(directory), Some(mission_id)) = (&log.directory, log.active_mission) else {
+        return;
+    };
+    let file = directory.join(format!("mission_{}_events.csv", mission_id));
+    let line = format!(
+        "{},{},{},{},{}\n",
+        chrono::Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        kind.name(),
+        csv_field(&vehicle_id.unwrap_or_default().to_lowercase()),
+        csv_field(detail),
+        csv_field(message),
+    );
+    if let Err(e) = append(&file, &line) {
+        logs::warn("exports", format!("Failed to append to {}: {}", file.display(), e));
+    }
+}
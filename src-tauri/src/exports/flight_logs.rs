@@ -0,0 +1,127 @@
+/*
+Flight log writers: convert a mission's recorded telemetry into one track file per vehicle
+in CSV (all fields), GPX (track points with elevation and time) or KML (Google Earth path).
+CSV altitude and speed are in the operator's display units, named in the header; GPX and KML
+are always in metres as their formats require.
+*/
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::telemetry::sql::TelemetryRecord;
+use crate::units::DisplayUnits;
+use crate::vehicles;
+use super::{csv_field, escape_xml, FlightLogFormat};
+
+// Group records by uppercase vehicle id, keeping their time order
+pub fn group_by_vehicle(records: Vec<TelemetryRecord>) -> BTreeMap<String, Vec<TelemetryRecord>> {
+    let mut tracks: BTreeMap<String, Vec<TelemetryRecord>> = BTreeMap::new();
+    for record in records {
+        tracks
+            .entry(record.vehicle_id.to_uppercase())
+            .or_default()
+            .push(record);
+    }
+    tracks
+}
+
+pub fn to_csv(records: &[TelemetryRecord], units: &DisplayUnits) -> String {
+    // Unit-suffixed columns, e.g. altitude_ft and speed_kn or speed_mps
+    let mut out = format!(
+        "timestamp,vehicle_id,latitude,longitude,altitude_{},speed_{},pitch,yaw,roll,battery_life,signal_strength,vehicle_status\n",
+        units.distance.symbol(),
+        units.speed.symbol().replace('/', "p"),
+    );
+    for r in records {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            r.recorded_at,
+            csv_field(&r.vehicle_id),
+            r.position.latitude,
+            r.position.longitude,
+            units.distance.from_si(r.altitude as f64),
+            units.speed.from_si(r.speed as f64),
+            r.pitch,
+            r.yaw,
+            r.roll,
+            r.battery_life,
+            r.signal_strength,
+            csv_field(&r.vehicle_status),
+        ));
+    }
+    out
+}
+
+pub fn to_gpx(track_name: &str, records: &[TelemetryRecord]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <gpx version=\"1.1\" creator=\"NGCP GCS\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+    );
+    out.push_str(&format!("  <trk>\n    <name>{}</name>\n    <trkseg>\n", escape_xml(track_name)));
+    for r in records {
+        out.push_str(&format!(
+            "      <trkpt lat=\"{}\" lon=\"{}\"><ele>{}</ele><time>{}</time></trkpt>\n",
+            r.position.latitude, r.position.longitude, r.altitude, r.recorded_at
+        ));
+    }
+    out.push_str("    </trkseg>\n  </trk>\n</gpx>\n");
+    out
+}
+
+pub fn to_kml(track_name: &str, records: &[TelemetryRecord]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <kml xmlns=\"http://www.opengis.net/kml/2.2\">\n  <Document>\n",
+    );
+    out.push_str(&format!(
+        "    <name>{0}</name>\n    <Placemark>\n      <name>{0}</name>\n      <LineString>\n        \
+         <altitudeMode>absolute</altitudeMode>\n        <coordinates>\n",
+        escape_xml(track_name)
+    ));
+    // KML coordinates are longitude,latitude,altitude
+    for r in records {
+        out.push_str(&format!(
+            "          {},{},{}\n",
+            r.position.longitude, r.position.latitude, r.altitude
+        ));
+    }
+    out.push_str("        </coordinates>\n      </LineString>\n    </Placemark>\n  </Document>\n</kml>\n");
+    out
+}
+
+pub fn render(format: FlightLogFormat, track_name: &str, records: &[TelemetryRecord]) -> String {
+    match format {
+        FlightLogFormat::Csv => to_csv(records, &DisplayUnits::current()),
+        FlightLogFormat::Gpx => to_gpx(track_name, records),
+        FlightLogFormat::Kml => to_kml(track_name, records),
+    }
+}
+
+// Write one file per vehicle into `directory`; returns the written paths
+pub fn write_flight_logs(
+    mission_id: i32,
+    records: Vec<TelemetryRecord>,
+    format: FlightLogFormat,
+    directory: &Path,
+) -> Result<Vec<String>, String> {
+    if records.is_empty() {
+        return Err(format!("No telemetry recorded for mission {}", mission_id));
+    }
+    fs::create_dir_all(directory)
+        .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+
+    let mut written = vec![];
+    for (vehicle_id, track) in group_by_vehicle(records) {
+        let track_name = format!("Mission {} {}", mission_id, vehicles::display_name(&vehicle_id));
+        let file = directory.join(format!(
+            "mission_{}_{}.{}",
+            mission_id,
+            vehicle_id.to_lowercase(),
+            format.extension()
+        ));
+        fs::write(&file, render(format, &track_name, &track))
+            .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+        written.push(file.display().to_string());
+    }
+    Ok(written)
+}
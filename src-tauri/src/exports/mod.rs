@@ -0,0 +1,46 @@
+/*
+Exports of recorded mission data to files outside the GCS, for the airframe teams' analysis
+tools and long-term storage, and the live event log written while a mission runs.
+*/
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+pub mod api;
+pub mod event_log;
+pub mod flight_logs;
+
+pub use api::{ExportsApi, ExportsApiImpl};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum FlightLogFormat {
+    Csv,
+    Gpx,
+    Kml,
+}
+
+impl FlightLogFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FlightLogFormat::Csv => "csv",
+            FlightLogFormat::Gpx => "gpx",
+            FlightLogFormat::Kml => "kml",
+        }
+    }
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub(crate) fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,68 @@
+/*
+Define the fault injection API: arm and clear faults and kill supervised tasks. Every procedure
+fails in release builds.
+*/
+use tauri::{Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{as_window, require_role, OperatorRole};
+use crate::logs;
+use crate::supervisor;
+use super::FaultConfig;
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "faults")]
+pub trait FaultsApi {
+    async fn get_faults() -> FaultConfig;
+    async fn set_faults(window: Window<impl Runtime>, config: FaultConfig) -> Result<FaultConfig, String>;
+    async fn clear_faults() -> FaultConfig;
+    // Abort the current run of a supervised task (e.g. "consumer_telemetry_eru")
+    async fn kill_task(window: Window<impl Runtime>, name: String) -> Result<(), String>;
+}
+
+fn require_enabled() -> Result<(), String> {
+    if !super::ENABLED {
+        return Err("Fault injection is only available in debug builds".into());
+    }
+    require_role(OperatorRole::Operator)
+}
+
+#[derive(Clone, Default)]
+pub struct FaultsApiImpl;
+
+#[resolvers]
+impl FaultsApi for FaultsApiImpl {
+    async fn get_faults(self) -> FaultConfig {
+        super::current()
+    }
+
+    async fn set_faults(
+        self,
+        window: Window<impl Runtime>,
+        config: FaultConfig,
+    ) -> Result<FaultConfig, String> {
+        as_window(&window, async { require_enabled() }).await?;
+        config.validate()?;
+        if config.is_active() {
+            logs::warn("faults", format!("Injecting faults: {:?}", config));
+        } else {
+            logs::info("faults", "Fault injection cleared");
+        }
+        super::set(config);
+        Ok(super::current())
+    }
+
+    async fn clear_faults(self) -> FaultConfig {
+        if super::current().is_active() {
+            logs::info("faults", "Fault injection cleared");
+        }
+        super::set(FaultConfig::default());
+        super::current()
+    }
+
+    async fn kill_task(self, window: Window<impl Runtime>, name: String) -> Result<(), String> {
+        as_window(&window, async { require_enabled() }).await?;
+        supervisor::kill(&name)?;
+        logs::warn("faults", format!("Killed task {}", name));
+        Ok(())
+    }
+}
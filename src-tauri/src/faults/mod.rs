@@ -0,0 +1,88 @@
+/*
+Fault injection for resilience testing in debug builds: drop or corrupt messages from the AMQP
+consumers, delay telemetry database writes and kill supervised tasks, so reconnection, retry
+and supervisor behaviour can be exercised without pulling cables. Release builds compile the
+hooks to no-ops and refuse to arm any fault.
+*/
+use std::borrow::Cow;
+use std::sync::RwLock;
+use std::time::Duration;
+use lazy_static::lazy_static;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+pub mod api;
+
+pub use api::{FaultsApi, FaultsApiImpl};
+
+pub const ENABLED: bool = cfg!(debug_assertions);
+const MAX_DB_WRITE_DELAY_MS: u32 = 60_000;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Type)]
+pub struct FaultConfig {
+    // Chance per consumed message of acking it unprocessed, as if it never arrived
+    pub drop_amqp_percent: f64,
+    // Chance per consumed message of truncating its payload before parsing
+    pub corrupt_payload_percent: f64,
+    pub db_write_delay_ms: u32,
+}
+
+impl FaultConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, percent) in [("Drop", self.drop_amqp_percent), ("Corrupt", self.corrupt_payload_percent)] {
+            if !(0.0..=100.0).contains(&percent) {
+                return Err(format!("{} chance must be between 0 and 100%", name));
+            }
+        }
+        if self.db_write_delay_ms > MAX_DB_WRITE_DELAY_MS {
+            return Err(format!("Database write delay must be at most {} ms", MAX_DB_WRITE_DELAY_MS));
+        }
+        Ok(())
+    }
+
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+}
+
+lazy_static! {
+    static ref FAULTS: RwLock<FaultConfig> = RwLock::new(FaultConfig::default());
+}
+
+pub fn current() -> FaultConfig {
+    FAULTS.read().unwrap().clone()
+}
+
+fn set(config: FaultConfig) {
+    *FAULTS.write().unwrap() = config;
+}
+
+fn roll(percent: f64) -> bool {
+    percent > 0.0 && rand::random::<f64>() * 100.0 < percent
+}
+
+// Whether the consumer should discard this message
+pub fn drop_message() -> bool {
+    ENABLED && roll(FAULTS.read().unwrap().drop_amqp_percent)
+}
+
+// The payload to parse; a truncated copy when corruption hits, which no JSON parser accepts
+pub fn corrupt(payload: &[u8]) -> Cow<'_, [u8]> {
+    if !ENABLED || payload.is_empty() || !roll(FAULTS.read().unwrap().corrupt_payload_percent) {
+        return Cow::Borrowed(payload);
+    }
+    let keep = rand::rng().random_range(0..payload.len());
+    Cow::Owned(payload[..keep].to_vec())
+}
+
+// Awaited before telemetry is written to the database
+pub async fn delay_db_write() {
+    if !ENABLED {
+        return;
+    }
+    let delay_ms = FAULTS.read().unwrap().db_write_delay_ms;
+    if delay_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(delay_ms.into())).await;
+    }
+}
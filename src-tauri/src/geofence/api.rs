@@ -0,0 +1,38 @@
+/*
+Define the geofence API: the same checks the telemetry pipeline runs on every report, against
+the current mission's zones, for positions the operator is placing (waypoints, targets).
+*/
+use taurpc::{procedures, resolvers};
+
+use crate::missions::types::{GeoCoordinateStruct, ZoneType};
+use super::GeofenceViolation;
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "geofence")]
+pub trait GeofenceApi {
+    async fn zone_contains(kind: ZoneType, zone_index: u32, point: GeoCoordinateStruct) -> bool;
+    async fn distance_to_zone_boundary(kind: ZoneType, zone_index: u32, point: GeoCoordinateStruct) -> Option<f64>;
+    async fn get_geofence_violations(point: GeoCoordinateStruct, altitude_m: f64) -> Vec<GeofenceViolation>;
+}
+
+#[derive(Clone, Default)]
+pub struct GeofenceApiImpl;
+
+#[resolvers]
+impl GeofenceApi for GeofenceApiImpl {
+    async fn zone_contains(self, kind: ZoneType, zone_index: u32, point: GeoCoordinateStruct) -> bool {
+        super::current().contains(&kind, zone_index as usize, &point)
+    }
+
+    async fn distance_to_zone_boundary(
+        self,
+        kind: ZoneType,
+        zone_index: u32,
+        point: GeoCoordinateStruct,
+    ) -> Option<f64> {
+        super::current().distance_to_boundary(&kind, zone_index as usize, &point)
+    }
+
+    async fn get_geofence_violations(self, point: GeoCoordinateStruct, altitude_m: f64) -> Vec<GeofenceViolation> {
+        super::current().violations_for(&point, altitude_m)
+    }
+}
@@ -0,0 +1,452 @@
+/*
+Geofence evaluation for the current mission. The service owns the mission's keep-in and
+keep-out zones, kept in step with the mission state by missions::api::events, and the warning
+thresholds set through the missions API. Every telemetry report is checked against it, and
+route planning, airspace monitoring and the simulator read the same geometry, so they all
+agree on where the zones are. The mission's ceiling is the top of its highest altitude band.
+*/
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::altitude_bands;
+use crate::config;
+use crate::geometry::locate_in_polygon;
+use crate::missions::types::{GeoCoordinateStruct, GeofenceThreshold, GeofenceType, MissionsStruct, ZoneType};
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use crate::telemetry::geos::Coordinate;
+use crate::telemetry::separation;
+use crate::vehicles;
+
+pub mod api;
+
+pub use api::{GeofenceApi, GeofenceApiImpl};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub enum ViolationKind {
+    InsideKeepOut,
+    // Outside the keep-out zone but within its warning distance
+    NearKeepOut,
+    // Outside every keep-in zone of a mission that has some
+    OutsideKeepIn,
+    AboveCeiling,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct GeofenceViolation {
+    pub kind: ViolationKind,
+    // Index of the zone in the mission (the nearest keep-in zone for OutsideKeepIn); None for
+    // AboveCeiling
+    pub zone_index: Option<u32>,
+    // Distance to the zone's boundary, or height above the ceiling
+    pub distance_m: f64,
+}
+
+// Distance from each keep-out zone at which a vehicle is warned
+#[derive(Clone, Debug, Default)]
+pub struct WarningDistances {
+    pub default_m: f64,
+    // By keep-out zone index
+    pub zones: HashMap<usize, f64>,
+}
+
+impl WarningDistances {
+    pub fn uniform(distance_m: f64) -> Self {
+        Self { default_m: distance_m, zones: HashMap::new() }
+    }
+
+    fn for_zone(&self, zone_index: usize) -> f64 {
+        self.zones.get(&zone_index).copied().unwrap_or(self.default_m)
+    }
+}
+
+// Zones of the current mission, indexed as in the mission
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MissionGeometry {
+    pub mission_id: Option<i32>,
+    pub keep_in: Vec<GeofenceType>,
+    pub keep_out: Vec<GeofenceType>,
+}
+
+impl MissionGeometry {
+    pub fn zones(&self, kind: &ZoneType) -> &[GeofenceType] {
+        match kind {
+            ZoneType::KeepIn => &self.keep_in,
+            ZoneType::KeepOut => &self.keep_out,
+        }
+    }
+
+    // False for unknown zones and zones with fewer than three vertices
+    pub fn contains(&self, kind: &ZoneType, zone_index: usize, point: &GeoCoordinateStruct) -> bool {
+        self.zones(kind)
+            .get(zone_index)
+            .and_then(|zone| locate_in_polygon(point, zone))
+            .is_some_and(|(inside, _)| inside)
+    }
+
+    // Distance (m) to the zone's boundary from either side; None for unknown zones and zones
+    // with fewer than three vertices
+    pub fn distance_to_boundary(&self, kind: &ZoneType, zone_index: usize, point: &GeoCoordinateStruct) -> Option<f64> {
+        let zone = self.zones(kind).get(zone_index)?;
+        locate_in_polygon(point, zone).map(|(_, distance)| distance)
+    }
+
+    // Every geofence rule a position breaks, keep-out zones in zone order first
+    pub fn violations_for(&self, point: &GeoCoordinateStruct, altitude_m: f64) -> Vec<GeofenceViolation> {
+        let distances = warning_distances(self.mission_id, config::get().geofence_warning_distance_m);
+        let mut violations: Vec<GeofenceViolation> = self
+            .keep_out
+            .iter()
+            .enumerate()
+            .filter_map(|(zone_index, zone)| {
+                let (inside, distance) = locate_in_polygon(point, zone)?;
+                let kind = if inside {
+                    ViolationKind::InsideKeepOut
+                } else if distance <= distances.for_zone(zone_index) {
+                    ViolationKind::NearKeepOut
+                } else {
+                    return None;
+                };
+                Some(GeofenceViolation { kind, zone_index: Some(zone_index as u32), distance_m: distance })
+            })
+            .collect();
+
+        let keep_in: Vec<(usize, bool, f64)> = self
+            .keep_in
+            .iter()
+            .enumerate()
+            .filter_map(|(zone_index, zone)| {
+                locate_in_polygon(point, zone).map(|(inside, distance)| (zone_index, inside, distance))
+            })
+            .collect();
+        if !keep_in.is_empty() && keep_in.iter().all(|(_, inside, _)| !inside) {
+            if let Some((zone_index, _, distance)) = keep_in.into_iter().min_by(|a, b| a.2.total_cmp(&b.2)) {
+                violations.push(GeofenceViolation {
+                    kind: ViolationKind::OutsideKeepIn,
+                    zone_index: Some(zone_index as u32),
+                    distance_m: distance,
+                });
+            }
+        }
+
+        if let Some(ceiling) = self.mission_id.and_then(ceiling_m) {
+            if altitude_m > ceiling {
+                violations.push(GeofenceViolation {
+                    kind: ViolationKind::AboveCeiling,
+                    zone_index: None,
+                    distance_m: altitude_m - ceiling,
+                });
+            }
+        }
+        violations
+    }
+}
+
+lazy_static! {
+    static ref GEOMETRY: RwLock<Arc<MissionGeometry>> = RwLock::new(Arc::new(MissionGeometry::default()));
+    // Per-mission and per-zone thresholds set through the missions API, by mission
+    static ref MISSION_THRESHOLDS: RwLock<HashMap<i32, Vec<GeofenceThreshold>>> =
+        RwLock::new(HashMap::new());
+}
+
+// Shared so a telemetry report doesn't copy the zones
+pub fn current() -> Arc<MissionGeometry> {
+    GEOMETRY.read().unwrap().clone()
+}
+
+// True when the keep-out zones changed
+pub fn set_geometry(geometry: MissionGeometry) -> bool {
+    let mut current = GEOMETRY.write().unwrap();
+    let keep_out_changed = current.keep_out != geometry.keep_out;
+    if **current != geometry {
+        *current = Arc::new(geometry);
+    }
+    keep_out_changed
+}
+
+// Follow the mission state: the current mission's zones, whatever its status, so vehicles are
+// warned before it starts too. True when the keep-out zones changed
+pub fn set_missions(state: &MissionsStruct) -> bool {
+    let geometry = state
+        .missions
+        .iter()
+        .find(|m| m.mission_id == state.current_mission)
+        .map(|m| MissionGeometry {
+            mission_id: Some(m.mission_id),
+            keep_in: m.zones.keep_in_zones.clone(),
+            keep_out: m.zones.keep_out_zones.clone(),
+        })
+        .unwrap_or_default();
+    set_geometry(geometry)
+}
+
+pub fn set_mission_thresholds(mission_id: i32, thresholds: Vec<GeofenceThreshold>) {
+    MISSION_THRESHOLDS.write().unwrap().insert(mission_id, thresholds);
+}
+
+// A zone's own threshold wins over the mission-wide one, which wins over `default_m`
+pub fn warning_distances(mission_id: Option<i32>, default_m: f64) -> WarningDistances {
+    let thresholds = MISSION_THRESHOLDS.read().unwrap();
+    let Some(thresholds) = mission_id.and_then(|id| thresholds.get(&id)) else {
+        return WarningDistances::uniform(default_m);
+    };
+    WarningDistances {
+        default_m: thresholds
+            .iter()
+            .find(|t| t.zone_index.is_none())
+            .map_or(default_m, |t| t.warning_distance_m),
+        zones: thresholds
+            .iter()
+            .filter_map(|t| Some((usize::try_from(t.zone_index?).ok()?, t.warning_distance_m)))
+            .collect(),
+    }
+}
+
+// None while the mission has no altitude bands
+fn ceiling_m(mission_id: i32) -> Option<f64> {
+    altitude_bands::bands_for(mission_id)
+        .iter()
+        .map(|band| band.max_altitude_m)
+        .reduce(f64::max)
+}
+
+// Check a vehicle's position, raising or clearing its Geofence alert; true while it is inside
+// a keep-out zone or within the zone's warning distance
+pub fn check_position(vehicle_id: &str, point: &Coordinate, altitude_m: f64) -> bool {
+    let point = GeoCoordinateStruct { lat: point.latitude, long: point.longitude };
+    let nearest = current()
+        .violations_for(&point, altitude_m)
+        .into_iter()
+        .filter(|v| matches!(v.kind, ViolationKind::InsideKeepOut | ViolationKind::NearKeepOut))
+        // A zone the vehicle is inside wins over a nearer one it is only approaching
+        .min_by(|a, b| {
+            let outside = |v: &GeofenceViolation| v.kind != ViolationKind::InsideKeepOut;
+            outside(a).cmp(&outside(b)).then(a.distance_m.total_cmp(&b.distance_m))
+        });
+    notifications::track(
+        nearest.is_some(),
+        NotificationCategory::Geofence,
+        NotificationSeverity::Critical,
+        vehicle_id,
+        || match &nearest {
+            Some(GeofenceViolation { kind: ViolationKind::InsideKeepOut, zone_index: Some(zone_index), .. }) => format!(
+                "{} is inside keep-out zone {}",
+                vehicles::display_name(vehicle_id),
+                zone_index + 1
+            ),
+            _ => format!("{} is approaching a keep-out zone", vehicles::display_name(vehicle_id)),
+        },
+    );
+    nearest.is_some()
+}
+
+// Re-check every vehicle's last known position, e.g. right after a keep-out zone changed,
+// instead of waiting for its next report
+pub fn recheck_positions() {
+    for (vehicle_id, position, altitude) in separation::recent_positions() {
+        check_position(&vehicle_id, &position, altitude);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(lat: f64, long: f64) -> GeoCoordinateStruct {
+        GeoCoordinateStruct { lat, long }
+    }
+
+    // Square about 1.1 km north-south and 0.9 km east-west, south-west corner at (lat, long)
+    fn square(lat: f64, long: f64) -> GeofenceType {
+        vec![
+            coord(lat, long),
+            coord(lat, long + 0.01),
+            coord(lat + 0.01, long + 0.01),
+            coord(lat + 0.01, long),
+        ]
+    }
+
+    // U open to the north: a base along the south with two arms, the notch between the arms
+    // spanning lat 34.01..34.03 and long -116.99..-116.98
+    fn u_shape() -> GeofenceType {
+        vec![
+            coord(34.0, -117.0),
+            coord(34.0, -116.97),
+            coord(34.03, -116.97),
+            coord(34.03, -116.98),
+            coord(34.01, -116.98),
+            coord(34.01, -116.99),
+            coord(34.03, -116.99),
+            coord(34.03, -117.0),
+        ]
+    }
+
+    // Geometry of a mission whose keep-out zones all warn at `warning_distance_m`
+    fn mission(mission_id: i32, keep_in: Vec<GeofenceType>, keep_out: Vec<GeofenceType>, warning_distance_m: f64) -> MissionGeometry {
+        set_mission_thresholds(
+            mission_id,
+            vec![GeofenceThreshold { mission_id, zone_index: None, warning_distance_m }],
+        );
+        MissionGeometry { mission_id: Some(mission_id), keep_in, keep_out }
+    }
+
+    fn kinds(violations: &[GeofenceViolation]) -> Vec<ViolationKind> {
+        violations.iter().map(|v| v.kind.clone()).collect()
+    }
+
+    #[test]
+    fn contains_points_inside_and_outside_a_square() {
+        let geometry = MissionGeometry { keep_out: vec![square(34.0, -117.0)], ..Default::default() };
+        assert!(geometry.contains(&ZoneType::KeepOut, 0, &coord(34.005, -116.995)));
+        assert!(!geometry.contains(&ZoneType::KeepOut, 0, &coord(34.02, -116.995)));
+        assert!(!geometry.contains(&ZoneType::KeepIn, 0, &coord(34.005, -116.995)));
+        assert!(!geometry.contains(&ZoneType::KeepOut, 1, &coord(34.005, -116.995)));
+    }
+
+    #[test]
+    fn contains_accepts_a_closed_ring() {
+        let mut zone = square(34.0, -117.0);
+        zone.push(zone[0].clone());
+        let geometry = MissionGeometry { keep_in: vec![zone], ..Default::default() };
+        assert!(geometry.contains(&ZoneType::KeepIn, 0, &coord(34.005, -116.995)));
+        assert!(!geometry.contains(&ZoneType::KeepIn, 0, &coord(33.99, -116.995)));
+    }
+
+    #[test]
+    fn contains_excludes_the_notch_of_a_concave_zone() {
+        let geometry = MissionGeometry { keep_out: vec![u_shape()], ..Default::default() };
+        // Inside the convex hull but between the arms
+        assert!(!geometry.contains(&ZoneType::KeepOut, 0, &coord(34.02, -116.985)));
+        assert!(geometry.contains(&ZoneType::KeepOut, 0, &coord(34.02, -116.995)));
+        assert!(geometry.contains(&ZoneType::KeepOut, 0, &coord(34.02, -116.975)));
+        assert!(geometry.contains(&ZoneType::KeepOut, 0, &coord(34.005, -116.985)));
+    }
+
+    #[test]
+    fn distance_to_boundary_is_measured_from_both_sides() {
+        let geometry = MissionGeometry { keep_out: vec![square(34.0, -117.0)], ..Default::default() };
+        // Centre: the east and west edges are 0.005 degrees of longitude away
+        let inside = geometry.distance_to_boundary(&ZoneType::KeepOut, 0, &coord(34.005, -116.995)).unwrap();
+        assert!((455.0..465.0).contains(&inside), "{}", inside);
+        // 0.002 degrees of latitude south of the southern edge
+        let outside = geometry.distance_to_boundary(&ZoneType::KeepOut, 0, &coord(33.998, -116.995)).unwrap();
+        assert!((220.0..225.0).contains(&outside), "{}", outside);
+    }
+
+    #[test]
+    fn distance_to_boundary_is_zero_on_edges_and_vertices() {
+        let geometry = MissionGeometry { keep_out: vec![square(34.0, -117.0)], ..Default::default() };
+        for point in [coord(34.0, -116.995), coord(34.005, -117.0), coord(34.01, -116.99)] {
+            let distance = geometry.distance_to_boundary(&ZoneType::KeepOut, 0, &point).unwrap();
+            assert!(distance < 1e-6, "{:?} is {} m from the boundary", point, distance);
+        }
+    }
+
+    #[test]
+    fn distance_to_boundary_uses_the_nearest_edge_of_a_concave_zone() {
+        let geometry = MissionGeometry { keep_out: vec![u_shape()], ..Default::default() };
+        // Middle of the notch: the arms' inner edges are nearer than the base's
+        let distance = geometry.distance_to_boundary(&ZoneType::KeepOut, 0, &coord(34.02, -116.985)).unwrap();
+        assert!((455.0..465.0).contains(&distance), "{}", distance);
+    }
+
+    #[test]
+    fn degenerate_zones_contain_nothing() {
+        let two_points = vec![coord(34.0, -117.0), coord(34.01, -116.99)];
+        let collinear = vec![coord(34.0, -117.0), coord(34.0, -116.995), coord(34.0, -116.99)];
+        let geometry = MissionGeometry { keep_out: vec![two_points, collinear, vec![]], ..Default::default() };
+
+        assert!(!geometry.contains(&ZoneType::KeepOut, 0, &coord(34.005, -116.995)));
+        assert_eq!(geometry.distance_to_boundary(&ZoneType::KeepOut, 0, &coord(34.005, -116.995)), None);
+        assert_eq!(geometry.distance_to_boundary(&ZoneType::KeepOut, 2, &coord(34.005, -116.995)), None);
+
+        // A collinear zone has a boundary, the line, but no inside
+        assert!(!geometry.contains(&ZoneType::KeepOut, 1, &coord(34.0, -116.995)));
+        let distance = geometry.distance_to_boundary(&ZoneType::KeepOut, 1, &coord(34.001, -116.995)).unwrap();
+        assert!((110.0..113.0).contains(&distance), "{}", distance);
+    }
+
+    #[test]
+    fn violations_for_keep_out_zones() {
+        let geometry = mission(9001, vec![], vec![square(34.0, -117.0), square(34.02, -117.0)], 600.0);
+
+        let inside = geometry.violations_for(&coord(34.005, -116.995), 0.0);
+        assert_eq!(kinds(&inside), vec![ViolationKind::InsideKeepOut]);
+        assert_eq!(inside[0].zone_index, Some(0));
+
+        // 0.002 degrees south of the first zone, well away from the second
+        let near = geometry.violations_for(&coord(33.998, -116.995), 0.0);
+        assert_eq!(kinds(&near), vec![ViolationKind::NearKeepOut]);
+        assert_eq!(near[0].zone_index, Some(0));
+
+        // Between the zones, within warning distance of both; reported in zone order
+        let between = geometry.violations_for(&coord(34.015, -116.995), 0.0);
+        assert_eq!(kinds(&between), vec![ViolationKind::NearKeepOut, ViolationKind::NearKeepOut]);
+        assert_eq!(between.iter().map(|v| v.zone_index).collect::<Vec<_>>(), vec![Some(0), Some(1)]);
+
+        assert!(geometry.violations_for(&coord(33.99, -116.995), 0.0).is_empty());
+    }
+
+    #[test]
+    fn violations_for_follow_the_warning_distance() {
+        let geometry = mission(9002, vec![], vec![square(34.0, -117.0)], 100.0);
+        assert!(geometry.violations_for(&coord(33.998, -116.995), 0.0).is_empty());
+
+        // The zone's own threshold wins over the mission-wide one
+        let geometry = mission(9003, vec![], vec![square(34.0, -117.0)], 100.0);
+        set_mission_thresholds(
+            9003,
+            vec![
+                GeofenceThreshold { mission_id: 9003, zone_index: None, warning_distance_m: 100.0 },
+                GeofenceThreshold { mission_id: 9003, zone_index: Some(0), warning_distance_m: 250.0 },
+            ],
+        );
+        assert_eq!(kinds(&geometry.violations_for(&coord(33.998, -116.995), 0.0)), vec![ViolationKind::NearKeepOut]);
+    }
+
+    #[test]
+    fn violations_for_flag_the_keep_out_boundary() {
+        let geometry = mission(9004, vec![], vec![square(34.0, -117.0)], 1.0);
+        // Inside or merely touching, a vehicle on the boundary is always reported
+        for point in [coord(34.0, -116.995), coord(34.005, -117.0), coord(34.01, -116.99)] {
+            let violations = geometry.violations_for(&point, 0.0);
+            assert_eq!(violations.len(), 1, "{:?}", point);
+            assert!(violations[0].distance_m < 1e-6);
+        }
+    }
+
+    #[test]
+    fn violations_for_concave_keep_out() {
+        let geometry = mission(9005, vec![], vec![u_shape()], 100.0);
+        assert!(geometry.violations_for(&coord(34.02, -116.985), 0.0).is_empty());
+        assert_eq!(kinds(&geometry.violations_for(&coord(34.02, -116.995), 0.0)), vec![ViolationKind::InsideKeepOut]);
+    }
+
+    #[test]
+    fn violations_for_keep_in_zones() {
+        let geometry = mission(9006, vec![square(34.0, -117.0), square(34.0, -116.98)], vec![], 0.0);
+
+        assert!(geometry.violations_for(&coord(34.005, -116.995), 0.0).is_empty());
+        assert!(geometry.violations_for(&coord(34.005, -116.975), 0.0).is_empty());
+
+        // Outside both, nearer the second zone
+        let outside = geometry.violations_for(&coord(34.005, -116.9835), 0.0);
+        assert_eq!(kinds(&outside), vec![ViolationKind::OutsideKeepIn]);
+        assert_eq!(outside[0].zone_index, Some(1));
+    }
+
+    #[test]
+    fn violations_for_ignore_zones_with_fewer_than_three_vertices() {
+        let two_points = vec![coord(34.0, -117.0), coord(34.01, -116.99)];
+        let geometry = mission(9007, vec![two_points.clone()], vec![two_points], 1000.0);
+        assert!(geometry.violations_for(&coord(34.005, -116.995), 0.0).is_empty());
+    }
+
+    #[test]
+    fn violations_for_skip_the_ceiling_without_altitude_bands() {
+        let geometry = mission(9008, vec![], vec![], 0.0);
+        assert!(geometry.violations_for(&coord(34.005, -116.995), 10_000.0).is_empty());
+    }
+}
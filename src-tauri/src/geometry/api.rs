@@ -0,0 +1,33 @@
+/*
+Define the geometry API: polygon metrics shown while drawing zones and search areas.
+*/
+use taurpc::{procedures, resolvers};
+
+use crate::missions::types::GeofenceType;
+use super::PolygonMetrics;
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "geometry")]
+pub trait GeometryApi {
+    async fn get_polygon_metrics(polygon: GeofenceType) -> PolygonMetrics;
+    async fn get_convex_hull(polygon: GeofenceType) -> GeofenceType;
+    // Same rules the mission API applies when a zone is saved
+    async fn validate_polygon(polygon: GeofenceType) -> Result<(), String>;
+}
+
+#[derive(Clone, Default)]
+pub struct GeometryApiImpl;
+
+#[resolvers]
+impl GeometryApi for GeometryApiImpl {
+    async fn get_polygon_metrics(self, polygon: GeofenceType) -> PolygonMetrics {
+        super::metrics(&polygon)
+    }
+
+    async fn get_convex_hull(self, polygon: GeofenceType) -> GeofenceType {
+        super::convex_hull(super::open_ring(&polygon))
+    }
+
+    async fn validate_polygon(self, polygon: GeofenceType) -> Result<(), String> {
+        super::validate_polygon(&polygon)
+    }
+}
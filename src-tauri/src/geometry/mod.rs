@@ -0,0 +1,328 @@
+/*
+Planar geometry on mission polygons (zones and search areas): area, perimeter, centroid,
+convex hull and simple-polygon checks. Polygons are projected onto a local metric plane
+around their vertex mean, which is accurate over the few kilometres a search area spans.
+*/
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::missions::types::{GeoCoordinateStruct, GeofenceType};
+use crate::telemetry::geos::{harversine_distance, Coordinate};
+
+pub mod api;
+pub mod partition;
+pub mod route;
+
+pub use api::{GeometryApi, GeometryApiImpl};
+
+pub const EARTH_RADIUS_M: f64 = 6371000.0;
+// Polygons smaller than this are treated as degenerate (collinear or repeated vertices)
+const MIN_POLYGON_AREA_M2: f64 = 1.0;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct PolygonMetrics {
+    pub vertex_count: u32,
+    pub area_m2: f64,
+    pub perimeter_m: f64,
+    pub centroid: Option<GeoCoordinateStruct>,
+    pub convex_hull: GeofenceType,
+    pub self_intersecting: bool,
+}
+
+// Equirectangular projection around a reference point; accurate over search-area distances
+pub struct LocalProjection {
+    lat0: f64,
+    long0: f64,
+    cos_lat0: f64,
+}
+
+impl LocalProjection {
+    pub fn new(origin: &GeoCoordinateStruct) -> Self {
+        Self {
+            lat0: origin.lat,
+            long0: origin.long,
+            cos_lat0: origin.lat.to_radians().cos(),
+        }
+    }
+
+    // Centred on the vertex mean, so distortion is spread evenly over the polygon
+    pub fn around(polygon: &[GeoCoordinateStruct]) -> Self {
+        let count = polygon.len().max(1) as f64;
+        let (lat, long) = polygon
+            .iter()
+            .fold((0.0, 0.0), |(lat, long), c| (lat + c.lat, long + c.long));
+        Self::new(&GeoCoordinateStruct { lat: lat / count, long: long / count })
+    }
+
+    pub fn to_xy(&self, coord: &GeoCoordinateStruct) -> (f64, f64) {
+        (
+            (coord.long - self.long0).to_radians() * self.cos_lat0 * EARTH_RADIUS_M,
+            (coord.lat - self.lat0).to_radians() * EARTH_RADIUS_M,
+        )
+    }
+
+    pub fn to_coord(&self, x: f64, y: f64) -> GeoCoordinateStruct {
+        GeoCoordinateStruct {
+            lat: self.lat0 + (y / EARTH_RADIUS_M).to_degrees(),
+            long: self.long0 + (x / (EARTH_RADIUS_M * self.cos_lat0)).to_degrees(),
+        }
+    }
+}
+
+pub fn point_in_polygon(x: f64, y: f64, polygon: &[(f64, f64)]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (xi, yi) = polygon[i];
+        let (xj, yj) = polygon[j];
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+pub fn distance_to_segment(px: f64, py: f64, a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq == 0.0 {
+        0.0
+    } else {
+        (((px - a.0) * dx + (py - a.1) * dy) / length_sq).clamp(0.0, 1.0)
+    };
+    let (cx, cy) = (a.0 + t * dx, a.1 + t * dy);
+    ((px - cx).powi(2) + (py - cy).powi(2)).sqrt()
+}
+
+// Whether a point is inside the polygon and its distance (m) to the boundary, from either side;
+// None for polygons with fewer than three vertices
+pub fn locate_in_polygon(point: &GeoCoordinateStruct, polygon: &[GeoCoordinateStruct]) -> Option<(bool, f64)> {
+    let polygon = open_ring(polygon);
+    if polygon.len() < 3 {
+        return None;
+    }
+    let projection = LocalProjection::around(polygon);
+    let ring: Vec<(f64, f64)> = polygon.iter().map(|c| projection.to_xy(c)).collect();
+    let (px, py) = projection.to_xy(point);
+    let distance = (0..ring.len())
+        .map(|i| distance_to_segment(px, py, ring[i], ring[(i + 1) % ring.len()]))
+        .fold(f64::INFINITY, f64::min);
+    Some((point_in_polygon(px, py, &ring), distance))
+}
+
+// Distance (m) from a point to the polygon's boundary, or 0 when the point is inside
+pub fn distance_to_polygon(point: &GeoCoordinateStruct, polygon: &[GeoCoordinateStruct]) -> f64 {
+    match locate_in_polygon(point, polygon) {
+        Some((true, _)) => 0.0,
+        Some((false, distance)) => distance,
+        None => f64::INFINITY,
+    }
+}
+
+// Shoelace sum; positive for counter-clockwise rings
+pub fn signed_area(points: &[(f64, f64)]) -> f64 {
+    if points.len() < 3 {
+        return 0.0;
+    }
+    let mut sum = 0.0;
+    for i in 0..points.len() {
+        let (x0, y0) = points[i];
+        let (x1, y1) = points[(i + 1) % points.len()];
+        sum += x0 * y1 - x1 * y0;
+    }
+    sum / 2.0
+}
+
+pub fn area_m2(polygon: &[GeoCoordinateStruct]) -> f64 {
+    let projection = LocalProjection::around(polygon);
+    let points: Vec<(f64, f64)> = polygon.iter().map(|c| projection.to_xy(c)).collect();
+    signed_area(&points).abs()
+}
+
+// Length of the closed ring, including the edge back to the first vertex
+pub fn perimeter_m(polygon: &[GeoCoordinateStruct]) -> f64 {
+    if polygon.len() < 2 {
+        return 0.0;
+    }
+    let to_coord = |c: &GeoCoordinateStruct| Coordinate { latitude: c.lat, longitude: c.long };
+    (0..polygon.len())
+        .map(|i| harversine_distance(&to_coord(&polygon[i]), &to_coord(&polygon[(i + 1) % polygon.len()])))
+        .sum()
+}
+
+// Area-weighted centroid; falls back to the vertex mean for degenerate polygons
+pub fn centroid(polygon: &[GeoCoordinateStruct]) -> Option<GeoCoordinateStruct> {
+    if polygon.is_empty() {
+        return None;
+    }
+    let projection = LocalProjection::around(polygon);
+    let points: Vec<(f64, f64)> = polygon.iter().map(|c| projection.to_xy(c)).collect();
+    let area = signed_area(&points);
+    if area.abs() < MIN_POLYGON_AREA_M2 {
+        return Some(projection.to_coord(0.0, 0.0));
+    }
+
+    let (mut cx, mut cy) = (0.0, 0.0);
+    for i in 0..points.len() {
+        let (x0, y0) = points[i];
+        let (x1, y1) = points[(i + 1) % points.len()];
+        let cross = x0 * y1 - x1 * y0;
+        cx += (x0 + x1) * cross;
+        cy += (y0 + y1) * cross;
+    }
+    Some(projection.to_coord(cx / (6.0 * area), cy / (6.0 * area)))
+}
+
+// Andrew's monotone chain; returns hull vertices counter-clockwise
+pub fn convex_hull(polygon: &[GeoCoordinateStruct]) -> GeofenceType {
+    if polygon.len() < 3 {
+        return polygon.to_vec();
+    }
+    let projection = LocalProjection::around(polygon);
+    let mut points: Vec<(f64, f64, usize)> = polygon
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let (x, y) = projection.to_xy(c);
+            (x, y, i)
+        })
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    points.dedup_by(|a, b| a.0 == b.0 && a.1 == b.1);
+
+    let cross = |o: &(f64, f64, usize), a: &(f64, f64, usize), b: &(f64, f64, usize)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    let mut hull: Vec<(f64, f64, usize)> = Vec::with_capacity(points.len() * 2);
+    for pass in [points.clone(), points.iter().rev().cloned().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2 && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], &point) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The last point of each chain is the first of the next
+        hull.pop();
+    }
+    hull.iter().map(|p| polygon[p.2].clone()).collect()
+}
+
+fn segments_intersect(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> bool {
+    let orient = |p: (f64, f64), q: (f64, f64), r: (f64, f64)| {
+        let value = (q.0 - p.0) * (r.1 - p.1) - (q.1 - p.1) * (r.0 - p.0);
+        if value > 0.0 {
+            1
+        } else if value < 0.0 {
+            -1
+        } else {
+            0
+        }
+    };
+    let on_segment = |p: (f64, f64), q: (f64, f64), r: (f64, f64)| {
+        r.0 >= p.0.min(q.0) && r.0 <= p.0.max(q.0) && r.1 >= p.1.min(q.1) && r.1 <= p.1.max(q.1)
+    };
+    let (o1, o2, o3, o4) = (orient(a, b, c), orient(a, b, d), orient(c, d, a), orient(c, d, b));
+    if o1 != o2 && o3 != o4 {
+        return true;
+    }
+    (o1 == 0 && on_segment(a, b, c))
+        || (o2 == 0 && on_segment(a, b, d))
+        || (o3 == 0 && on_segment(c, d, a))
+        || (o4 == 0 && on_segment(c, d, b))
+}
+
+// True when two non-adjacent edges cross or touch
+pub fn is_self_intersecting(polygon: &[GeoCoordinateStruct]) -> bool {
+    let n = polygon.len();
+    if n < 4 {
+        return false;
+    }
+    let projection = LocalProjection::around(polygon);
+    let points: Vec<(f64, f64)> = polygon.iter().map(|c| projection.to_xy(c)).collect();
+    for i in 0..n {
+        for j in (i + 2)..n {
+            // Edges i and j share a vertex when j is the edge closing the ring back to i
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+            if segments_intersect(points[i], points[(i + 1) % n], points[j], points[(j + 1) % n]) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+// Drop a closing vertex that repeats the first, as some editors emit closed rings
+pub fn open_ring(polygon: &[GeoCoordinateStruct]) -> &[GeoCoordinateStruct] {
+    match (polygon.first(), polygon.last()) {
+        (Some(first), Some(last)) if polygon.len() > 1 && first.lat == last.lat && first.long == last.long => {
+            &polygon[..polygon.len() - 1]
+        }
+        _ => polygon,
+    }
+}
+
+// Visvalingam-Whyatt: repeatedly drop the vertex spanning the smallest triangle with its
+// neighbours until at most `max_vertices` remain, counting a closing vertex; never below 3
+pub fn simplify_polygon(polygon: &[GeoCoordinateStruct], max_vertices: usize) -> GeofenceType {
+    let ring = open_ring(polygon);
+    let closed = ring.len() < polygon.len();
+    let target = max_vertices.saturating_sub(closed as usize).max(3);
+    if ring.len() <= target {
+        return polygon.to_vec();
+    }
+
+    let projection = LocalProjection::around(ring);
+    let mut points: Vec<(usize, (f64, f64))> = ring.iter().map(|c| projection.to_xy(c)).enumerate().collect();
+    while points.len() > target {
+        let n = points.len();
+        let triangle = |i: usize| signed_area(&[points[(i + n - 1) % n].1, points[i].1, points[(i + 1) % n].1]).abs();
+        let smallest = (0..n)
+            .min_by(|&a, &b| triangle(a).total_cmp(&triangle(b)))
+            .unwrap_or(0);
+        points.remove(smallest);
+    }
+
+    let mut simplified: GeofenceType = points.iter().map(|(i, _)| ring[*i].clone()).collect();
+    if closed {
+        simplified.push(simplified[0].clone());
+    }
+    simplified
+}
+
+pub fn metrics(polygon: &[GeoCoordinateStruct]) -> PolygonMetrics {
+    let polygon = open_ring(polygon);
+    PolygonMetrics {
+        vertex_count: polygon.len() as u32,
+        area_m2: area_m2(polygon),
+        perimeter_m: perimeter_m(polygon),
+        centroid: centroid(polygon),
+        convex_hull: convex_hull(polygon),
+        self_intersecting: is_self_intersecting(polygon),
+    }
+}
+
+// Zones and search areas must be simple polygons with a non-zero area once they have
+// three or more vertices; shorter rings are work in progress in the editor
+pub fn validate_polygon(polygon: &[GeoCoordinateStruct]) -> Result<(), String> {
+    if let Some(c) = polygon
+        .iter()
+        .find(|c| !c.lat.is_finite() || !c.long.is_finite() || c.lat.abs() > 90.0 || c.long.abs() > 180.0)
+    {
+        return Err(format!("Invalid vertex ({}, {})", c.lat, c.long));
+    }
+    let polygon = open_ring(polygon);
+    if polygon.len() < 3 {
+        return Ok(());
+    }
+    if is_self_intersecting(polygon) {
+        return Err("Polygon edges must not cross each other".into());
+    }
+    if area_m2(polygon) < MIN_POLYGON_AREA_M2 {
+        return Err("Polygon has no area; its vertices are collinear or repeated".into());
+    }
+    Ok(())
+}
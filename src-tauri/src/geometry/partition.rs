@@ -0,0 +1,87 @@
+/*
+Search-area partitioning: split one polygon into N strips of equal area by sweeping a cut
+line along the polygon's longer extent. Parallel strips keep each vehicle's lawnmower pattern
+simple. Cut positions are found by bisection on the clipped area. A concave polygon may yield
+a strip made of two pieces joined by a zero-width edge; it is still covered correctly.
+*/
+use crate::missions::types::{GeoCoordinateStruct, GeofenceType};
+use super::{open_ring, signed_area, validate_polygon, LocalProjection};
+
+pub const MAX_PARTITIONS: usize = 16;
+const BISECTION_STEPS: usize = 60;
+
+// Keep the part of the ring on one side of the line u = cut (Sutherland-Hodgman)
+fn clip(points: &[(f64, f64)], cut: f64, keep_below: bool) -> Vec<(f64, f64)> {
+    let inside = |p: &(f64, f64)| if keep_below { p.0 <= cut } else { p.0 >= cut };
+    let mut output = Vec::with_capacity(points.len() + 2);
+    for i in 0..points.len() {
+        let current = points[i];
+        let previous = points[(i + points.len() - 1) % points.len()];
+        let (current_in, previous_in) = (inside(&current), inside(&previous));
+        if current_in != previous_in {
+            let t = (cut - previous.0) / (current.0 - previous.0);
+            output.push((cut, previous.1 + t * (current.1 - previous.1)));
+        }
+        if current_in {
+            output.push(current);
+        }
+    }
+    output
+}
+
+fn strip(points: &[(f64, f64)], from: f64, to: f64) -> Vec<(f64, f64)> {
+    clip(&clip(points, from, false), to, true)
+}
+
+// Sub-polygons in sweep order (west to east, or south to north for tall areas)
+pub fn partition_polygon(polygon: &[GeoCoordinateStruct], parts: usize) -> Result<Vec<GeofenceType>, String> {
+    let polygon = open_ring(polygon);
+    if polygon.len() < 3 {
+        return Err("Search area needs at least 3 vertices".into());
+    }
+    validate_polygon(polygon)?;
+    if parts == 0 || parts > MAX_PARTITIONS {
+        return Err(format!("A search area can be split into 1 to {} parts", MAX_PARTITIONS));
+    }
+
+    let projection = LocalProjection::around(polygon);
+    let xy: Vec<(f64, f64)> = polygon.iter().map(|c| projection.to_xy(c)).collect();
+    let (min_x, max_x) = xy.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.0), hi.max(p.0)));
+    let (min_y, max_y) = xy.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.1), hi.max(p.1)));
+
+    // Sweep along the longer side; (u, v) is (x, y) or (y, x)
+    let sweep_y = max_y - min_y > max_x - min_x;
+    let swap = |p: (f64, f64)| if sweep_y { (p.1, p.0) } else { p };
+    let uv: Vec<(f64, f64)> = xy.iter().map(|&p| swap(p)).collect();
+    let (min_u, max_u) = if sweep_y { (min_y, max_y) } else { (min_x, max_x) };
+    let total = signed_area(&uv).abs();
+
+    let mut cuts = vec![min_u];
+    for k in 1..parts {
+        let target = total * k as f64 / parts as f64;
+        let (mut lo, mut hi) = (*cuts.last().unwrap(), max_u);
+        for _ in 0..BISECTION_STEPS {
+            let mid = (lo + hi) / 2.0;
+            if signed_area(&clip(&uv, mid, true)).abs() < target {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        cuts.push((lo + hi) / 2.0);
+    }
+    cuts.push(max_u);
+
+    Ok(cuts
+        .windows(2)
+        .map(|w| {
+            strip(&uv, w[0], w[1])
+                .into_iter()
+                .map(|p| {
+                    let (x, y) = swap(p);
+                    projection.to_coord(x, y)
+                })
+                .collect()
+        })
+        .collect())
+}
@@ -0,0 +1,154 @@
+/*
+Keep-out-aware route planning over a visibility graph. Every keep-out polygon is inflated by
+the configured clearance; the graph's nodes are the start, the goal and the inflated corners,
+and two nodes are joined when the straight leg between them stays out of every inflated zone.
+The shortest path through that graph is the route. A zone whose clearance band already
+contains the start or goal is checked against its original outline so the vehicle can leave it.
+*/
+use crate::missions::types::GeoCoordinateStruct;
+use super::{open_ring, point_in_polygon, signed_area, LocalProjection};
+
+// Corners sharper than this ratio of the clearance are clipped to keep nodes near the zone
+const MAX_MITER_RATIO: f64 = 3.0;
+// Nodes sit just outside the inflated outline so legs along it are not rejected
+const NODE_MARGIN_M: f64 = 1.0;
+
+type Point = (f64, f64);
+
+fn cross(o: Point, a: Point, b: Point) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+// True when segments ab and cd cross at a point interior to both
+fn segments_cross(a: Point, b: Point, c: Point, d: Point) -> bool {
+    let (d1, d2) = (cross(a, b, c), cross(a, b, d));
+    let (d3, d4) = (cross(c, d, a), cross(c, d, b));
+    ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0)) && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+}
+
+// Offset each vertex outward along its corner bisector; the ring is made counter-clockwise
+fn inflate(ring: &[Point], distance: f64) -> Vec<Point> {
+    let mut ring = ring.to_vec();
+    if signed_area(&ring) < 0.0 {
+        ring.reverse();
+    }
+    let n = ring.len();
+    let normal = |a: Point, b: Point| {
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let length = (dx * dx + dy * dy).sqrt().max(f64::EPSILON);
+        (dy / length, -dx / length)
+    };
+    (0..n)
+        .map(|i| {
+            let (prev, current, next) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+            let (n1, n2) = (normal(prev, current), normal(current, next));
+            let (bx, by) = (n1.0 + n2.0, n1.1 + n2.1);
+            let length = (bx * bx + by * by).sqrt();
+            if length < f64::EPSILON {
+                return (current.0 + n1.0 * distance, current.1 + n1.1 * distance);
+            }
+            let (bx, by) = (bx / length, by / length);
+            let cos_half = (bx * n1.0 + by * n1.1).max(1.0 / MAX_MITER_RATIO);
+            let miter = distance / cos_half;
+            (current.0 + bx * miter, current.1 + by * miter)
+        })
+        .collect()
+}
+
+fn leg_is_clear(a: Point, b: Point, obstacles: &[Vec<Point>]) -> bool {
+    obstacles.iter().all(|ring| {
+        let n = ring.len();
+        let crosses = (0..n).any(|i| segments_cross(a, b, ring[i], ring[(i + 1) % n]));
+        // A leg between two corners of the same zone can pass through it without crossing an edge
+        let midpoint = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+        !crosses && !point_in_polygon(midpoint.0, midpoint.1, ring)
+    })
+}
+
+// Shortest keep-out-free route from `from` to `to`, including both endpoints
+pub fn plan_route(
+    from: &GeoCoordinateStruct,
+    to: &GeoCoordinateStruct,
+    keep_out_zones: &[Vec<GeoCoordinateStruct>],
+    clearance_m: f64,
+) -> Result<Vec<GeoCoordinateStruct>, String> {
+    let zones: Vec<&[GeoCoordinateStruct]> = keep_out_zones
+        .iter()
+        .map(|z| open_ring(z))
+        .filter(|z| z.len() >= 3)
+        .collect();
+
+    let mut all_points = vec![from.clone(), to.clone()];
+    all_points.extend(zones.iter().flat_map(|z| z.iter().cloned()));
+    let projection = LocalProjection::around(&all_points);
+    let (start, goal) = (projection.to_xy(from), projection.to_xy(to));
+
+    let mut obstacles: Vec<Vec<Point>> = vec![];
+    let mut nodes: Vec<Point> = vec![start, goal];
+    for (index, zone) in zones.iter().enumerate() {
+        let outline: Vec<Point> = zone.iter().map(|c| projection.to_xy(c)).collect();
+        for (name, point) in [("Start", start), ("Destination", goal)] {
+            if point_in_polygon(point.0, point.1, &outline) {
+                return Err(format!("{} lies inside keep-out zone {}", name, index + 1));
+            }
+        }
+        let inflated = inflate(&outline, clearance_m);
+        nodes.extend(inflate(&outline, clearance_m + NODE_MARGIN_M));
+        let endpoint_in_band = [start, goal]
+            .iter()
+            .any(|p| point_in_polygon(p.0, p.1, &inflated));
+        obstacles.push(if endpoint_in_band { outline } else { inflated });
+    }
+
+    // Corners pushed into a neighbouring zone are unusable
+    let usable: Vec<bool> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, p)| i < 2 || !obstacles.iter().any(|ring| point_in_polygon(p.0, p.1, ring)))
+        .collect();
+
+    // Dijkstra over the implicit visibility graph; the node count stays in the hundreds
+    let n = nodes.len();
+    let distance = |a: Point, b: Point| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+    let mut best = vec![f64::INFINITY; n];
+    let mut previous: Vec<Option<usize>> = vec![None; n];
+    let mut done = vec![false; n];
+    best[0] = 0.0;
+    loop {
+        let current = (0..n)
+            .filter(|&i| !done[i] && usable[i] && best[i].is_finite())
+            .min_by(|&a, &b| best[a].total_cmp(&best[b]));
+        let Some(current) = current else { break };
+        if current == 1 {
+            break;
+        }
+        done[current] = true;
+        for next in 0..n {
+            if done[next] || !usable[next] || next == current {
+                continue;
+            }
+            let candidate = best[current] + distance(nodes[current], nodes[next]);
+            if candidate < best[next] && leg_is_clear(nodes[current], nodes[next], &obstacles) {
+                best[next] = candidate;
+                previous[next] = Some(current);
+            }
+        }
+    }
+
+    if !best[1].is_finite() {
+        return Err("No route avoids the keep-out zones".into());
+    }
+    let mut path = vec![1];
+    while let Some(p) = previous[*path.last().unwrap()] {
+        path.push(p);
+    }
+    path.reverse();
+    Ok(path
+        .into_iter()
+        .map(|i| match i {
+            0 => from.clone(),
+            1 => to.clone(),
+            _ => projection.to_coord(nodes[i].0, nodes[i].1),
+        })
+        .collect())
+}
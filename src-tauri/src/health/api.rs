@@ -0,0 +1,141 @@
+/*
+Define the health API: on-demand health checks, the periodic health_changed event and the
+backend heartbeat.
+*/
+use std::time::Duration;
+use sqlx::PgPool;
+use tauri::AppHandle;
+use taurpc::{procedures, resolvers};
+use tokio::time::interval;
+
+use crate::init_db::lazy_pool;
+use crate::clock;
+use crate::commands::serial;
+use crate::db_maintenance;
+use crate::logs;
+use crate::metrics;
+use crate::supervisor::{self, RestartPolicy};
+use crate::telemetry::rabbitmq::RabbitMQAPIImpl;
+use super::{check_database, check_disk, overall_status, BackendHeartbeat, HealthStatus, SystemHealth};
+
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const BACKEND_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+#[procedures(
+    event_trigger = HealthEventTrigger,
+    export_to = "../src/lib/bindings.ts",
+    path = "health"
+)]
+pub trait HealthApi {
+    #[taurpc(event)]
+    async fn health_changed(health: SystemHealth);
+    // Every second; nothing here waits on the database or broker, so only a hung backend stops it
+    #[taurpc(event)]
+    async fn backend_heartbeat(heartbeat: BackendHeartbeat);
+
+    async fn get_system_health() -> SystemHealth;
+}
+
+#[derive(Clone)]
+pub struct HealthApiImpl {
+    db: PgPool,
+    telemetry: RabbitMQAPIImpl,
+}
+
+impl HealthApiImpl {
+    pub async fn new(telemetry: RabbitMQAPIImpl) -> Self {
+        Self { db: lazy_pool(1), telemetry }
+    }
+
+    pub async fn check(&self) -> SystemHealth {
+        let disk_path = std::env::current_dir().unwrap_or_else(|_| ".".into());
+        let mut health = SystemHealth {
+            status: HealthStatus::Healthy,
+            database: check_database(&self.db).await,
+            rabbitmq: self.telemetry.rabbitmq_health().await,
+            heartbeat_monitor: self.telemetry.heartbeat_monitor_health().await,
+            command_transport: serial::health(),
+            database_maintenance: db_maintenance::health(),
+            disk: check_disk(&disk_path),
+            tasks: supervisor::statuses(),
+            checked_at: chrono::Utc::now().to_rfc3339(),
+        };
+        health.status = overall_status(&health);
+        health
+    }
+
+    // Re-check periodically; emit health_changed when status or component state changes
+    pub fn start_monitor(&self, app_handle: AppHandle) {
+        let api = self.clone();
+        supervisor::spawn("health_monitor", RestartPolicy::OnFailure, move || {
+            api.clone().run_monitor(app_handle.clone())
+        });
+    }
+
+    async fn run_monitor(self, app_handle: AppHandle) -> Result<(), String> {
+        let mut ticker = interval(HEALTH_CHECK_INTERVAL);
+        let mut previous: Option<SystemHealth> = None;
+        loop {
+            ticker.tick().await;
+            let health = self.check().await;
+
+            let changed = previous.as_ref().map_or(true, |prev| {
+                prev.status != health.status
+                    || prev.database.connected != health.database.connected
+                    || prev.rabbitmq != health.rabbitmq
+                    || prev.heartbeat_monitor.running != health.heartbeat_monitor.running
+                    || prev.command_transport.active != health.command_transport.active
+                    || prev.database_maintenance.last_run != health.database_maintenance.last_run
+                    || prev.tasks != health.tasks
+            });
+            if !changed {
+                continue;
+            }
+
+            if previous.as_ref().is_some_and(|prev| prev.status != health.status) {
+                logs::warn("health", format!("System health changed to {:?}", health.status));
+            }
+            if let Err(e) = HealthEventTrigger::new(app_handle.clone()).health_changed(health.clone()) {
+                logs::error("health", format!("Failed to emit health update: {}", e));
+            }
+            previous = Some(health);
+        }
+    }
+}
+
+// Emit backend_heartbeat until the app exits
+pub fn start_heartbeat(app_handle: AppHandle) {
+    let started = clock::now();
+    supervisor::spawn("backend_heartbeat", RestartPolicy::OnFailure, move || {
+        run_heartbeat(app_handle.clone(), started)
+    });
+}
+
+async fn run_heartbeat(app_handle: AppHandle, started: clock::Instant) -> Result<(), String> {
+    let mut ticker = clock::interval(BACKEND_HEARTBEAT_INTERVAL);
+    let mut sequence: u32 = 0;
+    loop {
+        ticker.tick().await;
+        sequence = sequence.wrapping_add(1);
+        let metrics = metrics::snapshot();
+        let heartbeat = BackendHeartbeat {
+            sequence,
+            uptime_secs: started.elapsed().as_secs_f64(),
+            interval_ms: BACKEND_HEARTBEAT_INTERVAL.as_millis() as u32,
+            telemetry_messages_per_sec: metrics.telemetry_messages_per_sec,
+            telemetry_messages_total: metrics.telemetry_messages_total,
+            pipeline_queues: metrics.pipeline_queues,
+            sent_at: chrono::Utc::now().to_rfc3339(),
+        };
+        if let Err(e) = HealthEventTrigger::new(app_handle.clone()).backend_heartbeat(heartbeat) {
+            logs::error("health", format!("Failed to emit backend heartbeat: {}", e));
+        }
+    }
+}
+
+#[resolvers]
+impl HealthApi for HealthApiImpl {
+    async fn get_system_health(self) -> SystemHealth {
+        self.check().await
+    }
+}
@@ -0,0 +1,105 @@
+/*
+Service health and diagnostics: database connectivity/latency, RabbitMQ connection and
+consumer state, heartbeat monitor liveness, the command transport in use, the last database
+maintenance run and free disk space. A background monitor re-checks periodically and emits
+health_changed when the picture changes; a separate task emits backend_heartbeat every second
+so the UI notices when the backend stops responding.
+*/
+use std::path::Path;
+use std::time::Instant;
+
+use crate::commands::serial::CommandTransport;
+use crate::supervisor::TaskState;
+
+pub mod api;
+pub mod types;
+
+pub use api::{start_heartbeat, HealthApi, HealthApiImpl, HealthEventTrigger};
+pub use types::*;
+
+// Below this much free space the disk is reported as degraded
+const LOW_DISK_SPACE_MB: f64 = 500.0;
+
+pub async fn check_database(db: &sqlx::PgPool) -> DatabaseHealth {
+    let started = Instant::now();
+    match sqlx::query("SELECT 1").execute(db).await {
+        Ok(_) => DatabaseHealth {
+            connected: true,
+            latency_ms: Some(started.elapsed().as_secs_f64() * 1000.0),
+            error: None,
+        },
+        Err(e) => DatabaseHealth {
+            connected: false,
+            latency_ms: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+pub fn check_disk(path: &Path) -> DiskHealth {
+    let (available_mb, total_mb) = match disk_space(path) {
+        Some((available, total)) => (Some(to_mb(available)), Some(to_mb(total))),
+        None => (None, None),
+    };
+    DiskHealth {
+        path: path.display().to_string(),
+        available_mb,
+        total_mb,
+    }
+}
+
+fn to_mb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+// (available, total) bytes on the filesystem holding `path`
+#[cfg(unix)]
+fn disk_space(path: &Path) -> Option<(u64, u64)> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is a valid NUL-terminated string and stats is a valid out pointer
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return None;
+    }
+    let block_size = stats.f_frsize as u64;
+    Some((
+        stats.f_bavail as u64 * block_size,
+        stats.f_blocks as u64 * block_size,
+    ))
+}
+
+#[cfg(not(unix))]
+fn disk_space(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
+
+// Overall status: any hard failure is unhealthy; stopped consumers, restarting tasks or low
+// disk are degraded
+pub fn overall_status(health: &SystemHealth) -> HealthStatus {
+    if !health.database.connected || !health.rabbitmq.connected || !health.heartbeat_monitor.running {
+        return HealthStatus::Unhealthy;
+    }
+    let consumer_down = health
+        .rabbitmq
+        .consumers
+        .iter()
+        .any(|c| c.state != ConsumerState::Running);
+    let task_restarting = health
+        .tasks
+        .iter()
+        .any(|t| t.state == TaskState::Restarting);
+    let low_disk = health
+        .disk
+        .available_mb
+        .is_some_and(|available| available < LOW_DISK_SPACE_MB);
+    // Commands still go out, but over the slower serial radio
+    let on_serial = health.command_transport.active == CommandTransport::Serial;
+    if consumer_down || task_restarting || low_disk || on_serial {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Healthy
+    }
+}
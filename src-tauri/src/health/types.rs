@@ -0,0 +1,84 @@
+/*
+Define service health types shared with the frontend status bar.
+*/
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::commands::serial::CommandTransportHealth;
+use crate::db_maintenance::DatabaseMaintenanceHealth;
+use crate::metrics::QueueDepth;
+use crate::supervisor::TaskStatus;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Type)]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct DatabaseHealth {
+    pub connected: bool,
+    pub latency_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum ConsumerState {
+    Starting,
+    Running,
+    Stopped,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct QueueConsumerHealth {
+    pub queue: String,
+    pub state: ConsumerState,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct RabbitMqHealth {
+    pub connected: bool,
+    pub consumers: Vec<QueueConsumerHealth>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct HeartbeatMonitorHealth {
+    pub running: bool,
+    pub last_tick_age_ms: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct DiskHealth {
+    pub path: String,
+    pub available_mb: Option<f64>,
+    pub total_mb: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct SystemHealth {
+    pub status: HealthStatus,
+    pub database: DatabaseHealth,
+    pub rabbitmq: RabbitMqHealth,
+    pub heartbeat_monitor: HeartbeatMonitorHealth,
+    pub command_transport: CommandTransportHealth,
+    pub database_maintenance: DatabaseMaintenanceHealth,
+    pub disk: DiskHealth,
+    pub tasks: Vec<TaskStatus>,
+    pub checked_at: String,
+}
+
+// Sent every interval_ms while the backend runs. Vehicles going quiet leave it flowing; when it
+// stops, the backend itself is hung.
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct BackendHeartbeat {
+    // Increments with every heartbeat; a gap means the frontend missed some
+    pub sequence: u32,
+    pub uptime_secs: f64,
+    pub interval_ms: u32,
+    pub telemetry_messages_per_sec: f64,
+    pub telemetry_messages_total: u32,
+    pub pipeline_queues: Vec<QueueDepth>,
+    pub sent_at: String,
+}
@@ -0,0 +1,72 @@
+/*
+Define the input API: start and stop gamepad manual control of a vehicle and report the
+gamepad and session state.
+*/
+use tauri::{AppHandle, Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, OperatorRole};
+use crate::commands::capabilities::{capabilities_for, CommandType};
+use crate::commands::CommandsApiImpl;
+use crate::logs;
+use super::{gamepad, ManualControlStatus};
+
+#[procedures(event_trigger = InputEventTrigger, export_to = "../src/lib/bindings.ts", path = "input")]
+pub trait InputApi {
+    #[taurpc(event)]
+    async fn on_manual_control_changed(status: ManualControlStatus);
+
+    async fn get_manual_control_status() -> ManualControlStatus;
+    // Stick input is only sent while the deadman button from the settings is held
+    async fn start_manual_control(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        vehicle_id: String,
+    ) -> Result<ManualControlStatus, String>;
+    async fn stop_manual_control() -> ManualControlStatus;
+}
+
+#[derive(Clone)]
+pub struct InputApiImpl {
+    commands: CommandsApiImpl,
+}
+
+impl InputApiImpl {
+    pub fn new(commands: CommandsApiImpl) -> Self {
+        Self { commands }
+    }
+}
+
+#[resolvers]
+impl InputApi for InputApiImpl {
+    async fn get_manual_control_status(self) -> ManualControlStatus {
+        super::status()
+    }
+
+    async fn start_manual_control(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        vehicle_id: String,
+    ) -> Result<ManualControlStatus, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let capabilities = capabilities_for(&vehicle_id)
+                .ok_or(format!("Unknown vehicle: {}", vehicle_id))?;
+            capabilities.require(CommandType::ManualControl)?;
+            self.commands.require_no_hold(CommandType::ManualControl)?;
+            let pad = gamepad::snapshot().ok_or("No gamepad connected")?;
+            logs::info(
+                "input",
+                format!("Manual control of {} started with {}", capabilities.vehicle_id, pad.name),
+            );
+            super::start_session(self.commands, app_handle, capabilities.vehicle_id);
+            Ok(super::status())
+        })
+        .await
+    }
+
+    async fn stop_manual_control(self) -> ManualControlStatus {
+        super::stop_manual_control(true);
+        super::status()
+    }
+}
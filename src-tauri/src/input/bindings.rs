@@ -0,0 +1,102 @@
+/*
+Gamepad bindings for manual control, stored with the operator settings. Each control channel
+reads one gamepad axis; the deadman button must be held for any stick input to be sent.
+*/
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::commands::types::ManualControlInput;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Type)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    // Analog triggers, reported from 0 (released) to 1 (fully pressed)
+    LeftTrigger,
+    RightTrigger,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Type)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    LeftThumb,
+    RightThumb,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct AxisBinding {
+    pub axis: GamepadAxis,
+    pub inverted: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+#[serde(default)]
+pub struct ManualControlBindings {
+    pub roll: AxisBinding,
+    pub pitch: AxisBinding,
+    pub yaw: AxisBinding,
+    pub throttle: AxisBinding,
+    pub deadman: GamepadButton,
+    // Sends HoldPosition while manual control is engaged
+    pub hold_button: Option<GamepadButton>,
+    // Axis values with a smaller magnitude are sent as 0
+    pub deadzone: f64,
+}
+
+impl Default for ManualControlBindings {
+    // Mode 2 layout: throttle and yaw on the left stick, pitch and roll on the right
+    fn default() -> Self {
+        let axis = |axis| AxisBinding { axis, inverted: false };
+        Self {
+            roll: axis(GamepadAxis::RightStickX),
+            pitch: axis(GamepadAxis::RightStickY),
+            yaw: axis(GamepadAxis::LeftStickX),
+            throttle: axis(GamepadAxis::LeftStickY),
+            deadman: GamepadButton::LeftBumper,
+            hold_button: Some(GamepadButton::South),
+            deadzone: 0.05,
+        }
+    }
+}
+
+impl ManualControlBindings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..0.5).contains(&self.deadzone) {
+            return Err("Gamepad deadzone must be between 0 and 0.5".into());
+        }
+        if self.hold_button == Some(self.deadman) {
+            return Err("The hold button cannot also be the deadman button".into());
+        }
+        Ok(())
+    }
+
+    fn apply(&self, binding: &AxisBinding, read: &impl Fn(GamepadAxis) -> f64) -> f64 {
+        let value = read(binding.axis).clamp(-1.0, 1.0);
+        let value = if binding.inverted { -value } else { value };
+        if value.abs() < self.deadzone {
+            return 0.0;
+        }
+        // Rescale so output starts from 0 at the deadzone edge instead of jumping
+        value.signum() * (value.abs() - self.deadzone) / (1.0 - self.deadzone)
+    }
+
+    pub fn map(&self, read: impl Fn(GamepadAxis) -> f64) -> ManualControlInput {
+        ManualControlInput {
+            roll: self.apply(&self.roll, &read),
+            pitch: self.apply(&self.pitch, &read),
+            yaw: self.apply(&self.yaw, &read),
+            throttle: self.apply(&self.throttle, &read),
+        }
+    }
+}
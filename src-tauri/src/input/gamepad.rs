@@ -0,0 +1,142 @@
+/*
+Gamepad polling through gilrs. gilrs is driven from a dedicated thread (its context is not
+Send on every platform); the thread keeps a snapshot of the first connected gamepad that the
+async manual-control stream reads at its own rate.
+*/
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+use gilrs::{Axis, Button, Gilrs};
+use lazy_static::lazy_static;
+
+use crate::logs;
+use super::bindings::{GamepadAxis, GamepadButton};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+const AXES: [GamepadAxis; 6] = [
+    GamepadAxis::LeftStickX,
+    GamepadAxis::LeftStickY,
+    GamepadAxis::RightStickX,
+    GamepadAxis::RightStickY,
+    GamepadAxis::LeftTrigger,
+    GamepadAxis::RightTrigger,
+];
+
+const BUTTONS: [GamepadButton; 12] = [
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::North,
+    GamepadButton::West,
+    GamepadButton::LeftBumper,
+    GamepadButton::RightBumper,
+    GamepadButton::LeftTrigger,
+    GamepadButton::RightTrigger,
+    GamepadButton::Select,
+    GamepadButton::Start,
+    GamepadButton::LeftThumb,
+    GamepadButton::RightThumb,
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct GamepadSnapshot {
+    pub name: String,
+    axes: HashMap<GamepadAxis, f64>,
+    pressed: HashSet<GamepadButton>,
+}
+
+impl GamepadSnapshot {
+    pub fn axis(&self, axis: GamepadAxis) -> f64 {
+        self.axes.get(&axis).copied().unwrap_or(0.0)
+    }
+
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.pressed.contains(&button)
+    }
+}
+
+lazy_static! {
+    // None while no gamepad is connected
+    static ref SNAPSHOT: Mutex<Option<GamepadSnapshot>> = Mutex::new(None);
+}
+
+fn to_gilrs_button(button: GamepadButton) -> Button {
+    match button {
+        GamepadButton::South => Button::South,
+        GamepadButton::East => Button::East,
+        GamepadButton::North => Button::North,
+        GamepadButton::West => Button::West,
+        GamepadButton::LeftBumper => Button::LeftTrigger,
+        GamepadButton::RightBumper => Button::RightTrigger,
+        GamepadButton::LeftTrigger => Button::LeftTrigger2,
+        GamepadButton::RightTrigger => Button::RightTrigger2,
+        GamepadButton::Select => Button::Select,
+        GamepadButton::Start => Button::Start,
+        GamepadButton::LeftThumb => Button::LeftThumb,
+        GamepadButton::RightThumb => Button::RightThumb,
+    }
+}
+
+pub fn snapshot() -> Option<GamepadSnapshot> {
+    SNAPSHOT.lock().unwrap().clone()
+}
+
+fn read(gilrs: &Gilrs) -> Option<GamepadSnapshot> {
+    let (_, gamepad) = gilrs.gamepads().find(|(_, gamepad)| gamepad.is_connected())?;
+    // gilrs reports analog triggers as buttons with a 0..1 value
+    let trigger = |button| gamepad.button_data(button).map_or(0.0, |data| data.value() as f64);
+    let axes = AXES
+        .iter()
+        .map(|axis| {
+            let value = match axis {
+                GamepadAxis::LeftStickX => gamepad.value(Axis::LeftStickX) as f64,
+                GamepadAxis::LeftStickY => gamepad.value(Axis::LeftStickY) as f64,
+                GamepadAxis::RightStickX => gamepad.value(Axis::RightStickX) as f64,
+                GamepadAxis::RightStickY => gamepad.value(Axis::RightStickY) as f64,
+                GamepadAxis::LeftTrigger => trigger(Button::LeftTrigger2),
+                GamepadAxis::RightTrigger => trigger(Button::RightTrigger2),
+            };
+            (*axis, value)
+        })
+        .collect();
+    let pressed = BUTTONS
+        .iter()
+        .copied()
+        .filter(|button| gamepad.is_pressed(to_gilrs_button(*button)))
+        .collect();
+    Some(GamepadSnapshot { name: gamepad.name().to_string(), axes, pressed })
+}
+
+// Start the polling thread; without gamepad support the snapshot simply stays empty
+pub fn start_polling() {
+    let spawned = std::thread::Builder::new()
+        .name("gamepad".into())
+        .spawn(|| {
+            let mut gilrs = match Gilrs::new() {
+                Ok(gilrs) => gilrs,
+                Err(e) => {
+                    logs::warn("input", format!("Gamepad input unavailable: {}", e));
+                    return;
+                }
+            };
+            let mut connected: Option<String> = None;
+            loop {
+                // Draining events is what updates gilrs' cached gamepad state
+                while gilrs.next_event().is_some() {}
+                let current = read(&gilrs);
+                let name = current.as_ref().map(|s| s.name.clone());
+                if name != connected {
+                    match &name {
+                        Some(name) => logs::info("input", format!("Gamepad connected: {}", name)),
+                        None => logs::warn("input", "Gamepad disconnected"),
+                    }
+                    connected = name;
+                }
+                *SNAPSHOT.lock().unwrap() = current;
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+    if let Err(e) = spawned {
+        logs::error("input", format!("Failed to start gamepad polling: {}", e));
+    }
+}
@@ -0,0 +1,187 @@
+/*
+Manual control from a gamepad. While a session is started for a vehicle, the gamepad is
+sampled at `manual_control_rate_hz` and each frame is mapped through the operator's bindings
+and sent through the commands module. Stick input is only sent while the deadman button is
+held: releasing it, losing the gamepad or stopping the session sends HoldPosition so the
+vehicle does not continue on stale input. An emergency stop ends the session without a hold.
+*/
+use std::sync::Mutex;
+use std::time::Duration;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Runtime};
+use tokio::time::{interval, MissedTickBehavior};
+
+use crate::commands::ping;
+use crate::commands::types::ManualControlInput;
+use crate::commands::CommandsApiImpl;
+use crate::config;
+use crate::logs;
+use crate::settings;
+
+pub mod api;
+pub mod bindings;
+pub mod gamepad;
+
+pub use api::{InputApi, InputApiImpl, InputEventTrigger};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct ManualControlStatus {
+    pub gamepad_connected: bool,
+    pub gamepad_name: Option<String>,
+    // Vehicle of the running session, if any
+    pub vehicle_id: Option<String>,
+    // Whether stick input is currently being streamed (deadman held)
+    pub engaged: bool,
+    pub last_input: Option<ManualControlInput>,
+    pub rate_hz: u32,
+    // Command link round trip to the session's vehicle, when measured
+    pub link_latency_ms: Option<f64>,
+}
+
+#[derive(Default)]
+struct Session {
+    // Bumped on every start/stop so a superseded stream task exits
+    generation: u64,
+    vehicle_id: Option<String>,
+    engaged: bool,
+    last_input: Option<ManualControlInput>,
+    // Generation of the last stop that must not be followed by a hold (emergency stop)
+    no_hold_generation: u64,
+}
+
+lazy_static! {
+    static ref SESSION: Mutex<Session> = Mutex::new(Session::default());
+}
+
+pub fn status() -> ManualControlStatus {
+    let session = SESSION.lock().unwrap();
+    let pad = gamepad::snapshot();
+    ManualControlStatus {
+        gamepad_connected: pad.is_some(),
+        gamepad_name: pad.map(|p| p.name),
+        vehicle_id: session.vehicle_id.clone(),
+        engaged: session.engaged,
+        last_input: session.last_input,
+        rate_hz: config::get().manual_control_rate_hz,
+        link_latency_ms: session.vehicle_id.as_deref().and_then(ping::latency_ms),
+    }
+}
+
+fn begin(vehicle_id: &str) -> u64 {
+    let mut session = SESSION.lock().unwrap();
+    session.generation += 1;
+    session.vehicle_id = Some(vehicle_id.to_string());
+    session.engaged = false;
+    session.last_input = None;
+    session.generation
+}
+
+// End the running session; `hold` sends HoldPosition if stick input was being streamed.
+// Returns the vehicle that was under manual control.
+pub fn stop_manual_control(hold: bool) -> Option<String> {
+    let mut session = SESSION.lock().unwrap();
+    session.generation += 1;
+    if !hold {
+        session.no_hold_generation = session.generation;
+    }
+    session.engaged = false;
+    session.vehicle_id.take()
+}
+
+fn is_current(generation: u64) -> bool {
+    SESSION.lock().unwrap().generation == generation
+}
+
+fn set_engaged(generation: u64, engaged: bool, last_input: Option<ManualControlInput>) {
+    let mut session = SESSION.lock().unwrap();
+    if session.generation == generation {
+        session.engaged = engaged;
+        session.last_input = last_input;
+    }
+}
+
+fn emit_status(app_handle: &AppHandle<impl Runtime>) {
+    if let Err(e) = InputEventTrigger::new(app_handle.clone()).on_manual_control_changed(status()) {
+        logs::error("input", format!("Failed to emit manual control status: {}", e));
+    }
+}
+
+async fn hold(commands: &CommandsApiImpl, vehicle_id: &str) {
+    if let Err(e) = commands.hold_position(vehicle_id.to_string()).await {
+        logs::error("input", format!("Failed to hold {} after manual control: {}", vehicle_id, e));
+    }
+}
+
+async fn run_session<R: Runtime>(
+    commands: CommandsApiImpl,
+    app_handle: AppHandle<R>,
+    vehicle_id: String,
+    generation: u64,
+) {
+    let rate_hz = config::get().manual_control_rate_hz.max(1);
+    let mut ticker = interval(Duration::from_secs_f64(1.0 / rate_hz as f64));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut engaged = false;
+    let mut hold_pressed = false;
+    let mut failing = false;
+
+    loop {
+        ticker.tick().await;
+        if !is_current(generation) {
+            break;
+        }
+        let bindings = settings::current().manual_control;
+        let pad = gamepad::snapshot();
+        let deadman = pad.as_ref().is_some_and(|p| p.is_pressed(bindings.deadman));
+        if deadman != engaged {
+            engaged = deadman;
+            set_engaged(generation, engaged, None);
+            if engaged {
+                logs::info("input", format!("Manual control of {} engaged", vehicle_id));
+            } else {
+                logs::warn("input", format!("Deadman released, holding {}", vehicle_id));
+                hold(&commands, &vehicle_id).await;
+            }
+            emit_status(&app_handle);
+        }
+        let Some(pad) = pad.filter(|_| engaged) else { continue };
+
+        let hold_now = bindings.hold_button.is_some_and(|b| pad.is_pressed(b));
+        if hold_now && !hold_pressed {
+            hold(&commands, &vehicle_id).await;
+        }
+        hold_pressed = hold_now;
+        if hold_now {
+            continue;
+        }
+
+        let input = bindings.map(|axis| pad.axis(axis));
+        set_engaged(generation, true, Some(input));
+        match commands.send_manual_control(&vehicle_id, input).await {
+            Ok(()) => failing = false,
+            Err(e) => {
+                // Log the first failure of a run, not one per frame
+                if !failing {
+                    logs::warn("input", format!("Manual control frame for {} failed: {}", vehicle_id, e));
+                }
+                failing = true;
+            }
+        }
+    }
+
+    let hold_on_stop = SESSION.lock().unwrap().no_hold_generation <= generation;
+    if engaged && hold_on_stop {
+        hold(&commands, &vehicle_id).await;
+    }
+    logs::info("input", format!("Manual control of {} ended", vehicle_id));
+    emit_status(&app_handle);
+}
+
+// Start streaming gamepad input to a vehicle, replacing any running session
+pub fn start_session<R: Runtime>(commands: CommandsApiImpl, app_handle: AppHandle<R>, vehicle_id: String) {
+    stop_manual_control(true);
+    let generation = begin(&vehicle_id);
+    tauri::async_runtime::spawn(run_session(commands, app_handle, vehicle_id, generation));
+}
@@ -0,0 +1,440 @@
+/*
+Application library: every backend module and the Tauri entry point run(). main.rs only calls
+run(); the library split lets the criterion benchmarks in benches/ reach the hot paths through
+the bench module.
+*/
+use std::env;
+use taurpc::Router;
+mod missions;
+mod telemetry;
+mod commands;
+mod auth;
+mod config;
+mod logs;
+mod debug_feed;
+mod health;
+mod metrics;
+mod supervisor;
+mod clock;
+mod events;
+mod snapshot;
+mod startup;
+mod settings;
+mod vehicles;
+mod notifications;
+mod annotations;
+mod altitude_bands;
+mod stage_templates;
+mod targets;
+mod maintenance;
+mod audit;
+mod exports;
+mod backup;
+mod db_maintenance;
+mod coordinates;
+mod geometry;
+mod geofence;
+mod units;
+mod terrain;
+mod tiles;
+mod weather;
+mod adsb;
+mod video;
+mod input;
+mod rest;
+mod ws;
+mod mqtt;
+mod protocols;
+mod mission_sync;
+mod ops_feed;
+mod simulator;
+mod faults;
+mod resync;
+mod window_routing;
+
+#[doc(hidden)]
+pub mod bench;
+
+use crate::telemetry::rabbitmq::RabbitMQAPI;
+use missions::api::{MissionApi, MissionApiImpl};
+use telemetry::rabbitmq::RabbitMQAPIImpl;
+use commands::{CommandsApiImpl};
+use commands::commands::CommandsApi;
+use auth::{AuthApi, AuthApiImpl};
+use config::{ConfigApi, ConfigApiImpl};
+use logs::{LogsApi, LogsApiImpl};
+use debug_feed::{DebugFeedApi, DebugFeedApiImpl};
+use health::{HealthApi, HealthApiImpl};
+use metrics::{MetricsApi, MetricsApiImpl};
+use annotations::{AnnotationsApi, AnnotationsApiImpl};
+use altitude_bands::{AltitudeBandsApi, AltitudeBandsApiImpl};
+use stage_templates::{StageTemplatesApi, StageTemplatesApiImpl};
+use notifications::{NotificationsApi, NotificationsApiImpl};
+use exports::{ExportsApi, ExportsApiImpl};
+use backup::{BackupApi, BackupApiImpl};
+use db_maintenance::{DbMaintenanceApi, DbMaintenanceApiImpl};
+use coordinates::{CoordinatesApi, CoordinatesApiImpl};
+use geometry::{GeometryApi, GeometryApiImpl};
+use geofence::{GeofenceApi, GeofenceApiImpl};
+use terrain::{TerrainApi, TerrainApiImpl};
+use tiles::{TilesApi, TilesApiImpl};
+use weather::{WeatherApi, WeatherApiImpl};
+use adsb::{AdsbApi, AdsbApiImpl};
+use video::{VideoApi, VideoApiImpl};
+use input::{InputApi, InputApiImpl};
+use mission_sync::{MissionSyncApi, MissionSyncApiImpl};
+use simulator::{SimulatorApi, SimulatorApiImpl};
+use faults::{FaultsApi, FaultsApiImpl};
+use resync::{ResyncApi, ResyncApiImpl};
+use window_routing::{WindowRoutingApi, WindowRoutingApiImpl};
+use targets::{TargetsApi, TargetsApiImpl};
+use maintenance::{MaintenanceApi, MaintenanceApiImpl};
+use audit::{AuditApi, AuditApiImpl};
+use settings::{SettingsApi, SettingsApiImpl};
+use vehicles::{VehiclesApi, VehiclesApiImpl};
+use startup::{Preflight, StartupApi, StartupApiImpl, StartupEventTrigger};
+mod init_db;
+use init_db::{clear_database, init_database_dummy_data};
+
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager, RunEvent, WindowEvent};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+fn spawn_opencv_sidecar(app_handle: tauri::AppHandle) -> Result<(), String> {
+    // Check if a sidecar process already exists
+    if let Some(state) = app_handle.try_state::<Arc<Mutex<Option<CommandChild>>>>() {
+        let child_process = state.lock().unwrap();
+        if child_process.is_some() {
+            println!("[tauri] Sidecar is already running. Skipping spawn.");
+            return Ok(());
+        }
+    }
+
+    // Spawn sidecar (sidecar function only expects the filename, not the whole path configured in externalBin)
+    let sidecar_command = app_handle
+        .shell()
+        .sidecar("opencv")
+        .map_err(|e| {println!("[tauri] Error constructing sidecar: {}", e.to_string()); e.to_string()})?;
+    let (mut rx, child) = sidecar_command.spawn().map_err(|e| {println!("[tauri] Error running sidecar: {}", e.to_string()); e.to_string()})?;
+
+    // Store the child process in the app state
+    if let Some(state) = app_handle.try_state::<Arc<Mutex<Option<CommandChild>>>>() {
+        *state.lock().unwrap() = Some(child);
+    } else {
+        return Err("Failed to access app state".to_string());
+    }
+
+    // Spawn an async task to handle sidecar communication
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(line_bytes) => {
+                    let line = String::from_utf8_lossy(&line_bytes);
+                    print!("[sidecar] {}", line);
+
+                    // Emit the line to the frontend
+                    app_handle
+                        .emit("sidecar-stdout", line.to_string())
+                        .expect("Failed to emit sidecar stdout event");
+                }
+
+                CommandEvent::Stderr(line_bytes) => {
+                    let line = String::from_utf8_lossy(&line_bytes);
+                    eprint!("[sidecar] {}", line);
+                    // Emit the error line to the frontend
+                    app_handle
+                        .emit("sidecar-stderr", line.to_string())
+                        .expect("Failed to emit sidecar stderr event");
+                }
+                _ => {}
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+fn start_sidecar(app_handle: tauri::AppHandle) -> Result<String, String> {
+    println!("[tauri] Received command to start sidecar.");
+    spawn_opencv_sidecar(app_handle)?;
+    Ok("Sidecar spawned and monitoring started.".to_string())
+}
+
+#[tauri::command]
+fn shutdown_sidecar(app_handle: tauri::AppHandle) -> Result<String, String> {
+    println!("[tauri] Received command to shutdown sidecar.");
+    // Access the sidecar process state
+    if let Some(state) = app_handle.try_state::<Arc<Mutex<Option<CommandChild>>>>() {
+        let mut child_process = state
+            .lock()
+            .map_err(|_| "[tauri] Failed to acquire lock on sidecar process.")?;
+
+        if let Some(mut process) = child_process.take() {
+            let command = "sidecar shutdown\n"; // Add newline to signal the end of the command
+
+            // Attempt to write the command to the sidecar's stdin
+            if let Err(err) = process.write(command.as_bytes()) {
+                println!("[tauri] Failed to write to sidecar stdin: {}", err);
+
+                // Restore the process reference if shutdown fails
+                *child_process = Some(process);
+                return Err(format!("Failed to write to sidecar stdin: {}", err));
+            }
+
+            println!("[tauri] Sent 'sidecar shutdown' command to sidecar.");
+            Ok("'sidecar shutdown' command sent.".to_string())
+        } else {
+            println!("[tauri] No active sidecar process to shutdown.");
+            Err("No active sidecar process to shutdown.".to_string())
+        }
+    } else {
+        Err("Sidecar process state not found.".to_string())
+    }
+}
+
+#[tokio::main]
+pub async fn run() {
+    let env_loaded = dotenvy::dotenv()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to load .env file: {}", e));
+
+    // Preflight: report missing dependencies instead of panicking in constructors
+    let mut preflight = Preflight::default();
+    preflight.check_env_file(env_loaded);
+    preflight.check_config();
+
+    if preflight.check_database().await {
+        if env::var("CLEAR_DATABASE_EVERYTIME")
+            .unwrap_or_default()
+            .to_lowercase()
+            == "true"
+        {
+            println!("Clearing database");
+            clear_database().await;
+        }
+
+        if preflight.apply_migrations().await {
+            if env::var("DUMMY_DATA_ENABLED")
+                .unwrap_or_default()
+                .to_lowercase()
+                == "true"
+            {
+                println!("Seeding dummy data...");
+                init_database_dummy_data().await;
+            }
+            preflight.reconcile_missions().await;
+        } else {
+            preflight.skip("Mission recovery", "migrations failed");
+        }
+    } else {
+        preflight.skip("Migrations", "database unreachable");
+        preflight.skip("Mission recovery", "database unreachable");
+    }
+
+    preflight.check_broker().await;
+    let startup_report = preflight.finish();
+
+    // Initialize APIs outside of Tauri setup
+    let rabbitmq_api = RabbitMQAPIImpl::new().await;
+
+    let commands_api = CommandsApiImpl::new().await.with_heartbeats(rabbitmq_api.heartbeat_handle());
+    commands_api.start_queue_worker();
+    commands_api.start_link_pinger();
+    let commands_handler = commands_api.clone();
+
+    let rabbitmq_api = rabbitmq_api.with_commands(commands_api.clone());
+    let missions_api = MissionApiImpl::new().await.with_commands(commands_api.clone());
+    let rabbitmq_api = rabbitmq_api
+        .with_lost_link_handler(Arc::new(missions_api.clone()))
+        .with_patient_sensor_handler(Arc::new(missions_api.clone()));
+    let auth_api = AuthApiImpl::new().await;
+    let health_api = HealthApiImpl::new(rabbitmq_api.clone()).await;
+    let settings_api = SettingsApiImpl::new().await;
+    let vehicles_api =
+        VehiclesApiImpl::new(missions_api.clone(), rabbitmq_api.clone(), commands_api.clone()).await;
+    let annotations_api = AnnotationsApiImpl::new().await;
+    let altitude_bands_api = AltitudeBandsApiImpl::new().await;
+    let stage_templates_api = StageTemplatesApiImpl::new().await;
+    let targets_api = TargetsApiImpl::new().await;
+    let maintenance_api = MaintenanceApiImpl::new().await;
+    let audit_api = AuditApiImpl::new().await;
+    let exports_api = ExportsApiImpl::new().await;
+    let backup_api = BackupApiImpl::new(missions_api.clone());
+    let db_maintenance_api = DbMaintenanceApiImpl::new().await;
+    let weather_api = WeatherApiImpl::new().await;
+    let video_api = VideoApiImpl::new().await;
+    let input_api = InputApiImpl::new(commands_api.clone());
+    let resync_api = ResyncApiImpl::new(missions_api.clone(), rabbitmq_api.clone());
+
+    let rest_state = rest::RestState {
+        missions: missions_api.clone(),
+        telemetry: rabbitmq_api.clone(),
+        health: health_api.clone(),
+    };
+    let ws_telemetry = rabbitmq_api.clone();
+    let sync_missions = missions_api.clone();
+
+    // Create router with both handlers
+    let router = Router::new()
+        .merge(missions_api.into_handler())
+        .merge(rabbitmq_api.clone().into_handler())
+        .merge(commands_handler.into_handler())
+        .merge(auth_api.into_handler())
+        .merge(ConfigApiImpl.into_handler())
+        .merge(LogsApiImpl.into_handler())
+        .merge(DebugFeedApiImpl.into_handler())
+        .merge(health_api.clone().into_handler())
+        .merge(MetricsApiImpl.into_handler())
+        .merge(StartupApiImpl.into_handler())
+        .merge(settings_api.into_handler())
+        .merge(vehicles_api.into_handler())
+        .merge(NotificationsApiImpl.into_handler())
+        .merge(annotations_api.into_handler())
+        .merge(altitude_bands_api.into_handler())
+        .merge(stage_templates_api.into_handler())
+        .merge(targets_api.into_handler())
+        .merge(maintenance_api.into_handler())
+        .merge(audit_api.into_handler())
+        .merge(exports_api.into_handler())
+        .merge(backup_api.into_handler())
+        .merge(db_maintenance_api.into_handler())
+        .merge(CoordinatesApiImpl.into_handler())
+        .merge(GeometryApiImpl.into_handler())
+        .merge(GeofenceApiImpl.into_handler())
+        .merge(TerrainApiImpl.into_handler())
+        .merge(TilesApiImpl.into_handler())
+        .merge(weather_api.clone().into_handler())
+        .merge(AdsbApiImpl.into_handler())
+        .merge(video_api.into_handler())
+        .merge(input_api.into_handler())
+        .merge(MissionSyncApiImpl.into_handler())
+        .merge(SimulatorApiImpl.into_handler())
+        .merge(FaultsApiImpl.into_handler())
+        .merge(resync_api.into_handler())
+        .merge(WindowRoutingApiImpl.into_handler());
+
+    let router_handler = router.into_handler();
+    startup::export_bindings_version();
+
+    tauri::Builder::default()
+        .plugin(tauri_plugin_shell::init())
+        // Offline map tiles: tiles://localhost/{z}/{x}/{y} (http://tiles.localhost/... on Windows)
+        .register_asynchronous_uri_scheme_protocol("tiles", |_ctx, request, responder| {
+            let path = request.uri().path().to_string();
+            tauri::async_runtime::spawn(async move {
+                responder.respond(tiles::serve(&path).await);
+            });
+        })
+        .setup(move |app| {
+            // Stream backend log events to the frontend log viewer
+            logs::set_app_handle(app.handle().clone());
+            debug_feed::set_app_handle(app.handle().clone());
+            notifications::set_app_handle(app.handle().clone());
+            health_api.start_monitor(app.handle().clone());
+            health::start_heartbeat(app.handle().clone());
+            weather_api.start_poller(app.handle().clone());
+            AdsbApiImpl.start_feed(app.handle().clone());
+            input::gamepad::start_polling();
+            rest::start(rest_state);
+            ws::start(ws_telemetry);
+            MissionSyncApiImpl.start(sync_missions, app.handle().clone());
+            ops_feed::start();
+            db_maintenance::start();
+
+            if let Err(e) = StartupEventTrigger::new(app.handle().clone()).on_startup_report(startup_report) {
+                logs::error("startup", format!("Failed to emit startup report: {}", e));
+            }
+
+            // Store the initial sidecar process in the app state
+            app.manage(Arc::new(Mutex::new(None::<CommandChild>)));
+            // Spawn the Python sidecar on startup
+            println!("[tauri] Creating sidecar...");
+            let sidecar_handle = app.handle().clone();
+            spawn_opencv_sidecar(sidecar_handle).ok();
+            println!("[tauri] Sidecar spawned and monitoring started.");
+
+            let rabbitmq_handle = app.handle().clone();
+            let rabbitmq = rabbitmq_api.with_app_handle(rabbitmq_handle);
+            rabbitmq.start_pipeline();
+            mqtt::start(rabbitmq.clone());
+            simulator::api::set_pipeline(rabbitmq.clone());
+
+            if env::var("INITIALIZE_RABBITMQ")
+                .unwrap_or_default()
+                .to_lowercase()
+                == "true"
+            {
+                // Initialize consumers
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = rabbitmq.init_consumers().await {
+                        logs::error("telemetry", format!("Failed to initialize telemetry consumers: {}", e));
+                    }
+                });
+            }
+
+            if env::var("TEST_PUBLISHER")
+                .unwrap_or_default()
+                .to_lowercase()
+                == "true"
+            {
+                // Start test publisher
+                tauri::async_runtime::spawn(async {
+                    println!("🚀 Starting RabbitMQ test publisher");
+                    if let Err(e) = telemetry::publisher::test_publisher().await {
+                        eprintln!("❌ Test publisher failed: {}", e);
+                    } else {
+                        println!("✅ Test publisher finished");
+                    }
+                });
+            }
+
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![start_sidecar, shutdown_sidecar,])
+        .invoke_handler(move |invoke| router_handler(invoke))
+        .build(tauri::generate_context!())
+        .expect("Error while running tauri application")
+        .run(|app_handle, event| match event {
+            // Ensure the Python sidecar is killed when the app is closed
+            RunEvent::ExitRequested { .. } => {
+                if let Some(child_process) =
+                    app_handle.try_state::<Arc<Mutex<Option<CommandChild>>>>()
+                {
+                    if let Ok(mut child) = child_process.lock() {
+                        if let Some(process) = child.as_mut() {
+                            // Send msg via stdin to sidecar where it self terminates
+                            let command = "sidecar shutdown\n";
+                            let buf: &[u8] = command.as_bytes();
+                            let _ = process.write(buf);
+
+                            // Force kill the process after a short delay to ensure cleanup
+                            std::thread::sleep(std::time::Duration::from_millis(500));
+                            if let Some(process) = child.take() {
+                                let _ = process.kill();
+                            }
+
+                            // TODO: This is kind of messy, find a better way to clear, preferably cross platform
+                            // Additional cleanup for Windows
+                            #[cfg(target_os = "windows")]
+                            {
+                                use std::process::Command;
+                                let _ = Command::new("taskkill")
+                                    .args(["/F", "/IM", "opencv.exe"])
+                                    .output();
+                            }
+
+                            println!("[tauri] Sidecar closed.");
+                        }
+                    }
+                }
+            }
+            // A label opened again later starts with every channel and nobody logged in
+            RunEvent::WindowEvent { label, event: WindowEvent::Destroyed, .. } => {
+                window_routing::unsubscribe(&label);
+                auth::session::end_session(&label);
+            }
+            _ => {}
+        });
+}
@@ -0,0 +1,39 @@
+/*
+Define the logs API: query the in-memory log buffer and subscribe to new log events.
+*/
+use taurpc::{procedures, resolvers};
+
+use super::{LogEntry, LogLevel};
+
+const DEFAULT_LOG_LIMIT: u32 = 200;
+
+#[procedures(
+    event_trigger = LogsEventTrigger,
+    export_to = "../src/lib/bindings.ts",
+    path = "logs"
+)]
+pub trait LogsApi {
+    #[taurpc(event)]
+    async fn log_event(entry: LogEntry);
+
+    async fn get_recent_logs(
+        level: Option<LogLevel>,
+        module: Option<String>,
+        limit: Option<u32>,
+    ) -> Vec<LogEntry>;
+}
+
+#[derive(Clone, Default)]
+pub struct LogsApiImpl;
+
+#[resolvers]
+impl LogsApi for LogsApiImpl {
+    async fn get_recent_logs(
+        self,
+        level: Option<LogLevel>,
+        module: Option<String>,
+        limit: Option<u32>,
+    ) -> Vec<LogEntry> {
+        super::recent(level, module, limit.unwrap_or(DEFAULT_LOG_LIMIT) as usize)
+    }
+}
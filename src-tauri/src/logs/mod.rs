@@ -0,0 +1,113 @@
+/*
+In-app log buffer: keeps the most recent structured log events in memory so operators can
+inspect backend errors from the UI. Every event is still printed to the terminal and is
+streamed to the frontend through the log_event event once the app handle is set.
+*/
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+
+pub mod api;
+
+pub use api::{LogsApi, LogsApiImpl, LogsEventTrigger};
+
+const LOG_BUFFER_CAPACITY: usize = 1000;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Type)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct LogEntry {
+    pub id: i32,
+    pub timestamp: String,
+    pub level: LogLevel,
+    pub module: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+struct LogBuffer {
+    next_id: i32,
+    entries: VecDeque<LogEntry>,
+}
+
+lazy_static! {
+    static ref LOGS: Mutex<LogBuffer> = Mutex::new(LogBuffer::default());
+}
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+// Start streaming log events to the frontend
+pub fn set_app_handle(app_handle: AppHandle) {
+    let _ = APP_HANDLE.set(app_handle);
+}
+
+pub fn record(level: LogLevel, module: &str, message: impl Into<String>) {
+    let message = message.into();
+    match level {
+        LogLevel::Warn | LogLevel::Error => eprintln!("[{}] {:?}: {}", module, level, message),
+        _ => println!("[{}] {:?}: {}", module, level, message),
+    }
+
+    let entry = {
+        let mut buffer = LOGS.lock().unwrap();
+        buffer.next_id = buffer.next_id.wrapping_add(1);
+        let entry = LogEntry {
+            id: buffer.next_id,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            level,
+            module: module.to_string(),
+            message,
+        };
+        if buffer.entries.len() >= LOG_BUFFER_CAPACITY {
+            buffer.entries.pop_front();
+        }
+        buffer.entries.push_back(entry.clone());
+        entry
+    };
+
+    if let Some(app_handle) = APP_HANDLE.get() {
+        // Not logged through record() to avoid recursing on emit failures
+        if let Err(e) = LogsEventTrigger::new(app_handle.clone()).log_event(entry) {
+            eprintln!("Failed to emit log event: {}", e);
+        }
+    }
+}
+
+pub fn info(module: &str, message: impl Into<String>) {
+    record(LogLevel::Info, module, message);
+}
+
+pub fn warn(module: &str, message: impl Into<String>) {
+    record(LogLevel::Warn, module, message);
+}
+
+pub fn error(module: &str, message: impl Into<String>) {
+    record(LogLevel::Error, module, message);
+}
+
+// Newest first; `level` is a minimum severity and `module` matches as a prefix
+pub fn recent(level: Option<LogLevel>, module: Option<String>, limit: usize) -> Vec<LogEntry> {
+    let buffer = LOGS.lock().unwrap();
+    buffer
+        .entries
+        .iter()
+        .rev()
+        .filter(|entry| level.map_or(true, |level| entry.level >= level))
+        .filter(|entry| {
+            module
+                .as_deref()
+                .map_or(true, |module| entry.module.starts_with(module))
+        })
+        .take(limit)
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,211 @@
+/*
+Define the maintenance API: batteries, battery swaps, maintenance notes and per-vehicle flight hours.
+*/
+use sqlx::PgPool;
+use tauri::{Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, current_operator, OperatorRole};
+use crate::init_db::lazy_pool;
+use crate::logs;
+use super::sql::{
+    delete_battery, delete_entry, insert_battery, insert_note, select_batteries, select_entries,
+    select_flight_hours, select_installed_battery, swap_battery, update_battery, update_note,
+};
+use super::{validate_note, Battery, BatteryInput, MaintenanceEntry, VehicleMaintenance};
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "maintenance")]
+pub trait MaintenanceApi {
+    async fn list_batteries() -> Result<Vec<Battery>, String>;
+    async fn create_battery(window: Window<impl Runtime>, input: BatteryInput) -> Result<Battery, String>;
+    async fn update_battery(
+        window: Window<impl Runtime>,
+        battery_id: i32,
+        input: BatteryInput,
+    ) -> Result<Battery, String>;
+    async fn delete_battery(window: Window<impl Runtime>, battery_id: i32) -> Result<(), String>;
+    // Installs the battery in the vehicle, counting one cycle; a battery in another vehicle moves
+    async fn swap_battery(
+        window: Window<impl Runtime>,
+        vehicle_id: String,
+        battery_id: i32,
+        note: Option<String>,
+    ) -> Result<MaintenanceEntry, String>;
+    async fn add_maintenance_note(
+        window: Window<impl Runtime>,
+        vehicle_id: String,
+        note: String,
+    ) -> Result<MaintenanceEntry, String>;
+    async fn update_maintenance_note(
+        window: Window<impl Runtime>,
+        entry_id: i32,
+        note: String,
+    ) -> Result<MaintenanceEntry, String>;
+    async fn delete_maintenance_entry(window: Window<impl Runtime>, entry_id: i32) -> Result<(), String>;
+    async fn get_vehicle_maintenance(vehicle_id: String) -> Result<VehicleMaintenance, String>;
+}
+
+#[derive(Clone)]
+pub struct MaintenanceApiImpl {
+    db: PgPool,
+}
+
+impl MaintenanceApiImpl {
+    pub async fn new() -> Self {
+        Self { db: lazy_pool(2) }
+    }
+}
+
+#[resolvers]
+impl MaintenanceApi for MaintenanceApiImpl {
+    async fn list_batteries(self) -> Result<Vec<Battery>, String> {
+        select_batteries(self.db.clone())
+            .await
+            .map_err(|e| format!("Failed to load batteries: {}", e))
+    }
+
+    async fn create_battery(
+        self,
+        window: Window<impl Runtime>,
+        input: BatteryInput,
+    ) -> Result<Battery, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            input.validate()?;
+            let battery = insert_battery(self.db.clone(), &input)
+                .await
+                .map_err(|e| format!("Failed to save battery: {}", e))?;
+            logs::info(
+                "maintenance",
+                format!("Battery {} registered by {}", battery.serial, current_operator()),
+            );
+            Ok(battery)
+        })
+        .await
+    }
+
+    async fn update_battery(
+        self,
+        window: Window<impl Runtime>,
+        battery_id: i32,
+        input: BatteryInput,
+    ) -> Result<Battery, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            input.validate()?;
+            update_battery(self.db.clone(), battery_id, &input)
+                .await
+                .map_err(|e| format!("Failed to update battery: {}", e))?
+                .ok_or(format!("Battery {} not found", battery_id))
+        })
+        .await
+    }
+
+    async fn delete_battery(self, window: Window<impl Runtime>, battery_id: i32) -> Result<(), String> {
+        authorized(&window, OperatorRole::MissionCommander, async move {
+            if !delete_battery(self.db.clone(), battery_id)
+                .await
+                .map_err(|e| format!("Failed to delete battery: {}", e))?
+            {
+                return Err(format!("Battery {} not found", battery_id));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn swap_battery(
+        self,
+        window: Window<impl Runtime>,
+        vehicle_id: String,
+        battery_id: i32,
+        note: Option<String>,
+    ) -> Result<MaintenanceEntry, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let vehicle_id = super::parse_vehicle_id(&vehicle_id)?;
+            let note = note.unwrap_or_default();
+            validate_note(note.trim())?;
+            let entry = swap_battery(self.db.clone(), &vehicle_id, battery_id, note.trim(), &current_operator())
+                .await
+                .map_err(|e| format!("Failed to swap battery: {}", e))?
+                .ok_or(format!("Battery {} not found", battery_id))?;
+            logs::info(
+                "maintenance",
+                format!("Battery {} installed in {} by {}", battery_id, vehicle_id, current_operator()),
+            );
+            Ok(entry)
+        })
+        .await
+    }
+
+    async fn add_maintenance_note(
+        self,
+        window: Window<impl Runtime>,
+        vehicle_id: String,
+        note: String,
+    ) -> Result<MaintenanceEntry, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let vehicle_id = super::parse_vehicle_id(&vehicle_id)?;
+            let note = note.trim();
+            if note.is_empty() {
+                return Err("Maintenance note cannot be empty".into());
+            }
+            validate_note(note)?;
+            insert_note(self.db.clone(), &vehicle_id, note, &current_operator())
+                .await
+                .map_err(|e| format!("Failed to save maintenance note: {}", e))
+        })
+        .await
+    }
+
+    async fn update_maintenance_note(
+        self,
+        window: Window<impl Runtime>,
+        entry_id: i32,
+        note: String,
+    ) -> Result<MaintenanceEntry, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let note = note.trim();
+            validate_note(note)?;
+            update_note(self.db.clone(), entry_id, note)
+                .await
+                .map_err(|e| format!("Failed to update maintenance note: {}", e))?
+                .ok_or(format!("Maintenance entry {} not found", entry_id))
+        })
+        .await
+    }
+
+    async fn delete_maintenance_entry(
+        self,
+        window: Window<impl Runtime>,
+        entry_id: i32,
+    ) -> Result<(), String> {
+        authorized(&window, OperatorRole::MissionCommander, async move {
+            if !delete_entry(self.db.clone(), entry_id)
+                .await
+                .map_err(|e| format!("Failed to delete maintenance entry: {}", e))?
+            {
+                return Err(format!("Maintenance entry {} not found", entry_id));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_vehicle_maintenance(self, vehicle_id: String) -> Result<VehicleMaintenance, String> {
+        let vehicle_id = super::parse_vehicle_id(&vehicle_id)?;
+        let flight_hours = select_flight_hours(self.db.clone(), &vehicle_id)
+            .await
+            .map_err(|e| format!("Failed to load flight hours: {}", e))?;
+        let battery = select_installed_battery(self.db.clone(), &vehicle_id)
+            .await
+            .map_err(|e| format!("Failed to load battery: {}", e))?;
+        let entries = select_entries(self.db.clone(), &vehicle_id)
+            .await
+            .map_err(|e| format!("Failed to load maintenance log: {}", e))?;
+        Ok(VehicleMaintenance {
+            vehicle_id,
+            flight_hours,
+            battery,
+            entries,
+        })
+    }
+}
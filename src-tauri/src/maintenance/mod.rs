@@ -0,0 +1,166 @@
+/*
+Battery and maintenance log per vehicle. Batteries are registered with their rated charge
+cycles; swapping one into a vehicle counts a cycle and logs the swap next to the operators'
+maintenance notes. Flight hours come from the airtime of each mission's KPIs, recorded once per
+mission and vehicle when the mission ends. Starting a mission warns about every participating
+vehicle whose installed battery has used up its rated cycles.
+*/
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::PgPool;
+
+use crate::logs;
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use crate::vehicles::{self, VEHICLE_IDS};
+
+pub mod api;
+pub mod sql;
+
+pub use api::{MaintenanceApi, MaintenanceApiImpl};
+
+const MAX_NOTE_LENGTH: usize = 2000;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum MaintenanceKind {
+    BatterySwap,
+    Note,
+}
+
+impl MaintenanceKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaintenanceKind::BatterySwap => "BatterySwap",
+            MaintenanceKind::Note => "Note",
+        }
+    }
+
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "BatterySwap" => MaintenanceKind::BatterySwap,
+            _ => MaintenanceKind::Note,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct Battery {
+    pub battery_id: i32,
+    pub serial: String,
+    // Vehicle the battery is installed in, None while on the shelf
+    pub vehicle_id: Option<String>,
+    pub cycle_count: i32,
+    pub max_cycles: i32,
+    pub created_at: String,
+}
+
+impl Battery {
+    pub fn worn_out(&self) -> bool {
+        self.cycle_count >= self.max_cycles
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct BatteryInput {
+    pub serial: String,
+    pub cycle_count: i32,
+    pub max_cycles: i32,
+}
+
+impl BatteryInput {
+    pub fn validate(&self) -> Result<(), String> {
+        let serial = self.serial.trim();
+        if serial.is_empty() || serial.chars().count() > 64 {
+            return Err("Battery serial must be between 1 and 64 characters".into());
+        }
+        if self.max_cycles <= 0 {
+            return Err("Rated cycle count must be positive".into());
+        }
+        if self.cycle_count < 0 {
+            return Err("Cycle count cannot be negative".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct MaintenanceEntry {
+    pub entry_id: i32,
+    pub vehicle_id: String,
+    pub kind: MaintenanceKind,
+    // Battery swapped in, for BatterySwap entries
+    pub battery_id: Option<i32>,
+    pub note: String,
+    pub recorded_by: String,
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct VehicleMaintenance {
+    pub vehicle_id: String,
+    pub flight_hours: f64,
+    pub battery: Option<Battery>,
+    // Newest first
+    pub entries: Vec<MaintenanceEntry>,
+}
+
+// Lowercase vehicle id, or an error for a vehicle the GCS doesn't know
+pub fn parse_vehicle_id(vehicle_id: &str) -> Result<String, String> {
+    let vehicle_id = vehicle_id.trim().to_lowercase();
+    if !VEHICLE_IDS.contains(&vehicle_id.as_str()) {
+        return Err(format!("Unknown vehicle {}", vehicle_id));
+    }
+    Ok(vehicle_id)
+}
+
+pub fn validate_note(note: &str) -> Result<(), String> {
+    if note.chars().count() > MAX_NOTE_LENGTH {
+        return Err(format!("Maintenance notes are limited to {} characters", MAX_NOTE_LENGTH));
+    }
+    Ok(())
+}
+
+// Installed batteries of `vehicle_ids` that used up their rated cycles
+pub async fn worn_batteries(db: PgPool, vehicle_ids: &[String]) -> Result<Vec<Battery>, sqlx::Error> {
+    let batteries = sql::select_batteries(db).await?;
+    Ok(batteries
+        .into_iter()
+        .filter(|b| b.worn_out())
+        .filter(|b| {
+            b.vehicle_id
+                .as_ref()
+                .is_some_and(|v| vehicle_ids.iter().any(|id| id.eq_ignore_ascii_case(v)))
+        })
+        .collect())
+}
+
+pub fn worn_battery_message(vehicle_id: &str, battery: &Battery) -> String {
+    format!(
+        "{} battery {} is at {} of {} rated cycles",
+        vehicles::display_name(vehicle_id),
+        battery.serial,
+        battery.cycle_count,
+        battery.max_cycles
+    )
+}
+
+// Warn about the installed batteries of `vehicle_ids` that used up their rated cycles
+pub async fn warn_worn_batteries(db: PgPool, vehicle_ids: &[String]) {
+    let batteries = match worn_batteries(db, vehicle_ids).await {
+        Ok(batteries) => batteries,
+        Err(e) => {
+            logs::error("maintenance", format!("Failed to load batteries: {}", e));
+            return;
+        }
+    };
+    for battery in &batteries {
+        let Some(vehicle_id) = battery.vehicle_id.as_deref() else {
+            continue;
+        };
+        notifications::notify(
+            NotificationCategory::Maintenance,
+            NotificationSeverity::Warning,
+            Some(vehicle_id),
+            worn_battery_message(vehicle_id, battery),
+        );
+    }
+}
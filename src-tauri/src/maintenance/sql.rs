@@ -0,0 +1,234 @@
+/*
+Define all battery, maintenance log and flight session database functions.
+*/
+use sqlx::postgres::PgRow;
+use sqlx::{query, PgPool, Row};
+
+use super::{Battery, BatteryInput, MaintenanceEntry, MaintenanceKind};
+
+const BATTERY_COLUMNS: &str = "
+    battery_id, serial, vehicle_id, cycle_count, max_cycles,
+    to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS created_at
+";
+
+const ENTRY_COLUMNS: &str = "
+    entry_id, vehicle_id, kind, battery_id, note, recorded_by,
+    to_char(recorded_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS recorded_at
+";
+
+fn to_battery(row: PgRow) -> Battery {
+    Battery {
+        battery_id: row.get("battery_id"),
+        serial: row.get("serial"),
+        vehicle_id: row.get("vehicle_id"),
+        cycle_count: row.get("cycle_count"),
+        max_cycles: row.get("max_cycles"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn to_entry(row: PgRow) -> MaintenanceEntry {
+    MaintenanceEntry {
+        entry_id: row.get("entry_id"),
+        vehicle_id: row.get("vehicle_id"),
+        kind: MaintenanceKind::parse(row.get("kind")),
+        battery_id: row.get("battery_id"),
+        note: row.get("note"),
+        recorded_by: row.get("recorded_by"),
+        recorded_at: row.get("recorded_at"),
+    }
+}
+
+pub async fn select_batteries(db_conn: PgPool) -> Result<Vec<Battery>, sqlx::Error> {
+    let rows = query(&format!("SELECT {} FROM batteries ORDER BY serial", BATTERY_COLUMNS))
+        .fetch_all(&db_conn)
+        .await?;
+    Ok(rows.into_iter().map(to_battery).collect())
+}
+
+pub async fn select_installed_battery(
+    db_conn: PgPool,
+    vehicle_id: &str,
+) -> Result<Option<Battery>, sqlx::Error> {
+    let row = query(&format!("SELECT {} FROM batteries WHERE vehicle_id = $1", BATTERY_COLUMNS))
+        .bind(vehicle_id)
+        .fetch_optional(&db_conn)
+        .await?;
+    Ok(row.map(to_battery))
+}
+
+pub async fn insert_battery(db_conn: PgPool, input: &BatteryInput) -> Result<Battery, sqlx::Error> {
+    let row = query(&format!(
+        "INSERT INTO batteries(serial, cycle_count, max_cycles)
+        VALUES ($1, $2, $3)
+        RETURNING {}",
+        BATTERY_COLUMNS
+    ))
+    .bind(input.serial.trim())
+    .bind(input.cycle_count)
+    .bind(input.max_cycles)
+    .fetch_one(&db_conn)
+    .await?;
+    Ok(to_battery(row))
+}
+
+pub async fn update_battery(
+    db_conn: PgPool,
+    battery_id: i32,
+    input: &BatteryInput,
+) -> Result<Option<Battery>, sqlx::Error> {
+    let row = query(&format!(
+        "UPDATE batteries SET serial = $2, cycle_count = $3, max_cycles = $4
+        WHERE battery_id = $1
+        RETURNING {}",
+        BATTERY_COLUMNS
+    ))
+    .bind(battery_id)
+    .bind(input.serial.trim())
+    .bind(input.cycle_count)
+    .bind(input.max_cycles)
+    .fetch_optional(&db_conn)
+    .await?;
+    Ok(row.map(to_battery))
+}
+
+pub async fn delete_battery(db_conn: PgPool, battery_id: i32) -> Result<bool, sqlx::Error> {
+    let result = query("DELETE FROM batteries WHERE battery_id = $1")
+        .bind(battery_id)
+        .execute(&db_conn)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+// Take the vehicle's battery out, install `battery_id` with one more cycle and log the swap.
+// None when the battery doesn't exist.
+pub async fn swap_battery(
+    db_conn: PgPool,
+    vehicle_id: &str,
+    battery_id: i32,
+    note: &str,
+    recorded_by: &str,
+) -> Result<Option<MaintenanceEntry>, sqlx::Error> {
+    let mut transaction = db_conn.begin().await?;
+    query("UPDATE batteries SET vehicle_id = NULL WHERE vehicle_id = $1")
+        .bind(vehicle_id)
+        .execute(&mut *transaction)
+        .await?;
+    let installed = query(
+        "UPDATE batteries SET vehicle_id = $2, cycle_count = cycle_count + 1 WHERE battery_id = $1",
+    )
+    .bind(battery_id)
+    .bind(vehicle_id)
+    .execute(&mut *transaction)
+    .await?;
+    if installed.rows_affected() == 0 {
+        transaction.rollback().await?;
+        return Ok(None);
+    }
+    let row = query(&format!(
+        "INSERT INTO maintenance_log(vehicle_id, kind, battery_id, note, recorded_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING {}",
+        ENTRY_COLUMNS
+    ))
+    .bind(vehicle_id)
+    .bind(MaintenanceKind::BatterySwap.name())
+    .bind(battery_id)
+    .bind(note)
+    .bind(recorded_by)
+    .fetch_one(&mut *transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(Some(to_entry(row)))
+}
+
+pub async fn insert_note(
+    db_conn: PgPool,
+    vehicle_id: &str,
+    note: &str,
+    recorded_by: &str,
+) -> Result<MaintenanceEntry, sqlx::Error> {
+    let row = query(&format!(
+        "INSERT INTO maintenance_log(vehicle_id, kind, note, recorded_by)
+        VALUES ($1, $2, $3, $4)
+        RETURNING {}",
+        ENTRY_COLUMNS
+    ))
+    .bind(vehicle_id)
+    .bind(MaintenanceKind::Note.name())
+    .bind(note)
+    .bind(recorded_by)
+    .fetch_one(&db_conn)
+    .await?;
+    Ok(to_entry(row))
+}
+
+pub async fn update_note(
+    db_conn: PgPool,
+    entry_id: i32,
+    note: &str,
+) -> Result<Option<MaintenanceEntry>, sqlx::Error> {
+    let row = query(&format!(
+        "UPDATE maintenance_log SET note = $2 WHERE entry_id = $1 RETURNING {}",
+        ENTRY_COLUMNS
+    ))
+    .bind(entry_id)
+    .bind(note)
+    .fetch_optional(&db_conn)
+    .await?;
+    Ok(row.map(to_entry))
+}
+
+pub async fn delete_entry(db_conn: PgPool, entry_id: i32) -> Result<bool, sqlx::Error> {
+    let result = query("DELETE FROM maintenance_log WHERE entry_id = $1")
+        .bind(entry_id)
+        .execute(&db_conn)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+// Newest first
+pub async fn select_entries(db_conn: PgPool, vehicle_id: &str) -> Result<Vec<MaintenanceEntry>, sqlx::Error> {
+    let rows = query(&format!(
+        "SELECT {} FROM maintenance_log WHERE vehicle_id = $1 ORDER BY recorded_at DESC, entry_id DESC",
+        ENTRY_COLUMNS
+    ))
+    .bind(vehicle_id)
+    .fetch_all(&db_conn)
+    .await?;
+    Ok(rows.into_iter().map(to_entry).collect())
+}
+
+// Airtime per vehicle of a finished mission; recording the same mission again replaces it
+pub async fn record_flight_sessions(
+    db_conn: PgPool,
+    mission_id: i32,
+    airtime_secs: &[(String, f64)],
+) -> Result<(), sqlx::Error> {
+    let mut transaction = db_conn.begin().await?;
+    for (vehicle_id, secs) in airtime_secs {
+        query("
+            INSERT INTO flight_sessions(mission_id, vehicle_id, airtime_secs)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (mission_id, vehicle_id) DO UPDATE
+            SET airtime_secs = EXCLUDED.airtime_secs, recorded_at = NOW()
+        ")
+        .bind(mission_id)
+        .bind(vehicle_id)
+        .bind(secs)
+        .execute(&mut *transaction)
+        .await?;
+    }
+    transaction.commit().await
+}
+
+pub async fn select_flight_hours(db_conn: PgPool, vehicle_id: &str) -> Result<f64, sqlx::Error> {
+    let row = query(
+        "SELECT (COALESCE(SUM(airtime_secs), 0) / 3600.0)::FLOAT8 AS flight_hours
+        FROM flight_sessions WHERE vehicle_id = $1",
+    )
+    .bind(vehicle_id)
+    .fetch_one(&db_conn)
+    .await?;
+    Ok(row.get("flight_hours"))
+}
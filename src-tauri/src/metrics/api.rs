@@ -0,0 +1,21 @@
+/*
+Define the metrics API exposing a snapshot of the internal metrics registry.
+*/
+use taurpc::{procedures, resolvers};
+
+use super::MetricsSnapshot;
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "metrics")]
+pub trait MetricsApi {
+    async fn get_metrics() -> MetricsSnapshot;
+}
+
+#[derive(Clone, Default)]
+pub struct MetricsApiImpl;
+
+#[resolvers]
+impl MetricsApi for MetricsApiImpl {
+    async fn get_metrics(self) -> MetricsSnapshot {
+        super::snapshot()
+    }
+}
@@ -0,0 +1,209 @@
+/*
+Internal metrics registry for the telemetry pipeline and command path: message throughput,
+pipeline queue depths, the age of the messages each telemetry queue delivers, DB insert latency, event emit latency and command round-trip time.
+Latency summaries are computed over the most recent samples so regressions show up quickly.
+Per-step pipeline latencies are recorded in trace mode (see trace).
+*/
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+pub mod api;
+pub mod trace;
+
+pub use api::{MetricsApi, MetricsApiImpl};
+
+const LATENCY_SAMPLE_WINDOW: usize = 1024;
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, Type)]
+pub struct LatencySummary {
+    pub count: u32,
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+// Backlog of one telemetry pipeline stage
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct QueueDepth {
+    pub stage: String,
+    pub depth: u32,
+    pub max_depth: u32,
+    pub capacity: u32,
+}
+
+// Age of the messages an AMQP telemetry queue delivers, from their vehicle timestamps; a queue
+// replaying a backlog shows old messages arriving at full rate
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct MessageAge {
+    pub queue: String,
+    pub latest_age_ms: f64,
+    pub max_age_ms: f64,
+    // Messages older than stale_telemetry_secs when they arrived
+    pub stale_messages: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct MetricsSnapshot {
+    pub telemetry_messages_total: u32,
+    pub telemetry_messages_per_sec: f64,
+    pub telemetry_parse_failures_total: u32,
+    pub pipeline_queues: Vec<QueueDepth>,
+    pub telemetry_message_ages: Vec<MessageAge>,
+    pub db_insert_latency: LatencySummary,
+    pub event_emit_latency: LatencySummary,
+    pub command_round_trip: LatencySummary,
+    pub pipeline_trace: trace::PipelineTrace,
+    pub commands_sent_total: u32,
+    pub commands_failed_total: u32,
+    pub collected_at: String,
+}
+
+#[derive(Debug, Default)]
+struct Latency {
+    count: u32,
+    samples: VecDeque<f64>,
+}
+
+impl Latency {
+    fn record(&mut self, elapsed: Duration) {
+        self.count = self.count.saturating_add(1);
+        if self.samples.len() >= LATENCY_SAMPLE_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(elapsed.as_secs_f64() * 1000.0);
+    }
+
+    fn summary(&self) -> LatencySummary {
+        if self.samples.is_empty() {
+            return LatencySummary::default();
+        }
+        let mut sorted: Vec<f64> = self.samples.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let percentile = |p: f64| sorted[((sorted.len() - 1) as f64 * p).round() as usize];
+        LatencySummary {
+            count: self.count,
+            avg_ms: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50_ms: percentile(0.5),
+            p95_ms: percentile(0.95),
+            p99_ms: percentile(0.99),
+            max_ms: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    telemetry_messages: u32,
+    recent_messages: VecDeque<Instant>,
+    telemetry_parse_failures: u32,
+    queues: BTreeMap<&'static str, QueueDepth>,
+    message_ages: BTreeMap<String, MessageAge>,
+    db_insert: Latency,
+    event_emit: Latency,
+    command_round_trip: Latency,
+    commands_sent: u32,
+    commands_failed: u32,
+}
+
+lazy_static! {
+    static ref METRICS: Mutex<Registry> = Mutex::new(Registry::default());
+}
+
+pub fn record_telemetry_message() {
+    let mut metrics = METRICS.lock().unwrap();
+    let now = Instant::now();
+    metrics.telemetry_messages = metrics.telemetry_messages.saturating_add(1);
+    metrics.recent_messages.push_back(now);
+    while metrics
+        .recent_messages
+        .front()
+        .is_some_and(|t| now.duration_since(*t) > RATE_WINDOW)
+    {
+        metrics.recent_messages.pop_front();
+    }
+}
+
+pub fn record_parse_failure() {
+    let mut metrics = METRICS.lock().unwrap();
+    metrics.telemetry_parse_failures = metrics.telemetry_parse_failures.saturating_add(1);
+}
+
+// Messages left in a pipeline stage's queue after it took the next one
+pub fn record_queue_depth(stage: &'static str, depth: usize, capacity: usize) {
+    let mut metrics = METRICS.lock().unwrap();
+    let queue = metrics.queues.entry(stage).or_insert_with(|| QueueDepth {
+        stage: stage.to_string(),
+        depth: 0,
+        max_depth: 0,
+        capacity: 0,
+    });
+    queue.depth = depth as u32;
+    queue.max_depth = queue.max_depth.max(depth as u32);
+    queue.capacity = capacity as u32;
+}
+
+// Age of a message a telemetry queue just delivered
+pub fn record_message_age(queue: &str, age_ms: f64, stale: bool) {
+    let mut metrics = METRICS.lock().unwrap();
+    let age = metrics.message_ages.entry(queue.to_string()).or_insert_with(|| MessageAge {
+        queue: queue.to_string(),
+        latest_age_ms: 0.0,
+        max_age_ms: 0.0,
+        stale_messages: 0,
+    });
+    age.latest_age_ms = age_ms;
+    age.max_age_ms = age.max_age_ms.max(age_ms);
+    if stale {
+        age.stale_messages = age.stale_messages.saturating_add(1);
+    }
+}
+
+pub fn record_db_insert(elapsed: Duration) {
+    METRICS.lock().unwrap().db_insert.record(elapsed);
+}
+
+pub fn record_event_emit(elapsed: Duration) {
+    METRICS.lock().unwrap().event_emit.record(elapsed);
+}
+
+// Publish-to-broker-confirm time of a command; failures are counted but not timed
+pub fn record_command(elapsed: Duration, success: bool) {
+    let mut metrics = METRICS.lock().unwrap();
+    if success {
+        metrics.commands_sent = metrics.commands_sent.saturating_add(1);
+        metrics.command_round_trip.record(elapsed);
+    } else {
+        metrics.commands_failed = metrics.commands_failed.saturating_add(1);
+    }
+}
+
+pub fn snapshot() -> MetricsSnapshot {
+    let metrics = METRICS.lock().unwrap();
+    let now = Instant::now();
+    let recent = metrics
+        .recent_messages
+        .iter()
+        .filter(|t| now.duration_since(**t) <= RATE_WINDOW)
+        .count();
+    MetricsSnapshot {
+        telemetry_messages_total: metrics.telemetry_messages,
+        telemetry_messages_per_sec: recent as f64 / RATE_WINDOW.as_secs_f64(),
+        telemetry_parse_failures_total: metrics.telemetry_parse_failures,
+        pipeline_queues: metrics.queues.values().cloned().collect(),
+        telemetry_message_ages: metrics.message_ages.values().cloned().collect(),
+        db_insert_latency: metrics.db_insert.summary(),
+        event_emit_latency: metrics.event_emit.summary(),
+        command_round_trip: metrics.command_round_trip.summary(),
+        pipeline_trace: trace::snapshot(),
+        commands_sent_total: metrics.commands_sent,
+        commands_failed_total: metrics.commands_failed,
+        collected_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
@@ -0,0 +1,93 @@
+/*
+Telemetry pipeline trace mode (telemetry_trace in the configuration). While it is on, every
+message carries the time it reached each pipeline step (received, parsed, enriched, persisted,
+emitted) and the time between consecutive steps is summarised, so the part of the end-to-end
+display latency each step adds, queue waits included, shows up at high message rates. It is
+off by default since it takes the metrics lock once more per message.
+*/
+use std::sync::Mutex;
+use std::time::Instant;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::config;
+use super::{Latency, LatencySummary};
+
+#[derive(Debug, Clone, Copy)]
+pub enum TraceStep {
+    Parsed,
+    Enriched,
+    Persisted,
+    Emitted,
+}
+
+// Timestamps of one message; steps it hasn't reached yet are None
+#[derive(Debug, Clone)]
+pub struct MessageTrace {
+    received: Instant,
+    steps: [Option<Instant>; 4],
+}
+
+impl MessageTrace {
+    // None while trace mode is off
+    pub fn start(received: Instant) -> Option<Self> {
+        config::get().telemetry_trace.then_some(Self { received, steps: [None; 4] })
+    }
+
+    pub fn mark(mut self, step: TraceStep) -> Self {
+        self.steps[step as usize] = Some(Instant::now());
+        self
+    }
+
+    // Record the message's step latencies once it has been emitted
+    pub fn finish(self) {
+        let mut trace = TRACE.lock().unwrap();
+        let mut previous = self.received;
+        for (latency, reached) in trace.steps.iter_mut().zip(self.steps) {
+            if let Some(reached) = reached {
+                latency.record(reached.duration_since(previous));
+                previous = reached;
+            }
+        }
+        trace.end_to_end.record(previous.duration_since(self.received));
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct PipelineTrace {
+    pub enabled: bool,
+    // Received to parsed: payload decompression and protocol parsing
+    pub parse: LatencySummary,
+    // Parsed to enriched, including the wait in the enrich queue
+    pub enrich: LatencySummary,
+    // Enriched to persisted, including the wait in the persist queue
+    pub persist: LatencySummary,
+    // Persisted to emitted, including the wait in the emit queue
+    pub emit: LatencySummary,
+    pub end_to_end: LatencySummary,
+}
+
+#[derive(Debug, Default)]
+struct TraceRegistry {
+    // In TraceStep order
+    steps: [Latency; 4],
+    end_to_end: Latency,
+}
+
+lazy_static! {
+    static ref TRACE: Mutex<TraceRegistry> = Mutex::new(TraceRegistry::default());
+}
+
+pub fn snapshot() -> PipelineTrace {
+    let trace = TRACE.lock().unwrap();
+    let [parse, enrich, persist, emit] = &trace.steps;
+    PipelineTrace {
+        enabled: config::get().telemetry_trace,
+        parse: parse.summary(),
+        enrich: enrich.summary(),
+        persist: persist.summary(),
+        emit: emit.summary(),
+        end_to_end: trace.end_to_end.summary(),
+    }
+}
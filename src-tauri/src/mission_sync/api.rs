@@ -0,0 +1,40 @@
+/*
+Define the mission sync API: sync status and conflict events, plus the broker transport task.
+*/
+use tauri::AppHandle;
+use taurpc::{procedures, resolvers};
+
+use crate::config;
+use crate::missions::api::MissionApiImpl;
+use crate::supervisor::{self, RestartPolicy};
+use super::{SyncConflict, SyncStatus};
+
+#[procedures(event_trigger = MissionSyncEventTrigger, export_to = "../src/lib/bindings.ts", path = "mission_sync")]
+pub trait MissionSyncApi {
+    #[taurpc(event)]
+    async fn on_sync_conflict(conflict: SyncConflict);
+
+    async fn get_sync_status() -> SyncStatus;
+}
+
+#[derive(Clone, Default)]
+pub struct MissionSyncApiImpl;
+
+impl MissionSyncApiImpl {
+    // Exchange mutations with other stations while mission_sync_station is set
+    pub fn start(&self, missions: MissionApiImpl, app_handle: AppHandle) {
+        if config::get().mission_sync_station.is_empty() {
+            return;
+        }
+        supervisor::spawn("mission_sync", RestartPolicy::Always, move || {
+            super::transport::run(missions.clone(), app_handle.clone())
+        });
+    }
+}
+
+#[resolvers]
+impl MissionSyncApi for MissionSyncApiImpl {
+    async fn get_sync_status(self) -> SyncStatus {
+        super::status()
+    }
+}
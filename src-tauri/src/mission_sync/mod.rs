@@ -0,0 +1,244 @@
+/*
+Mission state synchronization between GCS stations. When `mission_sync_station` is set, every
+local mission change (zones, stages, status, renames, deletions) is broadcast as a whole-mission
+mutation on the `mission_sync_exchange` fanout exchange, tagged with the station name, and
+mutations from other stations are applied to the local MissionsStruct and emitted to the
+frontend as if they were local edits.
+
+Each mission carries a revision. A mutation names the revision it was made from; when that is
+not the revision this station holds, both stations edited the mission concurrently. The
+version with the higher (revision, station) wins on every station, the losing station's
+version is discarded, and the conflict is reported. Remote mutations only update the
+in-memory state; each station's database keeps its own edits.
+*/
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tokio::sync::mpsc;
+
+use crate::config;
+use crate::missions::types::{MissionStruct, MissionsStruct};
+
+pub mod api;
+pub mod transport;
+
+pub use api::{MissionSyncApi, MissionSyncApiImpl, MissionSyncEventTrigger};
+
+// Conflicts kept for the status view
+const MAX_CONFLICTS: usize = 50;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MissionMutation {
+    pub origin: String,
+    pub mission_id: i32,
+    // Revision the origin held when it made the change
+    pub base_revision: u64,
+    pub revision: u64,
+    // None when the mission was deleted
+    pub mission: Option<MissionStruct>,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct SyncConflict {
+    pub mission_id: i32,
+    pub remote_station: String,
+    // Station whose version every station now holds
+    pub kept_station: String,
+    pub detected_at: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct SyncPeer {
+    pub station: String,
+    pub last_seen: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct SyncStatus {
+    pub enabled: bool,
+    pub station: String,
+    pub connected: bool,
+    pub peers: Vec<SyncPeer>,
+    // Most recent first
+    pub conflicts: Vec<SyncConflict>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Revision {
+    revision: u64,
+    origin: String,
+}
+
+#[derive(Default)]
+struct SyncState {
+    seeded: bool,
+    connected: bool,
+    // Mission as last broadcast or applied, serialized, to find what a local update changed
+    last: HashMap<i32, String>,
+    revisions: HashMap<i32, Revision>,
+    peers: HashMap<String, String>,
+    conflicts: Vec<SyncConflict>,
+}
+
+impl SyncState {
+    fn next_local(&mut self, station: &str, mission_id: i32, mission: Option<MissionStruct>) -> MissionMutation {
+        let entry = self.revisions.entry(mission_id).or_default();
+        let base_revision = entry.revision;
+        entry.revision += 1;
+        entry.origin = station.to_string();
+        MissionMutation {
+            origin: station.to_string(),
+            mission_id,
+            base_revision,
+            revision: entry.revision,
+            mission,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    fn accept(&mut self, mutation: &MissionMutation) {
+        match &mutation.mission {
+            Some(mission) => self.last.insert(mutation.mission_id, serialize(mission)),
+            None => self.last.remove(&mutation.mission_id),
+        };
+        self.revisions.insert(
+            mutation.mission_id,
+            Revision { revision: mutation.revision, origin: mutation.origin.clone() },
+        );
+    }
+}
+
+struct Outbox {
+    sender: mpsc::UnboundedSender<MissionMutation>,
+    // Taken by the running transport task; mutations queue here while it is disconnected
+    receiver: tokio::sync::Mutex<mpsc::UnboundedReceiver<MissionMutation>>,
+}
+
+lazy_static! {
+    static ref STATE: Mutex<SyncState> = Mutex::new(SyncState::default());
+    static ref OUTBOX: Outbox = {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Outbox { sender, receiver: tokio::sync::Mutex::new(receiver) }
+    };
+}
+
+fn station() -> Option<String> {
+    Some(config::get().mission_sync_station).filter(|station| !station.is_empty())
+}
+
+fn serialize(mission: &MissionStruct) -> String {
+    serde_json::to_string(mission).unwrap_or_default()
+}
+
+// Record the missions loaded at startup so they are not broadcast as changes
+pub fn seed(state: &MissionsStruct) {
+    let mut sync = STATE.lock().unwrap();
+    if sync.seeded {
+        return;
+    }
+    sync.seeded = true;
+    sync.last = state.missions.iter().map(|m| (m.mission_id, serialize(m))).collect();
+}
+
+// Queue a mutation for every mission that differs from what was last broadcast or applied
+pub fn record_local(state: &MissionsStruct) {
+    let Some(station) = station() else { return };
+    let mut sync = STATE.lock().unwrap();
+    let mut present = HashSet::new();
+    for mission in &state.missions {
+        present.insert(mission.mission_id);
+        let serialized = serialize(mission);
+        if sync.last.get(&mission.mission_id) == Some(&serialized) {
+            continue;
+        }
+        sync.last.insert(mission.mission_id, serialized);
+        let mutation = sync.next_local(&station, mission.mission_id, Some(mission.clone()));
+        let _ = OUTBOX.sender.send(mutation);
+    }
+    let deleted: Vec<i32> = sync.last.keys().filter(|id| !present.contains(id)).copied().collect();
+    for mission_id in deleted {
+        sync.last.remove(&mission_id);
+        let mutation = sync.next_local(&station, mission_id, None);
+        let _ = OUTBOX.sender.send(mutation);
+    }
+}
+
+pub enum Decision {
+    // Our own mutation echoed back by the exchange
+    Ignore,
+    Apply { conflict: Option<SyncConflict> },
+    // The local version won a conflict; broadcast it so the other station converges
+    KeepLocal { conflict: SyncConflict, resend: MissionMutation },
+}
+
+// Decide what to do with a mutation from another station and update the revision bookkeeping
+pub fn receive(mutation: &MissionMutation) -> Decision {
+    let Some(station) = station() else { return Decision::Ignore };
+    if mutation.origin == station {
+        return Decision::Ignore;
+    }
+    let mut sync = STATE.lock().unwrap();
+    sync.peers.insert(mutation.origin.clone(), mutation.timestamp.clone());
+    let local = sync.revisions.get(&mutation.mission_id).cloned().unwrap_or_default();
+    if mutation.base_revision == local.revision {
+        sync.accept(mutation);
+        return Decision::Apply { conflict: None };
+    }
+
+    let remote_wins = (mutation.revision, mutation.origin.as_str()) > (local.revision, local.origin.as_str());
+    let conflict = SyncConflict {
+        mission_id: mutation.mission_id,
+        remote_station: mutation.origin.clone(),
+        kept_station: if remote_wins { mutation.origin.clone() } else { station.clone() },
+        detected_at: chrono::Utc::now().to_rfc3339(),
+    };
+    sync.conflicts.insert(0, conflict.clone());
+    sync.conflicts.truncate(MAX_CONFLICTS);
+    if remote_wins {
+        sync.accept(mutation);
+        return Decision::Apply { conflict: Some(conflict) };
+    }
+
+    // Re-send the winning local version on top of the remote revision
+    let mission = sync
+        .last
+        .get(&mutation.mission_id)
+        .and_then(|serialized| serde_json::from_str(serialized).ok());
+    let resend = MissionMutation {
+        origin: station,
+        mission_id: mutation.mission_id,
+        base_revision: mutation.revision,
+        revision: local.revision,
+        mission,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    Decision::KeepLocal { conflict, resend }
+}
+
+pub fn send(mutation: MissionMutation) {
+    let _ = OUTBOX.sender.send(mutation);
+}
+
+fn set_connected(connected: bool) {
+    STATE.lock().unwrap().connected = connected;
+}
+
+pub fn status() -> SyncStatus {
+    let sync = STATE.lock().unwrap();
+    let mut peers: Vec<SyncPeer> = sync
+        .peers
+        .iter()
+        .map(|(station, last_seen)| SyncPeer { station: station.clone(), last_seen: last_seen.clone() })
+        .collect();
+    peers.sort_by(|a, b| a.station.cmp(&b.station));
+    SyncStatus {
+        enabled: station().is_some(),
+        station: config::get().mission_sync_station,
+        connected: sync.connected,
+        peers,
+        conflicts: sync.conflicts.clone(),
+    }
+}
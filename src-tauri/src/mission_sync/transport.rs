@@ -0,0 +1,135 @@
+/*
+AMQP transport for mission sync: queued local mutations are published to the fanout exchange,
+and every station consumes the exchange through its own exclusive queue.
+*/
+use futures_util::stream::StreamExt;
+use lapin::{
+    options::*, types::FieldTable, BasicProperties, Connection, ConnectionProperties, ExchangeKind,
+};
+use tauri::AppHandle;
+use tokio_amqp::*;
+
+use crate::config;
+use crate::logs;
+use crate::missions::api::MissionApiImpl;
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use super::{Decision, MissionMutation, MissionSyncEventTrigger, SyncConflict};
+
+fn report_conflict(app_handle: &AppHandle, conflict: &SyncConflict) {
+    notifications::notify(
+        NotificationCategory::MissionSync,
+        NotificationSeverity::Warning,
+        None,
+        format!(
+            "Mission {} was edited here and on {} at the same time; kept the version from {}",
+            conflict.mission_id, conflict.remote_station, conflict.kept_station
+        ),
+    );
+    if let Err(e) = MissionSyncEventTrigger::new(app_handle.clone()).on_sync_conflict(conflict.clone()) {
+        logs::error("mission_sync", format!("Failed to emit sync conflict: {}", e));
+    }
+}
+
+async fn handle(missions: &MissionApiImpl, app_handle: &AppHandle, payload: &[u8]) {
+    let mutation = match serde_json::from_slice::<MissionMutation>(payload) {
+        Ok(mutation) => mutation,
+        Err(e) => {
+            logs::warn("mission_sync", format!("Failed to parse mission mutation: {}", e));
+            return;
+        }
+    };
+    match super::receive(&mutation) {
+        Decision::Ignore => {}
+        Decision::Apply { conflict } => {
+            if let Some(conflict) = &conflict {
+                report_conflict(app_handle, conflict);
+            }
+            if let Err(e) = missions
+                .apply_remote_mission(app_handle, mutation.mission_id, mutation.mission)
+                .await
+            {
+                logs::error(
+                    "mission_sync",
+                    format!("Failed to apply mission {} from {}: {}", mutation.mission_id, mutation.origin, e),
+                );
+            }
+        }
+        Decision::KeepLocal { conflict, resend } => {
+            report_conflict(app_handle, &conflict);
+            super::send(resend);
+        }
+    }
+}
+
+// Connect, then publish local mutations and apply remote ones until the connection drops
+pub async fn run(missions: MissionApiImpl, app_handle: AppHandle) -> Result<(), String> {
+    let config = config::get();
+    let exchange = config.mission_sync_exchange.clone();
+    let connection = Connection::connect(&config.amqp_url, ConnectionProperties::default().with_tokio())
+        .await
+        .map_err(|e| format!("Failed to connect to RabbitMQ: {}", e))?;
+    let channel = connection
+        .create_channel()
+        .await
+        .map_err(|e| format!("Failed to create channel: {}", e))?;
+    channel
+        .exchange_declare(&exchange, ExchangeKind::Fanout, ExchangeDeclareOptions::default(), FieldTable::default())
+        .await
+        .map_err(|e| format!("Failed to declare exchange {}: {}", exchange, e))?;
+    let queue = channel
+        .queue_declare(
+            "",
+            QueueDeclareOptions { exclusive: true, auto_delete: true, ..Default::default() },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| format!("Failed to declare sync queue: {}", e))?;
+    channel
+        .queue_bind(queue.name().as_str(), &exchange, "", QueueBindOptions::default(), FieldTable::default())
+        .await
+        .map_err(|e| format!("Failed to bind sync queue: {}", e))?;
+    let mut consumer = channel
+        .basic_consume(
+            queue.name().as_str(),
+            &format!("mission_sync_{}", config.mission_sync_station),
+            BasicConsumeOptions { no_ack: true, ..Default::default() },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| format!("Failed to consume sync queue: {}", e))?;
+
+    let mut outgoing = super::OUTBOX.receiver.lock().await;
+    super::set_connected(true);
+    logs::info(
+        "mission_sync",
+        format!("Syncing missions as {} on exchange {}", config.mission_sync_station, exchange),
+    );
+
+    let result = loop {
+        tokio::select! {
+            mutation = outgoing.recv() => {
+                let Some(mutation) = mutation else { break Ok(()) };
+                let payload = match serde_json::to_vec(&mutation) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        logs::error("mission_sync", format!("Failed to serialize mission mutation: {}", e));
+                        continue;
+                    }
+                };
+                let published = channel
+                    .basic_publish(&exchange, "", BasicPublishOptions::default(), &payload, BasicProperties::default())
+                    .await;
+                if let Err(e) = published {
+                    break Err(format!("Failed to publish mission mutation: {}", e));
+                }
+            }
+            delivery = consumer.next() => match delivery {
+                Some(Ok(delivery)) => handle(&missions, &app_handle, &delivery.data).await,
+                Some(Err(e)) => break Err(format!("Mission sync consumer failed: {}", e)),
+                None => break Err("Mission sync consumer closed".to_string()),
+            },
+        }
+    };
+    super::set_connected(false);
+    result
+}
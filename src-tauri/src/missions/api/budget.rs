@@ -0,0 +1,60 @@
+/*
+Implement helper methods on MissionApiImpl for vertex budgets: how
+many vertices each vehicle accepts in a zone or search area, and
+fitting edited geometry to them before it is stored.
+*/
+
+use crate::commands::capabilities::{capabilities_for, zone_vertex_budget};
+use crate::config;
+use crate::geometry::{simplify_polygon, validate_polygon};
+use crate::missions::types::*;
+use super::MissionApiImpl;
+
+/// Search-area budget of a vehicle's capability model
+pub fn search_area_vertex_budget(vehicle_name: &VehicleEnum) -> i32 {
+    capabilities_for(&vehicle_name.to_string())
+        .map_or(config::get().max_zone_vertices, |c| c.max_zone_vertices)
+}
+
+/// `polygon` unchanged when it fits `budget`; otherwise an error naming the budget, or the
+/// polygon simplified down to it when `simplify` is set
+pub fn fit_to_budget(
+    polygon: GeofenceType,
+    budget: i32,
+    simplify: bool,
+    label: &str,
+) -> Result<GeofenceType, String> {
+    let budget = budget.max(3) as usize;
+    if polygon.len() <= budget {
+        return Ok(polygon);
+    }
+    if !simplify {
+        return Err(format!(
+            "{} has {} vertices but the vehicles accept at most {}",
+            label,
+            polygon.len(),
+            budget
+        ));
+    }
+    let simplified = simplify_polygon(&polygon, budget);
+    validate_polygon(&simplified).map_err(|e| {
+        format!("{} could not be simplified to {} vertices: {}", label, budget, e)
+    })?;
+    println!("Simplified {} from {} to {} vertices", label, polygon.len(), simplified.len());
+    Ok(simplified)
+}
+
+impl MissionApiImpl {
+    pub fn get_vertex_budget_helper(&self) -> VertexBudget {
+        VertexBudget {
+            max_zone_vertices: zone_vertex_budget(),
+            vehicles: [VehicleEnum::MEA, VehicleEnum::ERU, VehicleEnum::MRA]
+                .into_iter()
+                .map(|vehicle_name| VehicleVertexBudget {
+                    max_search_area_vertices: search_area_vertex_budget(&vehicle_name),
+                    vehicle_name,
+                })
+                .collect(),
+        }
+    }
+}
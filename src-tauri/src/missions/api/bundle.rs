@@ -0,0 +1,323 @@
+/*
+Implement helper methods on MissionApiImpl for mission archive
+bundles: a single zip holding the mission definition and notes,
+recorded telemetry, command history and alerts, so a complete
+mission record can be moved off the field laptop and imported
+on another station.
+*/
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::commands::sql::{insert_imported_command, select_command_history};
+use crate::commands::types::CommandRecord;
+use crate::exports::flight_logs::to_csv;
+use crate::logs;
+use crate::missions::store::MissionStore;
+use crate::missions::types::*;
+use crate::notifications::sql::{insert_imported_notification, select_notifications_between};
+use crate::notifications::Notification;
+use crate::telemetry::sql::{insert_telemetry_record, select_mission_telemetry, TelemetryRecord};
+use crate::units::DisplayUnits;
+use super::zones::convert_zone_format;
+use super::MissionApiImpl;
+
+const BUNDLE_FORMAT_VERSION: i32 = 1;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct BundleManifest {
+    format_version: i32,
+    mission_id: i32,
+    mission_name: String,
+    exported_at: String,
+}
+
+fn write_entry<T: Serialize>(
+    zip: &mut ZipWriter<File>,
+    name: &str,
+    value: &T,
+) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    write_raw_entry(zip, name, &json)
+}
+
+fn write_raw_entry(zip: &mut ZipWriter<File>, name: &str, data: &[u8]) -> Result<(), String> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(name, options)
+        .and_then(|_| zip.write_all(data).map_err(Into::into))
+        .map_err(|e| format!("Failed to write {} to bundle: {}", name, e))
+}
+
+fn read_entry<T: DeserializeOwned>(zip: &mut ZipArchive<File>, name: &str) -> Result<T, String> {
+    let mut contents = String::new();
+    zip.by_name(name)
+        .map_err(|e| format!("Bundle is missing {}: {}", name, e))?
+        .read_to_string(&mut contents)
+        .map_err(|e| format!("Failed to read {} from bundle: {}", name, e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Invalid {} in bundle: {}", name, e))
+}
+
+fn zone_strings(zones: &[GeofenceType]) -> Vec<String> {
+    zones
+        .iter()
+        .map(|zone| convert_zone_format(&serde_json::to_string(zone).unwrap_or_default()))
+        .collect()
+}
+
+fn vehicles_mut(vehicles: &mut VehiclesStruct) -> [&mut VehicleStruct; 3] {
+    [&mut vehicles.MEA, &mut vehicles.ERU, &mut vehicles.MRA]
+}
+
+// Write an exported mission (vehicles, stages, zones, status, times and notes) into `mission_id`,
+// a mission just created in `store`; returns it with the ids it got there. Shared by bundle
+// import and copying missions between stations (clone.rs)
+pub(super) async fn write_definition(
+    store: &dyn MissionStore,
+    mission_id: i32,
+    name: String,
+    export: MissionExport,
+) -> Result<MissionStruct, String> {
+    let db_error = |e: sqlx::Error| format!("Failed to import mission: {}", e);
+    let mut mission = export.mission;
+    mission.mission_id = mission_id;
+    mission.mission_name = name;
+    // Bundles from before lifecycle timestamps were recorded keep the import time
+    mission.created_at.get_or_insert_with(utc_timestamp);
+    // This station does not control the original vehicles, so never import as Active
+    if matches!(mission.mission_status, MissionStageStatusEnum::Active) {
+        mission.mission_status = MissionStageStatusEnum::Paused;
+    }
+
+    for vehicle in vehicles_mut(&mut mission.vehicles) {
+        let vehicle_id = store.select_vehicle_from_mission(
+            mission_id,
+            vehicle.vehicle_name.to_string(),
+        )
+        .await
+        .map_err(db_error)?;
+
+        let mut stage_ids: HashMap<i32, i32> = HashMap::new();
+        for stage in vehicle.stages.iter_mut() {
+            let area = zone_strings(std::slice::from_ref(&stage.search_area));
+            let new_id = store.insert_imported_stage(
+                vehicle_id,
+                &stage.stage_name,
+                area,
+                &format!("{:?}", stage.stage_status),
+            )
+            .await
+            .map_err(db_error)?;
+            store.restore_stage_times(
+                new_id,
+                stage.created_at.as_deref(),
+                stage.started_at.as_deref(),
+                stage.completed_at.as_deref(),
+            )
+            .await
+            .map_err(db_error)?;
+            stage_ids.insert(stage.stage_id, new_id);
+            stage.stage_id = new_id;
+        }
+        vehicle.current_stage = stage_ids.get(&vehicle.current_stage).copied().unwrap_or(-1);
+
+        let patient_status = match vehicle.patient_status {
+            Some(PatientStatusEnum::Secured) => "Secured",
+            _ => "Unsecured",
+        };
+        store.update_imported_vehicle(
+            vehicle_id,
+            vehicle.current_stage,
+            vehicle.is_auto,
+            patient_status,
+        )
+        .await
+        .map_err(db_error)?;
+    }
+
+    store.update_zones(
+        mission_id,
+        zone_strings(&mission.zones.keep_in_zones),
+        zone_strings(&mission.zones.keep_out_zones),
+    )
+    .await
+    .map_err(db_error)?;
+    store.update_mission_status(
+        mission_id,
+        &format!("{:?}", mission.mission_status),
+    )
+    .await
+    .map_err(db_error)?;
+    // After the status, whose trigger would stamp the import time
+    store.restore_mission_times(
+        mission_id,
+        mission.created_at.as_deref(),
+        mission.started_at.as_deref(),
+        mission.completed_at.as_deref(),
+    )
+    .await
+    .map_err(db_error)?;
+    if let Some(timezone) = mission.display_timezone.as_deref() {
+        store.update_mission_timezone(mission_id, Some(timezone))
+            .await
+            .map_err(db_error)?;
+    }
+
+    for note in &export.notes {
+        store.insert_imported_note(mission_id, note)
+            .await
+            .map_err(db_error)?;
+    }
+
+    Ok(mission)
+}
+
+impl MissionApiImpl {
+    pub async fn export_mission_bundle_helper(
+        &self,
+        mission_id: i32,
+        path: String,
+    ) -> Result<String, String> {
+        let export = self.export_mission_helper(mission_id).await?;
+        let telemetry = select_mission_telemetry(self.db.clone(), mission_id)
+            .await
+            .map_err(|e| format!("Failed to load telemetry: {}", e))?;
+        let commands = select_command_history(self.db.clone(), None, Some(mission_id), None)
+            .await
+            .map_err(|e| format!("Failed to load command history: {}", e))?;
+        // Alerts are not tied to missions; take those raised while the mission was recording
+        let alerts = match (telemetry.first(), telemetry.last()) {
+            (Some(first), Some(last)) => {
+                select_notifications_between(self.db.clone(), &first.recorded_at, &last.recorded_at)
+                    .await
+                    .map_err(|e| format!("Failed to load alerts: {}", e))?
+            }
+            _ => vec![],
+        };
+
+        let path = Path::new(&path);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut zip = ZipWriter::new(file);
+
+        let manifest = BundleManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            mission_id,
+            mission_name: export.mission.mission_name.clone(),
+            exported_at: export.exported_at.clone(),
+        };
+        write_entry(&mut zip, "manifest.json", &manifest)?;
+        write_entry(&mut zip, "mission.json", &export)?;
+        write_entry(&mut zip, "telemetry.json", &telemetry)?;
+        // Same data as telemetry.json, for opening in a spreadsheet without importing
+        write_raw_entry(&mut zip, "telemetry.csv", to_csv(&telemetry, &DisplayUnits::current()).as_bytes())?;
+        write_entry(&mut zip, "commands.json", &commands)?;
+        write_entry(&mut zip, "alerts.json", &alerts)?;
+        zip.finish()
+            .map_err(|e| format!("Failed to finish bundle: {}", e))?;
+
+        logs::info(
+            "missions",
+            format!(
+                "Exported mission {} bundle to {} ({} telemetry records, {} commands, {} alerts, {} notes)",
+                mission_id,
+                path.display(),
+                telemetry.len(),
+                commands.len(),
+                alerts.len(),
+                export.notes.len()
+            ),
+        );
+        Ok(path.display().to_string())
+    }
+
+    // Imports the bundle as a new mission and returns its id
+    pub async fn import_mission_bundle_helper(
+        &self,
+        app_handle: AppHandle<impl Runtime>,
+        path: String,
+    ) -> Result<i32, String> {
+        let file = File::open(&path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        let mut zip = ZipArchive::new(file).map_err(|e| format!("{} is not a mission bundle: {}", path, e))?;
+
+        let manifest: BundleManifest = read_entry(&mut zip, "manifest.json")?;
+        if manifest.format_version != BUNDLE_FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported bundle format version {} (expected {})",
+                manifest.format_version, BUNDLE_FORMAT_VERSION
+            ));
+        }
+        let export: MissionExport = read_entry(&mut zip, "mission.json")?;
+        let telemetry: Vec<TelemetryRecord> = read_entry(&mut zip, "telemetry.json")?;
+        let commands: Vec<CommandRecord> = read_entry(&mut zip, "commands.json")?;
+        let alerts: Vec<Notification> = read_entry(&mut zip, "alerts.json")?;
+
+        let name = format!("{} (imported)", export.mission.mission_name);
+        let created = self.clone().create_default_mission(&name).await;
+        let mission_id = created.mission_id;
+        if mission_id == 0 {
+            return Err("Failed to create the imported mission".into());
+        }
+
+        // Remove the partial mission if any part of the import fails
+        let mission = match self
+            .import_bundle_records(mission_id, name, export, &telemetry, &commands, &alerts)
+            .await
+        {
+            Ok(mission) => mission,
+            Err(e) => {
+                let _ = self.store.delete_mission(mission_id).await;
+                return Err(e);
+            }
+        };
+
+        let mut state = self.state.lock().await;
+        state.missions.push(mission);
+        logs::info(
+            "missions",
+            format!("Imported mission bundle {} as mission {}", path, mission_id),
+        );
+        self.emit_state_update(&app_handle, &state)?;
+        Ok(mission_id)
+    }
+
+    async fn import_bundle_records(
+        &self,
+        mission_id: i32,
+        name: String,
+        export: MissionExport,
+        telemetry: &[TelemetryRecord],
+        commands: &[CommandRecord],
+        alerts: &[Notification],
+    ) -> Result<MissionStruct, String> {
+        let db_error = |e: sqlx::Error| format!("Failed to import mission: {}", e);
+        let mission = write_definition(self.store.as_ref(), mission_id, name, export).await?;
+
+        for record in telemetry {
+            insert_telemetry_record(self.db.clone(), mission_id, record)
+                .await
+                .map_err(db_error)?;
+        }
+        for record in commands {
+            insert_imported_command(self.db.clone(), record, mission_id)
+                .await
+                .map_err(db_error)?;
+        }
+        for alert in alerts {
+            insert_imported_notification(self.db.clone(), alert)
+                .await
+                .map_err(db_error)?;
+        }
+
+        Ok(mission)
+    }
+}
@@ -0,0 +1,175 @@
+/*
+Implement helper methods on MissionApiImpl for copying a mission
+definition between GCS databases, for teams that plan on an office
+machine and fly from the field laptop. Only the plan is copied:
+vehicles, stages with their search areas, zones, display time zone
+and notes. The copy starts Inactive with no telemetry, commands or
+holds, whatever the original went through. A station that is running
+shows a mission pushed to it after its next start, so the field
+laptop normally pulls.
+*/
+
+use tauri::{AppHandle, Runtime};
+use crate::init_db::connect_remote;
+use crate::logs;
+use crate::missions::store::{MissionStore, PgMissionStore};
+use crate::missions::types::*;
+use super::bundle::write_definition;
+use super::MissionApiImpl;
+
+// The mission as a fresh plan: nothing started, nothing secured
+fn as_plan(mut export: MissionExport) -> MissionExport {
+    let mission = &mut export.mission;
+    mission.mission_status = MissionStageStatusEnum::Inactive;
+    mission.created_at = None;
+    mission.started_at = None;
+    mission.completed_at = None;
+    for vehicle in [&mut mission.vehicles.MEA, &mut mission.vehicles.ERU, &mut mission.vehicles.MRA] {
+        vehicle.patient_status = Some(PatientStatusEnum::Unsecured);
+        for stage in vehicle.stages.iter_mut() {
+            stage.stage_status = MissionStageStatusEnum::Inactive;
+            stage.created_at = None;
+            stage.started_at = None;
+            stage.completed_at = None;
+        }
+    }
+    export.holds.clear();
+    export
+}
+
+// Create the mission in `store` and write the plan into it, removing it again if that fails
+async fn copy_into(store: &dyn MissionStore, export: MissionExport) -> Result<MissionStruct, String> {
+    let name = export.mission.mission_name.clone();
+    let mission_id = store
+        .insert_new_mission(&name)
+        .await
+        .map_err(|e| format!("Failed to create the mission: {}", e))?;
+    match write_definition(store, mission_id, name, export).await {
+        Ok(mission) => Ok(mission),
+        Err(e) => {
+            let _ = store.delete_mission(mission_id).await;
+            Err(e)
+        }
+    }
+}
+
+impl MissionApiImpl {
+    // Returns the mission's id in the remote database
+    pub async fn push_mission_helper(
+        &self,
+        mission_id: i32,
+        remote_db_url: String,
+    ) -> Result<i32, String> {
+        let export = as_plan(self.export_mission_helper(mission_id).await?);
+        let (pool, remote) = connect_remote(&remote_db_url).await?;
+        let copied = copy_into(&PgMissionStore::new(pool.clone()), export).await;
+        pool.close().await;
+        let remote_id = copied?.mission_id;
+
+        logs::info(
+            "missions",
+            format!("Pushed mission {} to {} as mission {}", mission_id, remote, remote_id),
+        );
+        Ok(remote_id)
+    }
+
+    // Returns the mission's id in this station's database
+    pub async fn pull_mission_helper(
+        &self,
+        app_handle: AppHandle<impl Runtime>,
+        remote_db_url: String,
+        mission_id: i32,
+    ) -> Result<i32, String> {
+        let (pool, remote) = connect_remote(&remote_db_url).await?;
+        let loaded = async {
+            let mission = Self::load_mission(&pool, mission_id).await?;
+            let notes = PgMissionStore::new(pool.clone())
+                .select_mission_notes(mission_id)
+                .await
+                .map_err(|e| format!("Failed to load notes: {}", e))?;
+            Ok::<_, String>(MissionExport {
+                mission,
+                notes,
+                holds: vec![],
+                exported_at: chrono::Utc::now().to_rfc3339(),
+            })
+        }
+        .await;
+        pool.close().await;
+        let export = as_plan(loaded?);
+
+        let mut state = self.state.lock().await;
+        let mission = copy_into(self.store.as_ref(), export).await?;
+        let local_id = mission.mission_id;
+        state.missions.push(mission);
+        logs::info(
+            "missions",
+            format!("Pulled mission {} from {} as mission {}", mission_id, remote, local_id),
+        );
+        self.emit_state_update(&app_handle, &state)?;
+        Ok(local_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::missions::store::memory::MemoryMissionStore;
+    use crate::missions::api::tests::api_with_mission;
+
+    // Export of a mission in flight: MEA secured its patient and is searching its only stage
+    async fn export_in_flight() -> MissionExport {
+        let (api, store, mission_id) = api_with_mission().await;
+        let vehicle_id = store.select_vehicle_from_mission(mission_id, "MEA".to_string()).await.unwrap();
+        let stage_id = store.insert_new_stage(vehicle_id, "Sweep").await.unwrap();
+        store.insert_mission_note(mission_id, "alice", "Patient found").await.unwrap();
+        store.insert_mission_hold(mission_id, "Weather", "alice").await.unwrap();
+        {
+            let mut state = api.state.lock().await;
+            let mission = state.missions.iter_mut().find(|m| m.mission_id == mission_id).unwrap();
+            mission.mission_status = MissionStageStatusEnum::Active;
+            mission.started_at = Some(utc_timestamp());
+            let mea = &mut mission.vehicles.MEA;
+            mea.current_stage = stage_id;
+            mea.patient_status = Some(PatientStatusEnum::Secured);
+            mea.stages.push(StageStruct {
+                stage_name: "Sweep".to_string(),
+                stage_id,
+                stage_status: MissionStageStatusEnum::Active,
+                search_area: mission.zones.keep_out_zones[0].clone(),
+                created_at: Some(utc_timestamp()),
+                started_at: Some(utc_timestamp()),
+                completed_at: None,
+            });
+        }
+        api.export_mission_helper(mission_id).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn a_copied_mission_starts_over_as_a_plan() {
+        let export = as_plan(export_in_flight().await);
+        assert!(export.holds.is_empty());
+        assert_eq!(export.notes.len(), 1);
+
+        let target = MemoryMissionStore::new();
+        let copied = copy_into(&target, export.clone()).await.unwrap();
+        assert!(matches!(copied.mission_status, MissionStageStatusEnum::Inactive));
+        assert_eq!(copied.started_at, None);
+        assert_eq!(copied.zones.keep_out_zones.len(), 3);
+        assert_eq!(target.select_mission_notes(copied.mission_id).await.unwrap().len(), 1);
+
+        let mea = &copied.vehicles.MEA;
+        assert_eq!(mea.patient_status, Some(PatientStatusEnum::Unsecured));
+        assert_eq!(mea.stages.len(), 1);
+        let stage = &mea.stages[0];
+        assert!(matches!(stage.stage_status, MissionStageStatusEnum::Inactive));
+        assert_eq!(stage.started_at, None);
+        assert_eq!(stage.search_area, export.mission.vehicles.MEA.stages[0].search_area);
+
+        // The stage got a new id in the target store and is still the vehicle's current one
+        let vehicle_id = target.select_vehicle_from_mission(copied.mission_id, "MEA".to_string()).await.unwrap();
+        assert_eq!(mea.current_stage, stage.stage_id);
+        assert_eq!(target.current_stage(vehicle_id), Some(stage.stage_id));
+        assert_eq!(target.stage_status(stage.stage_id).as_deref(), Some("Inactive"));
+    }
+}
@@ -0,0 +1,62 @@
+/*
+Implement helper methods on MissionApiImpl for search-area
+coverage, computed from the recorded track of each stage's vehicle.
+*/
+
+use crate::config;
+use crate::missions::coverage::compute_coverage;
+use crate::missions::types::*;
+use crate::telemetry::sql::select_mission_telemetry;
+use super::MissionApiImpl;
+
+impl MissionApiImpl {
+    // Coverage of every stage with a search polygon; recomputed on each call so it can back a
+    // live progress display as well as the post-mission review
+    pub async fn get_mission_coverage_helper(
+        &self,
+        mission_id: i32,
+    ) -> Result<Vec<StageCoverage>, String> {
+        let mission = {
+            let state = self.state.lock().await;
+            state
+                .missions
+                .iter()
+                .find(|m| m.mission_id == mission_id)
+                .cloned()
+                .ok_or("Mission not found")?
+        };
+        let records = select_mission_telemetry(self.db.clone(), mission_id)
+            .await
+            .map_err(|e| format!("Failed to load telemetry: {}", e))?;
+        let footprint = config::get().sensor_footprint_width_m;
+
+        let mut coverage = vec![];
+        for vehicle in [&mission.vehicles.MEA, &mission.vehicles.ERU, &mission.vehicles.MRA] {
+            let name = vehicle.vehicle_name.to_string();
+            let track: Vec<GeoCoordinateStruct> = records
+                .iter()
+                .filter(|r| r.vehicle_id.eq_ignore_ascii_case(&name))
+                .map(|r| GeoCoordinateStruct {
+                    lat: r.position.latitude,
+                    long: r.position.longitude,
+                })
+                .collect();
+
+            for stage in &vehicle.stages {
+                let Some(result) = compute_coverage(&stage.search_area, &track, footprint) else {
+                    continue;
+                };
+                coverage.push(StageCoverage {
+                    mission_id,
+                    vehicle_name: vehicle.vehicle_name.clone(),
+                    stage_id: stage.stage_id,
+                    stage_name: stage.stage_name.clone(),
+                    coverage_percent: result.coverage_percent,
+                    area_m2: result.area_m2,
+                    uncovered_regions: result.uncovered_regions,
+                });
+            }
+        }
+        Ok(coverage)
+    }
+}
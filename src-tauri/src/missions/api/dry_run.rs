@@ -0,0 +1,178 @@
+/*
+Implement helper methods on MissionApiImpl for a mission dry run:
+the start-mission flow's checks and transmissions worked out without
+sending a command or changing mission state. Stage durations are
+estimated at the speed the simulator flies each vehicle, sweeping
+the search area in lanes one sensor footprint wide.
+*/
+
+use crate::config;
+use crate::geometry::{area_m2, centroid, validate_polygon};
+use crate::maintenance;
+use crate::missions::types::*;
+use crate::simulator;
+use crate::telemetry::geos::{harversine_distance, Coordinate};
+use super::missions::{disarmed_participants, participants};
+use super::MissionApiImpl;
+
+/// Number of messages a polygon of `vertices` goes out in, as chunked by the command dispatcher
+fn message_count(vertices: usize) -> i32 {
+    let chunk_size = config::get().max_zone_vertices.max(1) as usize;
+    vertices.div_ceil(chunk_size).max(1) as i32
+}
+
+fn distance_m(a: &GeoCoordinateStruct, b: &GeoCoordinateStruct) -> f64 {
+    harversine_distance(
+        &Coordinate { latitude: a.lat, longitude: a.long },
+        &Coordinate { latitude: b.lat, longitude: b.long },
+    )
+}
+
+impl MissionApiImpl {
+    pub async fn dry_run_mission_helper(&self, mission_id: i32) -> Result<MissionDryRun, String> {
+        let (mission, current_mission, previous) = {
+            let state = self.state.lock().await;
+            let mission = state
+                .missions
+                .iter()
+                .find(|m| m.mission_id == mission_id)
+                .cloned()
+                .ok_or("Mission not found")?;
+            let previous = state
+                .missions
+                .iter()
+                .find(|m| m.mission_id == state.current_mission && m.mission_id != mission_id)
+                .cloned();
+            (mission, state.current_mission, previous)
+        };
+
+        let mut failures = vec![];
+        let mut warnings = vec![];
+
+        // Same checks, in the same order, as start_mission
+        let disarmed = disarmed_participants(&mission);
+        if !disarmed.is_empty() {
+            failures.push(format!("Cannot start mission: {} disarmed", disarmed.join(", ")));
+        }
+        if let Err(e) = self.require_not_held(current_mission, "start mission") {
+            failures.push(e);
+        }
+        let vehicle_ids = participants(&mission);
+        if vehicle_ids.is_empty() {
+            warnings.push("No vehicle has a stage to fly".to_string());
+        }
+        match maintenance::worn_batteries(self.db.clone(), &vehicle_ids).await {
+            Ok(batteries) => warnings.extend(batteries.iter().filter_map(|battery| {
+                let vehicle_id = battery.vehicle_id.as_deref()?;
+                Some(maintenance::worn_battery_message(vehicle_id, battery))
+            })),
+            Err(e) => warnings.push(format!("Failed to check batteries: {}", e)),
+        }
+        if let Some(previous) = previous.filter(|m| {
+            matches!(m.mission_status, MissionStageStatusEnum::Active | MissionStageStatusEnum::Paused)
+        }) {
+            warnings.push(format!("Starting completes mission {}", previous.mission_name));
+        }
+
+        let mut transmissions = vec![];
+        let zones = [
+            (2, "keep_in", "Keep-in zone", &mission.zones.keep_in_zones),
+            (3, "keep_out", "Keep-out zone", &mission.zones.keep_out_zones),
+        ];
+        for (command_id, key, label, zones) in zones {
+            for (zone_index, zone) in zones.iter().enumerate() {
+                if zone.len() < 3 {
+                    warnings.push(format!("{} {} has fewer than 3 vertices and is not sent", label, zone_index + 1));
+                    continue;
+                }
+                if let Err(e) = validate_polygon(zone) {
+                    failures.push(format!("{} {}: {}", label, zone_index + 1, e));
+                }
+                transmissions.push(DryRunTransmission {
+                    vehicle_id: "ALL".to_string(),
+                    command_id,
+                    zone_key: format!("{}_{}", key, zone_index),
+                    vertices: zone.len() as i32,
+                    messages: message_count(zone.len()),
+                });
+            }
+        }
+
+        let footprint = config::get().sensor_footprint_width_m;
+        let mut stages = vec![];
+        // Stays None unless every participating vehicle's stages could be estimated
+        let mut vehicle_totals: Vec<Option<f64>> = vec![];
+        for vehicle in [&mission.vehicles.MEA, &mission.vehicles.ERU, &mission.vehicles.MRA] {
+            let name = vehicle.vehicle_name.to_string();
+            let speed = simulator::api::vehicle_speed_ms(&name).filter(|speed| *speed > 0.0);
+            if speed.is_none() && !vehicle.stages.is_empty() {
+                warnings.push(format!("{} has no simulated speed; its stages are not estimated", name));
+            }
+
+            let mut previous_center: Option<GeoCoordinateStruct> = None;
+            let mut vehicle_secs = Some(0.0);
+            for (stage_index, stage) in vehicle.stages.iter().enumerate() {
+                let polygon = stage.search_area.len() >= 3;
+                if polygon {
+                    if let Err(e) = validate_polygon(&stage.search_area) {
+                        failures.push(format!("{} stage {} search area: {}", name, stage.stage_name, e));
+                    }
+                }
+                // Only the first stage's search area goes out at start; later ones on transition
+                if stage_index == 0 {
+                    if polygon {
+                        transmissions.push(DryRunTransmission {
+                            vehicle_id: name.clone(),
+                            command_id: 4,
+                            zone_key: "search_area".to_string(),
+                            vertices: stage.search_area.len() as i32,
+                            messages: message_count(stage.search_area.len()),
+                        });
+                    } else {
+                        warnings.push(format!("{} stage {} has no search area to send", name, stage.stage_name));
+                    }
+                }
+
+                let area = polygon.then(|| area_m2(&stage.search_area));
+                let center = polygon.then(|| centroid(&stage.search_area)).flatten();
+                let transit_m = match (&previous_center, &center) {
+                    (Some(from), Some(to)) => distance_m(from, to),
+                    _ => 0.0,
+                };
+                let estimated_secs = match (area, speed) {
+                    (Some(area), Some(speed)) if footprint > 0.0 => Some((transit_m + area / footprint) / speed),
+                    _ => None,
+                };
+                vehicle_secs = vehicle_secs.zip(estimated_secs).map(|(total, secs)| total + secs);
+                if center.is_some() {
+                    previous_center = center;
+                }
+                stages.push(DryRunStage {
+                    vehicle_name: vehicle.vehicle_name.clone(),
+                    stage_id: stage.stage_id,
+                    stage_name: stage.stage_name.clone(),
+                    area_m2: area,
+                    transit_m,
+                    estimated_secs,
+                });
+            }
+            if !vehicle.stages.is_empty() {
+                vehicle_totals.push(vehicle_secs);
+            }
+        }
+        let estimated_duration_secs = vehicle_totals
+            .into_iter()
+            .collect::<Option<Vec<f64>>>()
+            .and_then(|totals| totals.into_iter().reduce(f64::max));
+
+        Ok(MissionDryRun {
+            mission_id,
+            transmissions,
+            failures,
+            warnings,
+            stages,
+            estimated_duration_secs,
+            checked_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+}
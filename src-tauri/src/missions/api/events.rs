@@ -1,28 +1,176 @@
 /*
 Implement helper methods on MissionApiImpl for emitting 
-mission-related events to the frontend and wrapping 
-any mission event-trigger logic.
+mission-related events to the frontend through an EventSink
+(the AppHandle in the app, anything else in tests). Every state
+update also emits scoped events for just the missions and stages
+that changed since the previous update, journals those changes for
+frontends that resync, logs the active mission's stage transitions
+to the live event log, queues lifecycle events for the ops feed and
+updates the mission tags stamped on telemetry and the geofence
+geometry. Mission starts and completions and stage transitions also
+get events of their own, carrying just the ids and statuses.
 */
 
-use tauri::{AppHandle, Runtime};
-use crate::missions::types::MissionsStruct;
-use super::{MissionApiImpl, MissionEventTrigger}; 
-
-// We need MissionEventTrigger. This is usually generated by the macro in mod.rs. 
-// If it's generated in `mod.rs`, we can import it via `super::MissionEventTrigger`.
+use crate::events::EventSink;
+use crate::exports::event_log::{self, EventKind};
+use crate::geofence;
+use crate::mission_sync;
+use crate::ops_feed;
+use crate::resync;
+use crate::snapshot::Snapshot;
+use crate::missions::types::{
+    EmergencyStopEvent, MissionHold, MissionNote, MissionStageStatusEnum,
+    MissionStruct, MissionsStruct, VehicleEnum,
+};
+use crate::telemetry::tagging;
+use super::MissionApiImpl;
 
 impl MissionApiImpl {
     /// Emit state changes to frontend
     /// Should be called after any state modification
     pub fn emit_state_update(
         &self,
-        app_handle: &AppHandle<impl Runtime>,
-        state: &MissionsStruct,
+        events: &(impl EventSink + ?Sized),
+        state: &Snapshot<MissionsStruct>,
+    ) -> Result<(), String> {
+        // Share the change with other GCS stations when mission sync is enabled
+        mission_sync::record_local(state);
+        // Telemetry is stamped with the running mission and its vehicles' current stages
+        tagging::set_missions(state);
+
+        // Keep-out zones are checked on every telemetry report; when one is drawn or moved
+        // during the mission, vehicles already inside or near it are flagged right away
+        if geofence::set_missions(state) {
+            let active = state.missions.iter().find(|m| m.mission_id == state.current_mission);
+            if active.is_some_and(|m| m.mission_status == MissionStageStatusEnum::Active) {
+                geofence::recheck_positions();
+            }
+        }
+
+        let current = state.share();
+        let previous = std::mem::replace(&mut *self.emitted.lock().unwrap(), current.clone());
+        resync::record_missions(&previous, &current);
+        ops_feed::record_missions(&previous, &current);
+        events.missions_updated(current.clone())?;
+        emit_scoped_changes(events, &previous, &current)
+    }
+
+    /// Emit the emergency stop broadcast result so the UI can raise a prominent alert
+    pub fn emit_emergency_stop(
+        &self,
+        events: &impl EventSink,
+        event: &EmergencyStopEvent,
+    ) -> Result<(), String> {
+        let emitted = self.emitted.lock().unwrap().clone();
+        ops_feed::record_emergency_stop(event, emitted.missions.iter().find(|m| m.mission_id == event.mission_id));
+        events.emergency_stop(event.clone())
+    }
+
+    /// Emit a new operator note so every open window's mission log updates
+    pub fn emit_note_added(
+        &self,
+        events: &impl EventSink,
+        note: &MissionNote,
     ) -> Result<(), String> {
-        MissionEventTrigger::new(app_handle.clone())
-            .on_updated(state.clone())
-            .map_err(|e| e.to_string())
+        events.note_added(note.clone())
+    }
+
+    /// Emit the mission's hold, or None once it is released
+    pub fn emit_hold_changed(
+        &self,
+        events: &impl EventSink,
+        mission_id: i32,
+        hold: Option<MissionHold>,
+    ) -> Result<(), String> {
+        events.hold_changed(mission_id, hold)
     }
 }
 
+fn emit_scoped_changes(
+    events: &(impl EventSink + ?Sized),
+    previous: &MissionsStruct,
+    current: &MissionsStruct,
+) -> Result<(), String> {
+    for mission in &current.missions {
+        let before = previous.missions.iter().find(|m| m.mission_id == mission.mission_id);
+        if before == Some(mission) {
+            continue;
+        }
+        events.mission_updated(mission.mission_id, mission.clone())?;
+        // Stage transitions first, so the stage a mission completes on is reported before it
+        emit_stage_changes(events, before, mission)?;
+        if let Some(before) = before {
+            emit_lifecycle(events, before, mission)?;
+        }
+    }
+    for mission in &previous.missions {
+        if !current.missions.iter().any(|m| m.mission_id == mission.mission_id) {
+            events.mission_deleted(mission.mission_id)?;
+        }
+    }
+    Ok(())
+}
 
+fn emit_stage_changes(
+    events: &(impl EventSink + ?Sized),
+    before: Option<&MissionStruct>,
+    mission: &MissionStruct,
+) -> Result<(), String> {
+    let vehicles = &mission.vehicles;
+    for vehicle in [&vehicles.MEA, &vehicles.ERU, &vehicles.MRA] {
+        let stages_before = before.map(|m| match vehicle.vehicle_name {
+            VehicleEnum::MEA => &m.vehicles.MEA.stages,
+            VehicleEnum::ERU => &m.vehicles.ERU.stages,
+            VehicleEnum::MRA => &m.vehicles.MRA.stages,
+        });
+        for stage in &vehicle.stages {
+            let stage_before = stages_before
+                .and_then(|stages| stages.iter().find(|s| s.stage_id == stage.stage_id));
+            let unchanged = stage_before.is_some_and(|s| s == stage);
+            // Status changes of the active mission's stages go to the live event log
+            let transitioned = stage_before.is_some_and(|s| s.stage_status != stage.stage_status);
+            if let Some(stage_before) = stage_before.filter(|_| transitioned) {
+                events.stage_transitioned(
+                    mission.mission_id,
+                    vehicle.vehicle_name.clone(),
+                    stage.stage_id,
+                    stage_before.stage_status.clone(),
+                    stage.stage_status.clone(),
+                )?;
+            }
+            if transitioned && event_log::active_mission() == Some(mission.mission_id) {
+                event_log::record(
+                    EventKind::StageTransition,
+                    Some(&vehicle.vehicle_name.to_string()),
+                    stage.stage_status.name(),
+                    &format!("Stage {} ({})", stage.stage_name, stage.stage_id),
+                );
+            }
+            if !unchanged {
+                events.stage_changed(
+                    mission.mission_id,
+                    vehicle.vehicle_name.clone(),
+                    crate::vehicles::alias(&vehicle.vehicle_name.to_string()),
+                    stage.clone(),
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Same transitions the ops feed reports as mission_started and mission_completed/aborted
+fn emit_lifecycle(
+    events: &(impl EventSink + ?Sized),
+    before: &MissionStruct,
+    mission: &MissionStruct,
+) -> Result<(), String> {
+    use MissionStageStatusEnum::*;
+    match (&before.mission_status, &mission.mission_status) {
+        (Inactive, Active) => events.mission_started(mission.mission_id),
+        (Active | Paused, status @ (Complete | Failed)) => {
+            events.mission_completed(mission.mission_id, status.clone())
+        }
+        _ => Ok(()),
+    }
+}
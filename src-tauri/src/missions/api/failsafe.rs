@@ -0,0 +1,74 @@
+/*
+Lost-link failsafe for the active mission: when the heartbeat monitor gives up on a vehicle,
+its current stage is marked Failed and, if configured, a return-to-home command is queued so
+it goes out as soon as the link comes back.
+*/
+
+use async_trait::async_trait;
+use crate::config;
+use crate::events::EventSink;
+use crate::logs;
+use crate::missions::types::*;
+use crate::telemetry::rabbitmq::LostLinkHandler;
+use super::MissionApiImpl;
+
+#[async_trait]
+impl LostLinkHandler for MissionApiImpl {
+    async fn lost_link(&self, events: &dyn EventSink, vehicle_id: &str) {
+        if let Err(e) = self.fail_current_stage(events, vehicle_id).await {
+            logs::error("missions", format!("Lost-link failsafe for {} failed: {}", vehicle_id, e));
+        }
+
+        if config::get().lost_link_return_home {
+            match self.commands.queue_return_to_home(vehicle_id).await {
+                Ok(()) => logs::info("missions", format!("Return-to-home queued for {} after lost link", vehicle_id)),
+                Err(e) => logs::error("missions", format!("Failed to queue return-to-home for {}: {}", vehicle_id, e)),
+            }
+        }
+    }
+}
+
+impl MissionApiImpl {
+    /// Marks the vehicle's current stage in the active mission as Failed; a no-op when no
+    /// mission is active or the vehicle takes no part in it.
+    async fn fail_current_stage(&self, events: &dyn EventSink, vehicle_id: &str) -> Result<(), String> {
+        let mut state = self.state.lock().await;
+        let current_mission = state.current_mission;
+        let Some(mission) = state
+            .missions
+            .iter_mut()
+            .find(|m| m.mission_id == current_mission)
+        else {
+            return Ok(());
+        };
+        if !matches!(mission.mission_status, MissionStageStatusEnum::Active) {
+            return Ok(());
+        }
+
+        let vehicles = &mut mission.vehicles;
+        let Some(vehicle) = [&mut vehicles.MEA, &mut vehicles.ERU, &mut vehicles.MRA]
+            .into_iter()
+            .find(|vehicle| vehicle.vehicle_name.to_string().eq_ignore_ascii_case(vehicle_id))
+        else {
+            return Ok(());
+        };
+        let current_stage = vehicle.current_stage;
+        let Some(stage) = vehicle.stages.iter_mut().find(|s| s.stage_id == current_stage) else {
+            return Ok(());
+        };
+        if !matches!(stage.stage_status, MissionStageStatusEnum::Active) {
+            return Ok(());
+        }
+
+        stage.set_status(MissionStageStatusEnum::Failed);
+        self.store.update_stage_status(stage.stage_id, "Failed")
+            .await
+            .map_err(|e| format!("Failed to persist failed stage: {}", e))?;
+        logs::warn(
+            "missions",
+            format!("Stage {} of {} failed: lost link", stage.stage_name, vehicle_id),
+        );
+
+        self.emit_state_update(events, &state)
+    }
+}
@@ -0,0 +1,135 @@
+/*
+Implement helper methods on MissionApiImpl for keep-out warning
+thresholds: how close a vehicle may come to a keep-out zone before
+telemetry flags it as approaching, for a whole mission or a single
+zone. Missions without thresholds use geofence_warning_distance_m
+from the configuration.
+*/
+
+use crate::auth::current_operator;
+use crate::logs;
+use crate::missions::store::MissionStore;
+use crate::missions::types::GeofenceThreshold;
+use crate::geofence;
+use super::MissionApiImpl;
+
+const MAX_WARNING_DISTANCE_M: f64 = 10_000.0;
+
+impl MissionApiImpl {
+    /// Fill the telemetry check's cache with every mission's thresholds
+    pub async fn load_geofence_thresholds(store: &dyn MissionStore) {
+        let thresholds = match store.select_geofence_thresholds(None).await {
+            Ok(thresholds) => thresholds,
+            Err(e) => {
+                logs::error("missions::db", format!("Failed to load geofence thresholds: {}", e));
+                return;
+            }
+        };
+        let mut missions: Vec<i32> = thresholds.iter().map(|t| t.mission_id).collect();
+        missions.dedup();
+        for mission_id in missions {
+            geofence::set_mission_thresholds(
+                mission_id,
+                thresholds.iter().filter(|t| t.mission_id == mission_id).cloned().collect(),
+            );
+        }
+    }
+
+    pub async fn list_geofence_thresholds_helper(&self, mission_id: i32) -> Result<Vec<GeofenceThreshold>, String> {
+        self.require_mission(mission_id).await?;
+        self.select_geofence_thresholds(mission_id).await
+    }
+
+    async fn select_geofence_thresholds(&self, mission_id: i32) -> Result<Vec<GeofenceThreshold>, String> {
+        self.store.select_geofence_thresholds(Some(mission_id))
+            .await
+            .map_err(|e| format!("Failed to load geofence thresholds: {}", e))
+    }
+
+    /// Set or replace the mission-wide threshold, or that of one keep-out zone
+    pub async fn set_geofence_threshold_helper(
+        &self,
+        threshold: GeofenceThreshold,
+    ) -> Result<Vec<GeofenceThreshold>, String> {
+        let distance = threshold.warning_distance_m;
+        if !distance.is_finite() || distance <= 0.0 || distance > MAX_WARNING_DISTANCE_M {
+            return Err(format!(
+                "Warning distance must be between 0 and {:.0} m",
+                MAX_WARNING_DISTANCE_M
+            ));
+        }
+
+        let state = self.state.lock().await;
+        let mission = state
+            .missions
+            .iter()
+            .find(|m| m.mission_id == threshold.mission_id)
+            .ok_or("Mission not found")?;
+        if let Some(zone_index) = threshold.zone_index {
+            if zone_index < 0 || zone_index as usize >= mission.zones.keep_out_zones.len() {
+                return Err("KeepOut index out of range".into());
+            }
+        }
+
+        let mut thresholds = self.select_geofence_thresholds(threshold.mission_id).await?;
+        thresholds.retain(|t| t.zone_index != threshold.zone_index);
+        thresholds.push(threshold.clone());
+        thresholds.sort_by_key(|t| t.zone_index.unwrap_or(-1));
+        self.save_geofence_thresholds(threshold.mission_id, thresholds).await
+    }
+
+    pub async fn clear_geofence_threshold_helper(
+        &self,
+        mission_id: i32,
+        zone_index: Option<i32>,
+    ) -> Result<Vec<GeofenceThreshold>, String> {
+        let state = self.state.lock().await;
+        if !state.missions.iter().any(|m| m.mission_id == mission_id) {
+            return Err("Mission not found".into());
+        }
+        let mut thresholds = self.select_geofence_thresholds(mission_id).await?;
+        let count = thresholds.len();
+        thresholds.retain(|t| t.zone_index != zone_index);
+        if thresholds.len() == count {
+            return Err(match zone_index {
+                Some(zone_index) => format!("Keep-out zone {} has no threshold of its own", zone_index),
+                None => format!("Mission {} has no mission-wide threshold", mission_id),
+            });
+        }
+        self.save_geofence_thresholds(mission_id, thresholds).await
+    }
+
+    /// Drop the removed keep-out zone's threshold and renumber those after it.
+    /// Callers hold the state lock.
+    pub async fn remove_zone_threshold(&self, mission_id: i32, zone_index: i32) -> Result<(), String> {
+        let thresholds = self.select_geofence_thresholds(mission_id).await?;
+        if !thresholds.iter().any(|t| t.zone_index.is_some_and(|i| i >= zone_index)) {
+            return Ok(());
+        }
+        let thresholds = thresholds
+            .into_iter()
+            .filter(|t| t.zone_index != Some(zone_index))
+            .map(|t| GeofenceThreshold {
+                zone_index: t.zone_index.map(|i| if i > zone_index { i - 1 } else { i }),
+                ..t
+            })
+            .collect();
+        self.save_geofence_thresholds(mission_id, thresholds).await.map(|_| ())
+    }
+
+    async fn save_geofence_thresholds(
+        &self,
+        mission_id: i32,
+        thresholds: Vec<GeofenceThreshold>,
+    ) -> Result<Vec<GeofenceThreshold>, String> {
+        self.store.replace_geofence_thresholds(mission_id, &thresholds, &current_operator())
+            .await
+            .map_err(|e| format!("Failed to save geofence thresholds: {}", e))?;
+        geofence::set_mission_thresholds(mission_id, thresholds.clone());
+        logs::info(
+            "missions",
+            format!("Geofence thresholds of mission {} updated by {}", mission_id, current_operator()),
+        );
+        Ok(thresholds)
+    }
+}
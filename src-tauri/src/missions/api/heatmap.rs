@@ -0,0 +1,45 @@
+/*
+Implement helper methods on MissionApiImpl for track heatmaps,
+binned from the telemetry recorded while the mission was active.
+*/
+
+use crate::missions::heatmap::compute_heatmap;
+use crate::missions::types::*;
+use crate::telemetry::sql::select_mission_telemetry;
+use super::MissionApiImpl;
+
+impl MissionApiImpl {
+    pub async fn get_mission_heatmap_helper(
+        &self,
+        mission_id: i32,
+        vehicle_name: Option<VehicleEnum>,
+        cell_size_m: f64,
+    ) -> Result<TrackHeatmap, String> {
+        if !cell_size_m.is_finite() || cell_size_m <= 0.0 {
+            return Err("Heatmap cell size must be a positive distance".into());
+        }
+        self.require_mission(mission_id).await?;
+        let records = select_mission_telemetry(self.db.clone(), mission_id)
+            .await
+            .map_err(|e| format!("Failed to load telemetry: {}", e))?;
+
+        let name = vehicle_name.as_ref().map(|v| v.to_string());
+        let track: Vec<GeoCoordinateStruct> = records
+            .iter()
+            .filter(|r| name.as_ref().is_none_or(|name| r.vehicle_id.eq_ignore_ascii_case(name)))
+            .map(|r| GeoCoordinateStruct {
+                lat: r.position.latitude,
+                long: r.position.longitude,
+            })
+            .collect();
+
+        let result = compute_heatmap(&track, cell_size_m);
+        Ok(TrackHeatmap {
+            mission_id,
+            vehicle_name,
+            cell_size_m: result.cell_size_m,
+            max_samples: result.max_samples,
+            cells: result.cells,
+        })
+    }
+}
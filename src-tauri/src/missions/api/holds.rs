@@ -0,0 +1,109 @@
+/*
+Implement helper methods on MissionApiImpl for mission-wide holds
+(e.g. weather or range safety). Unlike pausing, a hold leaves the
+vehicles flying their current stage; it blocks stage transitions and
+non-emergency commands until released, and every hold period is kept
+for the mission report.
+*/
+
+use tauri::{AppHandle, Runtime};
+use crate::auth::current_operator;
+use crate::input;
+use crate::logs;
+use crate::missions::types::{MissionHold, MissionStageStatusEnum};
+use super::MissionApiImpl;
+
+const MAX_REASON_LENGTH: usize = 500;
+
+impl MissionApiImpl {
+    /// Errors while the mission is on hold, e.g. "Cannot transition stage: mission 3 is on hold: ..."
+    pub fn require_not_held(&self, mission_id: i32, action: &str) -> Result<(), String> {
+        match self.commands.mission_hold() {
+            Some(hold) if hold.mission_id == mission_id => Err(format!(
+                "Cannot {}: mission {} is on hold: {}",
+                action, mission_id, hold.reason
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn set_mission_hold_helper(
+        &self,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        reason: String,
+    ) -> Result<MissionHold, String> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err("A hold needs a reason".into());
+        }
+        if reason.chars().count() > MAX_REASON_LENGTH {
+            return Err(format!("Hold reasons are limited to {} characters", MAX_REASON_LENGTH));
+        }
+
+        // Held across the insert so two holds cannot be opened at once
+        let state = self.state.lock().await;
+        let mission = state
+            .missions
+            .iter()
+            .find(|m| m.mission_id == mission_id)
+            .ok_or("Mission not found")?;
+        if state.current_mission != mission_id
+            || !matches!(mission.mission_status, MissionStageStatusEnum::Active)
+        {
+            return Err("Only the active mission can be put on hold".into());
+        }
+        if let Some(hold) = self.commands.mission_hold() {
+            return Err(format!("Mission {} is already on hold: {}", hold.mission_id, hold.reason));
+        }
+
+        let hold = self.store.insert_mission_hold(mission_id, reason, &current_operator())
+            .await
+            .map_err(|e| format!("Failed to save hold: {}", e))?;
+        self.commands.set_mission_hold(Some(hold.clone()));
+        // Manual control is blocked while held; a running gamepad session ends with a hold
+        input::stop_manual_control(true);
+        logs::warn(
+            "missions",
+            format!("Mission {} put on hold by {}: {}", mission_id, hold.held_by, hold.reason),
+        );
+        self.emit_hold_changed(&app_handle, mission_id, Some(hold.clone()))?;
+        Ok(hold)
+    }
+
+    pub async fn release_mission_hold_helper(
+        &self,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+    ) -> Result<MissionHold, String> {
+        let _state = self.state.lock().await;
+        let hold = self.end_hold(mission_id)
+            .await?
+            .ok_or(format!("Mission {} is not on hold", mission_id))?;
+        self.emit_hold_changed(&app_handle, mission_id, None)?;
+        Ok(hold)
+    }
+
+    /// Closes the mission's open hold, if any. Callers hold the state lock.
+    pub async fn end_hold(&self, mission_id: i32) -> Result<Option<MissionHold>, String> {
+        let Some(open) = self.commands.mission_hold().filter(|h| h.mission_id == mission_id) else {
+            return Ok(None);
+        };
+        let hold = self.store.end_mission_hold(open.hold_id)
+            .await
+            .map_err(|e| format!("Failed to end hold: {}", e))?;
+        self.commands.set_mission_hold(None);
+        logs::info(
+            "missions",
+            format!("Hold on mission {} released by {}", mission_id, current_operator()),
+        );
+        Ok(Some(hold))
+    }
+
+    pub async fn list_mission_holds_helper(&self, mission_id: i32) -> Result<Vec<MissionHold>, String> {
+        self.require_mission(mission_id).await?;
+        self.store.select_mission_holds(mission_id)
+            .await
+            .map_err(|e| format!("Failed to load holds: {}", e))
+    }
+}
@@ -0,0 +1,81 @@
+/*
+Implement helper methods on MissionApiImpl for post-mission KPIs.
+A finished mission's KPIs are computed once and read back from
+mission_kpis; those of a mission still running are computed on
+every request. Storing them also records each vehicle's airtime
+towards its flight hours.
+*/
+
+use sqlx::PgPool;
+
+use crate::logs;
+use crate::maintenance::sql::record_flight_sessions;
+use crate::missions::kpis::{compute_kpis, save_kpis, select_saved_kpis};
+use crate::missions::types::*;
+use super::MissionApiImpl;
+
+async fn persist_kpis(db: PgPool, kpis: &MissionKpis) -> Result<(), sqlx::Error> {
+    save_kpis(db.clone(), kpis).await?;
+    let airtime: Vec<(String, f64)> = kpis
+        .vehicles
+        .iter()
+        .map(|v| (v.vehicle_id.clone(), v.airtime_secs))
+        .collect();
+    record_flight_sessions(db, kpis.mission_id, &airtime).await
+}
+
+impl MissionApiImpl {
+    pub async fn get_mission_kpis_helper(&self, mission_id: i32) -> Result<MissionKpis, String> {
+        let finished = {
+            let state = self.state.lock().await;
+            let mission = state
+                .missions
+                .iter()
+                .find(|m| m.mission_id == mission_id)
+                .ok_or("Mission not found")?;
+            matches!(
+                mission.mission_status,
+                MissionStageStatusEnum::Complete | MissionStageStatusEnum::Failed
+            )
+        };
+
+        if finished {
+            if let Some(kpis) = select_saved_kpis(self.db.clone(), mission_id)
+                .await
+                .map_err(|e| format!("Failed to load mission KPIs: {}", e))?
+            {
+                return Ok(MissionKpis { persisted: true, ..kpis });
+            }
+        }
+
+        let mut kpis = compute_kpis(self.db.clone(), mission_id)
+            .await
+            .map_err(|e| format!("Failed to compute mission KPIs: {}", e))?;
+        if finished {
+            persist_kpis(self.db.clone(), &kpis)
+                .await
+                .map_err(|e| format!("Failed to save mission KPIs: {}", e))?;
+            kpis.persisted = true;
+        }
+        Ok(kpis)
+    }
+
+    /// Compute and store the KPIs of a mission that just completed or was
+    /// aborted, in the background so ending the mission doesn't wait on it
+    pub fn finalize_kpis(&self, mission_id: i32) {
+        let db = self.db.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = match compute_kpis(db.clone(), mission_id).await {
+                Ok(kpis) => persist_kpis(db, &kpis).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => logs::info("missions", format!("KPIs of mission {} saved", mission_id)),
+                Err(e) => logs::error(
+                    "missions::db",
+                    format!("Failed to save KPIs of mission {}: {}", mission_id, e),
+                ),
+            }
+        });
+    }
+}
@@ -1,26 +1,64 @@
 /*
 Implement helper methods on MissionApiImpl for mission-level 
 operations (create, rename, delete missions, get mission data, 
-update mission status, start mission flow).
+update mission status). Starting a mission is in start.rs.
 
 */
 
 use tauri::{AppHandle, Runtime};
+use crate::missions::sql;
 use crate::missions::types::*;
-use crate::missions::sql::{update_mission_name, delete_mission, update_mission_status, update_stage_status, update_auto_mode_vehicle};
-use crate::commands::commands::{CommandsApiImpl, GeoCoordinate};
-use crate::commands::CommandsApi;
+use crate::auth::{require_role, OperatorRole};
+use crate::audit;
+use crate::events::EventSink;
+use crate::logs;
+use crate::telemetry::arming;
 use super::MissionApiImpl;
 
+/// Vehicles with stages in the mission
+pub fn participants(mission: &MissionStruct) -> Vec<String> {
+    let vehicles = &mission.vehicles;
+    [&vehicles.MEA, &vehicles.ERU, &vehicles.MRA]
+        .into_iter()
+        .filter(|vehicle| !vehicle.stages.is_empty())
+        .map(|vehicle| vehicle.vehicle_name.to_string())
+        .collect()
+}
+
+/// Vehicles with stages in the mission that report themselves disarmed
+pub fn disarmed_participants(mission: &MissionStruct) -> Vec<String> {
+    participants(mission)
+        .into_iter()
+        .filter(|vehicle_id| arming::is_armed(vehicle_id) == Some(false))
+        .map(|vehicle_id| crate::vehicles::display_name(&vehicle_id))
+        .collect()
+}
+
+/// Justification for removing a zone or stage, required along with the mission-commander role
+/// while the mission is active or paused; None for a mission that isn't flying
+pub fn in_flight_justification(
+    mission: &MissionStruct,
+    justification: Option<&str>,
+) -> Result<Option<String>, String> {
+    if !matches!(
+        mission.mission_status,
+        MissionStageStatusEnum::Active | MissionStageStatusEnum::Paused
+    ) {
+        return Ok(None);
+    }
+    require_role(OperatorRole::MissionCommander)?;
+    audit::require_justification(justification).map(Some)
+}
+
 impl MissionApiImpl {
-    pub async fn get_mission_data_helper(&self, mission_id: i32) -> MissionStruct {
+    pub async fn get_mission_data_helper(&self, mission_id: i32) -> Result<MissionStruct, MissionError> {
         let state = self.state.lock().await;
         state
             .missions
             .iter()
             .find(|m| m.mission_id == mission_id)
-            .map(|m| m.clone())
-            .unwrap_or_else(|| panic!("Mission not found"))
+            .cloned()
+            .ok_or(MissionError::NotFound { mission_id })
     }
 
     pub async fn rename_mission_helper(
@@ -36,13 +74,44 @@ impl MissionApiImpl {
             .find(|m| m.mission_id == mission_id)
             .ok_or("Mission not found")?;
 
-        update_mission_name(self.db.clone(), mission.mission_id, &mission_name)
+        self.store.update_mission_name(mission.mission_id, &mission_name)
             .await
             .expect("Failed to update mission name");
         mission.mission_name = mission_name;
         self.emit_state_update(&app_handle, &state)
     }
 
+    /// Set the IANA time zone the mission's times are shown in; None shows UTC.
+    /// Timestamps themselves stay UTC.
+    pub async fn set_mission_timezone_helper(
+        &self,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        timezone: Option<String>,
+    ) -> Result<(), String> {
+        let timezone = timezone.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        if let Some(timezone) = &timezone {
+            let known = sql::timezone_exists(self.db.clone(), timezone)
+                .await
+                .map_err(|e| format!("Failed to check time zone: {}", e))?;
+            if !known {
+                return Err(format!("Unknown time zone {}", timezone));
+            }
+        }
+
+        let mut state = self.state.lock().await;
+        let mission = state
+            .missions
+            .iter_mut()
+            .find(|m| m.mission_id == mission_id)
+            .ok_or("Mission not found")?;
+        self.store.update_mission_timezone(mission_id, timezone.as_deref())
+            .await
+            .map_err(|e| format!("Failed to update mission time zone: {}", e))?;
+        mission.display_timezone = timezone;
+        self.emit_state_update(&app_handle, &state)
+    }
+
     pub async fn create_mission_helper(
         &self,
         app_handle: AppHandle<impl Runtime>,
@@ -74,7 +143,7 @@ impl MissionApiImpl {
         ) {
             return Err("Cannot delete active/past missions".into());
         }
-        delete_mission(self.db.clone(), state.missions[mission_index].mission_id)
+        self.store.delete_mission(state.missions[mission_index].mission_id)
             .await
             .expect("Failed to delete mission from database");
 
@@ -82,142 +151,76 @@ impl MissionApiImpl {
         self.emit_state_update(&app_handle, &state)
     }
 
-    pub async fn start_mission_helper(
+    pub async fn emergency_stop_all_helper(
         &self,
         app_handle: AppHandle<impl Runtime>,
-        mission_id: i32,
-    ) -> Result<(), String> {
-        let mut state = self.state.lock().await;
-        let commands_api = CommandsApiImpl::default();
+    ) -> Result<EmergencyStopEvent, String> {
+        println!("EMERGENCY STOP ALL triggered");
+        // Stop the vehicles before touching mission state so nothing delays the commands
+        let report = self.commands.emergency_stop_all().await;
 
-        // First, handle the previous mission if it exists
-        if let Some(prev_mission_index) = state.missions.iter().position(|m| m.mission_id == state.current_mission) {
-            state.missions[prev_mission_index].mission_status = MissionStageStatusEnum::Complete;
-            update_mission_status(self.db.clone(), state.missions[prev_mission_index].mission_id, "Complete").await.expect("Failed to update mission status");
+        let mut state = self.state.lock().await;
+        let current_mission = state.current_mission;
+        let mut mission_id = -1;
+        let mut mission_paused = false;
+        if let Some(mission) = state
+            .missions
+            .iter_mut()
+            .find(|m| m.mission_id == current_mission)
+        {
+            mission_id = mission.mission_id;
+            if matches!(mission.mission_status, MissionStageStatusEnum::Active) {
+                mission.set_status(MissionStageStatusEnum::Paused);
+                mission_paused = true;
+                if let Err(e) = self.store.update_mission_status(current_mission, "Paused").await {
+                    logs::error("missions::db", format!("Failed to persist paused mission status: {}", e));
+                }
+            }
         }
 
-        // Find and update the new mission
-        let start_mission_index = state.missions.iter().position(|m| m.mission_id == mission_id)
-            .ok_or("Mission not found")?;
-        
-        // Update mission status first
-        state.missions[start_mission_index].mission_status = MissionStageStatusEnum::Active;
-        state.current_mission = mission_id;
-        update_mission_status(self.db.clone(), mission_id, "Active").await.expect("Failed to update mission status");
+        let event = EmergencyStopEvent {
+            mission_id,
+            mission_paused,
+            report,
+        };
 
-        // Emit state update to ensure frontend reflects the change
         self.emit_state_update(&app_handle, &state)?;
+        self.emit_emergency_stop(&app_handle, &event)?;
+        Ok(event)
+    }
 
-        // Now handle the zone updates
-        let mission = &state.missions[start_mission_index];
-        
-        // Send keep-in zones (commandID: 2) only if there are valid zones
-        for zone in &mission.zones.keep_in_zones {
-            if zone.len() >= 3 {  // Only send if we have at least 3 coordinates
-                let coords: Vec<GeoCoordinate> = zone.iter()
-                    .take(6) // Limit to 6 points
-                    .map(|coord| GeoCoordinate {
-                        lat: coord.lat,
-                        long: coord.long,
-                    })
-                    .collect();
-                
-                // Send to ALL vehicles at once
-                commands_api.clone().send_zone_update("ALL".to_string(), "2".to_string(), coords).await?;
-            }
-        }
-
-        // Send keep-out zones (commandID: 3) only if there are valid zones
-        for zone in &mission.zones.keep_out_zones {
-            if zone.len() >= 3 {  // Only send if we have at least 3 coordinates
-                let coords: Vec<GeoCoordinate> = zone.iter()
-                    .take(6) // Limit to 6 points
-                    .map(|coord| GeoCoordinate {
-                        lat: coord.lat,
-                        long: coord.long,
-                    })
-                    .collect();
-                
-                // Send to ALL vehicles at once
-                commands_api.clone().send_zone_update("ALL".to_string(), "3".to_string(), coords).await?;
-            }
-        }
-
-        // Update vehicle stages and send search areas
-        let vehicles = &mut state.missions[start_mission_index].vehicles;
-        
-        // Set the first stage of each vehicle to active if they have stages
-        if !vehicles.MEA.stages.is_empty() {
-            vehicles.MEA.stages[0].stage_status = MissionStageStatusEnum::Active;
-            update_stage_status(
-                self.db.clone(),
-                vehicles.MEA.stages[0].stage_id,
-                "Active",
-            ).await.expect("Failed to update stage status");
+    pub async fn abort_mission_helper(
+        &self,
+        events: &impl EventSink,
+        mission_id: i32,
+    ) -> Result<(), String> {
+        println!("Aborting mission with ID: {}", mission_id);
+        let mut state = self.state.lock().await;
+        let mission = state
+            .missions
+            .iter_mut()
+            .find(|m| m.mission_id == mission_id)
+            .ok_or("Mission not found")?;
 
-            // Send search area for MEA only if it has valid coordinates
-            let search_area = &vehicles.MEA.stages[0].search_area;
-            if search_area.len() >= 3 {  // Only send if we have at least 3 coordinates
-                let coords: Vec<GeoCoordinate> = search_area.iter()
-                    .take(6)
-                    .map(|coord| GeoCoordinate {
-                        lat: coord.lat,
-                        long: coord.long,
-                    })
-                    .collect();
-                
-                commands_api.clone().send_zone_update("MEA".to_string(), "4".to_string(), coords).await?;
-            }
+        if !matches!(
+            mission.mission_status,
+            MissionStageStatusEnum::Active | MissionStageStatusEnum::Paused
+        ) {
+            return Err("Only an active or paused mission can be aborted".into());
         }
-        
-        if !vehicles.ERU.stages.is_empty() {
-            vehicles.ERU.stages[0].stage_status = MissionStageStatusEnum::Active;
-            update_stage_status(
-                self.db.clone(),
-                vehicles.ERU.stages[0].stage_id,
-                "Active",
-            ).await.expect("Failed to update stage status");
-
-            // Send search area for ERU only if it has valid coordinates
-            let search_area = &vehicles.ERU.stages[0].search_area;
-            if search_area.len() >= 3 {  // Only send if we have at least 3 coordinates
-                let coords: Vec<GeoCoordinate> = search_area.iter()
-                    .take(6)
-                    .map(|coord| GeoCoordinate {
-                        lat: coord.lat,
-                        long: coord.long,
-                    })
-                    .collect();
-                
-                commands_api.clone().send_zone_update("ERU".to_string(), "4".to_string(), coords).await?;
-            }
+        mission.set_status(MissionStageStatusEnum::Failed);
+        self.store.update_mission_status(mission_id, "Failed")
+            .await
+            .map_err(|e| format!("Failed to persist aborted mission: {}", e))?;
+        self.finalize_kpis(mission_id);
+        if self.end_hold(mission_id).await?.is_some() {
+            self.emit_hold_changed(events, mission_id, None)?;
         }
-        
-        if !vehicles.MRA.stages.is_empty() {
-            vehicles.MRA.stages[0].stage_status = MissionStageStatusEnum::Active;
-            update_stage_status(
-                self.db.clone(),
-                vehicles.MRA.stages[0].stage_id,
-                "Active",
-            ).await.expect("Failed to update stage status");
 
-            // Send search area for MRA only if it has valid coordinates
-            let search_area = &vehicles.MRA.stages[0].search_area;
-            if search_area.len() >= 3 {  // Only send if we have at least 3 coordinates
-                let coords: Vec<GeoCoordinate> = search_area.iter()
-                    .take(6)
-                    .map(|coord| GeoCoordinate {
-                        lat: coord.lat,
-                        long: coord.long,
-                    })
-                    .collect();
-                
-                commands_api.clone().send_zone_update("MRA".to_string(), "4".to_string(), coords).await?;
-            }
+        if state.current_mission == mission_id {
+            self.commands.set_active_mission(-1);
         }
-        
-        // Final state update after all changes
-        self.emit_state_update(&app_handle, &state)
+        self.emit_state_update(events, &state)
     }
 
     pub async fn set_auto_mode_helper(
@@ -241,8 +244,7 @@ impl MissionApiImpl {
             VehicleEnum::MRA => return Err("MRA auto mode unsupported".into()),
         };
 
-        update_auto_mode_vehicle(
-            self.db.clone(),
+        self.store.update_auto_mode_vehicle(
             mission.mission_id,
             vehicle.vehicle_name.to_string(),
             is_auto,
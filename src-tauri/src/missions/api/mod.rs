@@ -6,26 +6,59 @@ Keep trait methods as thin wrappers that call helper methods
 implemented in the other api/ files. 
 */
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use sqlx::PgPool;
-use tauri::{AppHandle, Runtime};
+use tauri::{AppHandle, Runtime, Window};
+use crate::missions::store::MissionStore;
+use crate::snapshot::Snapshot;
 use crate::missions::types::*;
-
+use crate::commands::CommandsApiImpl;
+use crate::commands::confirmation::DestructiveAction;
+use crate::auth::{authorized, OperatorRole};
+use crate::vehicles::VehicleAlias;
+
+pub mod budget;
+pub mod bundle;
+pub mod clone;
+pub mod coverage;
+pub mod dry_run;
 pub mod events;
+pub mod failsafe;
+pub mod geofence;
+pub mod heatmap;
+pub mod holds;
+pub mod kpis;
 pub mod missions;
+pub mod notes;
+pub mod partition;
+pub mod patient;
+pub mod search;
 pub mod stages;
+pub mod start;
 pub mod state;
 pub mod zones;
 
+#[cfg(test)]
+mod tests;
+
 #[derive(Clone)]
 pub struct MissionApiImpl {
-    state: Arc<Mutex<MissionsStruct>>,
+    state: Arc<Mutex<Snapshot<MissionsStruct>>>,
+    // State as of the last emitted update, diffed against for the scoped events
+    emitted: Arc<std::sync::Mutex<Arc<MissionsStruct>>>,
+    // Mission, vehicle, stage and note rows; db is still used for telemetry and history
+    store: Arc<dyn MissionStore>,
     db: PgPool,
+    commands: CommandsApiImpl,
+    // Progress of the last start_mission call, by mission
+    start_progress: Arc<std::sync::Mutex<HashMap<i32, MissionStartProgress>>>,
 }
 
 #[taurpc::procedures(
     event_trigger = MissionEventTrigger,
+    export_to = "../src/lib/bindings.ts",
     path = "mission"
 )]
 pub trait MissionApi {
@@ -33,41 +66,196 @@ pub trait MissionApi {
     // Event Handlers
     // ----------------------------
     #[taurpc(event)]
-    async fn on_updated(new_data: MissionsStruct);
+    async fn on_updated(new_data: Arc<MissionsStruct>);
+
+    // Scoped to what changed, for views that show a single mission or stage
+    #[taurpc(event)]
+    async fn on_mission_updated(mission_id: i32, mission: MissionStruct);
+
+    #[taurpc(event)]
+    async fn on_mission_deleted(mission_id: i32);
+
+    #[taurpc(event)]
+    async fn on_stage_changed(
+        mission_id: i32,
+        vehicle_name: VehicleEnum,
+        alias: VehicleAlias,
+        stage: StageStruct,
+    );
+
+    // Lifecycle transitions with just the ids and statuses, for sounds and notifications;
+    // on_updated still carries the full state
+    #[taurpc(event)]
+    async fn on_mission_started(mission_id: i32);
+
+    // `status` is Complete or Failed
+    #[taurpc(event)]
+    async fn on_mission_completed(mission_id: i32, status: MissionStageStatusEnum);
+
+    #[taurpc(event)]
+    async fn on_stage_transitioned(
+        mission_id: i32,
+        vehicle_name: VehicleEnum,
+        stage_id: i32,
+        previous_status: MissionStageStatusEnum,
+        status: MissionStageStatusEnum,
+    );
+
+    #[taurpc(event)]
+    async fn on_emergency_stop(event: EmergencyStopEvent);
+
+    #[taurpc(event)]
+    async fn on_note_added(note: MissionNote);
+
+    // The mission's open hold, or None once it is released
+    #[taurpc(event)]
+    async fn on_hold_changed(mission_id: i32, hold: Option<MissionHold>);
+
+    // Set automatically from the ERU and MEA patient sensors
+    #[taurpc(event)]
+    async fn on_patient_status_changed(change: PatientStatusChange);
 
     // ----------------------------
     // State Management
     // ----------------------------
-    async fn get_default_data() -> MissionsStruct;
-    async fn get_all_missions() -> MissionsStruct;
+    async fn get_default_data() -> Arc<MissionsStruct>;
+    async fn get_all_missions() -> Arc<MissionsStruct>;
     
     // ----------------------------
     // Mission Operations
     // ----------------------------
     async fn rename_mission(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         mission_name: String,
     ) -> Result<(), String>;
-    async fn get_mission_data(mission_id: i32) -> MissionStruct;
+    // IANA name such as Europe/Berlin; None shows the mission's times in UTC
+    async fn set_mission_timezone(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        timezone: Option<String>,
+    ) -> Result<(), String>;
+    async fn get_mission_data(mission_id: i32) -> Result<MissionStruct, MissionError>;
     async fn create_mission(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_name: String,
     ) -> Result<(), String>;
     async fn delete_mission(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
     ) -> Result<(), String>;
+    // Safe to call again after a failure: only the steps that didn't succeed are retried
     async fn start_mission(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
+    ) -> Result<MissionStartProgress, String>;
+    // None until start_mission was called for the mission
+    async fn get_mission_start_progress(mission_id: i32) -> Option<MissionStartProgress>;
+    // start_mission's checks and transmissions, reported without sending commands or changing state
+    async fn dry_run_mission(mission_id: i32) -> Result<MissionDryRun, String>;
+    // Safety officer's "everything stops" control: stops all vehicles and pauses the active mission
+    async fn emergency_stop_all(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        confirmation: Option<String>,
+    ) -> Result<EmergencyStopEvent, String>;
+    // Ends the active (or paused) mission as Failed
+    async fn abort_mission(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        confirmation: Option<String>,
     ) -> Result<(), String>;
 
+    // ----------------------------
+    // Mission Holds
+    // ----------------------------
+    // Holds the active mission, e.g. for weather: vehicles keep flying their current stage, but
+    // stage transitions and non-emergency commands are refused until the hold is released
+    async fn set_mission_hold(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        reason: String,
+    ) -> Result<MissionHold, String>;
+    async fn release_mission_hold(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+    ) -> Result<MissionHold, String>;
+    // Every hold period of the mission, oldest first
+    async fn list_mission_holds(mission_id: i32) -> Result<Vec<MissionHold>, String>;
+
     
+    // ----------------------------
+    // Search Coverage
+    // ----------------------------
+    async fn get_mission_coverage(mission_id: i32) -> Result<Vec<StageCoverage>, String>;
+    // Recorded positions binned into `cell_size_m` squares; all vehicles without a vehicle name
+    async fn get_mission_heatmap(
+        mission_id: i32,
+        vehicle_name: Option<VehicleEnum>,
+        cell_size_m: f64,
+    ) -> Result<TrackHeatmap, String>;
+
+    // ----------------------------
+    // KPIs
+    // ----------------------------
+    // Stored once the mission has completed or was aborted, live until then
+    async fn get_mission_kpis(mission_id: i32) -> Result<MissionKpis, String>;
+
+    // ----------------------------
+    // Search
+    // ----------------------------
+    // Missions, stages and zones across all missions whose name contains `query`
+    async fn search_mission_entities(query: String) -> Vec<MissionSearchHit>;
+
+    // ----------------------------
+    // Operator Notes
+    // ----------------------------
+    // Timestamped observations attributed to the current operator
+    async fn add_note(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        text: String,
+    ) -> Result<MissionNote, String>;
+    async fn list_notes(mission_id: i32) -> Result<Vec<MissionNote>, String>;
+    // Mission plan and operator log in one document
+    async fn export_mission(mission_id: i32) -> Result<MissionExport, String>;
+    // Zip archive of the mission, its notes, telemetry, command history and alerts
+    async fn export_mission_bundle(mission_id: i32, path: String) -> Result<String, String>;
+    // Restores a bundle as a new mission; returns the new mission id
+    async fn import_mission_bundle(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        path: String,
+    ) -> Result<i32, String>;
+    // Copy the mission's plan into another GCS database, e.g. from the office machine to the
+    // field laptop; returns its id there
+    async fn push_mission(
+        window: Window<impl Runtime>,
+        mission_id: i32,
+        remote_db_url: String,
+    ) -> Result<i32, String>;
+    // Copy a mission's plan from another GCS database; returns the new mission id
+    async fn pull_mission(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        remote_db_url: String,
+        mission_id: i32,
+    ) -> Result<i32, String>;
+
     // ----------------------------
     // Vehicle Operations
     // ----------------------------
     async fn set_auto_mode(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
@@ -77,21 +265,29 @@ pub trait MissionApi {
     // ----------------------------
     // Stage Operations
     // ----------------------------
+    // With a template, the stage gets the template's settings, and its name when `stage_name`
+    // is empty
     async fn add_stage(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
         stage_name: String,
+        template: Option<String>,
     ) -> Result<(), String>;
 
+    // Deleting a stage of the active mission needs the mission-commander role and a justification
     async fn delete_stage(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
         stage_id: i32,
+        justification: Option<String>,
     ) -> Result<(), String>;
 
     async fn rename_stage(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
@@ -100,40 +296,76 @@ pub trait MissionApi {
     ) -> Result<(), String>;
 
     async fn transition_stage(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
     ) -> Result<(), String>;
 
+    // Search areas over the vehicle's vertex budget are refused, or simplified with `simplify`
     async fn update_stage_area(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
         stage_id: i32,
         area: GeofenceType,
+        simplify: Option<bool>,
     ) -> Result<(), String>;
+    // Split `area` into equal-area strips, one per assignment in order, and store each as
+    // that stage's search area, simplified to the vehicle's vertex budget where needed
+    async fn partition_search_area(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        area: GeofenceType,
+        assignments: Vec<StageAssignment>,
+    ) -> Result<Vec<SearchPartition>, String>;
 
     // ----------------------------
     // Zone Operations
     // ----------------------------
+    // Vertex limits of zones and of each vehicle's search areas, from the capability model
+    async fn get_vertex_budget() -> VertexBudget;
     async fn add_zone(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         zone_type: ZoneType,
     ) -> Result<(), String>;
+    // Zones over the vertex budget are refused, or simplified down to it with `simplify`
     async fn update_zone(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         zone_type: ZoneType,
         zone_index: i32,
         zone_coords: GeofenceType,
+        simplify: Option<bool>,
     ) -> Result<(), String>;
+    // Deleting a zone of the active mission needs the mission-commander role and a justification,
+    // a keep-out zone also two-person confirmation when enabled
     async fn delete_zone(
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         zone_type: ZoneType,
         zone_index: i32,
+        confirmation: Option<String>,
+        justification: Option<String>,
     ) -> Result<(), String>;
+
+    // Keep-out warning distances; without a zone index a threshold covers the whole mission
+    async fn list_geofence_thresholds(mission_id: i32) -> Result<Vec<GeofenceThreshold>, String>;
+    async fn set_geofence_threshold(
+        window: Window<impl Runtime>,
+        threshold: GeofenceThreshold,
+    ) -> Result<Vec<GeofenceThreshold>, String>;
+    async fn clear_geofence_threshold(
+        window: Window<impl Runtime>,
+        mission_id: i32,
+        zone_index: Option<i32>,
+    ) -> Result<Vec<GeofenceThreshold>, String>;
 }
 
 /*==============================================================================
@@ -145,52 +377,265 @@ impl MissionApi for MissionApiImpl {
     // ----------------------------------
     // State Management Implementations
     // ----------------------------------
-    async fn get_default_data(self) -> MissionsStruct {
-        Self::new().await.state.lock().await.clone()
+    // The in-memory state mirrors the database, so there is nothing to reload
+    async fn get_default_data(self) -> Arc<MissionsStruct> {
+        self.snapshot().await
     }
 
-    async fn get_all_missions(self) -> MissionsStruct {
-        self.state.lock().await.clone()
+    async fn get_all_missions(self) -> Arc<MissionsStruct> {
+        self.state.lock().await.share()
     }
 
     // ----------------------------------
     // Mission Operations Implementations
     // ----------------------------------
-    async fn get_mission_data(self, mission_id: i32) -> MissionStruct {
+    async fn get_mission_data(self, mission_id: i32) -> Result<MissionStruct, MissionError> {
         self.get_mission_data_helper(mission_id).await
     }
 
     async fn rename_mission(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         mission_name: String,
     ) -> Result<(), String> {
-        self.rename_mission_helper(app_handle, mission_id, mission_name).await
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.rename_mission_helper(app_handle, mission_id, mission_name),
+        )
+        .await
+    }
+
+    async fn set_mission_timezone(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        timezone: Option<String>,
+    ) -> Result<(), String> {
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.set_mission_timezone_helper(app_handle, mission_id, timezone),
+        )
+        .await
     }
 
     async fn create_mission(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_name: String,
     ) -> Result<(), String> {
-        self.create_mission_helper(app_handle, mission_name).await
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.create_mission_helper(app_handle, mission_name),
+        )
+        .await
     }
 
     async fn delete_mission(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
     ) -> Result<(), String> {
-        self.delete_mission_helper(app_handle, mission_id).await
+        authorized(
+            &window,
+            OperatorRole::MissionCommander,
+            self.delete_mission_helper(app_handle, mission_id),
+        )
+        .await
     }
 
     async fn start_mission(
         self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+    ) -> Result<MissionStartProgress, String> {
+        authorized(
+            &window,
+            OperatorRole::MissionCommander,
+            self.start_mission_helper(&app_handle, mission_id),
+        )
+        .await
+    }
+
+    async fn get_mission_start_progress(self, mission_id: i32) -> Option<MissionStartProgress> {
+        self.start_progress_for(mission_id)
+    }
+
+    async fn dry_run_mission(self, mission_id: i32) -> Result<MissionDryRun, String> {
+        self.dry_run_mission_helper(mission_id).await
+    }
+
+    async fn emergency_stop_all(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        confirmation: Option<String>,
+    ) -> Result<EmergencyStopEvent, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            self.commands
+                .require_confirmation(DestructiveAction::EmergencyStop, confirmation)
+                .await?;
+            self.emergency_stop_all_helper(app_handle).await
+        })
+        .await
+    }
+
+    async fn abort_mission(
+        self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
+        confirmation: Option<String>,
     ) -> Result<(), String> {
-        self.start_mission_helper(app_handle, mission_id).await
+        authorized(&window, OperatorRole::MissionCommander, async move {
+            self.commands
+                .require_confirmation(DestructiveAction::MissionAbort, confirmation)
+                .await?;
+            self.abort_mission_helper(&app_handle, mission_id).await
+        })
+        .await
+    }
+
+    // ----------------------------------
+    // Mission Holds Implementations
+    // ----------------------------------
+    async fn set_mission_hold(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        reason: String,
+    ) -> Result<MissionHold, String> {
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.set_mission_hold_helper(app_handle, mission_id, reason),
+        )
+        .await
+    }
+
+    async fn release_mission_hold(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+    ) -> Result<MissionHold, String> {
+        authorized(
+            &window,
+            OperatorRole::MissionCommander,
+            self.release_mission_hold_helper(app_handle, mission_id),
+        )
+        .await
+    }
+
+    async fn list_mission_holds(self, mission_id: i32) -> Result<Vec<MissionHold>, String> {
+        self.list_mission_holds_helper(mission_id).await
+    }
+
+    // ----------------------------------
+    // Search Coverage Implementations
+    // ----------------------------------
+    async fn get_mission_coverage(self, mission_id: i32) -> Result<Vec<StageCoverage>, String> {
+        self.get_mission_coverage_helper(mission_id).await
+    }
+
+    async fn get_mission_heatmap(
+        self,
+        mission_id: i32,
+        vehicle_name: Option<VehicleEnum>,
+        cell_size_m: f64,
+    ) -> Result<TrackHeatmap, String> {
+        self.get_mission_heatmap_helper(mission_id, vehicle_name, cell_size_m).await
+    }
+
+    // ----------------------------------
+    // KPI Implementations
+    // ----------------------------------
+    async fn get_mission_kpis(self, mission_id: i32) -> Result<MissionKpis, String> {
+        self.get_mission_kpis_helper(mission_id).await
+    }
+
+    // ----------------------------------
+    // Search Implementations
+    // ----------------------------------
+    async fn search_mission_entities(self, query: String) -> Vec<MissionSearchHit> {
+        self.search_mission_entities_helper(&query).await
+    }
+
+    // ----------------------------------
+    // Operator Notes Implementations
+    // ----------------------------------
+    async fn add_note(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        text: String,
+    ) -> Result<MissionNote, String> {
+        authorized(&window, OperatorRole::Observer, self.add_note_helper(app_handle, mission_id, text)).await
+    }
+
+    async fn list_notes(self, mission_id: i32) -> Result<Vec<MissionNote>, String> {
+        self.list_notes_helper(mission_id).await
+    }
+
+    async fn export_mission(self, mission_id: i32) -> Result<MissionExport, String> {
+        self.export_mission_helper(mission_id).await
+    }
+
+    async fn export_mission_bundle(self, mission_id: i32, path: String) -> Result<String, String> {
+        self.export_mission_bundle_helper(mission_id, path).await
+    }
+
+    async fn import_mission_bundle(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        path: String,
+    ) -> Result<i32, String> {
+        authorized(
+            &window,
+            OperatorRole::MissionCommander,
+            self.import_mission_bundle_helper(app_handle, path),
+        )
+        .await
+    }
+
+    async fn push_mission(
+        self,
+        window: Window<impl Runtime>,
+        mission_id: i32,
+        remote_db_url: String,
+    ) -> Result<i32, String> {
+        authorized(
+            &window,
+            OperatorRole::MissionCommander,
+            self.push_mission_helper(mission_id, remote_db_url),
+        )
+        .await
+    }
+
+    async fn pull_mission(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        remote_db_url: String,
+        mission_id: i32,
+    ) -> Result<i32, String> {
+        authorized(
+            &window,
+            OperatorRole::MissionCommander,
+            self.pull_mission_helper(app_handle, remote_db_url, mission_id),
+        )
+        .await
     }
 
     // ----------------------------------
@@ -198,12 +643,18 @@ impl MissionApi for MissionApiImpl {
     // ----------------------------------
     async fn set_auto_mode(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
         is_auto: bool,
     ) -> Result<(), String> {
-        self.set_auto_mode_helper(app_handle, mission_id, vehicle_name, is_auto).await
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.set_auto_mode_helper(app_handle, mission_id, vehicle_name, is_auto),
+        )
+        .await
     }
 
     // ----------------------------------
@@ -211,86 +662,185 @@ impl MissionApi for MissionApiImpl {
     // ----------------------------------
     async fn add_stage(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
         stage_name: String,
+        template: Option<String>,
     ) -> Result<(), String> {
-        self.add_stage_helper(app_handle, mission_id, vehicle_name, stage_name).await
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.add_stage_helper(app_handle, mission_id, vehicle_name, stage_name, template),
+        )
+        .await
     }
 
     async fn update_stage_area(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
         stage_id: i32,
         area: GeofenceType,
+        simplify: Option<bool>,
     ) -> Result<(), String> {
-        self.update_stage_area_helper(app_handle, mission_id, vehicle_name, stage_id, area).await
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.update_stage_area_helper(app_handle, mission_id, vehicle_name, stage_id, area, simplify),
+        )
+        .await
+    }
+
+    async fn partition_search_area(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        area: GeofenceType,
+        assignments: Vec<StageAssignment>,
+    ) -> Result<Vec<SearchPartition>, String> {
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.partition_search_area_helper(app_handle, mission_id, area, assignments),
+        )
+        .await
     }
 
     async fn delete_stage(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
         stage_id: i32,
+        justification: Option<String>,
     ) -> Result<(), String> {
-        self.delete_stage_helper(app_handle, mission_id, vehicle_name, stage_id).await
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.delete_stage_helper(app_handle, mission_id, vehicle_name, stage_id, justification),
+        )
+        .await
     }
 
     async fn rename_stage(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
         stage_id: i32,
         stage_name: String,
     ) -> Result<(), String> {
-        self.rename_stage_helper(app_handle, mission_id, vehicle_name, stage_id, stage_name).await
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.rename_stage_helper(app_handle, mission_id, vehicle_name, stage_id, stage_name),
+        )
+        .await
     }
 
     async fn transition_stage(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         vehicle_name: VehicleEnum,
     ) -> Result<(), String> {
-        self.transition_stage_helper(app_handle, mission_id, vehicle_name).await
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.transition_stage_helper(app_handle, mission_id, vehicle_name),
+        )
+        .await
     }
 
     // ----------------------------------
     // Zone Operations Implementations
     // ----------------------------------
+    async fn get_vertex_budget(self) -> VertexBudget {
+        self.get_vertex_budget_helper()
+    }
+
     async fn add_zone(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         zone_type: ZoneType,
     ) -> Result<(), String> {
-        self.add_zone_helper(app_handle, mission_id, zone_type).await
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.add_zone_helper(app_handle, mission_id, zone_type),
+        )
+        .await
     }
 
     async fn update_zone(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         zone_type: ZoneType,
         zone_index: i32,
         zone_coords: GeofenceType,
+        simplify: Option<bool>,
     ) -> Result<(), String> {
-        self.update_zone_helper(app_handle, mission_id, zone_type, zone_index, zone_coords).await
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.update_zone_helper(app_handle, mission_id, zone_type, zone_index, zone_coords, simplify),
+        )
+        .await
     }
 
     async fn delete_zone(
         self,
+        window: Window<impl Runtime>,
         app_handle: AppHandle<impl Runtime>,
         mission_id: i32,
         zone_type: ZoneType,
         zone_index: i32,
+        confirmation: Option<String>,
+        justification: Option<String>,
     ) -> Result<(), String> {
-        self.delete_zone_helper(app_handle, mission_id, zone_type, zone_index).await
+        authorized(&window, OperatorRole::Operator, async move {
+            self.delete_zone_helper(app_handle, mission_id, zone_type, zone_index, confirmation, justification)
+                .await
+        })
+        .await
+    }
+
+    async fn list_geofence_thresholds(self, mission_id: i32) -> Result<Vec<GeofenceThreshold>, String> {
+        self.list_geofence_thresholds_helper(mission_id).await
+    }
+
+    async fn set_geofence_threshold(
+        self,
+        window: Window<impl Runtime>,
+        threshold: GeofenceThreshold,
+    ) -> Result<Vec<GeofenceThreshold>, String> {
+        authorized(&window, OperatorRole::Operator, self.set_geofence_threshold_helper(threshold)).await
+    }
+
+    async fn clear_geofence_threshold(
+        self,
+        window: Window<impl Runtime>,
+        mission_id: i32,
+        zone_index: Option<i32>,
+    ) -> Result<Vec<GeofenceThreshold>, String> {
+        authorized(
+            &window,
+            OperatorRole::Operator,
+            self.clear_geofence_threshold_helper(mission_id, zone_index),
+        )
+        .await
     }
 }
 
@@ -0,0 +1,77 @@
+/*
+Implement helper methods on MissionApiImpl for the per-mission
+operator log (add and list notes) and mission exports.
+*/
+
+use tauri::{AppHandle, Runtime};
+use crate::auth::current_operator;
+use crate::missions::types::{MissionExport, MissionNote};
+use crate::logs;
+use super::MissionApiImpl;
+
+const MAX_NOTE_LENGTH: usize = 2000;
+
+impl MissionApiImpl {
+    pub async fn require_mission(&self, mission_id: i32) -> Result<(), String> {
+        let state = self.state.lock().await;
+        if state.missions.iter().any(|m| m.mission_id == mission_id) {
+            Ok(())
+        } else {
+            Err("Mission not found".into())
+        }
+    }
+
+    pub async fn add_note_helper(
+        &self,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        text: String,
+    ) -> Result<MissionNote, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("Note is empty".into());
+        }
+        if text.chars().count() > MAX_NOTE_LENGTH {
+            return Err(format!("Notes are limited to {} characters", MAX_NOTE_LENGTH));
+        }
+        self.require_mission(mission_id).await?;
+
+        let note = self.store.insert_mission_note(mission_id, &current_operator(), text)
+            .await
+            .map_err(|e| format!("Failed to save note: {}", e))?;
+        logs::info(
+            "missions",
+            format!("Note added to mission {} by {}", mission_id, note.author),
+        );
+        self.emit_note_added(&app_handle, &note)?;
+        Ok(note)
+    }
+
+    pub async fn list_notes_helper(&self, mission_id: i32) -> Result<Vec<MissionNote>, String> {
+        self.require_mission(mission_id).await?;
+        self.store.select_mission_notes(mission_id)
+            .await
+            .map_err(|e| format!("Failed to load notes: {}", e))
+    }
+
+    pub async fn export_mission_helper(&self, mission_id: i32) -> Result<MissionExport, String> {
+        let mission = {
+            let state = self.state.lock().await;
+            state
+                .missions
+                .iter()
+                .find(|m| m.mission_id == mission_id)
+                .cloned()
+                .ok_or("Mission not found")?
+        };
+        let notes = self.list_notes_helper(mission_id).await?;
+        let holds = self.list_mission_holds_helper(mission_id).await?;
+
+        Ok(MissionExport {
+            mission,
+            notes,
+            holds,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+}
@@ -0,0 +1,90 @@
+/*
+Implement helper methods on MissionApiImpl for splitting one search area
+among several vehicles' stages.
+*/
+
+use tauri::{AppHandle, Runtime};
+use crate::geometry;
+use crate::geometry::partition::partition_polygon;
+use crate::missions::types::*;
+use super::budget::{fit_to_budget, search_area_vertex_budget};
+use super::MissionApiImpl;
+
+impl MissionApiImpl {
+    pub async fn partition_search_area_helper(
+        &self,
+        app_handle: AppHandle<impl Runtime>,
+        mission_id: i32,
+        area: GeofenceType,
+        assignments: Vec<StageAssignment>,
+    ) -> Result<Vec<SearchPartition>, String> {
+        if assignments.is_empty() {
+            return Err("At least one stage is needed to partition a search area".into());
+        }
+        for (i, a) in assignments.iter().enumerate() {
+            let duplicate = assignments[..i]
+                .iter()
+                .any(|b| b.stage_id == a.stage_id && b.vehicle_name.to_string() == a.vehicle_name.to_string());
+            if duplicate {
+                return Err(format!("Stage {} is assigned more than once", a.stage_id));
+            }
+        }
+
+        // Check every stage before writing so a bad assignment leaves no stage half-updated
+        {
+            let state = self.state.lock().await;
+            let mission = state
+                .missions
+                .iter()
+                .find(|m| m.mission_id == mission_id)
+                .ok_or("Mission not found")?;
+            for a in &assignments {
+                let vehicle = match a.vehicle_name {
+                    VehicleEnum::MEA => &mission.vehicles.MEA,
+                    VehicleEnum::ERU => &mission.vehicles.ERU,
+                    VehicleEnum::MRA => &mission.vehicles.MRA,
+                };
+                if !vehicle.stages.iter().any(|s| s.stage_id == a.stage_id) {
+                    return Err(format!(
+                        "Stage {} not found for {}",
+                        a.stage_id,
+                        a.vehicle_name.to_string()
+                    ));
+                }
+            }
+        }
+
+        // Cutting adds vertices the operator never drew, so parts over a vehicle's budget are
+        // simplified rather than refused; fitted before writing for the same reason as above
+        let parts = partition_polygon(&area, assignments.len())?
+            .into_iter()
+            .zip(&assignments)
+            .map(|(part, a)| fit_to_budget(part, search_area_vertex_budget(&a.vehicle_name), true, "Search area part"))
+            .collect::<Result<Vec<GeofenceType>, String>>()?;
+        let mut partitions = vec![];
+        for (assignment, part) in assignments.into_iter().zip(parts) {
+            self.update_stage_area_helper(
+                app_handle.clone(),
+                mission_id,
+                assignment.vehicle_name.clone(),
+                assignment.stage_id,
+                part.clone(),
+                None,
+            )
+            .await?;
+            println!(
+                "Assigned {:.0} m² of the search area to {} stage {}",
+                geometry::area_m2(&part),
+                assignment.vehicle_name.to_string(),
+                assignment.stage_id
+            );
+            partitions.push(SearchPartition {
+                vehicle_name: assignment.vehicle_name,
+                stage_id: assignment.stage_id,
+                area_m2: geometry::area_m2(&part),
+                area: part,
+            });
+        }
+        Ok(partitions)
+    }
+}
@@ -0,0 +1,113 @@
+/*
+Patient status from the ERU and MEA payload sensors: when a vehicle's patient sensor flag
+changes, its patient status in the active mission follows, the change is announced with
+on_patient_status_changed and recorded in the audit log.
+*/
+
+use async_trait::async_trait;
+use crate::audit::{self, AuditAction, AUTOMATIC_OPERATOR};
+use crate::events::EventSink;
+use crate::logs;
+use crate::missions::types::*;
+use crate::telemetry::patient::PatientSensorHandler;
+use crate::vehicles;
+use super::MissionApiImpl;
+
+#[async_trait]
+impl PatientSensorHandler for MissionApiImpl {
+    async fn patient_sensor_changed(&self, events: &dyn EventSink, vehicle_id: &str, secured: bool) {
+        let change = match self.apply_patient_sensor(events, vehicle_id, secured).await {
+            Ok(Some(change)) => change,
+            Ok(None) => return,
+            Err(e) => {
+                logs::error("missions", format!("Failed to update patient status of {}: {}", vehicle_id, e));
+                return;
+            }
+        };
+
+        let vehicle = vehicles::display_name(vehicle_id);
+        let previous = change.previous_status.as_ref().map_or("unknown", |status| status.name());
+        logs::info(
+            "missions",
+            format!("{} patient status set to {} by its sensor", vehicle, change.patient_status.name()),
+        );
+        // The status already changed; a failed audit write is reported, not undone
+        if let Err(e) = audit::record_as(
+            self.db.clone(),
+            AUTOMATIC_OPERATOR,
+            AuditAction::PatientStatusChanged,
+            Some(change.mission_id),
+            &format!("{} patient sensor reported {}", vehicle, change.patient_status.name()),
+            &format!("{} patient status {} -> {}", vehicle, previous, change.patient_status.name()),
+        )
+        .await
+        {
+            logs::error("missions", e);
+        }
+    }
+}
+
+impl MissionApiImpl {
+    /// Sets the vehicle's patient status in the active or paused mission; None when no such
+    /// mission exists, the vehicle carries no patient or its status already matches.
+    async fn apply_patient_sensor(
+        &self,
+        events: &dyn EventSink,
+        vehicle_id: &str,
+        secured: bool,
+    ) -> Result<Option<PatientStatusChange>, String> {
+        let patient_status = if secured {
+            PatientStatusEnum::Secured
+        } else {
+            PatientStatusEnum::Unsecured
+        };
+
+        let mut state = self.state.lock().await;
+        let current_mission = state.current_mission;
+        let Some(mission) = state
+            .missions
+            .iter_mut()
+            .find(|m| m.mission_id == current_mission)
+        else {
+            return Ok(None);
+        };
+        if !matches!(
+            mission.mission_status,
+            MissionStageStatusEnum::Active | MissionStageStatusEnum::Paused
+        ) {
+            return Ok(None);
+        }
+
+        let vehicles = &mut mission.vehicles;
+        let Some(vehicle) = [&mut vehicles.MEA, &mut vehicles.ERU]
+            .into_iter()
+            .find(|vehicle| vehicle.vehicle_name.to_string().eq_ignore_ascii_case(vehicle_id))
+        else {
+            return Ok(None);
+        };
+        if vehicle.patient_status.as_ref() == Some(&patient_status) {
+            return Ok(None);
+        }
+
+        self.store.update_patient_status(
+            current_mission,
+            vehicle.vehicle_name.to_string(),
+            patient_status.name(),
+        )
+        .await
+        .map_err(|e| format!("Failed to persist patient status: {}", e))?;
+        let change = PatientStatusChange {
+            mission_id: current_mission,
+            vehicle_name: vehicle.vehicle_name.clone(),
+            previous_status: vehicle.patient_status.replace(patient_status.clone()),
+            patient_status,
+            changed_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        self.emit_state_update(events, &state)?;
+        if let Err(e) = events.patient_status_changed(change.clone()) {
+            logs::warn("missions", format!("Failed to emit patient status change: {}", e));
+        }
+        Ok(Some(change))
+    }
+}
@@ -0,0 +1,71 @@
+/*
+Implement helper methods on MissionApiImpl for searching mission,
+stage and zone names across all missions (command-palette navigation).
+*/
+
+use crate::missions::types::*;
+use super::MissionApiImpl;
+
+// Enough for a palette; the operator narrows the query instead of scrolling
+const MAX_SEARCH_HITS: usize = 50;
+
+impl MissionApiImpl {
+    // Case-insensitive substring match; names starting with the query come first
+    pub async fn search_mission_entities_helper(&self, query: &str) -> Vec<MissionSearchHit> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return vec![];
+        }
+        let state = self.snapshot().await;
+
+        // (prefix match, hit) in mission order, then stable-sorted so prefix matches lead
+        let mut hits: Vec<(bool, MissionSearchHit)> = vec![];
+        let mut check = |name: &str, hit: &dyn Fn() -> MissionSearchHit| {
+            let name = name.to_lowercase();
+            if name.contains(&query) {
+                hits.push((name.starts_with(&query), hit()));
+            }
+        };
+
+        for mission in &state.missions {
+            let mission_name = &mission.mission_name;
+            check(mission_name, &|| MissionSearchHit::Mission {
+                mission_id: mission.mission_id,
+                mission_name: mission_name.clone(),
+            });
+
+            let vehicles = &mission.vehicles;
+            for vehicle in [&vehicles.MEA, &vehicles.ERU, &vehicles.MRA] {
+                for stage in &vehicle.stages {
+                    check(&stage.stage_name, &|| MissionSearchHit::Stage {
+                        mission_id: mission.mission_id,
+                        mission_name: mission_name.clone(),
+                        vehicle_name: vehicle.vehicle_name.clone(),
+                        stage_id: stage.stage_id,
+                        stage_name: stage.stage_name.clone(),
+                    });
+                }
+            }
+
+            let zones = [
+                (ZoneType::KeepIn, "Keep-in zone", &mission.zones.keep_in_zones),
+                (ZoneType::KeepOut, "Keep-out zone", &mission.zones.keep_out_zones),
+            ];
+            for (zone_type, kind, list) in zones {
+                for index in 0..list.len() {
+                    let label = format!("{} {}", kind, index + 1);
+                    check(&label, &|| MissionSearchHit::Zone {
+                        mission_id: mission.mission_id,
+                        mission_name: mission_name.clone(),
+                        zone_type: zone_type.clone(),
+                        zone_index: index as i32,
+                        label: label.clone(),
+                    });
+                }
+            }
+        }
+
+        hits.sort_by_key(|(prefix, _)| !*prefix);
+        hits.into_iter().take(MAX_SEARCH_HITS).map(|(_, hit)| hit).collect()
+    }
+}
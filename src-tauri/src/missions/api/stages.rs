@@ -1,14 +1,20 @@
 /*
 Implement helper methods on MissionApiImpl for stage-level operations
 (add, delete, rename stages, transition stages, update search area).
+Stages added from a template get the template's settings.
 */
 
 use tauri::{AppHandle, Runtime};
 use crate::missions::types::*;
-use crate::missions::sql::{select_vehicle_from_mission, update_stage_area, delete_stage, update_stage_name, transition_stage};
-use crate::commands::commands::{CommandsApiImpl, GeoCoordinate};
-use crate::commands::CommandsApi;
+use crate::commands::commands::{zone_key, GeoCoordinate};
+use crate::audit::{self, AuditAction};
+use crate::auth::current_operator;
+use crate::logs;
+use crate::stage_templates;
+use crate::telemetry::arming;
+use super::budget::{fit_to_budget, search_area_vertex_budget};
 use super::MissionApiImpl;
+use super::missions::in_flight_justification;
 
 impl MissionApiImpl {
     pub async fn add_stage_helper(
@@ -17,21 +23,39 @@ impl MissionApiImpl {
         mission_id: i32,
         vehicle_name: VehicleEnum,
         stage_name: String,
+        template: Option<String>,
     ) -> Result<(), String> {
+        let template = template
+            .map(|name| stage_templates::get(&name).ok_or(format!("No stage template named {}", name)))
+            .transpose()?;
+        let stage_name = match &template {
+            Some(template) if stage_name.trim().is_empty() => template.stage_name.clone(),
+            _ => stage_name,
+        };
+
         let mut state = self.state.lock().await;
         let mission = state
             .missions
             .iter_mut()
             .find(|m| m.mission_id == mission_id)
             .ok_or("Mission not found")?;
+        if let Some(template) = &template {
+            stage_templates::assign_band(
+                &app_handle,
+                template,
+                mission_id,
+                &vehicle_name.to_string(),
+                &current_operator(),
+            )
+            .await?;
+        }
 
         let vehicle = match vehicle_name {
             VehicleEnum::MEA => &mut mission.vehicles.MEA,
             VehicleEnum::ERU => &mut mission.vehicles.ERU,
             VehicleEnum::MRA => &mut mission.vehicles.MRA,
         };
-        let vehicle_id = select_vehicle_from_mission(
-            self.db.clone(),
+        let vehicle_id = self.store.select_vehicle_from_mission(
             mission.mission_id,
             vehicle.vehicle_name.to_string(),
         )
@@ -46,6 +70,12 @@ impl MissionApiImpl {
         
         println!("Default stage created: {:?}", &default_stage);
         let stage_id = default_stage.stage_id;
+        // The stage exists by now, so it is kept even when its settings can't be saved
+        if let Some(template) = &template {
+            if let Err(e) = stage_templates::save_settings(template, stage_id).await {
+                logs::warn("missions", e);
+            }
+        }
         vehicle.stages.push(default_stage);
 
         if vehicle.current_stage == -1 {
@@ -62,7 +92,10 @@ impl MissionApiImpl {
         vehicle_name: VehicleEnum,
         stage_id: i32,
         area: GeofenceType,
+        simplify: Option<bool>,
     ) -> Result<(), String> {
+        let budget = search_area_vertex_budget(&vehicle_name);
+        let area = fit_to_budget(area, budget, simplify.unwrap_or(false), "Search area")?;
         let mut state = self.state.lock().await;
         let mission = state
             .missions
@@ -95,14 +128,12 @@ impl MissionApiImpl {
         
         let search_area_array: Vec<String> = vec![search_area_string.clone()];
         
-        let vehicle_id = select_vehicle_from_mission(
-            self.db.clone(),
+        let vehicle_id = self.store.select_vehicle_from_mission(
             mission.mission_id,
             vehicle.vehicle_name.to_string(),
         ).await.expect("Failed to find vehicle mission");
 
-        let _ = update_stage_area(
-            self.db.clone(),
+        let _ = self.store.update_stage_area(
             stage.stage_id,
             search_area_array,
             vehicle_id,
@@ -117,6 +148,7 @@ impl MissionApiImpl {
         mission_id: i32,
         vehicle_name: VehicleEnum,
         stage_id: i32,
+        justification: Option<String>,
     ) -> Result<(), String> {
         println!("Deleting stage with ID: {}", stage_id);
         let mut state = self.state.lock().await;
@@ -125,6 +157,7 @@ impl MissionApiImpl {
             .iter_mut()
             .find(|m| m.mission_id == mission_id)
             .ok_or("Mission not found")?;
+        let justification = in_flight_justification(mission, justification.as_deref())?;
 
         let vehicle = match vehicle_name {
             VehicleEnum::MEA => &mut mission.vehicles.MEA,
@@ -142,7 +175,17 @@ impl MissionApiImpl {
         if matches!(stage.stage_status, MissionStageStatusEnum::Active | MissionStageStatusEnum::Complete) {
             return Err("Cannot delete current/completed stage".into());
         }
-        delete_stage(self.db.clone(), stage_id)
+        if let Some(justification) = &justification {
+            audit::record(
+                self.db.clone(),
+                AuditAction::StageDeleted,
+                Some(mission_id),
+                justification,
+                &format!("{:?} stage {} ({})", vehicle_name, stage.stage_name, stage_id),
+            )
+            .await?;
+        }
+        self.store.delete_stage(stage_id)
             .await
             .expect("Failed to delete stage from database");
 
@@ -175,7 +218,7 @@ impl MissionApiImpl {
             .find(|s| s.stage_id == stage_id)
             .ok_or("Stage not found")?;
 
-        update_stage_name(self.db.clone(), stage.stage_id, &stage_name)
+        self.store.update_stage_name(stage.stage_id, &stage_name)
             .await
             .expect("Failed to update stage name");
 
@@ -191,7 +234,7 @@ impl MissionApiImpl {
     ) -> Result<(), String> {
         println!("Transitioning stage for vehicle: {:?}", vehicle_name);
         let mut state = self.state.lock().await;
-        let commands_api = CommandsApiImpl::default();
+        let commands_api = self.commands.clone();
         let mission = state
             .missions
             .iter_mut()
@@ -202,19 +245,21 @@ impl MissionApiImpl {
             VehicleEnum::ERU => &mut mission.vehicles.ERU,
             VehicleEnum::MRA => &mut mission.vehicles.MRA,
         };
+        self.require_not_held(mission_id, "transition stage")?;
+        arming::require_armed(&vehicle.vehicle_name.to_string())
+            .map_err(|e| format!("Cannot transition stage: {}", e))?;
 
         println!("Current Stage: {:?}", vehicle.current_stage);
 
         // Mark current stage as complete
         if let Some(stage) = vehicle.stages.iter_mut().find(|s| s.stage_id == vehicle.current_stage) {
-            stage.stage_status = MissionStageStatusEnum::Complete;
+            stage.set_status(MissionStageStatusEnum::Complete);
         } else {
             println!("Stage with ID not found");
         }
 
         // Transition to next stage if available
-        let transitioned_stage = transition_stage(
-            self.db.clone(),
+        let transitioned_stage = self.store.transition_stage(
             mission.mission_id,
             vehicle.vehicle_name.to_string(),
             vehicle.current_stage,
@@ -229,12 +274,11 @@ impl MissionApiImpl {
 
         if let Some(stage) = vehicle.stages.iter_mut().find(|s| s.stage_id == transitioned_stage.unwrap_or(vehicle.current_stage)) {
             vehicle.current_stage = transitioned_stage.unwrap_or(vehicle.current_stage);
-            stage.stage_status = MissionStageStatusEnum::Active;
+            stage.set_status(MissionStageStatusEnum::Active);
 
             // Send search area for the new active stage if it has valid coordinates
             if stage.search_area.len() >= 3 {  // Only send if we have at least 3 coordinates
                 let coords: Vec<GeoCoordinate> = stage.search_area.iter()
-                    .take(6) // Limit to 6 points
                     .map(|coord| GeoCoordinate {
                         lat: coord.lat,
                         long: coord.long,
@@ -242,9 +286,10 @@ impl MissionApiImpl {
                     .collect();
                 
                 // Send search area (commandID: 4) to the specific vehicle
-                commands_api.clone().send_zone_update(
+                commands_api.send_zone_geometry(
                     vehicle.vehicle_name.to_string(),
-                    "4".to_string(),
+                    4,
+                    zone_key(4, 0),
                     coords
                 ).await?;
             }
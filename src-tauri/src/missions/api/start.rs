@@ -0,0 +1,214 @@
+/*
+Implement start_mission on MissionApiImpl. Starting a mission completes the previous one,
+activates the mission, sends its zones to every vehicle and activates each vehicle's first
+stage and sends its search area. Each of those steps is recorded in the mission's start
+progress, so when one fails the rest still run where they can, the failed steps are
+reported, and calling start_mission again retries only the steps that haven't succeeded.
+*/
+
+use crate::commands::commands::{zone_key, GeoCoordinate};
+use crate::events::EventSink;
+use crate::logs;
+use crate::maintenance;
+use crate::missions::types::*;
+use super::missions::{disarmed_participants, participants};
+use super::MissionApiImpl;
+
+impl MissionStartProgress {
+    fn new(mission_id: i32) -> Self {
+        Self { mission_id, steps: vec![], complete: false }
+    }
+
+    fn pending(&self, step: &str) -> bool {
+        !self.steps.iter().any(|s| s.step == step && s.done)
+    }
+
+    // True when the step succeeded
+    fn record(&mut self, step: &str, result: Result<(), String>) -> bool {
+        if let Err(e) = &result {
+            logs::warn("missions", format!("Mission {} start step {} failed: {}", self.mission_id, step, e));
+        }
+        let entry = MissionStartStep {
+            step: step.to_string(),
+            done: result.is_ok(),
+            error: result.err(),
+        };
+        let done = entry.done;
+        match self.steps.iter_mut().find(|s| s.step == step) {
+            Some(existing) => *existing = entry,
+            None => self.steps.push(entry),
+        }
+        done
+    }
+}
+
+fn to_coordinates(area: &GeofenceType) -> Vec<GeoCoordinate> {
+    area.iter().map(|c| GeoCoordinate { lat: c.lat, long: c.long }).collect()
+}
+
+impl MissionApiImpl {
+    pub fn start_progress_for(&self, mission_id: i32) -> Option<MissionStartProgress> {
+        self.start_progress.lock().unwrap().get(&mission_id).cloned()
+    }
+
+    pub async fn start_mission_helper(
+        &self,
+        events: &impl EventSink,
+        mission_id: i32,
+    ) -> Result<MissionStartProgress, String> {
+        let mut state = self.state.lock().await;
+        let commands_api = self.commands.clone();
+
+        // Arm interlock, checked before anything about the current mission changes
+        let mission = state.missions.iter().find(|m| m.mission_id == mission_id)
+            .ok_or("Mission not found")?;
+        let disarmed = disarmed_participants(mission);
+        if !disarmed.is_empty() {
+            return Err(format!("Cannot start mission: {} disarmed", disarmed.join(", ")));
+        }
+        // Completing a held mission would skip the decision to release it
+        self.require_not_held(state.current_mission, "start mission")?;
+
+        // A finished start only counts while the mission is still running; starting it again
+        // after it ended starts from scratch
+        let running = mission.mission_status == MissionStageStatusEnum::Active;
+        let mut progress = self
+            .start_progress_for(mission_id)
+            .filter(|p| !p.complete || running)
+            .unwrap_or_else(|| MissionStartProgress::new(mission_id));
+        if progress.complete {
+            return Ok(progress);
+        }
+        if progress.steps.is_empty() {
+            // Worn batteries only warn; the operator decides whether to fly on them
+            maintenance::warn_worn_batteries(self.db.clone(), &participants(mission)).await;
+        }
+
+        // The previous mission and the mission itself change status first; zones and search
+        // areas only go out for a mission that is active
+        // An aborted mission stays current until another starts, but keeps its Failed status
+        let previous_index = state.missions.iter().position(|m| {
+            m.mission_id == state.current_mission
+                && m.mission_id != mission_id
+                && matches!(m.mission_status, MissionStageStatusEnum::Active | MissionStageStatusEnum::Paused)
+        });
+        if let Some(index) = previous_index.filter(|_| progress.pending("complete_previous")) {
+            let previous = &mut state.missions[index];
+            let result = self.store.update_mission_status(previous.mission_id, "Complete")
+                .await
+                .map_err(|e| format!("Failed to complete mission {}: {}", previous.mission_id, e));
+            if result.is_ok() {
+                previous.set_status(MissionStageStatusEnum::Complete);
+                self.finalize_kpis(previous.mission_id);
+            }
+            if !progress.record("complete_previous", result) {
+                return self.finish_start(events, &state, progress);
+            }
+        }
+
+        let start_mission_index = state.missions.iter().position(|m| m.mission_id == mission_id)
+            .ok_or("Mission not found")?;
+        if progress.pending("activate_mission") {
+            let result = self.store.update_mission_status(mission_id, "Active")
+                .await
+                .map_err(|e| format!("Failed to update mission status: {}", e));
+            if result.is_ok() {
+                state.missions[start_mission_index].set_status(MissionStageStatusEnum::Active);
+                state.current_mission = mission_id;
+                commands_api.set_active_mission(mission_id);
+            }
+            if !progress.record("activate_mission", result) {
+                return self.finish_start(events, &state, progress);
+            }
+        }
+
+        // Emit state update to ensure frontend reflects the change
+        self.emit_state_update(events, &state)?;
+
+        // Keep-in zones (commandID: 2) and keep-out zones (commandID: 3) go to all vehicles at
+        // once; only polygons with at least 3 coordinates are sent
+        let zones = &state.missions[start_mission_index].zones;
+        let zone_sends: Vec<(String, i32, &GeofenceType)> = zones.keep_in_zones
+            .iter()
+            .enumerate()
+            .map(|(zone_index, zone)| (zone_key(2, zone_index as i32), 2, zone))
+            .chain(zones.keep_out_zones.iter().enumerate().map(|(zone_index, zone)| {
+                (zone_key(3, zone_index as i32), 3, zone)
+            }))
+            .filter(|(_, _, zone)| zone.len() >= 3)
+            .collect();
+        for (zone_key, command_id, zone) in zone_sends {
+            if progress.pending(&zone_key) {
+                let result = commands_api
+                    .send_zone_geometry("ALL".to_string(), command_id, zone_key.clone(), to_coordinates(zone))
+                    .await;
+                progress.record(&zone_key, result);
+            }
+        }
+
+        // Set the first stage of each vehicle to active and send its search area
+        for vehicle_name in [VehicleEnum::MEA, VehicleEnum::ERU, VehicleEnum::MRA] {
+            let vehicles = &mut state.missions[start_mission_index].vehicles;
+            let vehicle = match vehicle_name {
+                VehicleEnum::MEA => &mut vehicles.MEA,
+                VehicleEnum::ERU => &mut vehicles.ERU,
+                VehicleEnum::MRA => &mut vehicles.MRA,
+            };
+            let Some(stage) = vehicle.stages.first_mut() else {
+                continue;
+            };
+            let vehicle_id = vehicle_name.to_string();
+
+            let stage_step = format!("activate_stage_{}", vehicle_id.to_lowercase());
+            if progress.pending(&stage_step) {
+                let result = if stage.stage_status == MissionStageStatusEnum::Active {
+                    Ok(())
+                } else {
+                    self.store.update_stage_status(stage.stage_id, "Active")
+                        .await
+                        .map(|_| stage.set_status(MissionStageStatusEnum::Active))
+                        .map_err(|e| format!("Failed to update stage status: {}", e))
+                };
+                if !progress.record(&stage_step, result) {
+                    continue;
+                }
+            }
+
+            let area_step = format!("search_area_{}", vehicle_id.to_lowercase());
+            if stage.search_area.len() >= 3 && progress.pending(&area_step) {
+                let result = commands_api
+                    .send_zone_geometry(vehicle_id, 4, zone_key(4, 0), to_coordinates(&stage.search_area))
+                    .await;
+                progress.record(&area_step, result);
+            }
+        }
+
+        self.finish_start(events, &state, progress)
+    }
+
+    // Save the progress and emit the state; an incomplete start is an error naming the failed steps
+    fn finish_start(
+        &self,
+        events: &impl EventSink,
+        state: &crate::snapshot::Snapshot<MissionsStruct>,
+        mut progress: MissionStartProgress,
+    ) -> Result<MissionStartProgress, String> {
+        let failed: Vec<String> = progress
+            .steps
+            .iter()
+            .filter_map(|s| s.error.as_ref().map(|e| format!("{} ({})", s.step, e)))
+            .collect();
+        progress.complete = failed.is_empty();
+        self.start_progress.lock().unwrap().insert(progress.mission_id, progress.clone());
+        self.emit_state_update(events, state)?;
+        if failed.is_empty() {
+            Ok(progress)
+        } else {
+            Err(format!(
+                "Mission {} did not fully start; start it again to retry: {}",
+                progress.mission_id,
+                failed.join(", ")
+            ))
+        }
+    }
+}
@@ -0,0 +1,166 @@
+/*
+Unit tests of the mission helpers that only touch the store and the mission state, run against
+a MemoryMissionStore. Helpers that emit events get a NullEventSink; those that still take an
+AppHandle are not covered here.
+*/
+
+use std::sync::Arc;
+
+use crate::events::NullEventSink;
+use crate::missions::store::memory::MemoryMissionStore;
+use crate::missions::store::MissionStore;
+use crate::missions::types::*;
+use super::MissionApiImpl;
+
+fn square(lat: f64, long: f64) -> GeofenceType {
+    vec![
+        GeoCoordinateStruct { lat, long },
+        GeoCoordinateStruct { lat, long: long + 0.01 },
+        GeoCoordinateStruct { lat: lat + 0.01, long: long + 0.01 },
+        GeoCoordinateStruct { lat: lat + 0.01, long },
+    ]
+}
+
+// An API holding one mission with three keep-out zones, also created in the store
+pub(super) async fn api_with_mission() -> (MissionApiImpl, Arc<MemoryMissionStore>, i32) {
+    let store = Arc::new(MemoryMissionStore::new());
+    let api = MissionApiImpl::with_store(store.clone(), MissionsStruct { current_mission: 0, missions: vec![] });
+    let mut mission = api.clone().create_default_mission("Search and rescue").await;
+    mission.zones.keep_out_zones = vec![square(34.0, -117.0), square(34.02, -117.0), square(34.04, -117.0)];
+    let mission_id = mission.mission_id;
+    api.state.lock().await.missions.push(mission);
+    (api, store, mission_id)
+}
+
+fn threshold(mission_id: i32, zone_index: Option<i32>, warning_distance_m: f64) -> GeofenceThreshold {
+    GeofenceThreshold { mission_id, zone_index, warning_distance_m }
+}
+
+fn zone_indexes(thresholds: &[GeofenceThreshold]) -> Vec<Option<i32>> {
+    thresholds.iter().map(|t| t.zone_index).collect()
+}
+
+#[tokio::test]
+async fn geofence_thresholds_are_kept_per_zone_with_the_mission_wide_one_first() {
+    let (api, store, mission_id) = api_with_mission().await;
+
+    api.set_geofence_threshold_helper(threshold(mission_id, Some(2), 200.0)).await.unwrap();
+    api.set_geofence_threshold_helper(threshold(mission_id, Some(0), 300.0)).await.unwrap();
+    let saved = api.set_geofence_threshold_helper(threshold(mission_id, None, 500.0)).await.unwrap();
+    assert_eq!(zone_indexes(&saved), vec![None, Some(0), Some(2)]);
+
+    // Setting a zone again replaces its threshold
+    let saved = api.set_geofence_threshold_helper(threshold(mission_id, Some(0), 150.0)).await.unwrap();
+    assert_eq!(saved.len(), 3);
+    assert_eq!(saved[1].warning_distance_m, 150.0);
+
+    let listed = api.list_geofence_thresholds_helper(mission_id).await.unwrap();
+    assert_eq!(zone_indexes(&listed), zone_indexes(&saved));
+    assert_eq!(store.select_geofence_thresholds(Some(mission_id)).await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn geofence_thresholds_reject_bad_distances_and_zones() {
+    let (api, _, mission_id) = api_with_mission().await;
+
+    for distance in [0.0, -5.0, f64::NAN, f64::INFINITY, 10_001.0] {
+        assert!(api.set_geofence_threshold_helper(threshold(mission_id, None, distance)).await.is_err());
+    }
+    for zone_index in [-1, 3] {
+        assert_eq!(
+            api.set_geofence_threshold_helper(threshold(mission_id, Some(zone_index), 100.0)).await.err(),
+            Some("KeepOut index out of range".to_string()),
+        );
+    }
+    assert_eq!(
+        api.set_geofence_threshold_helper(threshold(mission_id + 100, None, 100.0)).await.err(),
+        Some("Mission not found".to_string()),
+    );
+    assert!(api.list_geofence_thresholds_helper(mission_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn clearing_a_geofence_threshold_needs_one_to_be_set() {
+    let (api, _, mission_id) = api_with_mission().await;
+    api.set_geofence_threshold_helper(threshold(mission_id, None, 500.0)).await.unwrap();
+    api.set_geofence_threshold_helper(threshold(mission_id, Some(1), 100.0)).await.unwrap();
+
+    assert!(api.clear_geofence_threshold_helper(mission_id, Some(0)).await.is_err());
+    let left = api.clear_geofence_threshold_helper(mission_id, None).await.unwrap();
+    assert_eq!(zone_indexes(&left), vec![Some(1)]);
+    assert!(api.clear_geofence_threshold_helper(mission_id, None).await.is_err());
+}
+
+#[tokio::test]
+async fn removing_a_zone_renumbers_the_thresholds_after_it() {
+    let (api, _, mission_id) = api_with_mission().await;
+    for zone_index in [None, Some(0), Some(1), Some(2)] {
+        let distance = 100.0 + zone_index.unwrap_or(-1) as f64;
+        api.set_geofence_threshold_helper(threshold(mission_id, zone_index, distance)).await.unwrap();
+    }
+
+    api.remove_zone_threshold(mission_id, 1).await.unwrap();
+    let thresholds = api.list_geofence_thresholds_helper(mission_id).await.unwrap();
+    assert_eq!(zone_indexes(&thresholds), vec![None, Some(0), Some(1)]);
+    // The old zone 2 moved down to 1 and kept its distance
+    assert_eq!(thresholds[2].warning_distance_m, 102.0);
+}
+
+#[tokio::test]
+async fn exports_carry_the_notes_and_holds_of_the_mission() {
+    let (api, store, mission_id) = api_with_mission().await;
+    store.insert_mission_note(mission_id, "alice", "Search area moved north").await.unwrap();
+    store.insert_mission_note(mission_id, "bob", "MEA battery swapped").await.unwrap();
+    let hold = store.insert_mission_hold(mission_id, "Weather", "alice").await.unwrap();
+    store.end_mission_hold(hold.hold_id).await.unwrap();
+
+    let export = api.export_mission_helper(mission_id).await.unwrap();
+    assert_eq!(export.mission.mission_id, mission_id);
+    assert_eq!(export.notes.iter().map(|n| n.author.as_str()).collect::<Vec<_>>(), vec!["alice", "bob"]);
+    assert_eq!(export.holds.len(), 1);
+    assert!(export.holds[0].ended_at.is_some());
+
+    assert_eq!(api.export_mission_helper(mission_id + 100).await.err(), Some("Mission not found".to_string()));
+    assert_eq!(api.list_notes_helper(mission_id + 100).await.err(), Some("Mission not found".to_string()));
+}
+
+#[tokio::test]
+async fn deleting_a_mission_in_the_store_takes_its_rows_along() {
+    let (api, store, mission_id) = api_with_mission().await;
+    store.insert_mission_note(mission_id, "alice", "Note").await.unwrap();
+    api.set_geofence_threshold_helper(threshold(mission_id, None, 500.0)).await.unwrap();
+
+    store.delete_mission(mission_id).await.unwrap();
+    assert!(store.select_mission_notes(mission_id).await.unwrap().is_empty());
+    assert!(store.select_geofence_thresholds(Some(mission_id)).await.unwrap().is_empty());
+    assert!(store.select_vehicle_from_mission(mission_id, "MEA".to_string()).await.is_err());
+}
+
+#[tokio::test]
+async fn starting_a_mission_after_an_abort_leaves_the_aborted_one_failed() {
+    let (api, store, aborted_id) = api_with_mission().await;
+    let next = api.clone().create_default_mission("Second sortie").await;
+    let next_id = next.mission_id;
+    {
+        let mut state = api.state.lock().await;
+        state.missions.push(next);
+        // No zones, so starting sends nothing to the vehicles
+        for mission in state.missions.iter_mut() {
+            mission.zones.keep_out_zones.clear();
+        }
+    }
+
+    api.start_mission_helper(&NullEventSink, aborted_id).await.unwrap();
+    api.abort_mission_helper(&NullEventSink, aborted_id).await.unwrap();
+    api.start_mission_helper(&NullEventSink, next_id).await.unwrap();
+
+    let state = api.state.lock().await;
+    let status = |mission_id: i32| {
+        state.missions.iter().find(|m| m.mission_id == mission_id).unwrap().mission_status.clone()
+    };
+    assert_eq!(status(aborted_id), MissionStageStatusEnum::Failed);
+    assert_eq!(status(next_id), MissionStageStatusEnum::Active);
+    assert_eq!(state.current_mission, next_id);
+    assert_eq!(store.mission_status(aborted_id).as_deref(), Some("Failed"));
+    assert_eq!(store.mission_status(next_id).as_deref(), Some("Active"));
+}
@@ -5,12 +5,17 @@ convert between DB zone format and coordinate types).
 */
 
 use tauri::{AppHandle, Runtime};
-use crate::missions::types::{GeofenceType, ZoneType};
-use crate::missions::sql::update_zones;
+use crate::missions::types::{GeofenceType, MissionStageStatusEnum, ZoneType};
+use crate::commands::confirmation::DestructiveAction;
+use crate::audit::{self, AuditAction};
+use crate::commands::capabilities::zone_vertex_budget;
+use crate::geometry::validate_polygon;
 use serde_json::Value;
 
 // We need to import the struct to implement methods on it.
 use super::MissionApiImpl;
+use super::budget::fit_to_budget;
+use super::missions::in_flight_justification;
 
 impl MissionApiImpl {
     pub async fn add_zone_helper(
@@ -47,7 +52,10 @@ impl MissionApiImpl {
         zone_type: ZoneType,
         zone_index: i32,
         zone_coords: GeofenceType,
+        simplify: Option<bool>,
     ) -> Result<(), String> {
+        validate_polygon(&zone_coords).map_err(|e| format!("Invalid zone: {}", e))?;
+        let zone_coords = fit_to_budget(zone_coords, zone_vertex_budget(), simplify.unwrap_or(false), "Zone")?;
         let mut state = self.state.lock().await;
         let mission = state
             .missions
@@ -90,8 +98,7 @@ impl MissionApiImpl {
 
 
         // update zones
-        update_zones(
-            self.db.clone(),
+        self.store.update_zones(
             mission.mission_id,
             keep_in_zones.clone(),
             keep_out_zones.clone(),
@@ -106,6 +113,8 @@ impl MissionApiImpl {
         mission_id: i32,
         zone_type: ZoneType,
         zone_index: i32,
+        confirmation: Option<String>,
+        justification: Option<String>,
     ) -> Result<(), String> {
         println!(
             "Deleting zone of type: {:?} at index: {}",
@@ -117,19 +126,44 @@ impl MissionApiImpl {
             .iter_mut()
             .find(|m| m.mission_id == mission_id)
             .ok_or("Mission not found")?;
+        let justification = in_flight_justification(mission, justification.as_deref())?;
 
         match zone_type {
             ZoneType::KeepIn => {
                 if zone_index >= mission.zones.keep_in_zones.len() as i32 {
                     return Err("KeepIn index out of range".into());
                 }
-                mission.zones.keep_in_zones.remove(zone_index as usize);
             }
             ZoneType::KeepOut => {
                 if zone_index >= mission.zones.keep_out_zones.len() as i32 {
                     return Err("KeepOut index out of range".into());
                 }
+                if matches!(mission.mission_status, MissionStageStatusEnum::Active) {
+                    self.commands
+                        .require_confirmation(DestructiveAction::KeepOutDeletion, confirmation)
+                        .await?;
+                }
+            }
+        }
+        // Nothing is removed from a flying mission unless the audit entry was written
+        if let Some(justification) = &justification {
+            audit::record(
+                self.db.clone(),
+                AuditAction::ZoneDeleted,
+                Some(mission_id),
+                justification,
+                &format!("{:?} zone {}", zone_type, zone_index),
+            )
+            .await?;
+        }
+
+        match zone_type {
+            ZoneType::KeepIn => {
+                mission.zones.keep_in_zones.remove(zone_index as usize);
+            }
+            ZoneType::KeepOut => {
                 mission.zones.keep_out_zones.remove(zone_index as usize);
+                self.remove_zone_threshold(mission_id, zone_index).await?;
             }
         }
 
@@ -149,8 +183,7 @@ impl MissionApiImpl {
 
 
         // update zones
-        update_zones(
-            self.db.clone(),
+        self.store.update_zones(
             mission.mission_id,
             keep_in_zones.clone(),
             keep_out_zones.clone(),
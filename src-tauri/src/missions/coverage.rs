@@ -0,0 +1,119 @@
+/*
+Search-area coverage: how much of a stage's search polygon a vehicle's track has overflown,
+given the width of the ground strip its sensor sees. The polygon is rasterised on a local
+metric grid; a cell counts as covered when its centre lies within half the footprint width
+of the track. Uncovered cells are merged row by row into rectangles for display.
+*/
+use crate::geometry::{distance_to_segment, point_in_polygon, LocalProjection};
+use crate::missions::types::{GeoCoordinateStruct, GeofenceType};
+
+// Upper bound on grid cells per polygon side, keeping large areas cheap to evaluate
+const MAX_GRID_CELLS_PER_SIDE: f64 = 400.0;
+
+pub struct CoverageResult {
+    pub coverage_percent: f64,
+    pub area_m2: f64,
+    pub uncovered_regions: Vec<GeofenceType>,
+}
+
+// None when the search area is not a polygon (fewer than 3 vertices)
+pub fn compute_coverage(
+    area: &GeofenceType,
+    track: &[GeoCoordinateStruct],
+    footprint_width_m: f64,
+) -> Option<CoverageResult> {
+    if area.len() < 3 {
+        return None;
+    }
+    let projection = LocalProjection::new(&area[0]);
+    let polygon: Vec<(f64, f64)> = area.iter().map(|c| projection.to_xy(c)).collect();
+    let path: Vec<(f64, f64)> = track.iter().map(|c| projection.to_xy(c)).collect();
+    let radius = footprint_width_m / 2.0;
+
+    let (min_x, max_x) = polygon.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.0), hi.max(p.0)));
+    let (min_y, max_y) = polygon.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.1), hi.max(p.1)));
+    let span = (max_x - min_x).max(max_y - min_y);
+    if span <= 0.0 {
+        return None;
+    }
+    let cell = (footprint_width_m / 4.0).max(span / MAX_GRID_CELLS_PER_SIDE);
+    let cols = ((max_x - min_x) / cell).ceil().max(1.0) as usize;
+    let rows = ((max_y - min_y) / cell).ceil().max(1.0) as usize;
+    let center = |col: usize, row: usize| {
+        (
+            min_x + (col as f64 + 0.5) * cell,
+            min_y + (row as f64 + 0.5) * cell,
+        )
+    };
+
+    let mut inside = vec![false; cols * rows];
+    let mut covered = vec![false; cols * rows];
+    for row in 0..rows {
+        for col in 0..cols {
+            let (x, y) = center(col, row);
+            inside[row * cols + col] = point_in_polygon(x, y, &polygon);
+        }
+    }
+
+    // A lone fix still covers the footprint around it
+    let segments: Vec<((f64, f64), (f64, f64))> = match path.len() {
+        0 => vec![],
+        1 => vec![(path[0], path[0])],
+        _ => path.windows(2).map(|w| (w[0], w[1])).collect(),
+    };
+    for (a, b) in segments {
+        let to_col = |x: f64| (((x - min_x) / cell).floor().max(0.0) as usize).min(cols - 1);
+        let to_row = |y: f64| (((y - min_y) / cell).floor().max(0.0) as usize).min(rows - 1);
+        let (lo_x, hi_x) = (a.0.min(b.0) - radius, a.0.max(b.0) + radius);
+        let (lo_y, hi_y) = (a.1.min(b.1) - radius, a.1.max(b.1) + radius);
+        if hi_x < min_x || lo_x > max_x || hi_y < min_y || lo_y > max_y {
+            continue;
+        }
+        for row in to_row(lo_y)..=to_row(hi_y) {
+            for col in to_col(lo_x)..=to_col(hi_x) {
+                let index = row * cols + col;
+                if inside[index] && !covered[index] {
+                    let (x, y) = center(col, row);
+                    covered[index] = distance_to_segment(x, y, a, b) <= radius;
+                }
+            }
+        }
+    }
+
+    let inside_count = inside.iter().filter(|&&i| i).count();
+    if inside_count == 0 {
+        return None;
+    }
+    let covered_count = covered.iter().filter(|&&c| c).count();
+
+    // Merge horizontal runs of uncovered cells into rectangles
+    let mut uncovered_regions = vec![];
+    for row in 0..rows {
+        let mut col = 0;
+        while col < cols {
+            let index = row * cols + col;
+            if !inside[index] || covered[index] {
+                col += 1;
+                continue;
+            }
+            let start = col;
+            while col < cols && inside[row * cols + col] && !covered[row * cols + col] {
+                col += 1;
+            }
+            let (x0, x1) = (min_x + start as f64 * cell, min_x + col as f64 * cell);
+            let (y0, y1) = (min_y + row as f64 * cell, min_y + (row + 1) as f64 * cell);
+            uncovered_regions.push(vec![
+                projection.to_coord(x0, y0),
+                projection.to_coord(x1, y0),
+                projection.to_coord(x1, y1),
+                projection.to_coord(x0, y1),
+            ]);
+        }
+    }
+
+    Some(CoverageResult {
+        coverage_percent: covered_count as f64 * 100.0 / inside_count as f64,
+        area_m2: inside_count as f64 * cell * cell,
+        uncovered_regions,
+    })
+}
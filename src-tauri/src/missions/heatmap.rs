@@ -0,0 +1,59 @@
+/*
+Track heatmaps: the recorded positions of a mission are binned into square cells on a local
+metric grid, and each cell is weighted by its share of the busiest cell. Telemetry arrives at a
+steady rate, so the sample count of a cell stands for the time spent over it. Only cells with
+samples are returned, so a long transit stays cheap to send.
+*/
+use std::collections::HashMap;
+
+use crate::geometry::LocalProjection;
+use crate::missions::types::{GeoCoordinateStruct, HeatmapCell};
+
+// Upper bound on grid cells per side of the track's bounding box; wider cells are used beyond
+const MAX_GRID_CELLS_PER_SIDE: f64 = 500.0;
+pub const MIN_CELL_SIZE_M: f64 = 5.0;
+
+pub struct HeatmapResult {
+    // Cell size used, at least the requested one
+    pub cell_size_m: f64,
+    pub cells: Vec<HeatmapCell>,
+    pub max_samples: u32,
+}
+
+pub fn compute_heatmap(track: &[GeoCoordinateStruct], cell_size_m: f64) -> HeatmapResult {
+    let Some(origin) = track.first() else {
+        return HeatmapResult { cell_size_m, cells: vec![], max_samples: 0 };
+    };
+    let projection = LocalProjection::new(origin);
+    let points: Vec<(f64, f64)> = track.iter().map(|c| projection.to_xy(c)).collect();
+
+    let (min_x, max_x) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.0), hi.max(p.0)));
+    let (min_y, max_y) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.1), hi.max(p.1)));
+    let span = (max_x - min_x).max(max_y - min_y);
+    let cell = cell_size_m.max(MIN_CELL_SIZE_M).max(span / MAX_GRID_CELLS_PER_SIDE);
+
+    let mut counts: HashMap<(u32, u32), u32> = HashMap::new();
+    for (x, y) in &points {
+        let col = ((x - min_x) / cell).floor() as u32;
+        let row = ((y - min_y) / cell).floor() as u32;
+        *counts.entry((row, col)).or_default() += 1;
+    }
+    let max_samples = counts.values().copied().max().unwrap_or(0);
+
+    let mut cells: Vec<HeatmapCell> = counts
+        .into_iter()
+        .map(|((row, col), samples)| HeatmapCell {
+            row,
+            col,
+            center: projection.to_coord(
+                min_x + (col as f64 + 0.5) * cell,
+                min_y + (row as f64 + 0.5) * cell,
+            ),
+            samples,
+            weight: samples as f64 / max_samples as f64,
+        })
+        .collect();
+    cells.sort_by_key(|c| (c.row, c.col));
+
+    HeatmapResult { cell_size_m: cell, cells, max_samples }
+}
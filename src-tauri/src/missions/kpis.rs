@@ -0,0 +1,153 @@
+/*
+Post-mission KPIs, aggregated in the database from a mission's telemetry, targets, holds and
+command log. The mission runs from its first to its last telemetry report; search time leaves
+out the time spent on hold. A vehicle counts as airborne above AIRBORNE_ALTITUDE_M, and gaps
+between its reports longer than the heartbeat timeout are not counted as airtime. Once a mission
+has ended its KPIs are stored as JSON in mission_kpis, so reports read them back instead of
+scanning its telemetry again.
+*/
+use sqlx::{query, PgPool, Row};
+
+use crate::config;
+use crate::missions::types::{MissionKpis, VehicleKpis};
+
+const AIRBORNE_ALTITUDE_M: f64 = 1.0;
+
+pub async fn compute_kpis(db_conn: PgPool, mission_id: i32) -> Result<MissionKpis, sqlx::Error> {
+    let row = query("
+        WITH span AS (
+            SELECT MIN(recorded_at) AS started, MAX(recorded_at) AS ended
+            FROM telemetry WHERE mission_id = $1
+        )
+        SELECT
+            to_char(span.started AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS started_at,
+            to_char(span.ended AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS ended_at,
+            EXTRACT(EPOCH FROM span.ended - span.started)::FLOAT8 AS span_secs,
+            (
+                SELECT COALESCE(SUM(EXTRACT(EPOCH FROM
+                    LEAST(COALESCE(h.ended_at, NOW()), span.ended) - GREATEST(h.started_at, span.started)
+                )) FILTER (WHERE COALESCE(h.ended_at, NOW()) > span.started AND h.started_at < span.ended), 0)::FLOAT8
+                FROM mission_holds h WHERE h.mission_id = $1
+            ) AS held_secs,
+            (
+                SELECT GREATEST(EXTRACT(EPOCH FROM MIN(t.discovered_at) - span.started), 0)::FLOAT8
+                FROM targets t WHERE t.mission_id = $1
+            ) AS first_detection_secs,
+            (
+                SELECT AVG(EXTRACT(EPOCH FROM t.delivered_at - t.secured_at))::FLOAT8
+                FROM targets t
+                WHERE t.mission_id = $1 AND t.secured_at IS NOT NULL AND t.delivered_at IS NOT NULL
+            ) AS secured_to_delivered_secs,
+            to_char(NOW() AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS computed_at
+        FROM span
+    ")
+    .bind(mission_id)
+    .fetch_one(&db_conn)
+    .await?;
+
+    let span_secs: Option<f64> = row.get("span_secs");
+    let held_secs: f64 = row.get("held_secs");
+    // Without telemetry there is no start to measure the first detection from
+    let first_detection_secs: Option<f64> = span_secs.and(row.get("first_detection_secs"));
+
+    let airtime = query("
+        SELECT vehicle_id, COALESCE(SUM(EXTRACT(EPOCH FROM gap)) FILTER (
+            WHERE airborne AND previous_airborne AND gap <= make_interval(secs => $3)
+        ), 0)::FLOAT8 AS airtime_secs
+        FROM (
+            SELECT LOWER(vehicle_id) AS vehicle_id,
+                altitude > $2 AS airborne,
+                LAG(altitude > $2) OVER w AS previous_airborne,
+                recorded_at - LAG(recorded_at) OVER w AS gap
+            FROM telemetry
+            WHERE mission_id = $1
+            WINDOW w AS (PARTITION BY LOWER(vehicle_id) ORDER BY recorded_at)
+        ) reports
+        GROUP BY vehicle_id
+    ")
+    .bind(mission_id)
+    .bind(AIRBORNE_ALTITUDE_M)
+    .bind(config::get().heartbeat_timeout_secs as f64)
+    .fetch_all(&db_conn)
+    .await?;
+
+    let commands = query("
+        SELECT LOWER(vehicle_id) AS vehicle_id,
+            COUNT(*)::INTEGER AS total,
+            (COUNT(*) FILTER (WHERE result = 'Sent'))::INTEGER AS sent,
+            (COUNT(*) FILTER (WHERE result LIKE 'Failed%'))::INTEGER AS failed
+        FROM commands
+        WHERE mission_id = $1
+        GROUP BY LOWER(vehicle_id)
+    ")
+    .bind(mission_id)
+    .fetch_all(&db_conn)
+    .await?;
+
+    let mut vehicles: Vec<VehicleKpis> = airtime
+        .iter()
+        .map(|row| VehicleKpis {
+            vehicle_id: row.get("vehicle_id"),
+            airtime_secs: row.get("airtime_secs"),
+            commands_total: 0,
+            commands_sent: 0,
+            commands_failed: 0,
+        })
+        .collect();
+    for row in &commands {
+        let vehicle_id: String = row.get("vehicle_id");
+        let index = match vehicles.iter().position(|v| v.vehicle_id == vehicle_id) {
+            Some(index) => index,
+            None => {
+                vehicles.push(VehicleKpis {
+                    vehicle_id,
+                    airtime_secs: 0.0,
+                    commands_total: 0,
+                    commands_sent: 0,
+                    commands_failed: 0,
+                });
+                vehicles.len() - 1
+            }
+        };
+        vehicles[index].commands_total = row.get("total");
+        vehicles[index].commands_sent = row.get("sent");
+        vehicles[index].commands_failed = row.get("failed");
+    }
+    vehicles.sort_by(|a, b| a.vehicle_id.cmp(&b.vehicle_id));
+
+    Ok(MissionKpis {
+        mission_id,
+        started_at: row.get("started_at"),
+        ended_at: row.get("ended_at"),
+        time_to_first_detection_secs: first_detection_secs,
+        search_time_secs: span_secs.map(|span| (span - held_secs).max(0.0)),
+        secured_to_delivered_secs: row.get("secured_to_delivered_secs"),
+        total_commands: vehicles.iter().map(|v| v.commands_total).sum(),
+        vehicles,
+        computed_at: row.get("computed_at"),
+        persisted: false,
+    })
+}
+
+pub async fn select_saved_kpis(db_conn: PgPool, mission_id: i32) -> Result<Option<MissionKpis>, sqlx::Error> {
+    let row = query("SELECT kpis FROM mission_kpis WHERE mission_id = $1")
+        .bind(mission_id)
+        .fetch_optional(&db_conn)
+        .await?;
+    // A row that no longer deserializes is recomputed and overwritten
+    Ok(row.and_then(|row| serde_json::from_str(&row.get::<String, _>("kpis")).ok()))
+}
+
+pub async fn save_kpis(db_conn: PgPool, kpis: &MissionKpis) -> Result<(), sqlx::Error> {
+    let json = serde_json::to_string(kpis).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+    query("
+        INSERT INTO mission_kpis (mission_id, kpis, computed_at)
+        VALUES ($1, $2, NOW())
+        ON CONFLICT (mission_id) DO UPDATE SET kpis = EXCLUDED.kpis, computed_at = NOW()
+    ")
+    .bind(kpis.mission_id)
+    .bind(json)
+    .execute(&db_conn)
+    .await?;
+    Ok(())
+}
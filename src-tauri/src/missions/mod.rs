@@ -1,7 +1,12 @@
 /*
-Declares api, types, sql submodules
+Declares api, types, sql, store, coverage, heatmap, kpis, reconcile submodules
 Serve as the main entry point for the missions module.
 */
 pub mod api;
 pub mod types;
-pub mod sql;
\ No newline at end of file
+pub mod sql;
+pub mod store;
+pub mod coverage;
+pub mod heatmap;
+pub mod kpis;
+pub mod reconcile;
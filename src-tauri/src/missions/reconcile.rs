@@ -0,0 +1,119 @@
+/*
+Crash recovery for mission progress. A vehicle's current_stage_id and the statuses of its stages
+are written by separate statements, so a crash in the middle of a start or transition can leave
+them disagreeing. Run at startup before missions are loaded, this repairs the rows:
+- a current stage that isn't one of the vehicle's stages moves to its first unfinished stage,
+  or -1 when it has no stages
+- in an active or paused mission the current stage is Active, earlier Active stages become
+  Complete and later ones Inactive
+- a mission that was never started has no Active stage
+- of several Active missions only the most recently started stays Active; the others are Paused
+Finished missions keep their stage statuses, since aborting leaves the flown stage Active.
+*/
+use sqlx::{query, PgPool, Row};
+
+pub struct Repair {
+    pub mission_id: i32,
+    pub detail: String,
+}
+
+pub async fn reconcile(db_conn: PgPool) -> Result<Vec<Repair>, sqlx::Error> {
+    let mut repairs = vec![];
+    let mut transaction = db_conn.begin().await?;
+
+    let active = query("
+        SELECT mission_id FROM missions WHERE status = 'Active'
+        ORDER BY started_at DESC NULLS LAST, mission_id DESC
+    ")
+    .fetch_all(&mut *transaction)
+    .await?;
+    for row in active.iter().skip(1) {
+        let mission_id: i32 = row.get("mission_id");
+        query("UPDATE missions SET status = 'Paused' WHERE mission_id = $1")
+            .bind(mission_id)
+            .execute(&mut *transaction)
+            .await?;
+        repairs.push(Repair {
+            mission_id,
+            detail: format!("Mission {} was active alongside a later mission; paused", mission_id),
+        });
+    }
+
+    let vehicles = query("
+        SELECT vehicles.vehicle_id, vehicles.mission_id, vehicles.vehicle_name, vehicles.current_stage_id,
+            COALESCE(missions.status, 'Inactive') AS mission_status
+        FROM vehicles
+        JOIN missions ON missions.mission_id = vehicles.mission_id
+        ORDER BY vehicles.mission_id, vehicles.vehicle_id
+    ")
+    .fetch_all(&mut *transaction)
+    .await?;
+
+    for vehicle in &vehicles {
+        let vehicle_id: i32 = vehicle.get("vehicle_id");
+        let mission_id: i32 = vehicle.get("mission_id");
+        let vehicle_name: String = vehicle.get("vehicle_name");
+        let mission_status: String = vehicle.get("mission_status");
+        let label = format!("Mission {} {}", mission_id, vehicle_name);
+
+        // Ordered by stage_id, the order transitions follow
+        let stages: Vec<(i32, String)> = query("
+            SELECT stage_id, COALESCE(status, 'Inactive') AS status
+            FROM stages WHERE vehicle_id = $1 ORDER BY stage_id
+        ")
+        .bind(vehicle_id)
+        .fetch_all(&mut *transaction)
+        .await?
+        .iter()
+        .map(|row| (row.get("stage_id"), row.get("status")))
+        .collect();
+
+        let mut current: i32 = vehicle.get("current_stage_id");
+        if !stages.iter().any(|(stage_id, _)| *stage_id == current) {
+            let repaired = stages
+                .iter()
+                .find(|(_, status)| status != "Complete" && status != "Failed")
+                .or(stages.last())
+                .map_or(-1, |(stage_id, _)| *stage_id);
+            if repaired != current {
+                query("UPDATE vehicles SET current_stage_id = $1 WHERE vehicle_id = $2")
+                    .bind(repaired)
+                    .bind(vehicle_id)
+                    .execute(&mut *transaction)
+                    .await?;
+                repairs.push(Repair {
+                    mission_id,
+                    detail: format!(
+                        "{}: current stage {} is not one of its stages; set to {}",
+                        label, current, repaired
+                    ),
+                });
+                current = repaired;
+            }
+        }
+
+        let position = stages.iter().position(|(stage_id, _)| *stage_id == current);
+        for (index, (stage_id, status)) in stages.iter().enumerate() {
+            let expected = match (mission_status.as_str(), position) {
+                ("Active" | "Paused", Some(p)) if index == p && status == "Inactive" => Some("Active"),
+                ("Active" | "Paused", Some(p)) if index < p && status == "Active" => Some("Complete"),
+                ("Active" | "Paused", Some(p)) if index > p && status == "Active" => Some("Inactive"),
+                ("Inactive", _) if status == "Active" => Some("Inactive"),
+                _ => None,
+            };
+            let Some(expected) = expected else { continue };
+            query("UPDATE stages SET status = $1 WHERE stage_id = $2")
+                .bind(expected)
+                .bind(stage_id)
+                .execute(&mut *transaction)
+                .await?;
+            repairs.push(Repair {
+                mission_id,
+                detail: format!("{}: stage {} was {}; set to {}", label, stage_id, status, expected),
+            });
+        }
+    }
+
+    transaction.commit().await?;
+    Ok(repairs)
+}
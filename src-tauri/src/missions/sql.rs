@@ -1,8 +1,11 @@
 /*
 Define all mission-related database functions (mission CRUD, vehicle selection and auto-mode, stage CRUD and transition, zone updates).
 */
+use sqlx::postgres::PgRow;
 use sqlx::{query, PgPool, Row};
 
+use crate::missions::types::{GeofenceThreshold, MissionHold, MissionNote};
+
 pub async fn insert_new_mission(
     db_conn: PgPool,
     mission_name: &str,
@@ -267,6 +270,24 @@ pub async fn update_auto_mode_vehicle(
     Ok(())
 }
 
+pub async fn update_patient_status(
+    db_conn: PgPool,
+    mission_id: i32,
+    vehicle_name: String,
+    patient_status: &str,
+) -> Result<(), sqlx::Error> {
+    query("
+        UPDATE vehicles SET patient_status = $1 WHERE vehicle_name = $2 AND mission_id = $3
+    ")
+    .bind(patient_status)
+    .bind(vehicle_name)
+    .bind(mission_id)
+    .execute(&db_conn)
+    .await?;
+
+    Ok(())
+}
+
 
 pub async fn transition_stage(
     db_conn: PgPool,
@@ -398,3 +419,289 @@ pub async fn update_zones(
 
     Ok(())
 }
+
+fn to_mission_note(row: PgRow) -> MissionNote {
+    MissionNote {
+        note_id: row.get("note_id"),
+        mission_id: row.get("mission_id"),
+        author: row.get("author"),
+        text: row.get("text"),
+        created_at: row.get("created_at"),
+    }
+}
+
+pub async fn insert_mission_note(
+    db_conn: PgPool,
+    mission_id: i32,
+    author: &str,
+    text: &str,
+) -> Result<MissionNote, sqlx::Error> {
+    let row = query("
+        INSERT INTO mission_notes(mission_id, author, text)
+        VALUES ($1, $2, $3)
+        RETURNING note_id, mission_id, author, text,
+            to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS created_at
+    ")
+    .bind(mission_id)
+    .bind(author)
+    .bind(text)
+    .fetch_one(&db_conn)
+    .await?;
+
+    Ok(to_mission_note(row))
+}
+
+// Oldest first, so the log reads in the order it was written (note ids are sequential)
+pub async fn select_mission_notes(
+    db_conn: PgPool,
+    mission_id: i32,
+) -> Result<Vec<MissionNote>, sqlx::Error> {
+    let rows = query("
+        SELECT note_id, mission_id, author, text,
+            to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS created_at
+        FROM mission_notes
+        WHERE mission_id = $1
+        ORDER BY note_id
+    ")
+    .bind(mission_id)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_mission_note).collect())
+}
+
+// Re-insert a note from a mission bundle, keeping its author and timestamp
+pub async fn insert_imported_note(
+    db_conn: PgPool,
+    mission_id: i32,
+    note: &MissionNote,
+) -> Result<(), sqlx::Error> {
+    query("
+        INSERT INTO mission_notes(mission_id, author, text, created_at)
+        VALUES ($1, $2, $3, $4::TIMESTAMPTZ)
+    ")
+    .bind(mission_id)
+    .bind(&note.author)
+    .bind(&note.text)
+    .bind(&note.created_at)
+    .execute(&db_conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn update_mission_timezone(
+    db_conn: PgPool,
+    mission_id: i32,
+    timezone: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    query("UPDATE missions SET display_timezone = $2 WHERE mission_id = $1")
+        .bind(mission_id)
+        .bind(timezone)
+        .execute(&db_conn)
+        .await?;
+    Ok(())
+}
+
+// Whether Postgres knows the IANA time zone, e.g. Europe/Berlin
+pub async fn timezone_exists(db_conn: PgPool, timezone: &str) -> Result<bool, sqlx::Error> {
+    let row = query("SELECT EXISTS(SELECT 1 FROM pg_timezone_names WHERE name = $1) AS known")
+        .bind(timezone)
+        .fetch_one(&db_conn)
+        .await?;
+    Ok(row.get("known"))
+}
+
+// Lifecycle timestamps of an imported mission or stage; `table` and `id_column` are constants
+pub async fn restore_times(
+    db_conn: PgPool,
+    table: &str,
+    id_column: &str,
+    id: i32,
+    created_at: Option<&str>,
+    started_at: Option<&str>,
+    completed_at: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    query(&format!(
+        "UPDATE {} SET created_at = COALESCE($2::TIMESTAMPTZ, created_at),
+            started_at = $3::TIMESTAMPTZ, completed_at = $4::TIMESTAMPTZ
+        WHERE {} = $1",
+        table, id_column
+    ))
+    .bind(id)
+    .bind(created_at)
+    .bind(started_at)
+    .bind(completed_at)
+    .execute(&db_conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn insert_imported_stage(
+    db_conn: PgPool,
+    vehicle_id: i32,
+    stage_name: &str,
+    search_area: Vec<String>,
+    status: &str,
+) -> Result<i32, sqlx::Error> {
+    let row = query("
+        INSERT INTO stages(vehicle_id, stage_name, search_area, status)
+        VALUES ($1, $2, $3, $4) RETURNING stage_id
+    ")
+    .bind(vehicle_id)
+    .bind(stage_name)
+    .bind(search_area)
+    .bind(status)
+    .fetch_one(&db_conn)
+    .await?;
+
+    Ok(row.get("stage_id"))
+}
+
+pub async fn update_imported_vehicle(
+    db_conn: PgPool,
+    vehicle_id: i32,
+    current_stage_id: i32,
+    is_auto: Option<bool>,
+    patient_status: &str,
+) -> Result<(), sqlx::Error> {
+    query("
+        UPDATE vehicles
+        SET current_stage_id = $2, is_auto = COALESCE($3, is_auto), patient_status = $4
+        WHERE vehicle_id = $1
+    ")
+    .bind(vehicle_id)
+    .bind(current_stage_id)
+    .bind(is_auto)
+    .bind(patient_status)
+    .execute(&db_conn)
+    .await?;
+
+    Ok(())
+}
+
+fn to_mission_hold(row: PgRow) -> MissionHold {
+    MissionHold {
+        hold_id: row.get("hold_id"),
+        mission_id: row.get("mission_id"),
+        reason: row.get("reason"),
+        held_by: row.get("held_by"),
+        started_at: row.get("started_at"),
+        ended_at: row.get("ended_at"),
+    }
+}
+
+const HOLD_COLUMNS: &str = "
+    hold_id, mission_id, reason, held_by,
+    to_char(started_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS started_at,
+    to_char(ended_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS ended_at
+";
+
+pub async fn insert_mission_hold(
+    db_conn: PgPool,
+    mission_id: i32,
+    reason: &str,
+    held_by: &str,
+) -> Result<MissionHold, sqlx::Error> {
+    let row = query(&format!("
+        INSERT INTO mission_holds(mission_id, reason, held_by)
+        VALUES ($1, $2, $3)
+        RETURNING {}
+    ", HOLD_COLUMNS))
+    .bind(mission_id)
+    .bind(reason)
+    .bind(held_by)
+    .fetch_one(&db_conn)
+    .await?;
+
+    Ok(to_mission_hold(row))
+}
+
+pub async fn end_mission_hold(db_conn: PgPool, hold_id: i32) -> Result<MissionHold, sqlx::Error> {
+    let row = query(&format!("
+        UPDATE mission_holds
+        SET ended_at = NOW()
+        WHERE hold_id = $1
+        RETURNING {}
+    ", HOLD_COLUMNS))
+    .bind(hold_id)
+    .fetch_one(&db_conn)
+    .await?;
+
+    Ok(to_mission_hold(row))
+}
+
+// Oldest first; at most the last one is still open
+pub async fn select_mission_holds(
+    db_conn: PgPool,
+    mission_id: i32,
+) -> Result<Vec<MissionHold>, sqlx::Error> {
+    let rows = query(&format!("
+        SELECT {}
+        FROM mission_holds
+        WHERE mission_id = $1
+        ORDER BY hold_id
+    ", HOLD_COLUMNS))
+    .bind(mission_id)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_mission_hold).collect())
+}
+
+// zone_index -1 in the table is the mission-wide threshold
+const MISSION_WIDE_ZONE: i32 = -1;
+
+fn to_geofence_threshold(row: PgRow) -> GeofenceThreshold {
+    let zone_index: i32 = row.get("zone_index");
+    GeofenceThreshold {
+        mission_id: row.get("mission_id"),
+        zone_index: (zone_index != MISSION_WIDE_ZONE).then_some(zone_index),
+        warning_distance_m: row.get("warning_distance"),
+    }
+}
+
+// With a mission id: that mission's thresholds; otherwise every mission's
+pub async fn select_geofence_thresholds(
+    db_conn: PgPool,
+    mission_id: Option<i32>,
+) -> Result<Vec<GeofenceThreshold>, sqlx::Error> {
+    let rows = query("
+        SELECT mission_id, zone_index, warning_distance
+        FROM geofence_thresholds
+        WHERE $1::INTEGER IS NULL OR mission_id = $1
+        ORDER BY mission_id, zone_index
+    ")
+    .bind(mission_id)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_geofence_threshold).collect())
+}
+
+// Replace all of a mission's thresholds in one transaction
+pub async fn replace_geofence_thresholds(
+    db_conn: PgPool,
+    mission_id: i32,
+    thresholds: &[GeofenceThreshold],
+    updated_by: &str,
+) -> Result<(), sqlx::Error> {
+    let mut transaction = db_conn.begin().await?;
+    query("DELETE FROM geofence_thresholds WHERE mission_id = $1")
+        .bind(mission_id)
+        .execute(&mut *transaction)
+        .await?;
+    for threshold in thresholds {
+        query("
+            INSERT INTO geofence_thresholds(mission_id, zone_index, warning_distance, updated_by)
+            VALUES ($1, $2, $3, $4)
+        ")
+        .bind(mission_id)
+        .bind(threshold.zone_index.unwrap_or(MISSION_WIDE_ZONE))
+        .bind(threshold.warning_distance_m)
+        .bind(updated_by)
+        .execute(&mut *transaction)
+        .await?;
+    }
+    transaction.commit().await
+}
@@ -0,0 +1,459 @@
+/*
+In-memory MissionStore for unit tests of the mission helpers, with the same row semantics as
+the Postgres schema (cascading deletes, current stage defaults, ordered stage transitions).
+*/
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use async_trait::async_trait;
+
+use crate::missions::types::{GeofenceThreshold, MissionHold, MissionNote};
+use super::MissionStore;
+
+struct StoredMission {
+    name: String,
+    status: String,
+    display_timezone: Option<String>,
+    keep_in_zones: Vec<String>,
+    keep_out_zones: Vec<String>,
+}
+
+struct StoredVehicle {
+    mission_id: i32,
+    name: String,
+    current_stage_id: i32,
+    auto_mode: bool,
+    is_auto: Option<bool>,
+    patient_status: String,
+}
+
+struct StoredStage {
+    vehicle_id: i32,
+    name: String,
+    status: String,
+    search_area: Vec<String>,
+}
+
+// Ids are shared by every table, like separate sequences they only ever increase
+#[derive(Default)]
+struct Tables {
+    last_id: i32,
+    missions: BTreeMap<i32, StoredMission>,
+    vehicles: BTreeMap<i32, StoredVehicle>,
+    stages: BTreeMap<i32, StoredStage>,
+    notes: Vec<MissionNote>,
+    holds: Vec<MissionHold>,
+    geofence_thresholds: Vec<GeofenceThreshold>,
+}
+
+impl Tables {
+    fn next_id(&mut self) -> i32 {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    fn vehicle_id(&self, mission_id: i32, vehicle_name: &str) -> Option<i32> {
+        self.vehicles
+            .iter()
+            .find(|(_, v)| v.mission_id == mission_id && v.name == vehicle_name)
+            .map(|(id, _)| *id)
+    }
+
+    fn delete_mission(&mut self, mission_id: i32) {
+        // Vehicles, their stages, notes, holds and thresholds go with the mission, as with ON
+        // DELETE CASCADE
+        let vehicle_ids: Vec<i32> = self
+            .vehicles
+            .iter()
+            .filter(|(_, v)| v.mission_id == mission_id)
+            .map(|(id, _)| *id)
+            .collect();
+        self.stages.retain(|_, s| !vehicle_ids.contains(&s.vehicle_id));
+        self.vehicles.retain(|_, v| v.mission_id != mission_id);
+        self.notes.retain(|n| n.mission_id != mission_id);
+        self.holds.retain(|h| h.mission_id != mission_id);
+        self.geofence_thresholds.retain(|t| t.mission_id != mission_id);
+        self.missions.remove(&mission_id);
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryMissionStore {
+    tables: Mutex<Tables>,
+}
+
+impl MemoryMissionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Status of a mission as saved, for asserting on status changes
+    pub fn mission_status(&self, mission_id: i32) -> Option<String> {
+        self.tables.lock().unwrap().missions.get(&mission_id).map(|m| m.status.clone())
+    }
+
+    // Status of a stage, for asserting on transitions
+    pub fn stage_status(&self, stage_id: i32) -> Option<String> {
+        self.tables.lock().unwrap().stages.get(&stage_id).map(|s| s.status.clone())
+    }
+
+    // Current stage of a vehicle, -1 when it has none
+    pub fn current_stage(&self, vehicle_id: i32) -> Option<i32> {
+        self.tables.lock().unwrap().vehicles.get(&vehicle_id).map(|v| v.current_stage_id)
+    }
+}
+
+#[async_trait]
+impl MissionStore for MemoryMissionStore {
+    async fn insert_new_mission(&self, mission_name: &str) -> Result<i32, sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        let mission_id = tables.next_id();
+        tables.missions.insert(
+            mission_id,
+            StoredMission {
+                name: mission_name.to_string(),
+                status: "Inactive".to_string(),
+                display_timezone: None,
+                keep_in_zones: vec![],
+                keep_out_zones: vec![],
+            },
+        );
+        for name in ["MRA", "ERU", "MEA"] {
+            let vehicle_id = tables.next_id();
+            tables.vehicles.insert(
+                vehicle_id,
+                StoredVehicle {
+                    mission_id,
+                    name: name.to_string(),
+                    current_stage_id: -1,
+                    auto_mode: false,
+                    is_auto: None,
+                    patient_status: "Unsecured".to_string(),
+                },
+            );
+        }
+        Ok(mission_id)
+    }
+
+    async fn update_mission_name(&self, mission_id: i32, new_mission_name: &str) -> Result<(), sqlx::Error> {
+        if let Some(mission) = self.tables.lock().unwrap().missions.get_mut(&mission_id) {
+            mission.name = new_mission_name.to_string();
+        }
+        Ok(())
+    }
+
+    async fn delete_mission(&self, mission_id: i32) -> Result<(), sqlx::Error> {
+        self.tables.lock().unwrap().delete_mission(mission_id);
+        Ok(())
+    }
+
+    async fn update_mission_status(&self, mission_id: i32, status: &str) -> Result<(), sqlx::Error> {
+        if let Some(mission) = self.tables.lock().unwrap().missions.get_mut(&mission_id) {
+            mission.status = status.to_string();
+        }
+        Ok(())
+    }
+
+    async fn update_mission_timezone(&self, mission_id: i32, timezone: Option<&str>) -> Result<(), sqlx::Error> {
+        if let Some(mission) = self.tables.lock().unwrap().missions.get_mut(&mission_id) {
+            mission.display_timezone = timezone.map(str::to_string);
+        }
+        Ok(())
+    }
+
+    async fn update_zones(
+        &self,
+        mission_id: i32,
+        keep_in_zones: Vec<String>,
+        keep_out_zones: Vec<String>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        let mission = tables.missions.entry(mission_id).or_insert_with(|| StoredMission {
+            name: String::new(),
+            status: "Inactive".to_string(),
+            display_timezone: None,
+            keep_in_zones: vec![],
+            keep_out_zones: vec![],
+        });
+        mission.keep_in_zones = keep_in_zones;
+        mission.keep_out_zones = keep_out_zones;
+        Ok(())
+    }
+
+    async fn select_vehicle_from_mission(&self, mission_id: i32, vehicle_name: String) -> Result<i32, sqlx::Error> {
+        self.tables
+            .lock()
+            .unwrap()
+            .vehicle_id(mission_id, &vehicle_name)
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn update_auto_mode_vehicle(
+        &self,
+        mission_id: i32,
+        vehicle_name: String,
+        is_auto: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        if let Some(vehicle_id) = tables.vehicle_id(mission_id, &vehicle_name) {
+            if let Some(vehicle) = tables.vehicles.get_mut(&vehicle_id) {
+                vehicle.auto_mode = is_auto;
+            }
+        }
+        Ok(())
+    }
+
+    async fn update_patient_status(
+        &self,
+        mission_id: i32,
+        vehicle_name: String,
+        patient_status: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        if let Some(vehicle_id) = tables.vehicle_id(mission_id, &vehicle_name) {
+            if let Some(vehicle) = tables.vehicles.get_mut(&vehicle_id) {
+                vehicle.patient_status = patient_status.to_string();
+            }
+        }
+        Ok(())
+    }
+
+    async fn insert_new_stage(&self, vehicle_id: i32, stage_name: &str) -> Result<i32, sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        if !tables.vehicles.contains_key(&vehicle_id) {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        let stage_id = tables.next_id();
+        tables.stages.insert(
+            stage_id,
+            StoredStage {
+                vehicle_id,
+                name: stage_name.to_string(),
+                status: "Inactive".to_string(),
+                search_area: vec![],
+            },
+        );
+        if let Some(vehicle) = tables.vehicles.get_mut(&vehicle_id) {
+            if vehicle.current_stage_id == -1 {
+                vehicle.current_stage_id = stage_id;
+            }
+        }
+        Ok(stage_id)
+    }
+
+    async fn delete_stage(&self, stage_id: i32) -> Result<(), sqlx::Error> {
+        self.tables.lock().unwrap().stages.remove(&stage_id);
+        Ok(())
+    }
+
+    async fn update_stage_name(&self, stage_id: i32, new_stage_name: &str) -> Result<(), sqlx::Error> {
+        if let Some(stage) = self.tables.lock().unwrap().stages.get_mut(&stage_id) {
+            stage.name = new_stage_name.to_string();
+        }
+        Ok(())
+    }
+
+    async fn update_stage_status(&self, stage_id: i32, status: &str) -> Result<(), sqlx::Error> {
+        if let Some(stage) = self.tables.lock().unwrap().stages.get_mut(&stage_id) {
+            stage.status = status.to_string();
+        }
+        Ok(())
+    }
+
+    async fn update_stage_area(&self, stage_id: i32, area: Vec<String>, vehicle_id: i32) -> Result<i32, sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        let current_stage_id = tables
+            .vehicles
+            .get(&vehicle_id)
+            .map(|v| v.current_stage_id)
+            .ok_or(sqlx::Error::RowNotFound)?;
+        if let Some(stage) = tables.stages.get_mut(&stage_id) {
+            stage.search_area = area;
+            if current_stage_id == stage_id {
+                stage.status = "Active".to_string();
+            }
+        }
+        Ok(current_stage_id)
+    }
+
+    async fn transition_stage(
+        &self,
+        mission_id: i32,
+        vehicle_name: String,
+        current_stage_id: i32,
+    ) -> Result<Option<i32>, sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        let Some(vehicle_id) = tables.vehicle_id(mission_id, &vehicle_name) else {
+            return Ok(None);
+        };
+        // Stage ids in order, as the ORDER BY stage_id query returns them
+        let stage_ids: Vec<i32> = tables
+            .stages
+            .iter()
+            .filter(|(_, s)| s.vehicle_id == vehicle_id)
+            .map(|(id, _)| *id)
+            .collect();
+        let Some(pos) = stage_ids.iter().position(|&id| id == current_stage_id) else {
+            return Ok(None);
+        };
+        let Some(&next_stage_id) = stage_ids.get(pos + 1) else {
+            return Ok(None);
+        };
+        if let Some(vehicle) = tables.vehicles.get_mut(&vehicle_id) {
+            vehicle.current_stage_id = next_stage_id;
+        }
+        for (stage_id, status) in [(current_stage_id, "Complete"), (next_stage_id, "Active")] {
+            if let Some(stage) = tables.stages.get_mut(&stage_id) {
+                stage.status = status.to_string();
+            }
+        }
+        Ok(Some(next_stage_id))
+    }
+
+    async fn insert_mission_note(&self, mission_id: i32, author: &str, text: &str) -> Result<MissionNote, sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        if !tables.missions.contains_key(&mission_id) {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        let note = MissionNote {
+            note_id: tables.next_id(),
+            mission_id,
+            author: author.to_string(),
+            text: text.to_string(),
+            created_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        };
+        tables.notes.push(note.clone());
+        Ok(note)
+    }
+
+    async fn select_mission_notes(&self, mission_id: i32) -> Result<Vec<MissionNote>, sqlx::Error> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables.notes.iter().filter(|n| n.mission_id == mission_id).cloned().collect())
+    }
+
+    async fn insert_mission_hold(&self, mission_id: i32, reason: &str, held_by: &str) -> Result<MissionHold, sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        if !tables.missions.contains_key(&mission_id) {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        let hold = MissionHold {
+            hold_id: tables.next_id(),
+            mission_id,
+            reason: reason.to_string(),
+            held_by: held_by.to_string(),
+            started_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            ended_at: None,
+        };
+        tables.holds.push(hold.clone());
+        Ok(hold)
+    }
+
+    async fn end_mission_hold(&self, hold_id: i32) -> Result<MissionHold, sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        let hold = tables
+            .holds
+            .iter_mut()
+            .find(|h| h.hold_id == hold_id)
+            .ok_or(sqlx::Error::RowNotFound)?;
+        hold.ended_at = Some(chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string());
+        Ok(hold.clone())
+    }
+
+    async fn select_mission_holds(&self, mission_id: i32) -> Result<Vec<MissionHold>, sqlx::Error> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables.holds.iter().filter(|h| h.mission_id == mission_id).cloned().collect())
+    }
+
+    async fn select_geofence_thresholds(&self, mission_id: Option<i32>) -> Result<Vec<GeofenceThreshold>, sqlx::Error> {
+        let tables = self.tables.lock().unwrap();
+        let mut thresholds: Vec<GeofenceThreshold> = tables
+            .geofence_thresholds
+            .iter()
+            .filter(|t| mission_id.is_none_or(|id| t.mission_id == id))
+            .cloned()
+            .collect();
+        thresholds.sort_by_key(|t| (t.mission_id, t.zone_index.unwrap_or(-1)));
+        Ok(thresholds)
+    }
+
+    async fn replace_geofence_thresholds(
+        &self,
+        mission_id: i32,
+        thresholds: &[GeofenceThreshold],
+        _updated_by: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        if !tables.missions.contains_key(&mission_id) {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        tables.geofence_thresholds.retain(|t| t.mission_id != mission_id);
+        tables.geofence_thresholds.extend(thresholds.iter().cloned());
+        Ok(())
+    }
+
+    async fn insert_imported_note(&self, mission_id: i32, note: &MissionNote) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        let note_id = tables.next_id();
+        tables.notes.push(MissionNote { note_id, mission_id, ..note.clone() });
+        Ok(())
+    }
+
+    async fn insert_imported_stage(
+        &self,
+        vehicle_id: i32,
+        stage_name: &str,
+        search_area: Vec<String>,
+        status: &str,
+    ) -> Result<i32, sqlx::Error> {
+        let mut tables = self.tables.lock().unwrap();
+        if !tables.vehicles.contains_key(&vehicle_id) {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        let stage_id = tables.next_id();
+        tables.stages.insert(
+            stage_id,
+            StoredStage {
+                vehicle_id,
+                name: stage_name.to_string(),
+                status: status.to_string(),
+                search_area,
+            },
+        );
+        Ok(stage_id)
+    }
+
+    async fn update_imported_vehicle(
+        &self,
+        vehicle_id: i32,
+        current_stage_id: i32,
+        is_auto: Option<bool>,
+        patient_status: &str,
+    ) -> Result<(), sqlx::Error> {
+        if let Some(vehicle) = self.tables.lock().unwrap().vehicles.get_mut(&vehicle_id) {
+            vehicle.current_stage_id = current_stage_id;
+            vehicle.is_auto = is_auto.or(vehicle.is_auto);
+            vehicle.patient_status = patient_status.to_string();
+        }
+        Ok(())
+    }
+
+    // Timestamps are not kept in memory
+    async fn restore_mission_times(
+        &self,
+        _mission_id: i32,
+        _created_at: Option<&str>,
+        _started_at: Option<&str>,
+        _completed_at: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    async fn restore_stage_times(
+        &self,
+        _stage_id: i32,
+        _created_at: Option<&str>,
+        _started_at: Option<&str>,
+        _completed_at: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+/*
+Storage behind the mission helpers. MissionStore covers every call in sql.rs so the helper
+logic in api/ does not depend on a live database: PgMissionStore forwards to sql.rs, and
+memory::MemoryMissionStore keeps the same tables in memory for unit tests.
+*/
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use crate::missions::sql;
+use crate::missions::types::{GeofenceThreshold, MissionHold, MissionNote};
+
+#[cfg(test)]
+pub mod memory;
+
+#[async_trait]
+pub trait MissionStore: Send + Sync {
+    // Creates the mission with its MEA, ERU and MRA vehicles
+    async fn insert_new_mission(&self, mission_name: &str) -> Result<i32, sqlx::Error>;
+    async fn update_mission_name(&self, mission_id: i32, new_mission_name: &str) -> Result<(), sqlx::Error>;
+    async fn delete_mission(&self, mission_id: i32) -> Result<(), sqlx::Error>;
+    async fn update_mission_status(&self, mission_id: i32, status: &str) -> Result<(), sqlx::Error>;
+    // None shows the mission's times in UTC
+    async fn update_mission_timezone(&self, mission_id: i32, timezone: Option<&str>) -> Result<(), sqlx::Error>;
+    async fn update_zones(
+        &self,
+        mission_id: i32,
+        keep_in_zones: Vec<String>,
+        keep_out_zones: Vec<String>,
+    ) -> Result<(), sqlx::Error>;
+
+    async fn select_vehicle_from_mission(&self, mission_id: i32, vehicle_name: String) -> Result<i32, sqlx::Error>;
+    async fn update_auto_mode_vehicle(
+        &self,
+        mission_id: i32,
+        vehicle_name: String,
+        is_auto: bool,
+    ) -> Result<(), sqlx::Error>;
+    async fn update_patient_status(
+        &self,
+        mission_id: i32,
+        vehicle_name: String,
+        patient_status: &str,
+    ) -> Result<(), sqlx::Error>;
+
+    // Also makes the stage current when the vehicle had none
+    async fn insert_new_stage(&self, vehicle_id: i32, stage_name: &str) -> Result<i32, sqlx::Error>;
+    async fn delete_stage(&self, stage_id: i32) -> Result<(), sqlx::Error>;
+    async fn update_stage_name(&self, stage_id: i32, new_stage_name: &str) -> Result<(), sqlx::Error>;
+    async fn update_stage_status(&self, stage_id: i32, status: &str) -> Result<(), sqlx::Error>;
+    // Returns the vehicle's current stage; that stage becomes Active
+    async fn update_stage_area(&self, stage_id: i32, area: Vec<String>, vehicle_id: i32) -> Result<i32, sqlx::Error>;
+    // Moves the vehicle to the stage after current_stage_id, if there is one
+    async fn transition_stage(
+        &self,
+        mission_id: i32,
+        vehicle_name: String,
+        current_stage_id: i32,
+    ) -> Result<Option<i32>, sqlx::Error>;
+
+    async fn insert_mission_note(&self, mission_id: i32, author: &str, text: &str) -> Result<MissionNote, sqlx::Error>;
+    async fn select_mission_notes(&self, mission_id: i32) -> Result<Vec<MissionNote>, sqlx::Error>;
+
+    async fn insert_mission_hold(&self, mission_id: i32, reason: &str, held_by: &str) -> Result<MissionHold, sqlx::Error>;
+    // Closes the hold now and returns it with its end time
+    async fn end_mission_hold(&self, hold_id: i32) -> Result<MissionHold, sqlx::Error>;
+    async fn select_mission_holds(&self, mission_id: i32) -> Result<Vec<MissionHold>, sqlx::Error>;
+
+    // With a mission id: that mission's keep-out warning thresholds; otherwise every mission's
+    async fn select_geofence_thresholds(&self, mission_id: Option<i32>) -> Result<Vec<GeofenceThreshold>, sqlx::Error>;
+    async fn replace_geofence_thresholds(
+        &self,
+        mission_id: i32,
+        thresholds: &[GeofenceThreshold],
+        updated_by: &str,
+    ) -> Result<(), sqlx::Error>;
+
+    // Mission bundle import
+    async fn insert_imported_note(&self, mission_id: i32, note: &MissionNote) -> Result<(), sqlx::Error>;
+    async fn insert_imported_stage(
+        &self,
+        vehicle_id: i32,
+        stage_name: &str,
+        search_area: Vec<String>,
+        status: &str,
+    ) -> Result<i32, sqlx::Error>;
+    async fn update_imported_vehicle(
+        &self,
+        vehicle_id: i32,
+        current_stage_id: i32,
+        is_auto: Option<bool>,
+        patient_status: &str,
+    ) -> Result<(), sqlx::Error>;
+    // Keep the lifecycle timestamps of the exporting station rather than the import time
+    async fn restore_mission_times(
+        &self,
+        mission_id: i32,
+        created_at: Option<&str>,
+        started_at: Option<&str>,
+        completed_at: Option<&str>,
+    ) -> Result<(), sqlx::Error>;
+    async fn restore_stage_times(
+        &self,
+        stage_id: i32,
+        created_at: Option<&str>,
+        started_at: Option<&str>,
+        completed_at: Option<&str>,
+    ) -> Result<(), sqlx::Error>;
+}
+
+pub struct PgMissionStore {
+    db: PgPool,
+}
+
+impl PgMissionStore {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl MissionStore for PgMissionStore {
+    async fn insert_new_mission(&self, mission_name: &str) -> Result<i32, sqlx::Error> {
+        sql::insert_new_mission(self.db.clone(), mission_name).await
+    }
+
+    async fn update_mission_name(&self, mission_id: i32, new_mission_name: &str) -> Result<(), sqlx::Error> {
+        sql::update_mission_name(self.db.clone(), mission_id, new_mission_name).await
+    }
+
+    async fn delete_mission(&self, mission_id: i32) -> Result<(), sqlx::Error> {
+        sql::delete_mission(self.db.clone(), mission_id).await
+    }
+
+    async fn update_mission_status(&self, mission_id: i32, status: &str) -> Result<(), sqlx::Error> {
+        sql::update_mission_status(self.db.clone(), mission_id, status).await
+    }
+
+    async fn update_mission_timezone(&self, mission_id: i32, timezone: Option<&str>) -> Result<(), sqlx::Error> {
+        sql::update_mission_timezone(self.db.clone(), mission_id, timezone).await
+    }
+
+    async fn update_zones(
+        &self,
+        mission_id: i32,
+        keep_in_zones: Vec<String>,
+        keep_out_zones: Vec<String>,
+    ) -> Result<(), sqlx::Error> {
+        sql::update_zones(self.db.clone(), mission_id, keep_in_zones, keep_out_zones).await
+    }
+
+    async fn select_vehicle_from_mission(&self, mission_id: i32, vehicle_name: String) -> Result<i32, sqlx::Error> {
+        sql::select_vehicle_from_mission(self.db.clone(), mission_id, vehicle_name).await
+    }
+
+    async fn update_auto_mode_vehicle(
+        &self,
+        mission_id: i32,
+        vehicle_name: String,
+        is_auto: bool,
+    ) -> Result<(), sqlx::Error> {
+        sql::update_auto_mode_vehicle(self.db.clone(), mission_id, vehicle_name, is_auto).await
+    }
+
+    async fn update_patient_status(
+        &self,
+        mission_id: i32,
+        vehicle_name: String,
+        patient_status: &str,
+    ) -> Result<(), sqlx::Error> {
+        sql::update_patient_status(self.db.clone(), mission_id, vehicle_name, patient_status).await
+    }
+
+    async fn insert_new_stage(&self, vehicle_id: i32, stage_name: &str) -> Result<i32, sqlx::Error> {
+        sql::insert_new_stage(self.db.clone(), vehicle_id, stage_name).await
+    }
+
+    async fn delete_stage(&self, stage_id: i32) -> Result<(), sqlx::Error> {
+        sql::delete_stage(self.db.clone(), stage_id).await
+    }
+
+    async fn update_stage_name(&self, stage_id: i32, new_stage_name: &str) -> Result<(), sqlx::Error> {
+        sql::update_stage_name(self.db.clone(), stage_id, new_stage_name).await
+    }
+
+    async fn update_stage_status(&self, stage_id: i32, status: &str) -> Result<(), sqlx::Error> {
+        sql::update_stage_status(self.db.clone(), stage_id, status).await
+    }
+
+    async fn update_stage_area(&self, stage_id: i32, area: Vec<String>, vehicle_id: i32) -> Result<i32, sqlx::Error> {
+        sql::update_stage_area(self.db.clone(), stage_id, area, vehicle_id).await
+    }
+
+    async fn transition_stage(
+        &self,
+        mission_id: i32,
+        vehicle_name: String,
+        current_stage_id: i32,
+    ) -> Result<Option<i32>, sqlx::Error> {
+        sql::transition_stage(self.db.clone(), mission_id, vehicle_name, current_stage_id).await
+    }
+
+    async fn insert_mission_note(&self, mission_id: i32, author: &str, text: &str) -> Result<MissionNote, sqlx::Error> {
+        sql::insert_mission_note(self.db.clone(), mission_id, author, text).await
+    }
+
+    async fn select_mission_notes(&self, mission_id: i32) -> Result<Vec<MissionNote>, sqlx::Error> {
+        sql::select_mission_notes(self.db.clone(), mission_id).await
+    }
+
+    async fn insert_mission_hold(&self, mission_id: i32, reason: &str, held_by: &str) -> Result<MissionHold, sqlx::Error> {
+        sql::insert_mission_hold(self.db.clone(), mission_id, reason, held_by).await
+    }
+
+    async fn end_mission_hold(&self, hold_id: i32) -> Result<MissionHold, sqlx::Error> {
+        sql::end_mission_hold(self.db.clone(), hold_id).await
+    }
+
+    async fn select_mission_holds(&self, mission_id: i32) -> Result<Vec<MissionHold>, sqlx::Error> {
+        sql::select_mission_holds(self.db.clone(), mission_id).await
+    }
+
+    async fn select_geofence_thresholds(&self, mission_id: Option<i32>) -> Result<Vec<GeofenceThreshold>, sqlx::Error> {
+        sql::select_geofence_thresholds(self.db.clone(), mission_id).await
+    }
+
+    async fn replace_geofence_thresholds(
+        &self,
+        mission_id: i32,
+        thresholds: &[GeofenceThreshold],
+        updated_by: &str,
+    ) -> Result<(), sqlx::Error> {
+        sql::replace_geofence_thresholds(self.db.clone(), mission_id, thresholds, updated_by).await
+    }
+
+    async fn insert_imported_note(&self, mission_id: i32, note: &MissionNote) -> Result<(), sqlx::Error> {
+        sql::insert_imported_note(self.db.clone(), mission_id, note).await
+    }
+
+    async fn insert_imported_stage(
+        &self,
+        vehicle_id: i32,
+        stage_name: &str,
+        search_area: Vec<String>,
+        status: &str,
+    ) -> Result<i32, sqlx::Error> {
+        sql::insert_imported_stage(self.db.clone(), vehicle_id, stage_name, search_area, status).await
+    }
+
+    async fn update_imported_vehicle(
+        &self,
+        vehicle_id: i32,
+        current_stage_id: i32,
+        is_auto: Option<bool>,
+        patient_status: &str,
+    ) -> Result<(), sqlx::Error> {
+        sql::update_imported_vehicle(self.db.clone(), vehicle_id, current_stage_id, is_auto, patient_status).await
+    }
+
+    async fn restore_mission_times(
+        &self,
+        mission_id: i32,
+        created_at: Option<&str>,
+        started_at: Option<&str>,
+        completed_at: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sql::restore_times(self.db.clone(), "missions", "mission_id", mission_id, created_at, started_at, completed_at).await
+    }
+
+    async fn restore_stage_times(
+        &self,
+        stage_id: i32,
+        created_at: Option<&str>,
+        started_at: Option<&str>,
+        completed_at: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sql::restore_times(self.db.clone(), "stages", "stage_id", stage_id, created_at, started_at, completed_at).await
+    }
+}
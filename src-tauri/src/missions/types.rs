@@ -2,6 +2,7 @@
 Define all mission-related data types shared with the frontend (missions, vehicles, stages, zones, enums, coordinate types).
 */
 use std::{num::ParseFloatError, str::FromStr};
+use crate::commands::types::EmergencyStopReport;
 
 #[taurpc::ipc_type]
 #[derive(Debug)]
@@ -11,25 +12,94 @@ pub struct MissionsStruct {
 }
 
 #[taurpc::ipc_type]
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct MissionStruct {
     pub mission_name: String,
     pub mission_id: i32,
     pub mission_status: MissionStageStatusEnum,
     pub vehicles: VehiclesStruct,
     pub zones: ZonesStruct,
+    // UTC, like every stored timestamp; None for missions created before they were recorded
+    pub created_at: Option<String>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    // IANA time zone reports and the UI show the mission's times in; None shows UTC
+    pub display_timezone: Option<String>,
 }
 
+// Typed mission API errors; the frontend gets e.g. { NotFound: { mission_id: 3 } }
 #[derive(Debug, serde::Deserialize, serde::Serialize, Clone, specta::Type)]
+pub enum MissionError {
+    // Deleted or never existed, e.g. a request racing a deletion
+    NotFound { mission_id: i32 },
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone, PartialEq, specta::Type)]
 pub enum MissionStageStatusEnum {
     Active,
     Inactive,
     Complete,
     Failed,
+    Paused,
+}
+
+impl MissionStageStatusEnum {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MissionStageStatusEnum::Active => "Active",
+            MissionStageStatusEnum::Inactive => "Inactive",
+            MissionStageStatusEnum::Complete => "Complete",
+            MissionStageStatusEnum::Failed => "Failed",
+            MissionStageStatusEnum::Paused => "Paused",
+        }
+    }
+}
+
+// Current time as stored timestamps are returned, e.g. 2024-05-01T14:03:27Z
+pub fn utc_timestamp() -> String {
+    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+// Same rules as the stamp_status_change trigger on the missions and stages tables: the first
+// activation sets started_at, completing or failing sets completed_at, and becoming active
+// again clears it
+fn stamp_status_change(
+    previous: &MissionStageStatusEnum,
+    status: &MissionStageStatusEnum,
+    started_at: &mut Option<String>,
+    completed_at: &mut Option<String>,
+) {
+    if previous == status {
+        return;
+    }
+    match status {
+        MissionStageStatusEnum::Active => {
+            started_at.get_or_insert_with(utc_timestamp);
+            *completed_at = None;
+        }
+        MissionStageStatusEnum::Complete | MissionStageStatusEnum::Failed => {
+            *completed_at = Some(utc_timestamp());
+        }
+        _ => {}
+    }
+}
+
+impl MissionStruct {
+    pub fn set_status(&mut self, status: MissionStageStatusEnum) {
+        stamp_status_change(&self.mission_status, &status, &mut self.started_at, &mut self.completed_at);
+        self.mission_status = status;
+    }
+}
+
+impl StageStruct {
+    pub fn set_status(&mut self, status: MissionStageStatusEnum) {
+        stamp_status_change(&self.stage_status, &status, &mut self.started_at, &mut self.completed_at);
+        self.stage_status = status;
+    }
 }
 
 #[taurpc::ipc_type]
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct VehicleStruct {
     pub vehicle_name: VehicleEnum,
     pub current_stage: i32,
@@ -39,7 +109,7 @@ pub struct VehicleStruct {
 }
 
 #[taurpc::ipc_type]
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[allow(non_snake_case)]
 // create a VehiclesStruct for each vehicle
 // since each mission requires all 3 vehicles to exist
@@ -50,7 +120,7 @@ pub struct VehiclesStruct {
     pub MRA: VehicleStruct,
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize, Clone, specta::Type)]
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone, PartialEq, specta::Type)]
 pub enum VehicleEnum {
     MEA,
     ERU,
@@ -67,25 +137,37 @@ impl VehicleEnum {
     }
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize, Clone, specta::Type)]
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone, PartialEq, specta::Type)]
 pub enum PatientStatusEnum {
     Secured,
     Unsecured,
 }
 
+impl PatientStatusEnum {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PatientStatusEnum::Secured => "Secured",
+            PatientStatusEnum::Unsecured => "Unsecured",
+        }
+    }
+}
+
 #[taurpc::ipc_type]
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct StageStruct {
     pub stage_name: String,
     pub stage_id: i32,
     pub stage_status: MissionStageStatusEnum,
     pub search_area: GeofenceType,
+    pub created_at: Option<String>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
 }
 
 
 // TODO: Change ZoneType and ZonesStruct to match
 #[taurpc::ipc_type]
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct ZonesStruct {
     pub keep_in_zones: Vec<GeofenceType>,
     pub keep_out_zones: Vec<GeofenceType>,
@@ -98,7 +180,7 @@ pub enum ZoneType {
 }
 
 #[taurpc::ipc_type]
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct GeoCoordinateStruct {
     pub lat: f64,
     pub long: f64,
@@ -122,3 +204,260 @@ impl FromStr for GeoCoordinateStruct {
 
 
 pub type GeofenceType = Vec<GeoCoordinateStruct>;
+
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct EmergencyStopEvent {
+    pub mission_id: i32, // -1 if no mission was active
+    pub mission_paused: bool,
+    pub report: EmergencyStopReport,
+}
+
+// Patient status of a vehicle in the active mission, set from its payload's patient sensor
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct PatientStatusChange {
+    pub mission_id: i32,
+    pub vehicle_name: VehicleEnum,
+    pub previous_status: Option<PatientStatusEnum>,
+    pub patient_status: PatientStatusEnum,
+    pub changed_at: String,
+}
+
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct MissionNote {
+    pub note_id: i32,
+    pub mission_id: i32,
+    pub author: String,
+    pub text: String,
+    pub created_at: String,
+}
+
+// Mission-wide hold, e.g. for weather: vehicles keep loitering while stage transitions and
+// non-emergency commands are blocked
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct MissionHold {
+    pub hold_id: i32,
+    pub mission_id: i32,
+    pub reason: String,
+    pub held_by: String,
+    pub started_at: String,
+    // None while the hold is in effect
+    pub ended_at: Option<String>,
+}
+
+// Distance from a keep-out zone at which a vehicle is warned. Without a zone index it applies
+// to every keep-out zone of the mission that has no threshold of its own
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct GeofenceThreshold {
+    pub mission_id: i32,
+    // Index into the mission's keep_out_zones
+    pub zone_index: Option<i32>,
+    pub warning_distance_m: f64,
+}
+
+// Self-contained mission record for debriefs: mission plan plus the operator log
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct MissionExport {
+    pub mission: MissionStruct,
+    pub notes: Vec<MissionNote>,
+    // Hold periods, oldest first; missing from bundles exported before holds existed
+    #[serde(default)]
+    pub holds: Vec<MissionHold>,
+    pub exported_at: String,
+}
+
+// Share of a stage's search area overflown by its vehicle, with the regions still to search
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct StageCoverage {
+    pub mission_id: i32,
+    pub vehicle_name: VehicleEnum,
+    pub stage_id: i32,
+    pub stage_name: String,
+    pub coverage_percent: f64,
+    pub area_m2: f64,
+    pub uncovered_regions: Vec<GeofenceType>,
+}
+
+// Per-vehicle figures of a mission's KPIs
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct VehicleKpis {
+    pub vehicle_id: String,
+    // Time airborne with telemetry, not counting gaps longer than the heartbeat timeout
+    pub airtime_secs: f64,
+    pub commands_total: i32,
+    pub commands_sent: i32,
+    pub commands_failed: i32,
+}
+
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct MissionKpis {
+    pub mission_id: i32,
+    // First and last telemetry recorded for the mission
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub time_to_first_detection_secs: Option<f64>,
+    // From start to end, without the time spent on hold
+    pub search_time_secs: Option<f64>,
+    // Mean over the targets that were both secured and delivered
+    pub secured_to_delivered_secs: Option<f64>,
+    pub vehicles: Vec<VehicleKpis>,
+    pub total_commands: i32,
+    pub computed_at: String,
+    // Stored once the mission finished; live figures otherwise
+    pub persisted: bool,
+}
+
+// One grid cell of a track heatmap
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct HeatmapCell {
+    // Counted from the south-west corner of the track's bounding box
+    pub row: u32,
+    pub col: u32,
+    pub center: GeoCoordinateStruct,
+    pub samples: u32,
+    // Share of the busiest cell's samples, 0 to 1
+    pub weight: f64,
+}
+
+// Where a mission's vehicles spent their time, for the replay and report views
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct TrackHeatmap {
+    pub mission_id: i32,
+    // None when every vehicle's track is binned together
+    pub vehicle_name: Option<VehicleEnum>,
+    // May be wider than requested so the grid stays bounded
+    pub cell_size_m: f64,
+    pub max_samples: u32,
+    // Cells without samples are left out
+    pub cells: Vec<HeatmapCell>,
+}
+
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct VehicleVertexBudget {
+    pub vehicle_name: VehicleEnum,
+    pub max_search_area_vertices: i32,
+}
+
+// Most vertices a zone or search area may have, from the vehicles' capability model
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct VertexBudget {
+    // Zones go to every vehicle, so this is the smallest vehicle budget
+    pub max_zone_vertices: i32,
+    pub vehicles: Vec<VehicleVertexBudget>,
+}
+
+// Zone or search area that starting the mission would send
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct DryRunTransmission {
+    // "ALL" for zones, the stage's vehicle for search areas
+    pub vehicle_id: String,
+    pub command_id: i32,
+    pub zone_key: String,
+    pub vertices: i32,
+    // More than one when the polygon exceeds max_zone_vertices and goes out in chunks
+    pub messages: i32,
+}
+
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct DryRunStage {
+    pub vehicle_name: VehicleEnum,
+    pub stage_id: i32,
+    pub stage_name: String,
+    // None when the search area is not a polygon yet
+    pub area_m2: Option<f64>,
+    // From the centroid of the vehicle's previous stage; 0 for its first
+    pub transit_m: f64,
+    // Transit plus a lawnmower sweep of the area at the simulated speed; None without either
+    pub estimated_secs: Option<f64>,
+}
+
+// What start_mission would do, without sending anything or changing any state
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct MissionDryRun {
+    pub mission_id: i32,
+    pub transmissions: Vec<DryRunTransmission>,
+    // Checks that would refuse the start, or make a transmission fail
+    pub failures: Vec<String>,
+    // Would not stop the mission from starting
+    pub warnings: Vec<String>,
+    pub stages: Vec<DryRunStage>,
+    // Vehicles fly their stages in parallel, so the slowest one's total
+    pub estimated_duration_secs: Option<f64>,
+    pub checked_at: String,
+}
+
+// Stage that receives one part of a partitioned search area
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct StageAssignment {
+    pub vehicle_name: VehicleEnum,
+    pub stage_id: i32,
+}
+
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct SearchPartition {
+    pub vehicle_name: VehicleEnum,
+    pub stage_id: i32,
+    pub area: GeofenceType,
+    pub area_m2: f64,
+}
+
+// Command-palette search result; zones have no names, so they match on labels like "Keep-in zone 2"
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone, specta::Type)]
+#[serde(tag = "kind")]
+pub enum MissionSearchHit {
+    Mission {
+        mission_id: i32,
+        mission_name: String,
+    },
+    Stage {
+        mission_id: i32,
+        mission_name: String,
+        vehicle_name: VehicleEnum,
+        stage_id: i32,
+        stage_name: String,
+    },
+    Zone {
+        mission_id: i32,
+        mission_name: String,
+        zone_type: ZoneType,
+        zone_index: i32,
+        label: String,
+    },
+}
+
+// One step of starting a mission, e.g. activate_mission, keep_out_1 or search_area_mea
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct MissionStartStep {
+    pub step: String,
+    pub done: bool,
+    // Why the last attempt failed; None once it succeeded
+    pub error: Option<String>,
+}
+
+// Steps of the mission's last start_mission call, in the order they ran; calling start_mission
+// again retries only the steps that aren't done
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct MissionStartProgress {
+    pub mission_id: i32,
+    pub steps: Vec<MissionStartStep>,
+    pub complete: bool,
+}
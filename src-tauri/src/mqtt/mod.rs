@@ -0,0 +1,142 @@
+/*
+MQTT transport for vehicles that do not speak AMQP. Vehicles listed in `mqtt_vehicles` publish
+their telemetry to `mqtt_telemetry_topic` and receive commands on `mqtt_command_topic`, in the
+same protocol as over AMQP (see protocols); "{vehicle}" in either topic is replaced by the
+vehicle id. Their telemetry runs through the normal processing pipeline, so alerts, storage and
+frontend events behave the same for both transports. All other vehicles stay on AMQP.
+*/
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use reqwest::Url;
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+
+use crate::commands::commands::CommandsStruct;
+use crate::config;
+use crate::health::ConsumerState;
+use crate::logs;
+use crate::protocols;
+use crate::supervisor::{self, RestartPolicy};
+use crate::telemetry::rabbitmq::RabbitMQAPIImpl;
+
+const KEEP_ALIVE: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    // Client of the running bridge; None while it is stopped
+    static ref CLIENT: RwLock<Option<AsyncClient>> = RwLock::new(None);
+}
+
+static CONNECTED: AtomicBool = AtomicBool::new(false);
+
+// Whether a vehicle's telemetry and commands go over MQTT instead of AMQP
+pub fn handles(vehicle_id: &str) -> bool {
+    let config = config::get();
+    !config.mqtt_url.is_empty()
+        && config.mqtt_vehicles.iter().any(|v| v.eq_ignore_ascii_case(vehicle_id))
+}
+
+fn topic(template: &str, vehicle_id: &str) -> String {
+    template.replace("{vehicle}", &vehicle_id.to_lowercase())
+}
+
+// Broker options from an mqtt://[user:password@]host[:port] URL
+pub fn options(url: &str) -> Result<MqttOptions, String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid MQTT URL {}: {}", url, e))?;
+    if url.scheme() != "mqtt" {
+        return Err(format!("Unsupported MQTT URL scheme: {}", url.scheme()));
+    }
+    let host = url.host_str().ok_or("MQTT URL has no host")?;
+    let client_id = format!("ngcp-gcs-{}", std::process::id());
+    let mut options = MqttOptions::new(client_id, host, url.port().unwrap_or(1883));
+    options.set_keep_alive(KEEP_ALIVE);
+    if !url.username().is_empty() {
+        options.set_credentials(url.username(), url.password().unwrap_or(""));
+    }
+    Ok(options)
+}
+
+pub async fn publish_command(command: &CommandsStruct) -> Result<(), String> {
+    let client = CLIENT.read().unwrap().clone();
+    let client = client
+        .filter(|_| CONNECTED.load(Ordering::SeqCst))
+        .ok_or("MQTT broker not connected")?;
+    let payload = protocols::encode_command(command)?;
+    let topic = topic(&config::get().mqtt_command_topic, &command.vehicle_id);
+    client
+        .publish(&topic, QoS::AtLeastOnce, false, payload)
+        .await
+        .map_err(|e| format!("Failed to publish to {}: {}", topic, e))
+}
+
+async fn set_state(telemetry: &RabbitMQAPIImpl, topics: &[(String, String)], state: ConsumerState, error: Option<String>) {
+    for (_, topic) in topics {
+        telemetry
+            .set_consumer_state(&format!("mqtt:{}", topic), state, error.clone())
+            .await;
+    }
+}
+
+async fn handle_publish(telemetry: &RabbitMQAPIImpl, vehicle_id: &str, payload: &[u8]) {
+    let received = Instant::now();
+    match protocols::parse_telemetry(Some(vehicle_id), payload) {
+        Ok(data) if data.vehicle_id.eq_ignore_ascii_case(vehicle_id) => {
+            telemetry.ingest_telemetry(data, received).await;
+        }
+        Ok(data) => logs::warn(
+            "mqtt",
+            format!("Ignoring telemetry for {} on the {} topic", data.vehicle_id, vehicle_id),
+        ),
+        Err(e) => logs::warn("mqtt", format!("Failed to parse telemetry from {}: {}", vehicle_id, e)),
+    }
+}
+
+async fn run(telemetry: RabbitMQAPIImpl) -> Result<(), String> {
+    let config = config::get();
+    let topics: Vec<(String, String)> = config
+        .mqtt_vehicles
+        .iter()
+        .map(|vehicle_id| (vehicle_id.to_lowercase(), topic(&config.mqtt_telemetry_topic, vehicle_id)))
+        .collect();
+    set_state(&telemetry, &topics, ConsumerState::Starting, None).await;
+
+    let (client, mut event_loop) = AsyncClient::new(options(&config.mqtt_url)?, 64);
+    *CLIENT.write().unwrap() = Some(client.clone());
+
+    let error = 'events: loop {
+        match event_loop.poll().await {
+            // Subscriptions do not survive a reconnect with a clean session
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                logs::info("mqtt", format!("Connected to MQTT broker at {}", config.mqtt_url));
+                for (_, topic) in &topics {
+                    if let Err(e) = client.try_subscribe(topic, QoS::AtLeastOnce) {
+                        break 'events format!("Failed to subscribe to {}: {}", topic, e);
+                    }
+                }
+                CONNECTED.store(true, Ordering::SeqCst);
+                set_state(&telemetry, &topics, ConsumerState::Running, None).await;
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                if let Some((vehicle_id, _)) = topics.iter().find(|(_, topic)| *topic == publish.topic) {
+                    handle_publish(&telemetry, vehicle_id, &publish.payload).await;
+                }
+            }
+            Ok(_) => {}
+            Err(e) => break format!("MQTT connection failed: {}", e),
+        }
+    };
+
+    CONNECTED.store(false, Ordering::SeqCst);
+    *CLIENT.write().unwrap() = None;
+    set_state(&telemetry, &topics, ConsumerState::Stopped, Some(error.clone())).await;
+    Err(error)
+}
+
+// Start the bridge when an MQTT broker and vehicles are configured
+pub fn start(telemetry: RabbitMQAPIImpl) {
+    let config = config::get();
+    if config.mqtt_url.is_empty() || config.mqtt_vehicles.is_empty() {
+        return;
+    }
+    supervisor::spawn("mqtt_bridge", RestartPolicy::Always, move || run(telemetry.clone()));
+}
@@ -0,0 +1,61 @@
+/*
+Define the notifications API: list and acknowledge persisted alerts, and subscribe to new ones.
+*/
+use tauri::{Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, current_operator, OperatorRole};
+use super::sql::{acknowledge_notification, select_notifications};
+use super::{db, Notification};
+
+const DEFAULT_NOTIFICATION_LIMIT: u32 = 100;
+
+#[procedures(
+    event_trigger = NotificationsEventTrigger,
+    export_to = "../src/lib/bindings.ts",
+    path = "notifications"
+)]
+pub trait NotificationsApi {
+    #[taurpc(event)]
+    async fn on_notification(notification: Notification);
+
+    async fn list_notifications(
+        unacknowledged_only: bool,
+        limit: Option<u32>,
+    ) -> Result<Vec<Notification>, String>;
+    async fn ack_notification(
+        window: Window<impl Runtime>,
+        notification_id: i32,
+    ) -> Result<Notification, String>;
+}
+
+#[derive(Clone, Default)]
+pub struct NotificationsApiImpl;
+
+#[resolvers]
+impl NotificationsApi for NotificationsApiImpl {
+    async fn list_notifications(
+        self,
+        unacknowledged_only: bool,
+        limit: Option<u32>,
+    ) -> Result<Vec<Notification>, String> {
+        let limit = limit.unwrap_or(DEFAULT_NOTIFICATION_LIMIT);
+        select_notifications(db(), unacknowledged_only, limit as i64)
+            .await
+            .map_err(|e| format!("Failed to load notifications: {}", e))
+    }
+
+    async fn ack_notification(
+        self,
+        window: Window<impl Runtime>,
+        notification_id: i32,
+    ) -> Result<Notification, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            acknowledge_notification(db(), notification_id, &current_operator())
+                .await
+                .map_err(|e| format!("Failed to acknowledge notification: {}", e))?
+                .ok_or(format!("Notification {} not found", notification_id))
+        })
+        .await
+    }
+}
@@ -0,0 +1,179 @@
+/*
+Notification center. Operator-facing alerts (geofence, low battery, disconnects, command
+failures) are persisted with severity and acknowledgment state and streamed to the frontend
+through the on_notification event. Conditions reported on every telemetry message are
+raised once when they start and cleared when they end, so a vehicle sitting at low battery
+produces one notification rather than one per message.
+*/
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::PgPool;
+use tauri::AppHandle;
+use taurpc::Windows;
+
+use crate::exports::event_log::{self, EventKind};
+use crate::init_db::lazy_pool;
+use crate::logs;
+use crate::window_routing::{self, EventChannel};
+
+pub mod api;
+pub mod sql;
+
+pub use api::{NotificationsApi, NotificationsApiImpl, NotificationsEventTrigger};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Type)]
+pub enum NotificationSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Type)]
+pub enum NotificationCategory {
+    Geofence,
+    LowBattery,
+    WeakSignal,
+    Disconnect,
+    CommandFailure,
+    TargetDetected,
+    // vehicle_id holds the pair, e.g. "mea-mra"
+    Separation,
+    TerrainClearance,
+    // Mission hold recommendations from weather limits
+    Weather,
+    // vehicle_id holds the aircraft and what it approaches, e.g. "a1b2c3-eru" or "a1b2c3-keep-in"
+    AirspaceIntrusion,
+    // Concurrent edits of a mission on two GCS stations
+    MissionSync,
+    // A vehicle reported that it armed or disarmed
+    Arming,
+    // The lost-link failsafe ran for a vehicle that did not reconnect in time
+    LostLink,
+    // vehicle_id holds the vehicle outside its band, or the converging pair, e.g. "eru-mra"
+    AltitudeBand,
+    // vehicle_id holds the AMQP queue whose consumer stopped and is being recreated
+    TelemetryConsumer,
+    // A vehicle's signal strength fell steadily over the last minute
+    SignalTrend,
+    // Battery past its rated cycles in a vehicle of a starting mission
+    Maintenance,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct Notification {
+    pub id: i32,
+    pub category: NotificationCategory,
+    pub severity: NotificationSeverity,
+    pub vehicle_id: Option<String>,
+    pub message: String,
+    pub created_at: String,
+    pub acknowledged: bool,
+    pub acknowledged_by: Option<String>,
+    pub acknowledged_at: Option<String>,
+}
+
+lazy_static! {
+    // Conditions currently raised, keyed by category and lowercase vehicle id
+    static ref ACTIVE: Mutex<HashSet<(NotificationCategory, String)>> = Mutex::new(HashSet::new());
+    static ref DB: PgPool = lazy_pool(2);
+}
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+// Start streaming notifications to the frontend
+pub fn set_app_handle(app_handle: AppHandle) {
+    let _ = APP_HANDLE.set(app_handle);
+}
+
+pub(crate) fn db() -> PgPool {
+    DB.clone()
+}
+
+// Persist and broadcast a notification in the background
+pub fn notify(
+    category: NotificationCategory,
+    severity: NotificationSeverity,
+    vehicle_id: Option<&str>,
+    message: impl Into<String>,
+) {
+    let message = message.into();
+    let vehicle_id = vehicle_id.map(|v| v.to_lowercase());
+    let summary = format!("{:?} ({:?}): {}", category, severity, message);
+    match severity {
+        NotificationSeverity::Info => logs::info("notifications", summary),
+        _ => logs::warn("notifications", summary),
+    }
+    event_log::record(
+        EventKind::Alert,
+        vehicle_id.as_deref(),
+        &format!("{:?} ({:?})", category, severity),
+        &message,
+    );
+
+    tauri::async_runtime::spawn(async move {
+        let notification =
+            match sql::insert_notification(db(), category, severity, vehicle_id.as_deref(), &message).await {
+                Ok(notification) => notification,
+                Err(e) => {
+                    logs::error("notifications", format!("Failed to save notification: {}", e));
+                    return;
+                }
+            };
+        if let Some(app_handle) = APP_HANDLE.get() {
+            // Critical alerts reach every window, whatever it subscribed to
+            let scope = if notification.severity == NotificationSeverity::Critical {
+                Windows::All
+            } else {
+                window_routing::scope(app_handle, EventChannel::Notifications)
+            };
+            if let Err(e) = NotificationsEventTrigger::new(app_handle.clone())
+                .send_to(scope)
+                .on_notification(notification)
+            {
+                logs::error("notifications", format!("Failed to emit notification: {}", e));
+            }
+        }
+    });
+}
+
+// Notify only when a vehicle enters the condition; repeated calls are ignored until cleared
+pub fn raise(
+    category: NotificationCategory,
+    severity: NotificationSeverity,
+    vehicle_id: &str,
+    message: impl Into<String>,
+) {
+    let newly_active = ACTIVE
+        .lock()
+        .unwrap()
+        .insert((category, vehicle_id.to_lowercase()));
+    if newly_active {
+        notify(category, severity, Some(vehicle_id), message);
+    }
+}
+
+// The vehicle left the condition; the next raise() notifies again
+pub fn clear(category: NotificationCategory, vehicle_id: &str) {
+    ACTIVE
+        .lock()
+        .unwrap()
+        .remove(&(category, vehicle_id.to_lowercase()));
+}
+
+// Raise or clear depending on whether the condition currently holds
+pub fn track(
+    condition: bool,
+    category: NotificationCategory,
+    severity: NotificationSeverity,
+    vehicle_id: &str,
+    message: impl FnOnce() -> String,
+) {
+    if condition {
+        raise(category, severity, vehicle_id, message());
+    } else {
+        clear(category, vehicle_id);
+    }
+}
@@ -0,0 +1,190 @@
+/*
+Define all notification database functions. Category and severity are stored by name.
+*/
+use sqlx::postgres::PgRow;
+use sqlx::{query, PgPool, Row};
+
+use super::{Notification, NotificationCategory, NotificationSeverity};
+
+const NOTIFICATION_COLUMNS: &str = "
+    notification_id, category, severity, vehicle_id, message, acknowledged_by,
+    to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS created_at,
+    to_char(acknowledged_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS acknowledged_at
+";
+
+fn parse_category(name: &str) -> NotificationCategory {
+    match name {
+        "Geofence" => NotificationCategory::Geofence,
+        "LowBattery" => NotificationCategory::LowBattery,
+        "WeakSignal" => NotificationCategory::WeakSignal,
+        "Disconnect" => NotificationCategory::Disconnect,
+        "TargetDetected" => NotificationCategory::TargetDetected,
+        "Separation" => NotificationCategory::Separation,
+        "TerrainClearance" => NotificationCategory::TerrainClearance,
+        "Weather" => NotificationCategory::Weather,
+        "AirspaceIntrusion" => NotificationCategory::AirspaceIntrusion,
+        "MissionSync" => NotificationCategory::MissionSync,
+        "Arming" => NotificationCategory::Arming,
+        "AltitudeBand" => NotificationCategory::AltitudeBand,
+        "LostLink" => NotificationCategory::LostLink,
+        "TelemetryConsumer" => NotificationCategory::TelemetryConsumer,
+        "SignalTrend" => NotificationCategory::SignalTrend,
+        "Maintenance" => NotificationCategory::Maintenance,
+        _ => NotificationCategory::CommandFailure,
+    }
+}
+
+fn parse_severity(name: &str) -> NotificationSeverity {
+    match name {
+        "Critical" => NotificationSeverity::Critical,
+        "Warning" => NotificationSeverity::Warning,
+        _ => NotificationSeverity::Info,
+    }
+}
+
+fn to_notification(row: PgRow) -> Notification {
+    let acknowledged_at: Option<String> = row.get("acknowledged_at");
+    Notification {
+        id: row.get("notification_id"),
+        category: parse_category(row.get("category")),
+        severity: parse_severity(row.get("severity")),
+        vehicle_id: row.get("vehicle_id"),
+        message: row.get("message"),
+        created_at: row.get("created_at"),
+        acknowledged: acknowledged_at.is_some(),
+        acknowledged_by: row.get("acknowledged_by"),
+        acknowledged_at,
+    }
+}
+
+pub async fn insert_notification(
+    db_conn: PgPool,
+    category: NotificationCategory,
+    severity: NotificationSeverity,
+    vehicle_id: Option<&str>,
+    message: &str,
+) -> Result<Notification, sqlx::Error> {
+    let row = query(&format!(
+        "INSERT INTO notifications(category, severity, vehicle_id, message)
+        VALUES ($1, $2, $3, $4)
+        RETURNING {}",
+        NOTIFICATION_COLUMNS
+    ))
+    .bind(format!("{:?}", category))
+    .bind(format!("{:?}", severity))
+    .bind(vehicle_id)
+    .bind(message)
+    .fetch_one(&db_conn)
+    .await?;
+
+    Ok(to_notification(row))
+}
+
+// Newest first
+pub async fn select_notifications(
+    db_conn: PgPool,
+    unacknowledged_only: bool,
+    limit: i64,
+) -> Result<Vec<Notification>, sqlx::Error> {
+    let rows = query(&format!(
+        "SELECT {}
+        FROM notifications
+        WHERE ($1 = FALSE OR acknowledged_at IS NULL)
+        ORDER BY notification_id DESC
+        LIMIT $2",
+        NOTIFICATION_COLUMNS
+    ))
+    .bind(unacknowledged_only)
+    .bind(limit)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_notification).collect())
+}
+
+// Newest first; includes pair alerts such as "mea-mra" that name the vehicle
+pub async fn select_vehicle_notifications(
+    db_conn: PgPool,
+    vehicle_id: &str,
+    limit: i64,
+) -> Result<Vec<Notification>, sqlx::Error> {
+    let rows = query(&format!(
+        "SELECT {}
+        FROM notifications
+        WHERE $1 = ANY(string_to_array(LOWER(vehicle_id), '-'))
+        ORDER BY notification_id DESC
+        LIMIT $2",
+        NOTIFICATION_COLUMNS
+    ))
+    .bind(vehicle_id.to_lowercase())
+    .bind(limit)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_notification).collect())
+}
+
+// Returns None when the notification does not exist; acknowledging twice keeps the first ack
+pub async fn acknowledge_notification(
+    db_conn: PgPool,
+    notification_id: i32,
+    operator: &str,
+) -> Result<Option<Notification>, sqlx::Error> {
+    let row = query(&format!(
+        "UPDATE notifications
+        SET acknowledged_by = COALESCE(acknowledged_by, $2),
+            acknowledged_at = COALESCE(acknowledged_at, NOW())
+        WHERE notification_id = $1
+        RETURNING {}",
+        NOTIFICATION_COLUMNS
+    ))
+    .bind(notification_id)
+    .bind(operator)
+    .fetch_optional(&db_conn)
+    .await?;
+
+    Ok(row.map(to_notification))
+}
+
+// Oldest first; used to collect the alerts raised while a mission was flown
+pub async fn select_notifications_between(
+    db_conn: PgPool,
+    from: &str,
+    to: &str,
+) -> Result<Vec<Notification>, sqlx::Error> {
+    let rows = query(&format!(
+        "SELECT {}
+        FROM notifications
+        WHERE notifications.created_at BETWEEN $1::TIMESTAMPTZ AND $2::TIMESTAMPTZ
+        ORDER BY notification_id",
+        NOTIFICATION_COLUMNS
+    ))
+    .bind(from)
+    .bind(to)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_notification).collect())
+}
+
+// Re-insert an alert from a mission bundle, keeping its timestamps and acknowledgment
+pub async fn insert_imported_notification(
+    db_conn: PgPool,
+    notification: &Notification,
+) -> Result<(), sqlx::Error> {
+    query("
+        INSERT INTO notifications(category, severity, vehicle_id, message, created_at, acknowledged_by, acknowledged_at)
+        VALUES ($1, $2, $3, $4, $5::TIMESTAMPTZ, $6, $7::TIMESTAMPTZ)
+    ")
+    .bind(format!("{:?}", notification.category))
+    .bind(format!("{:?}", notification.severity))
+    .bind(&notification.vehicle_id)
+    .bind(&notification.message)
+    .bind(&notification.created_at)
+    .bind(&notification.acknowledged_by)
+    .bind(&notification.acknowledged_at)
+    .execute(&db_conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,170 @@
+/*
+Operations feed: mission lifecycle events (started, paused, resumed, stage transitions,
+completed, aborted, emergency stops) published as JSON for the competition's scoring and ops
+systems, so they can follow a mission without watching the GCS screen. Events go to
+`ops_feed_webhook_url` (POSTed as application/json) and/or the `ops_feed_exchange` topic exchange
+on the AMQP broker with routing key "ops.<event>"; with neither set the feed is off.
+
+Events are queued in order and retried until delivered, so one that fails is sent again and
+may reach a sink twice; consumers drop duplicates by (session_id, sequence).
+*/
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::commands::sequence::session_id;
+use crate::config;
+use crate::missions::types::{EmergencyStopEvent, MissionStageStatusEnum, MissionStruct, MissionsStruct};
+use crate::supervisor::{self, RestartPolicy};
+
+pub mod transport;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OpsEvent {
+    // e.g. "mission_started"; also the last part of the AMQP routing key
+    pub event: String,
+    pub session_id: String,
+    pub sequence: u32,
+    pub mission_id: i32,
+    pub mission_name: Option<String>,
+    pub vehicle: Option<String>,
+    pub stage_id: Option<i32>,
+    pub stage_name: Option<String>,
+    pub status: Option<String>,
+    pub previous_status: Option<String>,
+    pub detail: Option<String>,
+    pub timestamp: String,
+}
+
+struct Outbox {
+    sender: mpsc::UnboundedSender<OpsEvent>,
+    // Taken by the running delivery task; events queue here while it is down
+    receiver: tokio::sync::Mutex<mpsc::UnboundedReceiver<OpsEvent>>,
+}
+
+lazy_static! {
+    static ref OUTBOX: Outbox = {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Outbox { sender, receiver: tokio::sync::Mutex::new(receiver) }
+    };
+    // Event taken from the outbox but not yet delivered, sent first by a restarted task
+    static ref IN_FLIGHT: Mutex<Option<OpsEvent>> = Mutex::new(None);
+}
+
+static LAST_SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+pub fn enabled() -> bool {
+    let config = config::get();
+    !config.ops_feed_webhook_url.is_empty() || !config.ops_feed_exchange.is_empty()
+}
+
+fn base(name: &str, mission_id: i32) -> OpsEvent {
+    OpsEvent {
+        event: name.to_string(),
+        session_id: session_id().to_string(),
+        sequence: LAST_SEQUENCE.fetch_add(1, Ordering::SeqCst) + 1,
+        mission_id,
+        mission_name: None,
+        vehicle: None,
+        stage_id: None,
+        stage_name: None,
+        status: None,
+        previous_status: None,
+        detail: None,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+fn event(name: &str, mission: &MissionStruct) -> OpsEvent {
+    OpsEvent {
+        mission_name: Some(mission.mission_name.clone()),
+        status: Some(mission.mission_status.name().to_string()),
+        ..base(name, mission.mission_id)
+    }
+}
+
+fn publish(event: OpsEvent) {
+    let _ = OUTBOX.sender.send(event);
+}
+
+// Name of a mission status change, None for changes the feed doesn't report (e.g. a reset)
+fn lifecycle_event(from: &MissionStageStatusEnum, to: &MissionStageStatusEnum) -> Option<&'static str> {
+    use MissionStageStatusEnum::*;
+    match (from, to) {
+        (Inactive, Active) => Some("mission_started"),
+        (Active, Paused) => Some("mission_paused"),
+        (Paused, Active) => Some("mission_resumed"),
+        (Active | Paused, Complete) => Some("mission_completed"),
+        (Active | Paused, Failed) => Some("mission_aborted"),
+        _ => None,
+    }
+}
+
+// Queue the lifecycle events between two emitted mission states
+pub fn record_missions(previous: &MissionsStruct, current: &MissionsStruct) {
+    if !enabled() {
+        return;
+    }
+    for mission in &current.missions {
+        let Some(before) = previous.missions.iter().find(|m| m.mission_id == mission.mission_id) else {
+            continue;
+        };
+        // Stage transitions first, so the stage a mission completes on is reported before it
+        let vehicles = [
+            (&before.vehicles.MEA, &mission.vehicles.MEA),
+            (&before.vehicles.ERU, &mission.vehicles.ERU),
+            (&before.vehicles.MRA, &mission.vehicles.MRA),
+        ];
+        for (vehicle_before, vehicle) in vehicles {
+            for stage in &vehicle.stages {
+                let Some(stage_before) = vehicle_before.stages.iter().find(|s| s.stage_id == stage.stage_id) else {
+                    continue;
+                };
+                if stage_before.stage_status == stage.stage_status {
+                    continue;
+                }
+                publish(OpsEvent {
+                    vehicle: Some(vehicle.vehicle_name.to_string()),
+                    stage_id: Some(stage.stage_id),
+                    stage_name: Some(stage.stage_name.clone()),
+                    status: Some(stage.stage_status.name().to_string()),
+                    previous_status: Some(stage_before.stage_status.name().to_string()),
+                    ..event("stage_transition", mission)
+                });
+            }
+        }
+        if let Some(name) = lifecycle_event(&before.mission_status, &mission.mission_status) {
+            publish(OpsEvent {
+                previous_status: Some(before.mission_status.name().to_string()),
+                ..event(name, mission)
+            });
+        }
+    }
+}
+
+pub fn record_emergency_stop(stop: &EmergencyStopEvent, mission: Option<&MissionStruct>) {
+    if !enabled() {
+        return;
+    }
+    let detail = format!(
+        "Stopped: {}; failed: {}; skipped: {}",
+        stop.report.stopped_vehicles.join(", "),
+        stop.report.failed_vehicles.join(", "),
+        stop.report.skipped_vehicles.join(", "),
+    );
+    let stopped = match mission {
+        Some(mission) => event("emergency_stop", mission),
+        None => base("emergency_stop", stop.mission_id),
+    };
+    publish(OpsEvent { detail: Some(detail), ..stopped });
+}
+
+// Deliver queued events until the app exits
+pub fn start() {
+    if !enabled() {
+        return;
+    }
+    supervisor::spawn("ops_feed", RestartPolicy::Always, transport::run);
+}
@@ -0,0 +1,108 @@
+/*
+Delivery of ops feed events to the webhook and the AMQP exchange. One event is delivered to
+every configured sink before the next is taken, keeping the feed in order; a failure returns
+the error so the supervisor restarts the task, which sends the undelivered event again.
+*/
+use std::time::Duration;
+use lazy_static::lazy_static;
+use lapin::{
+    options::*, types::FieldTable, BasicProperties, Channel, Connection, ConnectionProperties,
+    ExchangeKind,
+};
+use tokio_amqp::*;
+
+use crate::config;
+use crate::logs;
+use super::{OpsEvent, IN_FLIGHT, OUTBOX};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+lazy_static! {
+    static ref CLIENT: reqwest::Client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("Failed to build ops feed HTTP client");
+}
+
+async fn post(url: &str, payload: &[u8]) -> Result<(), String> {
+    let response = CLIENT
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(payload.to_vec())
+        .send()
+        .await
+        .map_err(|e| format!("Failed to post ops event: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Ops feed webhook answered {}", response.status()));
+    }
+    Ok(())
+}
+
+async fn open_exchange(exchange: &str) -> Result<(Connection, Channel), String> {
+    let connection = Connection::connect(&config::get().amqp_url, ConnectionProperties::default().with_tokio())
+        .await
+        .map_err(|e| format!("Failed to connect to RabbitMQ: {}", e))?;
+    let channel = connection
+        .create_channel()
+        .await
+        .map_err(|e| format!("Failed to create channel: {}", e))?;
+    channel
+        .exchange_declare(
+            exchange,
+            ExchangeKind::Topic,
+            ExchangeDeclareOptions { durable: true, ..Default::default() },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| format!("Failed to declare exchange {}: {}", exchange, e))?;
+    Ok((connection, channel))
+}
+
+async fn deliver(event: &OpsEvent, channel: Option<&Channel>) -> Result<(), String> {
+    let config = config::get();
+    let payload = serde_json::to_vec(event).map_err(|e| format!("Failed to serialize ops event: {}", e))?;
+    if !config.ops_feed_webhook_url.is_empty() {
+        post(&config.ops_feed_webhook_url, &payload).await?;
+    }
+    if let Some(channel) = channel {
+        channel
+            .basic_publish(
+                &config.ops_feed_exchange,
+                &format!("ops.{}", event.event),
+                BasicPublishOptions::default(),
+                &payload,
+                BasicProperties::default()
+                    .with_content_type("application/json".into())
+                    .with_delivery_mode(2),
+            )
+            .await
+            .map_err(|e| format!("Failed to publish ops event: {}", e))?;
+    }
+    Ok(())
+}
+
+pub async fn run() -> Result<(), String> {
+    let exchange = config::get().ops_feed_exchange;
+    // Kept for the life of the task; a dropped connection fails the next publish
+    let amqp = if exchange.is_empty() { None } else { Some(open_exchange(&exchange).await?) };
+    let channel = amqp.as_ref().map(|(_, channel)| channel);
+    let mut receiver = OUTBOX.receiver.lock().await;
+    logs::info("ops_feed", "Publishing mission events to the ops feed");
+
+    loop {
+        let pending = IN_FLIGHT.lock().unwrap().clone();
+        let event = match pending {
+            Some(event) => event,
+            None => {
+                let Some(event) = receiver.recv().await else { return Ok(()) };
+                *IN_FLIGHT.lock().unwrap() = Some(event.clone());
+                event
+            }
+        };
+        if let Err(e) = deliver(&event, channel).await {
+            logs::warn("ops_feed", format!("Failed to deliver {} event: {}", event.event, e));
+            return Err(e);
+        }
+        *IN_FLIGHT.lock().unwrap() = None;
+    }
+}
@@ -0,0 +1,73 @@
+/*
+Vehicle protocol adapters. An adapter turns a vehicle's telemetry messages into TelemetryData,
+encodes commands for it and reports what it can be commanded to do. Telemetry processing, the
+MQTT bridge and the commands module go through the registry here, so a new vehicle type or
+protocol revision is added by registering an adapter instead of editing them. Adapters are
+matched most recently registered first; the built-in NGCP JSON adapter comes last and serves
+every vehicle no other adapter claims.
+*/
+use std::sync::{Arc, RwLock};
+use lazy_static::lazy_static;
+
+use crate::commands::capabilities::VehicleCapabilities;
+use crate::commands::commands::CommandsStruct;
+use crate::logs;
+use crate::telemetry::types::TelemetryData;
+
+pub mod ngcp;
+
+pub trait VehicleProtocolAdapter: Send + Sync {
+    // Short name for logs, e.g. "ngcp-json"
+    fn name(&self) -> &'static str;
+    // Whether this adapter speaks to the vehicle
+    fn handles(&self, vehicle_id: &str) -> bool;
+    fn parse_telemetry(&self, payload: &[u8]) -> Result<TelemetryData, String>;
+    fn encode_command(&self, command: &CommandsStruct) -> Result<Vec<u8>, String>;
+    // None for a vehicle the adapter has no capability model for
+    fn capabilities(&self, vehicle_id: &str) -> Option<VehicleCapabilities>;
+}
+
+lazy_static! {
+    static ref ADAPTERS: RwLock<Vec<Arc<dyn VehicleProtocolAdapter>>> =
+        RwLock::new(vec![Arc::new(ngcp::NgcpJsonAdapter)]);
+}
+
+// Add an adapter ahead of the ones registered before it
+#[allow(dead_code)]
+pub fn register(adapter: Arc<dyn VehicleProtocolAdapter>) {
+    logs::info("protocols", format!("Registered vehicle protocol adapter {}", adapter.name()));
+    ADAPTERS.write().unwrap().insert(0, adapter);
+}
+
+pub fn adapter_for(vehicle_id: &str) -> Option<Arc<dyn VehicleProtocolAdapter>> {
+    ADAPTERS.read().unwrap().iter().find(|a| a.handles(vehicle_id)).cloned()
+}
+
+// Parse a telemetry message. With the sending vehicle known (e.g. from its MQTT topic) its adapter
+// parses it; otherwise the first adapter that parses it and serves the vehicle it names wins.
+pub fn parse_telemetry(vehicle_id: Option<&str>, payload: &[u8]) -> Result<TelemetryData, String> {
+    if let Some(vehicle_id) = vehicle_id {
+        let adapter = adapter_for(vehicle_id).ok_or(format!("No protocol adapter for {}", vehicle_id))?;
+        return adapter.parse_telemetry(payload);
+    }
+    let adapters = ADAPTERS.read().unwrap().clone();
+    let mut first_error = None;
+    for adapter in &adapters {
+        match adapter.parse_telemetry(payload) {
+            Ok(data) if adapter.handles(&data.vehicle_id) => return Ok(data),
+            Ok(data) => {
+                first_error.get_or_insert(format!("{} does not serve {}", adapter.name(), data.vehicle_id));
+            }
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    Err(first_error.unwrap_or_else(|| "No protocol adapters registered".into()))
+}
+
+pub fn encode_command(command: &CommandsStruct) -> Result<Vec<u8>, String> {
+    adapter_for(&command.vehicle_id)
+        .ok_or(format!("No protocol adapter for {}", command.vehicle_id))?
+        .encode_command(command)
+}
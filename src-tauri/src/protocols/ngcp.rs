@@ -0,0 +1,33 @@
+/*
+The NGCP JSON protocol the MEA, ERU, MRA and FRA speak: TelemetryData and CommandsStruct
+serialized as JSON, over AMQP or MQTT alike.
+*/
+use crate::commands::capabilities::{ngcp_capabilities, VehicleCapabilities};
+use crate::commands::commands::CommandsStruct;
+use crate::telemetry::types::TelemetryData;
+use super::VehicleProtocolAdapter;
+
+pub struct NgcpJsonAdapter;
+
+impl VehicleProtocolAdapter for NgcpJsonAdapter {
+    fn name(&self) -> &'static str {
+        "ngcp-json"
+    }
+
+    // Fallback for every vehicle, so an unknown id still parses and gets a "no capabilities" error
+    fn handles(&self, _vehicle_id: &str) -> bool {
+        true
+    }
+
+    fn parse_telemetry(&self, payload: &[u8]) -> Result<TelemetryData, String> {
+        serde_json::from_slice::<TelemetryData>(payload).map_err(|e| e.to_string())
+    }
+
+    fn encode_command(&self, command: &CommandsStruct) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(command).map_err(|e| format!("Failed to serialize command: {}", e))
+    }
+
+    fn capabilities(&self, vehicle_id: &str) -> Option<VehicleCapabilities> {
+        ngcp_capabilities(vehicle_id)
+    }
+}
@@ -0,0 +1,149 @@
+/*
+Read-only HTTP API for external tools (judge dashboards, logging scripts). When
+`rest_api_addr` is set, mission state and live telemetry are served as JSON under /api; the
+server never accepts commands. With `rest_api_token` set, requests must carry
+`Authorization: Bearer <token>`.
+
+    GET /api/health                   system health
+    GET /api/missions                 all missions and the current mission id
+    GET /api/missions/{mission_id}    one mission
+    GET /api/telemetry                latest telemetry for every vehicle
+    GET /api/telemetry/{vehicle_id}   latest telemetry for one vehicle
+    GET /api/vehicles                 heartbeat connection state per vehicle
+    GET /api/separation               inter-vehicle separation matrix
+*/
+use std::collections::HashMap;
+use std::sync::Arc;
+use axum::extract::{Path, Request, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+
+use crate::config;
+use crate::health::{HealthApiImpl, SystemHealth};
+use crate::logs;
+use crate::missions::api::MissionApiImpl;
+use crate::missions::types::{MissionStruct, MissionsStruct};
+use crate::supervisor::{self, RestartPolicy};
+use crate::telemetry::rabbitmq::RabbitMQAPIImpl;
+use crate::telemetry::separation::{self, SeparationMatrix};
+use crate::telemetry::types::{TelemetryData, VehicleTelemetryData};
+
+type ApiError = (StatusCode, String);
+
+#[derive(Clone)]
+pub struct RestState {
+    pub missions: MissionApiImpl,
+    pub telemetry: RabbitMQAPIImpl,
+    pub health: HealthApiImpl,
+}
+
+async fn get_health(State(state): State<RestState>) -> Json<SystemHealth> {
+    Json(state.health.check().await)
+}
+
+async fn get_missions(State(state): State<RestState>) -> Json<Arc<MissionsStruct>> {
+    Json(state.missions.snapshot().await)
+}
+
+async fn get_mission(
+    State(state): State<RestState>,
+    Path(mission_id): Path<i32>,
+) -> Result<Json<MissionStruct>, ApiError> {
+    state
+        .missions
+        .snapshot()
+        .await
+        .missions
+        .iter()
+        .find(|m| m.mission_id == mission_id)
+        .cloned()
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, format!("Mission {} not found", mission_id)))
+}
+
+async fn get_telemetry(State(state): State<RestState>) -> Json<Arc<VehicleTelemetryData>> {
+    Json(state.telemetry.telemetry_snapshot())
+}
+
+async fn get_vehicle_telemetry(
+    State(state): State<RestState>,
+    Path(vehicle_id): Path<String>,
+) -> Result<Json<TelemetryData>, ApiError> {
+    let telemetry = state.telemetry.telemetry_snapshot();
+    telemetry
+        .vehicle(&vehicle_id.to_lowercase())
+        .cloned()
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, format!("No telemetry for vehicle {}", vehicle_id)))
+}
+
+async fn get_vehicles(State(state): State<RestState>) -> Json<HashMap<String, bool>> {
+    let heartbeats = state.telemetry.get_heartbeat_status().await;
+    Json(
+        heartbeats
+            .into_iter()
+            .map(|(vehicle_id, heartbeat)| (vehicle_id, heartbeat.is_connected))
+            .collect(),
+    )
+}
+
+async fn get_separation() -> Json<SeparationMatrix> {
+    Json(separation::separation_matrix())
+}
+
+// Bearer token check (when configured) and CORS for browser dashboards
+async fn authorize(request: Request, next: Next) -> Response {
+    let token = config::get().rest_api_token;
+    if !token.is_empty() {
+        let presented = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if presented != Some(token.as_str()) {
+            return (StatusCode::UNAUTHORIZED, "Missing or invalid API token").into_response();
+        }
+    }
+    let mut response = next.run(request).await;
+    response
+        .headers_mut()
+        .insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+    response
+}
+
+fn router(state: RestState) -> Router {
+    Router::new()
+        .route("/api/health", get(get_health))
+        .route("/api/missions", get(get_missions))
+        .route("/api/missions/:mission_id", get(get_mission))
+        .route("/api/telemetry", get(get_telemetry))
+        .route("/api/telemetry/:vehicle_id", get(get_vehicle_telemetry))
+        .route("/api/vehicles", get(get_vehicles))
+        .route("/api/separation", get(get_separation))
+        .layer(middleware::from_fn(authorize))
+        .with_state(state)
+}
+
+async fn serve(state: RestState, addr: String) -> Result<(), String> {
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| format!("Failed to bind REST API to {}: {}", addr, e))?;
+    logs::info("rest", format!("Serving read-only REST API on http://{}", addr));
+    axum::serve(listener, router(state))
+        .await
+        .map_err(|e| format!("REST API server failed: {}", e))
+}
+
+// Start the server when rest_api_addr is configured
+pub fn start(state: RestState) {
+    let addr = config::get().rest_api_addr;
+    if addr.is_empty() {
+        return;
+    }
+    supervisor::spawn("rest_api", RestartPolicy::OnFailure, move || {
+        serve(state.clone(), addr.clone())
+    });
+}
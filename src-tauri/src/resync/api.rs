@@ -0,0 +1,47 @@
+/*
+Define the resync API: what a reconnecting frontend missed of the mission and telemetry state.
+*/
+use taurpc::{procedures, resolvers};
+
+use crate::missions::api::MissionApiImpl;
+use crate::telemetry::rabbitmq::RabbitMQAPIImpl;
+use super::Resync;
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "resync")]
+pub trait ResyncApi {
+    // Changes after `since_version`, the version of the previous resync; a full snapshot
+    // without one or once those changes are no longer kept
+    async fn resync(since_version: Option<u32>) -> Resync;
+}
+
+#[derive(Clone)]
+pub struct ResyncApiImpl {
+    missions: MissionApiImpl,
+    telemetry: RabbitMQAPIImpl,
+}
+
+impl ResyncApiImpl {
+    pub fn new(missions: MissionApiImpl, telemetry: RabbitMQAPIImpl) -> Self {
+        Self { missions, telemetry }
+    }
+}
+
+#[resolvers]
+impl ResyncApi for ResyncApiImpl {
+    async fn resync(self, since_version: Option<u32>) -> Resync {
+        if let Some((version, missions, telemetry_changed)) = since_version.and_then(super::deltas_since) {
+            return Resync::Deltas {
+                version,
+                missions,
+                telemetry: telemetry_changed.then(|| self.telemetry.telemetry_snapshot()),
+            };
+        }
+        // Read before the state, so changes racing the snapshot are delivered again next time
+        let version = super::version();
+        Resync::Snapshot {
+            version,
+            missions: self.missions.snapshot().await,
+            telemetry: self.telemetry.telemetry_snapshot(),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+/*
+Catch-up for a frontend that missed events, e.g. a webview reloaded mid-mission. Every mission
+change sent to the frontend gets the next state version and is kept in a bounded journal;
+telemetry only bumps the version, since the latest reports supersede the ones before. A
+frontend resyncing from the version it last saw gets the mission deltas after it and the
+current telemetry if it changed, or a full snapshot when it has no version yet or the deltas
+it missed have already left the journal. Versions restart with the backend.
+*/
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::missions::types::{MissionStruct, MissionsStruct};
+use crate::telemetry::types::VehicleTelemetryData;
+
+pub mod api;
+
+pub use api::{ResyncApi, ResyncApiImpl};
+
+// Mission deltas kept; older ones are only recoverable through a snapshot
+const JOURNAL_CAPACITY: usize = 500;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+#[serde(tag = "kind")]
+pub enum MissionDelta {
+    Updated { version: u32, mission: MissionStruct },
+    Deleted { version: u32, mission_id: i32 },
+    CurrentMission { version: u32, mission_id: i32 },
+}
+
+impl MissionDelta {
+    fn version(&self) -> u32 {
+        match self {
+            MissionDelta::Updated { version, .. }
+            | MissionDelta::Deleted { version, .. }
+            | MissionDelta::CurrentMission { version, .. } => *version,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+#[serde(tag = "kind")]
+pub enum Resync {
+    // Everything, to replace the frontend's state
+    Snapshot {
+        version: u32,
+        missions: Arc<MissionsStruct>,
+        telemetry: Arc<VehicleTelemetryData>,
+    },
+    // Oldest first; deltas applied again are harmless, as each one carries the whole mission
+    Deltas {
+        version: u32,
+        missions: Vec<MissionDelta>,
+        // None when no telemetry arrived since the requested version
+        telemetry: Option<Arc<VehicleTelemetryData>>,
+    },
+}
+
+#[derive(Default)]
+struct Journal {
+    version: u32,
+    telemetry_version: u32,
+    // Version of the newest delta pushed out of the journal
+    evicted_through: u32,
+    deltas: VecDeque<MissionDelta>,
+}
+
+impl Journal {
+    fn push(&mut self, delta: impl FnOnce(u32) -> MissionDelta) {
+        self.version += 1;
+        self.deltas.push_back(delta(self.version));
+        if self.deltas.len() > JOURNAL_CAPACITY {
+            if let Some(evicted) = self.deltas.pop_front() {
+                self.evicted_through = evicted.version();
+            }
+        }
+    }
+}
+
+lazy_static! {
+    static ref JOURNAL: Mutex<Journal> = Mutex::new(Journal::default());
+}
+
+pub fn version() -> u32 {
+    JOURNAL.lock().unwrap().version
+}
+
+// Journal what changed between two emitted mission states
+pub fn record_missions(previous: &MissionsStruct, current: &MissionsStruct) {
+    let mut journal = JOURNAL.lock().unwrap();
+    for mission in &current.missions {
+        let before = previous.missions.iter().find(|m| m.mission_id == mission.mission_id);
+        if before != Some(mission) {
+            journal.push(|version| MissionDelta::Updated { version, mission: mission.clone() });
+        }
+    }
+    for mission in &previous.missions {
+        if !current.missions.iter().any(|m| m.mission_id == mission.mission_id) {
+            journal.push(|version| MissionDelta::Deleted { version, mission_id: mission.mission_id });
+        }
+    }
+    if previous.current_mission != current.current_mission {
+        journal.push(|version| MissionDelta::CurrentMission { version, mission_id: current.current_mission });
+    }
+}
+
+pub fn record_telemetry() {
+    let mut journal = JOURNAL.lock().unwrap();
+    journal.version += 1;
+    journal.telemetry_version = journal.version;
+}
+
+// Current version, the mission deltas after `since` and whether telemetry changed after it;
+// None when a snapshot is needed instead
+fn deltas_since(since: u32) -> Option<(u32, Vec<MissionDelta>, bool)> {
+    let journal = JOURNAL.lock().unwrap();
+    // A version from before a backend restart, or one whose deltas were partly evicted
+    if since > journal.version || since < journal.evicted_through {
+        return None;
+    }
+    let deltas = journal.deltas.iter().filter(|d| d.version() > since).cloned().collect();
+    Some((journal.version, deltas, journal.telemetry_version > since))
+}
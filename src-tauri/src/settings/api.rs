@@ -0,0 +1,92 @@
+/*
+Define the settings API: read and update the persisted operator settings, and notify every
+window when they change.
+*/
+use sqlx::PgPool;
+use tauri::{AppHandle, Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, current_operator, OperatorRole};
+use crate::init_db::lazy_pool;
+use crate::logs;
+use super::sql::{select_setting, upsert_setting};
+use super::{set_current, OperatorSettings};
+
+const SETTINGS_KEY: &str = "operator";
+
+#[procedures(
+    event_trigger = SettingsEventTrigger,
+    export_to = "../src/lib/bindings.ts",
+    path = "settings"
+)]
+pub trait SettingsApi {
+    #[taurpc(event)]
+    async fn on_settings_changed(settings: OperatorSettings);
+
+    async fn get_settings() -> Result<OperatorSettings, String>;
+    async fn update_settings(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        settings: OperatorSettings,
+    ) -> Result<OperatorSettings, String>;
+}
+
+#[derive(Clone)]
+pub struct SettingsApiImpl {
+    db: PgPool,
+}
+
+impl SettingsApiImpl {
+    pub async fn new() -> Self {
+        let api = Self { db: lazy_pool(2) };
+        // Warm the cache; defaults stay in place if the database is unavailable
+        if let Err(e) = api.load().await {
+            logs::warn("settings", format!("Using default settings: {}", e));
+        }
+        api
+    }
+
+    async fn load(&self) -> Result<OperatorSettings, String> {
+        let stored = select_setting(self.db.clone(), SETTINGS_KEY)
+            .await
+            .map_err(|e| format!("Failed to load settings: {}", e))?;
+        let settings = match stored {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Stored settings are invalid: {}", e))?,
+            None => OperatorSettings::default(),
+        };
+        set_current(settings.clone());
+        Ok(settings)
+    }
+}
+
+#[resolvers]
+impl SettingsApi for SettingsApiImpl {
+    async fn get_settings(self) -> Result<OperatorSettings, String> {
+        self.load().await
+    }
+
+    async fn update_settings(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        settings: OperatorSettings,
+    ) -> Result<OperatorSettings, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            settings.validate()?;
+
+            let value = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+            upsert_setting(self.db.clone(), SETTINGS_KEY, &value, &current_operator())
+                .await
+                .map_err(|e| format!("Failed to save settings: {}", e))?;
+            set_current(settings.clone());
+            logs::info("settings", format!("Settings updated by {}", current_operator()));
+
+            if let Err(e) = SettingsEventTrigger::new(app_handle).on_settings_changed(settings.clone()) {
+                logs::warn("settings", format!("Failed to emit settings change: {}", e));
+            }
+            Ok(settings)
+        })
+        .await
+    }
+}
@@ -0,0 +1,187 @@
+/*
+Operator settings: display units, map defaults, alert thresholds, confirmation prompts,
+gamepad bindings and the database maintenance schedule.
+Settings are persisted in the settings table so they survive restarts and are shared by
+every frontend window; the latest copy is cached for backend consumers.
+*/
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::input::bindings::ManualControlBindings;
+use crate::units::UnitOverrides;
+
+pub mod api;
+pub mod sql;
+
+pub use api::{SettingsApi, SettingsApiImpl};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum UnitSystem {
+    Metric,
+    Imperial,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+#[serde(default)]
+pub struct MapDefaults {
+    pub center_lat: f64,
+    pub center_long: f64,
+    pub zoom: f64,
+}
+
+impl Default for MapDefaults {
+    fn default() -> Self {
+        Self {
+            center_lat: 33.932573934575075,
+            center_long: -117.63059569114814,
+            zoom: 16.0,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+#[serde(default)]
+pub struct AlertThresholds {
+    // Battery percentage at or below which a vehicle is flagged
+    pub low_battery_percent: i32,
+    // Signal strength (dBm) at or below which a vehicle is flagged
+    pub weak_signal_strength: i32,
+    // Drop in signal strength over the last minute (dB) reported as a degrading link
+    pub signal_trend_drop_dbm: f64,
+    // Two vehicles are too close when both their horizontal and vertical separation drop
+    // below these distances (m)
+    pub min_horizontal_separation_m: f64,
+    pub min_vertical_separation_m: f64,
+}
+
+impl Default for AlertThresholds {
+    fn default() -> Self {
+        Self {
+            low_battery_percent: 20,
+            weak_signal_strength: -70,
+            signal_trend_drop_dbm: 10.0,
+            min_horizontal_separation_m: 50.0,
+            min_vertical_separation_m: 15.0,
+        }
+    }
+}
+
+// Frontend "are you sure?" prompts; two-person confirmation is enforced separately
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+#[serde(default)]
+pub struct ConfirmationPrompts {
+    pub emergency_stop: bool,
+    pub mission_abort: bool,
+    pub zone_delete: bool,
+}
+
+impl Default for ConfirmationPrompts {
+    fn default() -> Self {
+        Self {
+            emergency_stop: true,
+            mission_abort: true,
+            zone_delete: true,
+        }
+    }
+}
+
+// When db_maintenance runs its vacuum, reindex and audit pruning
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+#[serde(default)]
+pub struct DatabaseMaintenanceSchedule {
+    pub enabled: bool,
+    // Hours between runs; a run due during an active or paused mission waits until it ends
+    pub interval_hours: u32,
+    // Rebuild the telemetry tables' indexes in each run
+    pub reindex: bool,
+    // Audit log entries older than this are deleted; 0 keeps them all
+    pub audit_retention_days: u32,
+}
+
+impl Default for DatabaseMaintenanceSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 24,
+            reindex: true,
+            audit_retention_days: 365,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+#[serde(default)]
+pub struct OperatorSettings {
+    pub units: UnitSystem,
+    // Individual quantities shown in a unit other than the unit system's
+    pub unit_overrides: UnitOverrides,
+    pub map: MapDefaults,
+    pub alerts: AlertThresholds,
+    pub confirmations: ConfirmationPrompts,
+    pub manual_control: ManualControlBindings,
+    pub database_maintenance: DatabaseMaintenanceSchedule,
+}
+
+impl Default for OperatorSettings {
+    fn default() -> Self {
+        Self {
+            units: UnitSystem::Metric,
+            unit_overrides: UnitOverrides::default(),
+            map: MapDefaults::default(),
+            alerts: AlertThresholds::default(),
+            confirmations: ConfirmationPrompts::default(),
+            manual_control: ManualControlBindings::default(),
+            database_maintenance: DatabaseMaintenanceSchedule::default(),
+        }
+    }
+}
+
+impl OperatorSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-90.0..=90.0).contains(&self.map.center_lat)
+            || !(-180.0..=180.0).contains(&self.map.center_long)
+        {
+            return Err("Map center must be a valid latitude/longitude".into());
+        }
+        if !(1.0..=22.0).contains(&self.map.zoom) {
+            return Err("Map zoom must be between 1 and 22".into());
+        }
+        if !(0..=100).contains(&self.alerts.low_battery_percent) {
+            return Err("Low battery threshold must be between 0 and 100 percent".into());
+        }
+        if self.alerts.weak_signal_strength > 0 {
+            return Err("Weak signal threshold must be a non-positive dBm value".into());
+        }
+        if !self.alerts.signal_trend_drop_dbm.is_finite() || self.alerts.signal_trend_drop_dbm <= 0.0 {
+            return Err("Signal trend drop must be a positive dB value".into());
+        }
+        for minimum in [self.alerts.min_horizontal_separation_m, self.alerts.min_vertical_separation_m] {
+            if !minimum.is_finite() || minimum < 0.0 {
+                return Err("Separation minima must be non-negative distances".into());
+            }
+        }
+        if !(1..=720).contains(&self.database_maintenance.interval_hours) {
+            return Err("Database maintenance interval must be between 1 and 720 hours".into());
+        }
+        if self.database_maintenance.audit_retention_days > 3650 {
+            return Err("Audit retention must be at most 3650 days".into());
+        }
+        self.manual_control.validate()?;
+        Ok(())
+    }
+}
+
+lazy_static! {
+    static ref SETTINGS: RwLock<OperatorSettings> = RwLock::new(OperatorSettings::default());
+}
+
+// Latest saved settings, for backend checks such as alert thresholds
+pub fn current() -> OperatorSettings {
+    SETTINGS.read().unwrap().clone()
+}
+
+fn set_current(settings: OperatorSettings) {
+    *SETTINGS.write().unwrap() = settings;
+}
@@ -0,0 +1,33 @@
+/*
+Define all settings database functions. Settings are stored as a JSON document per key.
+*/
+use sqlx::{query, PgPool, Row};
+
+pub async fn select_setting(db_conn: PgPool, key: &str) -> Result<Option<String>, sqlx::Error> {
+    let row = query("SELECT value FROM settings WHERE key = $1")
+        .bind(key)
+        .fetch_optional(&db_conn)
+        .await?;
+    Ok(row.map(|row| row.get("value")))
+}
+
+pub async fn upsert_setting(
+    db_conn: PgPool,
+    key: &str,
+    value: &str,
+    updated_by: &str,
+) -> Result<(), sqlx::Error> {
+    query("
+        INSERT INTO settings(key, value, updated_by, updated_at)
+        VALUES ($1, $2, $3, NOW())
+        ON CONFLICT (key) DO UPDATE
+        SET value = EXCLUDED.value, updated_by = EXCLUDED.updated_by, updated_at = NOW()
+    ")
+    .bind(key)
+    .bind(value)
+    .bind(updated_by)
+    .execute(&db_conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,246 @@
+/*
+Define the simulator API: start and stop the telemetry simulation or a scenario script and
+report its state. The running simulation is a background task; starting again replaces it.
+*/
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use tauri::{AppHandle, Runtime, Window};
+use taurpc::{procedures, resolvers};
+use tokio::time::{interval, MissedTickBehavior};
+
+use crate::auth::{authorized, OperatorRole};
+use crate::config;
+use crate::logs;
+use crate::telemetry::publisher::RabbitMQPublisher;
+use crate::telemetry::rabbitmq::RabbitMQAPIImpl;
+use super::scenario;
+use super::{
+    Scenario, ScenarioProgress, SimulatedVehicleState, SimulationConfig, SimulationOutput, SimulationStatus,
+    VehicleSim,
+};
+
+#[procedures(event_trigger = SimulatorEventTrigger, export_to = "../src/lib/bindings.ts", path = "simulator")]
+pub trait SimulatorApi {
+    #[taurpc(event)]
+    async fn on_simulation_changed(status: SimulationStatus);
+
+    async fn get_simulation_status() -> SimulationStatus;
+    async fn get_default_simulation_config() -> SimulationConfig;
+    async fn start_simulation(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        config: SimulationConfig,
+    ) -> Result<SimulationStatus, String>;
+    // Run a JSON or YAML scenario script with its own simulation config
+    async fn run_scenario(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        path: String,
+    ) -> Result<SimulationStatus, String>;
+    async fn stop_simulation(app_handle: AppHandle<impl Runtime>) -> SimulationStatus;
+}
+
+#[derive(Default)]
+struct Session {
+    // Bumped on every start/stop so a superseded simulation task exits
+    generation: u64,
+    running: bool,
+    config: SimulationConfig,
+    vehicles: Vec<SimulatedVehicleState>,
+    scenario: Option<ScenarioProgress>,
+}
+
+lazy_static! {
+    static ref SESSION: Mutex<Session> = Mutex::new(Session::default());
+    // Telemetry pipeline for SimulationOutput::Pipeline, set once the app handle exists
+    static ref PIPELINE: Mutex<Option<RabbitMQAPIImpl>> = Mutex::new(None);
+}
+
+pub fn set_pipeline(telemetry: RabbitMQAPIImpl) {
+    *PIPELINE.lock().unwrap() = Some(telemetry);
+}
+
+// Speed the simulator flies `vehicle_id` at: the running or last configuration, else the default
+pub fn vehicle_speed_ms(vehicle_id: &str) -> Option<f64> {
+    let session = SESSION.lock().unwrap();
+    session
+        .config
+        .vehicles
+        .iter()
+        .find(|v| v.vehicle_id.eq_ignore_ascii_case(vehicle_id))
+        .map(|v| v.speed_ms)
+}
+
+fn status() -> SimulationStatus {
+    let session = SESSION.lock().unwrap();
+    SimulationStatus {
+        running: session.running,
+        config: session.config.clone(),
+        vehicles: session.vehicles.clone(),
+        scenario: session.scenario.clone(),
+    }
+}
+
+fn emit_status(app_handle: &AppHandle<impl Runtime>) {
+    if let Err(e) = SimulatorEventTrigger::new(app_handle.clone()).on_simulation_changed(status()) {
+        logs::error("simulator", format!("Failed to emit simulation status: {}", e));
+    }
+}
+
+// Stop the running simulation; returns whether one was running
+fn stop() -> bool {
+    let mut session = SESSION.lock().unwrap();
+    session.generation += 1;
+    std::mem::replace(&mut session.running, false)
+}
+
+async fn run(config: SimulationConfig, scenario: Option<Scenario>, generation: u64) -> Result<(), String> {
+    let publisher = match config.output {
+        SimulationOutput::Queues => Some(
+            RabbitMQPublisher::new(&config::get().amqp_url)
+                .await
+                .map_err(|e| format!("Failed to connect to RabbitMQ: {}", e))?,
+        ),
+        SimulationOutput::Pipeline => None,
+    };
+    let pipeline = PIPELINE.lock().unwrap().clone();
+    if publisher.is_none() && pipeline.is_none() {
+        return Err("Telemetry pipeline is not ready".into());
+    }
+
+    let dt_secs = 1.0 / config.rate_hz as f64;
+    let mut ticker = interval(Duration::from_secs_f64(dt_secs));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut vehicles: Vec<VehicleSim> = config.vehicles.iter().cloned().map(VehicleSim::new).collect();
+    let events = scenario.as_ref().map_or(&[][..], |s| &s.events[..]);
+    let mut fired = 0;
+    let mut tick: u64 = 0;
+
+    loop {
+        ticker.tick().await;
+        let elapsed_secs = tick as f64 * dt_secs;
+        tick += 1;
+        while let Some(event) = events.get(fired).filter(|e| e.at_secs <= elapsed_secs) {
+            fired += 1;
+            let target = vehicles.iter_mut().find(|v| v.vehicle_id().eq_ignore_ascii_case(&event.vehicle_id));
+            let Some(vehicle) = target else { continue };
+            match scenario::apply(event, vehicle) {
+                Ok(()) => logs::info(
+                    "simulator",
+                    format!("T+{:.0}s {}: {:?}", elapsed_secs, event.vehicle_id.to_uppercase(), event.action),
+                ),
+                Err(e) => logs::warn("simulator", format!("T+{:.0}s: {}", elapsed_secs, e)),
+            }
+        }
+        let reports: Vec<_> = vehicles.iter_mut().filter_map(|v| v.step(dt_secs)).collect();
+        {
+            let mut session = SESSION.lock().unwrap();
+            if session.generation != generation {
+                return Ok(());
+            }
+            session.vehicles = vehicles.iter().map(VehicleSim::state).collect();
+            session.scenario = scenario.as_ref().map(|s| s.progress(elapsed_secs, fired));
+        }
+        for data in reports {
+            match (&publisher, &pipeline) {
+                (Some(publisher), _) => {
+                    let vehicle_id = data.vehicle_id.clone();
+                    publisher
+                        .publish_telemetry(&vehicle_id, data)
+                        .await
+                        .map_err(|e| format!("Failed to publish simulated telemetry: {}", e))?;
+                }
+                (None, Some(pipeline)) => pipeline.ingest_telemetry(data, Instant::now()).await,
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+// Replace any running simulation
+fn start<R: Runtime>(app_handle: AppHandle<R>, config: SimulationConfig, scenario: Option<Scenario>) {
+    stop();
+    let generation = {
+        let mut session = SESSION.lock().unwrap();
+        session.running = true;
+        session.config = config.clone();
+        session.vehicles.clear();
+        session.scenario = None;
+        session.generation
+    };
+    logs::info(
+        "simulator",
+        format!("Simulating {} vehicles at {} Hz ({:?})", config.vehicles.len(), config.rate_hz, config.output),
+    );
+
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = run(config, scenario, generation).await;
+        let mut session = SESSION.lock().unwrap();
+        if session.generation != generation {
+            return;
+        }
+        session.running = false;
+        drop(session);
+        if let Err(e) = result {
+            logs::error("simulator", format!("Simulation stopped: {}", e));
+        }
+        emit_status(&handle);
+    });
+    emit_status(&app_handle);
+}
+
+#[derive(Clone, Default)]
+pub struct SimulatorApiImpl;
+
+#[resolvers]
+impl SimulatorApi for SimulatorApiImpl {
+    async fn get_simulation_status(self) -> SimulationStatus {
+        status()
+    }
+
+    async fn get_default_simulation_config(self) -> SimulationConfig {
+        SimulationConfig::default()
+    }
+
+    async fn start_simulation(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        config: SimulationConfig,
+    ) -> Result<SimulationStatus, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            config.validate()?;
+            start(app_handle, config, None);
+            Ok(status())
+        })
+        .await
+    }
+
+    async fn run_scenario(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        path: String,
+    ) -> Result<SimulationStatus, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let scenario = Scenario::load(&path)?;
+            logs::info(
+                "simulator",
+                format!("Running scenario {} ({} events)", scenario.name, scenario.events.len()),
+            );
+            start(app_handle, scenario.simulation.clone(), Some(scenario));
+            Ok(status())
+        })
+        .await
+    }
+
+    async fn stop_simulation(self, app_handle: AppHandle<impl Runtime>) -> SimulationStatus {
+        if stop() {
+            logs::info("simulator", "Simulation stopped");
+            emit_status(&app_handle);
+        }
+        status()
+    }
+}
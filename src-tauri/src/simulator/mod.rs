@@ -0,0 +1,279 @@
+/*
+Built-in telemetry simulator for UI and mission-logic development without vehicles. Each
+simulated vehicle flies its route in a loop at a constant speed and altitude, drains its
+battery at a fixed rate and can drop out for a while to exercise the heartbeat timeout. Reports
+are published to the vehicles' RabbitMQ telemetry queues or handed straight to the telemetry
+pipeline when no broker is running.
+*/
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::geometry::LocalProjection;
+use crate::missions::types::{GeoCoordinateStruct, GeofenceType};
+use crate::telemetry::broadcast::TELEMETRY_VEHICLES;
+use crate::telemetry::types::{Coordinate, RequestCoordinate, TelemetryData};
+
+pub mod api;
+pub mod scenario;
+
+pub use api::{SimulatorApi, SimulatorApiImpl, SimulatorEventTrigger};
+pub use scenario::{Scenario, ScenarioProgress};
+
+pub const MAX_RATE_HZ: u32 = 20;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Type)]
+pub enum SimulationOutput {
+    // Published to the AMQP telemetry queues like real vehicles (needs the consumers running)
+    Queues,
+    // Processed in-app without a broker
+    Pipeline,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct SimulatedVehicle {
+    pub vehicle_id: String,
+    // Flown in a loop; a single point hovers
+    pub route: GeofenceType,
+    pub speed_ms: f64,
+    pub altitude_m: f64,
+    pub battery_drain_percent_per_min: f64,
+    // Chance per minute of going silent for dropout_secs
+    pub dropout_chance_per_min: f64,
+    pub dropout_secs: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct SimulationConfig {
+    pub rate_hz: u32,
+    pub output: SimulationOutput,
+    pub vehicles: Vec<SimulatedVehicle>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct SimulatedVehicleState {
+    pub vehicle_id: String,
+    pub position: GeoCoordinateStruct,
+    pub battery_percent: f64,
+    pub dropped_out: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct SimulationStatus {
+    pub running: bool,
+    pub config: SimulationConfig,
+    pub vehicles: Vec<SimulatedVehicleState>,
+    pub scenario: Option<ScenarioProgress>,
+}
+
+// Square loop of `size_m` near the default map position, shifted by `offset_m` per vehicle
+fn square_route(offset_m: f64, size_m: f64) -> GeofenceType {
+    let projection = LocalProjection::new(&GeoCoordinateStruct {
+        lat: 33.932573934575075,
+        long: -117.63059569114814,
+    });
+    [(0.0, 0.0), (size_m, 0.0), (size_m, size_m), (0.0, size_m)]
+        .iter()
+        .map(|(x, y)| projection.to_coord(x + offset_m, y + offset_m))
+        .collect()
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        let vehicle = |vehicle_id: &str, offset_m, speed_ms, altitude_m| SimulatedVehicle {
+            vehicle_id: vehicle_id.to_string(),
+            route: square_route(offset_m, 400.0),
+            speed_ms,
+            altitude_m,
+            battery_drain_percent_per_min: 1.0,
+            dropout_chance_per_min: 0.0,
+            dropout_secs: 15,
+        };
+        Self {
+            rate_hz: 2,
+            output: SimulationOutput::Pipeline,
+            vehicles: vec![
+                vehicle("eru", 0.0, 8.0, 0.0),
+                vehicle("mea", 200.0, 15.0, 60.0),
+                vehicle("mra", -200.0, 20.0, 90.0),
+            ],
+        }
+    }
+}
+
+impl SimulationConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_RATE_HZ).contains(&self.rate_hz) {
+            return Err(format!("Simulation rate must be between 1 and {} Hz", MAX_RATE_HZ));
+        }
+        for vehicle in &self.vehicles {
+            let id = vehicle.vehicle_id.to_lowercase();
+            if !TELEMETRY_VEHICLES.contains(&id.as_str()) {
+                return Err(format!("Cannot simulate vehicle {}", vehicle.vehicle_id));
+            }
+            if self.vehicles.iter().filter(|v| v.vehicle_id.eq_ignore_ascii_case(&id)).count() > 1 {
+                return Err(format!("{} is simulated more than once", id.to_uppercase()));
+            }
+            if vehicle.route.is_empty() {
+                return Err(format!("{} needs at least one route point", id.to_uppercase()));
+            }
+            let rates = [vehicle.speed_ms, vehicle.altitude_m, vehicle.battery_drain_percent_per_min];
+            if rates.iter().any(|value| !value.is_finite() || *value < 0.0) {
+                return Err(format!("{} speed, altitude and battery drain must be non-negative", id.to_uppercase()));
+            }
+            if !(0.0..=1.0).contains(&vehicle.dropout_chance_per_min) {
+                return Err(format!("{} dropout chance must be between 0 and 1", id.to_uppercase()));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct VehicleSim {
+    config: SimulatedVehicle,
+    projection: LocalProjection,
+    // Route in local metres, closed back to the first point
+    path: Vec<(f64, f64)>,
+    length_m: f64,
+    travelled_m: f64,
+    battery_percent: f64,
+    dropout_remaining_secs: f64,
+    // Scenario overrides
+    signal_dbm: Option<i32>,
+    request_coordinate: RequestCoordinate,
+}
+
+impl VehicleSim {
+    pub fn new(config: SimulatedVehicle) -> Self {
+        let route = config.route.clone();
+        let mut sim = Self {
+            config,
+            projection: LocalProjection::around(&route),
+            path: vec![],
+            length_m: 0.0,
+            travelled_m: 0.0,
+            battery_percent: 100.0,
+            dropout_remaining_secs: 0.0,
+            signal_dbm: None,
+            request_coordinate: RequestCoordinate::default(),
+        };
+        sim.set_route(route);
+        sim
+    }
+
+    pub fn vehicle_id(&self) -> &str {
+        &self.config.vehicle_id
+    }
+
+    // Start flying `route` from its first point
+    pub fn set_route(&mut self, route: GeofenceType) {
+        self.projection = LocalProjection::around(&route);
+        self.path = route.iter().map(|c| self.projection.to_xy(c)).collect();
+        self.path.push(self.path[0]);
+        self.length_m = self.path.windows(2).map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1)).sum();
+        self.travelled_m = 0.0;
+        self.config.route = route;
+    }
+
+    pub fn set_speed(&mut self, speed_ms: f64) {
+        self.config.speed_ms = speed_ms;
+    }
+
+    pub fn set_battery(&mut self, percent: f64) {
+        self.battery_percent = percent;
+    }
+
+    pub fn set_signal(&mut self, dbm: i32) {
+        self.signal_dbm = Some(dbm);
+    }
+
+    // Silent for `duration_secs`, or until reconnect() when None
+    pub fn disconnect(&mut self, duration_secs: Option<u32>) {
+        self.dropout_remaining_secs = duration_secs.map_or(f64::INFINITY, |secs| secs as f64);
+    }
+
+    pub fn reconnect(&mut self) {
+        self.dropout_remaining_secs = 0.0;
+    }
+
+    // Reported from now on; a new flag value is what makes it a new request
+    pub fn request_coordinate(&mut self, position: &GeoCoordinateStruct) {
+        self.request_coordinate = RequestCoordinate {
+            message_flag: self.request_coordinate.message_flag + 1,
+            request_location: Coordinate { latitude: position.lat, longitude: position.long },
+            patient_secured: Some(false),
+        };
+    }
+
+    // Position and heading (degrees from north) at the current distance along the route
+    fn locate(&self) -> ((f64, f64), f64) {
+        if self.length_m <= 0.0 {
+            return (self.path[0], 0.0);
+        }
+        let mut remaining = self.travelled_m % self.length_m;
+        for w in self.path.windows(2) {
+            let (dx, dy) = (w[1].0 - w[0].0, w[1].1 - w[0].1);
+            let segment = dx.hypot(dy);
+            if remaining <= segment && segment > 0.0 {
+                let t = remaining / segment;
+                let heading = dx.atan2(dy).to_degrees().rem_euclid(360.0);
+                return ((w[0].0 + dx * t, w[0].1 + dy * t), heading);
+            }
+            remaining -= segment;
+        }
+        (self.path[0], 0.0)
+    }
+
+    // Advance by `dt_secs`; None while the vehicle is dropped out
+    pub fn step(&mut self, dt_secs: f64) -> Option<TelemetryData> {
+        self.travelled_m += self.config.speed_ms * dt_secs;
+        self.battery_percent =
+            (self.battery_percent - self.config.battery_drain_percent_per_min * dt_secs / 60.0).max(0.0);
+
+        if self.dropout_remaining_secs > 0.0 {
+            self.dropout_remaining_secs -= dt_secs;
+            return None;
+        }
+        if rand::random::<f64>() < self.config.dropout_chance_per_min * dt_secs / 60.0 {
+            self.dropout_remaining_secs = self.config.dropout_secs as f64;
+            return None;
+        }
+
+        let ((x, y), heading) = self.locate();
+        let position = self.projection.to_coord(x, y);
+        let noise = |amplitude: f64| (rand::random::<f64>() - 0.5) * 2.0 * amplitude;
+        Some(TelemetryData {
+            vehicle_id: self.config.vehicle_id.to_lowercase(),
+            signal_strength: self.signal_dbm.unwrap_or((-55.0 + noise(5.0)).round() as i32),
+            pitch: noise(2.0) as f32,
+            yaw: heading as f32,
+            roll: noise(2.0) as f32,
+            speed: self.config.speed_ms as f32,
+            altitude: (self.config.altitude_m + noise(0.5)).max(0.0) as f32,
+            battery_life: self.battery_percent.round() as i32,
+            current_position: Coordinate { latitude: position.lat, longitude: position.long },
+            vehicle_status: String::new(),
+            request_coordinate: self.request_coordinate.clone(),
+            timestamp: Some(chrono::Utc::now().timestamp_millis()),
+            armed: None,
+            ping_reply: None,
+            display: None,
+            alias: None,
+            link_latency_ms: None,
+            mission_id: None,
+            stage_id: None,
+            capabilities: None,
+            data_age_ms: None,
+            stale: None,
+        })
+    }
+
+    pub fn state(&self) -> SimulatedVehicleState {
+        let ((x, y), _) = self.locate();
+        SimulatedVehicleState {
+            vehicle_id: self.config.vehicle_id.to_lowercase(),
+            position: self.projection.to_coord(x, y),
+            battery_percent: self.battery_percent,
+            dropped_out: self.dropout_remaining_secs > 0.0,
+        }
+    }
+}
@@ -0,0 +1,144 @@
+/*
+Scenario scripts for the simulator: a simulation config plus timed events applied to the
+simulated vehicles, so alerting, failsafe and stage-transition logic can be exercised the same
+way every run. Scripts are JSON, or YAML when the file ends in .yaml/.yml:
+
+    name: eru-dropout
+    simulation: { rate_hz: 2, output: Pipeline, vehicles: [...] }   # optional, default fleet
+    events:
+      - { at_secs: 120, vehicle_id: eru, action: { type: Disconnect, duration_secs: 30 } }
+      - { at_secs: 200, vehicle_id: mea, action: { type: SetBattery, percent: 15 } }
+      - { at_secs: 240, vehicle_id: mra, action: { type: EnterKeepOutZone, zone_index: 0 } }
+
+Event times are simulated time from the start of the run, so a slow tick does not shift them.
+*/
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::geofence;
+use crate::missions::types::{GeoCoordinateStruct, GeofenceType};
+use super::{SimulationConfig, VehicleSim};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+#[serde(tag = "type")]
+pub enum ScenarioAction {
+    // Stop reporting, for duration_secs or until Reconnect
+    Disconnect { duration_secs: Option<u32> },
+    Reconnect,
+    SetBattery { percent: f64 },
+    SetSignal { dbm: i32 },
+    SetSpeed { speed_ms: f64 },
+    // Jump to a position and hover there
+    MoveTo { position: GeoCoordinateStruct },
+    FlyRoute { route: GeofenceType },
+    // Jump into a keep-out zone of the current mission and hover there
+    EnterKeepOutZone { zone_index: u32 },
+    RequestCoordinate { position: GeoCoordinateStruct },
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct ScenarioEvent {
+    pub at_secs: f64,
+    pub vehicle_id: String,
+    pub action: ScenarioAction,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub simulation: SimulationConfig,
+    pub events: Vec<ScenarioEvent>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct ScenarioProgress {
+    pub name: String,
+    pub elapsed_secs: f64,
+    pub events_fired: u32,
+    pub events_total: u32,
+}
+
+impl Scenario {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read scenario {}: {}", path, e))?;
+        let yaml = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"));
+        let mut scenario: Scenario = if yaml {
+            serde_yaml::from_str(&text).map_err(|e| format!("Invalid scenario {}: {}", path, e))?
+        } else {
+            serde_json::from_str(&text).map_err(|e| format!("Invalid scenario {}: {}", path, e))?
+        };
+        scenario.validate()?;
+        scenario.events.sort_by(|a, b| a.at_secs.total_cmp(&b.at_secs));
+        Ok(scenario)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.simulation.validate()?;
+        for event in &self.events {
+            let vehicle = event.vehicle_id.to_uppercase();
+            if !event.at_secs.is_finite() || event.at_secs < 0.0 {
+                return Err(format!("Event for {} has an invalid time", vehicle));
+            }
+            if !self.simulation.vehicles.iter().any(|v| v.vehicle_id.eq_ignore_ascii_case(&event.vehicle_id)) {
+                return Err(format!("Event at T+{}s targets {}, which is not simulated", event.at_secs, vehicle));
+            }
+            match &event.action {
+                ScenarioAction::SetBattery { percent } if !(0.0..=100.0).contains(percent) => {
+                    return Err(format!("Battery for {} must be between 0 and 100%", vehicle));
+                }
+                ScenarioAction::SetSpeed { speed_ms } if !speed_ms.is_finite() || *speed_ms < 0.0 => {
+                    return Err(format!("Speed for {} must be non-negative", vehicle));
+                }
+                ScenarioAction::FlyRoute { route } if route.is_empty() => {
+                    return Err(format!("Route for {} needs at least one point", vehicle));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn progress(&self, elapsed_secs: f64, events_fired: usize) -> ScenarioProgress {
+        ScenarioProgress {
+            name: self.name.clone(),
+            elapsed_secs,
+            events_fired: events_fired as u32,
+            events_total: self.events.len() as u32,
+        }
+    }
+}
+
+// Vertex mean of a keep-out zone of the current mission; inside for the convex zones missions use
+fn keep_out_zone_centre(zone_index: u32) -> Option<GeoCoordinateStruct> {
+    let geometry = geofence::current();
+    let zone = geometry.keep_out.get(zone_index as usize)?;
+    let count = zone.len().max(1) as f64;
+    Some(GeoCoordinateStruct {
+        lat: zone.iter().map(|c| c.lat).sum::<f64>() / count,
+        long: zone.iter().map(|c| c.long).sum::<f64>() / count,
+    })
+}
+
+pub fn apply(event: &ScenarioEvent, vehicle: &mut VehicleSim) -> Result<(), String> {
+    match &event.action {
+        ScenarioAction::Disconnect { duration_secs } => vehicle.disconnect(*duration_secs),
+        ScenarioAction::Reconnect => vehicle.reconnect(),
+        ScenarioAction::SetBattery { percent } => vehicle.set_battery(*percent),
+        ScenarioAction::SetSignal { dbm } => vehicle.set_signal(*dbm),
+        ScenarioAction::SetSpeed { speed_ms } => vehicle.set_speed(*speed_ms),
+        ScenarioAction::MoveTo { position } => vehicle.set_route(vec![position.clone()]),
+        ScenarioAction::FlyRoute { route } => vehicle.set_route(route.clone()),
+        ScenarioAction::EnterKeepOutZone { zone_index } => {
+            let centre = keep_out_zone_centre(*zone_index)
+                .ok_or(format!("The current mission has no keep-out zone {}", zone_index))?;
+            vehicle.set_route(vec![centre]);
+        }
+        ScenarioAction::RequestCoordinate { position } => vehicle.request_coordinate(position),
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+/*
+Copy-on-write state shared with emitters. The live mission state sits behind a Snapshot (the
+telemetry shards in telemetry::state hold Arcs the same way): readers and events take an Arc of
+the current value without copying it, and a write only clones the value when an earlier
+snapshot is still held somewhere (e.g. a WebSocket client that has not sent it yet). At high
+telemetry rates this replaces a deep clone per message.
+*/
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+#[derive(Debug, Default)]
+pub struct Snapshot<T>(Arc<T>);
+
+impl<T> Snapshot<T> {
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(value))
+    }
+
+    // The current value; later writes do not affect it
+    pub fn share(&self) -> Arc<T> {
+        self.0.clone()
+    }
+}
+
+impl<T> Deref for Snapshot<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Clone> DerefMut for Snapshot<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        Arc::make_mut(&mut self.0)
+    }
+}
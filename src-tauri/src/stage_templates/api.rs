@@ -0,0 +1,130 @@
+/*
+Define the stage templates API: list, save and delete templates, and read the settings a
+stage was created with.
+*/
+use sqlx::PgPool;
+use tauri::{Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::audit::AUTOMATIC_OPERATOR;
+use crate::auth::{authorized, current_operator, OperatorRole};
+use crate::init_db::lazy_pool;
+use crate::logs;
+use super::sql::{count_templates, delete_template, select_stage_settings, select_templates, upsert_template};
+use super::{built_in_templates, set_templates, StageSettings, StageTemplate};
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "stage_templates")]
+pub trait StageTemplatesApi {
+    async fn list_stage_templates() -> Result<Vec<StageTemplate>, String>;
+    // Create or replace the template with the same name; returns every template
+    async fn save_stage_template(
+        window: Window<impl Runtime>,
+        template: StageTemplate,
+    ) -> Result<Vec<StageTemplate>, String>;
+    async fn delete_stage_template(
+        window: Window<impl Runtime>,
+        name: String,
+    ) -> Result<Vec<StageTemplate>, String>;
+    // None for stages not created from a template
+    async fn get_stage_settings(stage_id: i32) -> Result<Option<StageSettings>, String>;
+}
+
+#[derive(Clone)]
+pub struct StageTemplatesApiImpl {
+    db: PgPool,
+}
+
+impl StageTemplatesApiImpl {
+    pub async fn new() -> Self {
+        let api = Self { db: lazy_pool(2) };
+        if let Err(e) = api.seed().await {
+            logs::warn("stage_templates", e);
+        }
+        // Warm the cache used by add_stage
+        if let Err(e) = api.load().await {
+            logs::warn("stage_templates", e);
+        }
+        api
+    }
+
+    async fn seed(&self) -> Result<(), String> {
+        let count = count_templates(self.db.clone())
+            .await
+            .map_err(|e| format!("Failed to count stage templates: {}", e))?;
+        if count > 0 {
+            return Ok(());
+        }
+        for template in built_in_templates() {
+            upsert_template(self.db.clone(), &template, AUTOMATIC_OPERATOR)
+                .await
+                .map_err(|e| format!("Failed to seed stage template {}: {}", template.name, e))?;
+        }
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Vec<StageTemplate>, String> {
+        let templates = select_templates(self.db.clone())
+            .await
+            .map_err(|e| format!("Failed to load stage templates: {}", e))?;
+        set_templates(templates.clone());
+        Ok(templates)
+    }
+}
+
+#[resolvers]
+impl StageTemplatesApi for StageTemplatesApiImpl {
+    async fn list_stage_templates(self) -> Result<Vec<StageTemplate>, String> {
+        self.load().await
+    }
+
+    async fn save_stage_template(
+        self,
+        window: Window<impl Runtime>,
+        template: StageTemplate,
+    ) -> Result<Vec<StageTemplate>, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            template.validate()?;
+            let template = StageTemplate {
+                name: template.name.trim().to_string(),
+                stage_name: template.stage_name.trim().to_string(),
+                ..template
+            };
+            upsert_template(self.db.clone(), &template, &current_operator())
+                .await
+                .map_err(|e| format!("Failed to save stage template: {}", e))?;
+            logs::info(
+                "stage_templates",
+                format!("Stage template {} saved by {}", template.name, current_operator()),
+            );
+            self.load().await
+        })
+        .await
+    }
+
+    async fn delete_stage_template(
+        self,
+        window: Window<impl Runtime>,
+        name: String,
+    ) -> Result<Vec<StageTemplate>, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let removed = delete_template(self.db.clone(), &name)
+                .await
+                .map_err(|e| format!("Failed to delete stage template: {}", e))?;
+            if !removed {
+                return Err(format!("No stage template named {}", name));
+            }
+            logs::info(
+                "stage_templates",
+                format!("Stage template {} deleted by {}", name, current_operator()),
+            );
+            self.load().await
+        })
+        .await
+    }
+
+    async fn get_stage_settings(self, stage_id: i32) -> Result<Option<StageSettings>, String> {
+        select_stage_settings(self.db.clone(), stage_id)
+            .await
+            .map_err(|e| format!("Failed to load stage settings: {}", e))
+    }
+}
@@ -0,0 +1,199 @@
+/*
+Stage templates: a named bundle of what a common stage needs set up (its name, the vehicle's
+default altitude band, when the stage counts as complete and how its search pattern is
+generated), so add_stage can create it fully configured. A template's settings are copied onto
+the stage when it is created (stage_settings), so editing or deleting the template later leaves
+existing stages as they are. The built-in templates are seeded on the first run.
+*/
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::PgPool;
+use tauri::{AppHandle, Runtime};
+
+use crate::altitude_bands::{self, AltitudeBand};
+use crate::init_db::lazy_pool;
+use crate::logs;
+
+pub mod api;
+pub mod sql;
+
+pub use api::{StageTemplatesApi, StageTemplatesApiImpl};
+
+const MAX_NAME_LENGTH: usize = 64;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct TemplateAltitudeBand {
+    pub min_altitude_m: f64,
+    pub max_altitude_m: f64,
+}
+
+// The stage is complete once any criterion that is set is met
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct CompletionCriteria {
+    // Share of the search area overflown
+    pub coverage_percent: Option<f64>,
+    // Time since the stage started
+    pub max_duration_mins: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub enum SearchPatternKind {
+    // Parallel legs across the whole search area
+    Lawnmower,
+    // Outward square spiral from the area's centre, for a last known position
+    ExpandingSquare,
+    // Legs radiating through the area's centre
+    Sector,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct SearchPatternSetting {
+    pub pattern: SearchPatternKind,
+    // Distance between legs; None uses sensor_footprint_width_m from the configuration
+    pub track_spacing_m: Option<f64>,
+    // Direction of the first leg, clockwise from north; None leaves it to the generator
+    pub heading_deg: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct StageTemplate {
+    pub name: String,
+    // Name given to stages created from the template
+    pub stage_name: String,
+    // Assigned to the vehicle when it has no band in the mission yet
+    pub altitude_band: Option<TemplateAltitudeBand>,
+    pub completion: Option<CompletionCriteria>,
+    pub search_pattern: Option<SearchPatternSetting>,
+}
+
+// Settings a stage was created with
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct StageSettings {
+    pub stage_id: i32,
+    pub template_name: String,
+    pub completion: Option<CompletionCriteria>,
+    pub search_pattern: Option<SearchPatternSetting>,
+}
+
+impl StageTemplate {
+    pub fn validate(&self) -> Result<(), String> {
+        let name = self.name.trim();
+        if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+            return Err(format!("Template name must be 1 to {} characters", MAX_NAME_LENGTH));
+        }
+        if self.stage_name.trim().is_empty() {
+            return Err("Template stage name must not be empty".into());
+        }
+        if let Some(band) = &self.altitude_band {
+            let finite = band.min_altitude_m.is_finite() && band.max_altitude_m.is_finite();
+            if !finite || band.min_altitude_m < 0.0 || band.max_altitude_m <= band.min_altitude_m {
+                return Err("Template altitude band must run from a non-negative minimum to a higher maximum".into());
+            }
+        }
+        if let Some(completion) = &self.completion {
+            if completion.coverage_percent.is_some_and(|p| !(p > 0.0 && p <= 100.0)) {
+                return Err("Completion coverage must be above 0 and at most 100 %".into());
+            }
+            if completion.max_duration_mins == Some(0) {
+                return Err("Completion duration must be at least a minute".into());
+            }
+        }
+        if let Some(pattern) = &self.search_pattern {
+            if pattern.track_spacing_m.is_some_and(|s| !s.is_finite() || s <= 0.0) {
+                return Err("Search pattern track spacing must be a positive distance".into());
+            }
+            if pattern.heading_deg.is_some_and(|h| !(0.0..360.0).contains(&h)) {
+                return Err("Search pattern heading must be between 0 and 360°".into());
+            }
+        }
+        Ok(())
+    }
+}
+
+// Seeded on the first run; operators edit or delete them like their own
+pub fn built_in_templates() -> Vec<StageTemplate> {
+    let search = |name: &str,
+                  stage_name: &str,
+                  pattern: SearchPatternKind,
+                  coverage_percent: Option<f64>,
+                  max_duration_mins: Option<u32>| StageTemplate {
+        name: name.to_string(),
+        stage_name: stage_name.to_string(),
+        altitude_band: None,
+        completion: Some(CompletionCriteria { coverage_percent, max_duration_mins }),
+        search_pattern: Some(SearchPatternSetting { pattern, track_spacing_m: None, heading_deg: None }),
+    };
+    vec![
+        search("Area search", "Search", SearchPatternKind::Lawnmower, Some(95.0), None),
+        search("Last known position", "Point search", SearchPatternKind::ExpandingSquare, Some(90.0), Some(20)),
+        search("Sector search", "Sector search", SearchPatternKind::Sector, None, Some(30)),
+        StageTemplate {
+            name: "Patient transport".to_string(),
+            stage_name: "Transport patient".to_string(),
+            altitude_band: None,
+            completion: None,
+            search_pattern: None,
+        },
+    ]
+}
+
+lazy_static! {
+    static ref DB: PgPool = lazy_pool(1);
+    // Every template, by name, for add_stage
+    static ref TEMPLATES: RwLock<Vec<StageTemplate>> = RwLock::new(Vec::new());
+}
+
+fn set_templates(templates: Vec<StageTemplate>) {
+    *TEMPLATES.write().unwrap() = templates;
+}
+
+pub fn get(name: &str) -> Option<StageTemplate> {
+    TEMPLATES.read().unwrap().iter().find(|t| t.name == name).cloned()
+}
+
+// Give the vehicle the template's altitude band unless it already has one in the mission;
+// done before the stage is created, so a band that overlaps another vehicle's fails add_stage
+pub async fn assign_band(
+    app_handle: &AppHandle<impl Runtime>,
+    template: &StageTemplate,
+    mission_id: i32,
+    vehicle_id: &str,
+    operator: &str,
+) -> Result<(), String> {
+    let Some(band) = &template.altitude_band else {
+        return Ok(());
+    };
+    let vehicle_id = vehicle_id.to_lowercase();
+    if altitude_bands::bands_for(mission_id).iter().any(|b| b.vehicle_id == vehicle_id) {
+        return Ok(());
+    }
+    let band = AltitudeBand {
+        mission_id,
+        vehicle_id,
+        min_altitude_m: band.min_altitude_m,
+        max_altitude_m: band.max_altitude_m,
+    };
+    let bands = altitude_bands::assign_band(DB.clone(), band, operator).await?;
+    altitude_bands::emit_bands_changed(app_handle.clone(), mission_id, &bands);
+    Ok(())
+}
+
+// Copy the template's completion criteria and search pattern onto a stage created from it
+pub async fn save_settings(template: &StageTemplate, stage_id: i32) -> Result<(), String> {
+    let settings = StageSettings {
+        stage_id,
+        template_name: template.name.clone(),
+        completion: template.completion.clone(),
+        search_pattern: template.search_pattern.clone(),
+    };
+    sql::upsert_stage_settings(DB.clone(), &settings)
+        .await
+        .map_err(|e| format!("Failed to save the settings of stage {}: {}", stage_id, e))?;
+    logs::info(
+        "stage_templates",
+        format!("Stage {} created from template {}", stage_id, template.name),
+    );
+    Ok(())
+}
@@ -0,0 +1,114 @@
+/*
+Define all stage template database functions. Completion criteria and search pattern settings
+are stored as JSON text.
+*/
+use serde::de::DeserializeOwned;
+use sqlx::postgres::PgRow;
+use sqlx::{query, query_scalar, PgPool, Row};
+
+use super::{StageSettings, StageTemplate, TemplateAltitudeBand};
+
+fn to_json<T: serde::Serialize>(value: &Option<T>) -> Option<String> {
+    value.as_ref().and_then(|v| serde_json::to_string(v).ok())
+}
+
+// A value that no longer parses (e.g. from a newer version) reads as unset
+fn from_json<T: DeserializeOwned>(row: &PgRow, column: &str) -> Option<T> {
+    row.get::<Option<String>, _>(column).and_then(|json| serde_json::from_str(&json).ok())
+}
+
+fn to_template(row: PgRow) -> StageTemplate {
+    let min_altitude: Option<f64> = row.get("min_altitude");
+    let max_altitude: Option<f64> = row.get("max_altitude");
+    StageTemplate {
+        name: row.get("name"),
+        stage_name: row.get("stage_name"),
+        altitude_band: min_altitude
+            .zip(max_altitude)
+            .map(|(min_altitude_m, max_altitude_m)| TemplateAltitudeBand { min_altitude_m, max_altitude_m }),
+        completion: from_json(&row, "completion"),
+        search_pattern: from_json(&row, "search_pattern"),
+    }
+}
+
+pub async fn select_templates(db_conn: PgPool) -> Result<Vec<StageTemplate>, sqlx::Error> {
+    let rows = query(
+        "SELECT name, stage_name, min_altitude, max_altitude, completion, search_pattern
+        FROM stage_templates
+        ORDER BY name",
+    )
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_template).collect())
+}
+
+pub async fn count_templates(db_conn: PgPool) -> Result<i64, sqlx::Error> {
+    query_scalar("SELECT COUNT(*) FROM stage_templates").fetch_one(&db_conn).await
+}
+
+pub async fn upsert_template(
+    db_conn: PgPool,
+    template: &StageTemplate,
+    updated_by: &str,
+) -> Result<(), sqlx::Error> {
+    let band = template.altitude_band.as_ref();
+    query("
+        INSERT INTO stage_templates(name, stage_name, min_altitude, max_altitude, completion, search_pattern, updated_by, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+        ON CONFLICT (name) DO UPDATE
+        SET stage_name = EXCLUDED.stage_name, min_altitude = EXCLUDED.min_altitude,
+            max_altitude = EXCLUDED.max_altitude, completion = EXCLUDED.completion,
+            search_pattern = EXCLUDED.search_pattern, updated_by = EXCLUDED.updated_by, updated_at = NOW()
+    ")
+    .bind(&template.name)
+    .bind(&template.stage_name)
+    .bind(band.map(|b| b.min_altitude_m))
+    .bind(band.map(|b| b.max_altitude_m))
+    .bind(to_json(&template.completion))
+    .bind(to_json(&template.search_pattern))
+    .bind(updated_by)
+    .execute(&db_conn)
+    .await?;
+    Ok(())
+}
+
+// Returns whether a template was removed
+pub async fn delete_template(db_conn: PgPool, name: &str) -> Result<bool, sqlx::Error> {
+    let result = query("DELETE FROM stage_templates WHERE name = $1")
+        .bind(name)
+        .execute(&db_conn)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn upsert_stage_settings(db_conn: PgPool, settings: &StageSettings) -> Result<(), sqlx::Error> {
+    query("
+        INSERT INTO stage_settings(stage_id, template_name, completion, search_pattern)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (stage_id) DO UPDATE
+        SET template_name = EXCLUDED.template_name, completion = EXCLUDED.completion,
+            search_pattern = EXCLUDED.search_pattern
+    ")
+    .bind(settings.stage_id)
+    .bind(&settings.template_name)
+    .bind(to_json(&settings.completion))
+    .bind(to_json(&settings.search_pattern))
+    .execute(&db_conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn select_stage_settings(db_conn: PgPool, stage_id: i32) -> Result<Option<StageSettings>, sqlx::Error> {
+    let row = query("SELECT stage_id, template_name, completion, search_pattern FROM stage_settings WHERE stage_id = $1")
+        .bind(stage_id)
+        .fetch_optional(&db_conn)
+        .await?;
+
+    Ok(row.map(|row| StageSettings {
+        stage_id: row.get("stage_id"),
+        template_name: row.get("template_name"),
+        completion: from_json(&row, "completion"),
+        search_pattern: from_json(&row, "search_pattern"),
+    }))
+}
@@ -0,0 +1,35 @@
+/*
+Define the startup API: the preflight report as an event and an on-demand getter for
+frontends that start listening after the event was sent, and the bindings version check.
+*/
+use taurpc::{procedures, resolvers};
+
+use super::StartupReport;
+
+#[procedures(
+    event_trigger = StartupEventTrigger,
+    export_to = "../src/lib/bindings.ts",
+    path = "startup"
+)]
+pub trait StartupApi {
+    #[taurpc(event)]
+    async fn on_startup_report(report: StartupReport);
+
+    async fn get_startup_report() -> StartupReport;
+    // Called by the frontend on load with the BINDINGS_VERSION it was built with
+    async fn check_bindings(frontend_version: u32) -> Result<(), String>;
+}
+
+#[derive(Clone, Default)]
+pub struct StartupApiImpl;
+
+#[resolvers]
+impl StartupApi for StartupApiImpl {
+    async fn get_startup_report(self) -> StartupReport {
+        super::report()
+    }
+
+    async fn check_bindings(self, frontend_version: u32) -> Result<(), String> {
+        super::check_bindings(frontend_version)
+    }
+}
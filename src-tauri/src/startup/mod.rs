@@ -0,0 +1,362 @@
+/*
+Startup preflight: checks each external dependency in order (environment file, config,
+database, migrations, broker, queues) and records the outcome as a structured report
+instead of panicking, so the UI can show exactly which dependency is missing.
+*/
+use std::sync::Mutex;
+use std::time::Duration;
+use lapin::{options::QueueDeclareOptions, types::FieldTable, Connection, ConnectionProperties};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::{postgres::PgConnection, Connection as _, Row};
+use tokio::time::timeout;
+
+use crate::audit::{self, AuditAction, AUTOMATIC_OPERATOR};
+use crate::config::{self, topology, GcsConfig};
+use crate::init_db::{initialize_database, lazy_pool, REQUIRED_TABLES};
+use crate::logs;
+use crate::missions::reconcile;
+use crate::targets::DETECTION_QUEUE;
+use crate::weather::provider::Provider;
+
+pub mod api;
+
+pub use api::{StartupApi, StartupApiImpl, StartupEventTrigger};
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+// Shape of the TauRPC bindings; bump whenever a procedure, event or exported type changes
+pub const BINDINGS_VERSION: u32 = 46;
+// Written next to bindings.ts so the frontend is built against the version it was generated for
+const BINDINGS_VERSION_EXPORT: &str = "../src/lib/bindingsVersion.ts";
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct StartupCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, Type)]
+pub struct StartupReport {
+    pub ok: bool,
+    pub checks: Vec<StartupCheck>,
+    pub generated_at: String,
+}
+
+lazy_static! {
+    static ref REPORT: Mutex<StartupReport> = Mutex::new(StartupReport::default());
+}
+
+pub fn report() -> StartupReport {
+    REPORT.lock().unwrap().clone()
+}
+
+// TauRPC exports bindings.ts in debug builds only, so the version file follows the same rule
+pub fn export_bindings_version() {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let contents = format!(
+        "// Generated by the backend together with bindings.ts; do not edit\n\
+         export const BINDINGS_VERSION = {};\n",
+        BINDINGS_VERSION
+    );
+    if let Err(e) = std::fs::write(BINDINGS_VERSION_EXPORT, contents) {
+        logs::warn("startup", format!("Failed to export bindings version: {}", e));
+    }
+}
+
+pub fn check_bindings(frontend_version: u32) -> Result<(), String> {
+    if frontend_version == BINDINGS_VERSION {
+        return Ok(());
+    }
+    let message = format!(
+        "Frontend was built against bindings version {} but the backend serves version {}; \
+         regenerate the bindings and rebuild the frontend",
+        frontend_version, BINDINGS_VERSION
+    );
+    logs::error("startup", message.clone());
+    Err(message)
+}
+
+#[derive(Default)]
+pub struct Preflight {
+    checks: Vec<StartupCheck>,
+}
+
+impl Preflight {
+    fn record(&mut self, name: &str, result: Result<String, String>) -> bool {
+        let passed = result.is_ok();
+        let detail = result.unwrap_or_else(|e| e);
+        if passed {
+            logs::info("startup", format!("{}: {}", name, detail));
+        } else {
+            logs::error("startup", format!("{} failed: {}", name, detail));
+        }
+        self.checks.push(StartupCheck {
+            name: name.to_string(),
+            passed,
+            detail,
+        });
+        passed
+    }
+
+    pub fn check_env_file(&mut self, loaded: Result<(), String>) -> bool {
+        self.record(
+            "Environment file",
+            loaded.map(|_| "Loaded .env".to_string()),
+        )
+    }
+
+    pub fn check_config(&mut self) -> bool {
+        self.record("Configuration", validate_config(&config::get()))
+    }
+
+    pub async fn check_database(&mut self) -> bool {
+        let url = config::get().database_url;
+        let result = match timeout(CHECK_TIMEOUT, PgConnection::connect(&url)).await {
+            Ok(Ok(connection)) => {
+                let _ = connection.close().await;
+                Ok("Database reachable".to_string())
+            }
+            Ok(Err(e)) => Err(format!("Cannot connect to database: {}", e)),
+            Err(_) => Err("Timed out connecting to database".to_string()),
+        };
+        self.record("Database", result)
+    }
+
+    // Create missing tables, then confirm every required table exists
+    pub async fn apply_migrations(&mut self) -> bool {
+        let result = match initialize_database().await {
+            Ok(()) => verify_tables().await,
+            Err(e) => Err(format!("Failed to create tables: {}", e)),
+        };
+        self.record("Migrations", result)
+    }
+
+    // Repair stage progress an unclean shutdown left inconsistent, before missions are loaded;
+    // each repair is also written to the audit log
+    pub async fn reconcile_missions(&mut self) -> bool {
+        let db = lazy_pool(1);
+        let result = match reconcile::reconcile(db.clone()).await {
+            Ok(repairs) if repairs.is_empty() => Ok("Mission progress consistent".to_string()),
+            Ok(repairs) => {
+                for repair in &repairs {
+                    if let Err(e) = audit::record_as(
+                        db.clone(),
+                        AUTOMATIC_OPERATOR,
+                        AuditAction::StateReconciled,
+                        Some(repair.mission_id),
+                        "Inconsistent after restart",
+                        &repair.detail,
+                    )
+                    .await
+                    {
+                        logs::error("startup", e);
+                    }
+                }
+                let details: Vec<&str> = repairs.iter().map(|r| r.detail.as_str()).collect();
+                Ok(format!("Repaired {}: {}", repairs.len(), details.join("; ")))
+            }
+            Err(e) => Err(format!("Failed to reconcile mission progress: {}", e)),
+        };
+        self.record("Mission recovery", result)
+    }
+
+    pub async fn check_broker(&mut self) -> bool {
+        let config = config::get();
+        let connection = match timeout(
+            CHECK_TIMEOUT,
+            Connection::connect(&config.amqp_url, ConnectionProperties::default()),
+        )
+        .await
+        {
+            Ok(Ok(connection)) => connection,
+            Ok(Err(e)) => return self.record("Message broker", Err(format!("Cannot connect to RabbitMQ: {}", e))),
+            Err(_) => return self.record("Message broker", Err("Timed out connecting to RabbitMQ".to_string())),
+        };
+        self.record("Message broker", Ok("RabbitMQ reachable".to_string()));
+
+        let queues_result = declare_queues(&connection, &config).await;
+        let _ = connection.close(0, "").await;
+        self.record("Queues", queues_result)
+    }
+
+    pub fn skip(&mut self, name: &str, reason: &str) {
+        self.record(name, Err(format!("Skipped: {}", reason)));
+    }
+
+    // Store the report so the frontend can fetch it once it is listening
+    pub fn finish(self) -> StartupReport {
+        let report = StartupReport {
+            ok: self.checks.iter().all(|c| c.passed),
+            checks: self.checks,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+        };
+        *REPORT.lock().unwrap() = report.clone();
+        report
+    }
+}
+
+fn validate_config(config: &GcsConfig) -> Result<String, String> {
+    let mut problems = vec![];
+    if !config.database_url.starts_with("postgres://") && !config.database_url.starts_with("postgresql://") {
+        problems.push("database_url must be a postgres:// URL".to_string());
+    }
+    if !config.amqp_url.starts_with("amqp://") && !config.amqp_url.starts_with("amqps://") {
+        problems.push("amqp_url must be an amqp:// URL".to_string());
+    }
+    if config.vehicles.is_empty() {
+        problems.push("vehicle roster is empty".to_string());
+    }
+    if config.heartbeat_check_interval_secs == 0 {
+        problems.push("heartbeat_check_interval_secs must be at least 1".to_string());
+    }
+    if config.heartbeat_timeout_secs <= config.heartbeat_check_interval_secs {
+        problems.push("heartbeat_timeout_secs must exceed heartbeat_check_interval_secs".to_string());
+    }
+    if !config.stale_telemetry_secs.is_finite() || config.stale_telemetry_secs <= 0.0 {
+        problems.push("stale_telemetry_secs must be a positive number".to_string());
+    }
+    if config.max_zone_vertices < 3 {
+        problems.push("max_zone_vertices must be at least 3".to_string());
+    }
+    if !config.sensor_footprint_width_m.is_finite() || config.sensor_footprint_width_m <= 0.0 {
+        problems.push("sensor_footprint_width_m must be a positive number".to_string());
+    }
+    if !config.route_clearance_m.is_finite() || config.route_clearance_m < 0.0 {
+        problems.push("route_clearance_m must be a non-negative number".to_string());
+    }
+    if !config.min_terrain_clearance_m.is_finite() || config.min_terrain_clearance_m < 0.0 {
+        problems.push("min_terrain_clearance_m must be a non-negative number".to_string());
+    }
+    if !["{z}", "{x}", "{y}"].iter().all(|p| config.tile_source_url.contains(p)) {
+        problems.push("tile_source_url must contain {z}, {x} and {y}".to_string());
+    }
+    if let Err(e) = Provider::parse(&config.weather_provider) {
+        problems.push(e);
+    }
+    if config.weather_poll_interval_secs == 0 {
+        problems.push("weather_poll_interval_secs must be at least 1".to_string());
+    }
+    if [config.max_wind_speed_ms, config.max_wind_gust_ms, config.max_precipitation_mm]
+        .iter()
+        .any(|limit| !limit.is_finite() || *limit < 0.0)
+    {
+        problems.push("weather limits must be non-negative numbers".to_string());
+    }
+    if !config.adsb_feed_addr.is_empty() && !config.adsb_feed_addr.contains(':') {
+        problems.push("adsb_feed_addr must be host:port".to_string());
+    }
+    if [config.adsb_alert_distance_m, config.adsb_alert_altitude_m]
+        .iter()
+        .any(|limit| !limit.is_finite() || *limit <= 0.0)
+    {
+        problems.push("ADS-B alert distances must be positive numbers".to_string());
+    }
+    if !(1..=50).contains(&config.manual_control_rate_hz) {
+        problems.push("manual_control_rate_hz must be between 1 and 50".to_string());
+    }
+    if !config.rest_api_addr.is_empty() && config.rest_api_addr.parse::<std::net::SocketAddr>().is_err() {
+        problems.push("rest_api_addr must be an ip:port address".to_string());
+    }
+    if !config.telemetry_ws_addr.is_empty() && config.telemetry_ws_addr.parse::<std::net::SocketAddr>().is_err() {
+        problems.push("telemetry_ws_addr must be an ip:port address".to_string());
+    }
+    if !config.mqtt_vehicles.is_empty() {
+        if config.mqtt_url.is_empty() {
+            problems.push("mqtt_url must be set when mqtt_vehicles is not empty".to_string());
+        } else if let Err(e) = crate::mqtt::options(&config.mqtt_url) {
+            problems.push(e);
+        }
+        if let Some(vehicle) = config.mqtt_vehicles.iter().find(|v| !config.vehicles.contains(v)) {
+            problems.push(format!("mqtt_vehicles contains {}, which is not in vehicles", vehicle));
+        }
+        if ![&config.mqtt_telemetry_topic, &config.mqtt_command_topic]
+            .iter()
+            .all(|topic| topic.contains("{vehicle}"))
+        {
+            problems.push("MQTT topics must contain {vehicle}".to_string());
+        }
+    }
+    if !config.serial_port.is_empty() && config.serial_baud_rate == 0 {
+        problems.push("serial_baud_rate must be positive".to_string());
+    }
+    problems.extend(topology::validate(config));
+    if !config.mission_sync_station.is_empty() && config.mission_sync_exchange.is_empty() {
+        problems.push("mission_sync_exchange must be set when mission sync is enabled".to_string());
+    }
+    if !config.ops_feed_webhook_url.is_empty()
+        && !reqwest::Url::parse(&config.ops_feed_webhook_url)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    {
+        problems.push("ops_feed_webhook_url must be an http(s) URL".to_string());
+    }
+    if !config.geofence_warning_distance_m.is_finite() || config.geofence_warning_distance_m < 0.0 {
+        problems.push("geofence_warning_distance_m must be a non-negative number".to_string());
+    }
+
+    if problems.is_empty() {
+        Ok(format!("Configuration valid ({} vehicles)", config.vehicles.len()))
+    } else {
+        Err(problems.join("; "))
+    }
+}
+
+async fn verify_tables() -> Result<String, String> {
+    let mut connection = PgConnection::connect(&config::get().database_url)
+        .await
+        .map_err(|e| format!("Cannot connect to database: {}", e))?;
+    let rows = sqlx::query(
+        "SELECT table_name::TEXT AS table_name FROM information_schema.tables WHERE table_schema = 'public'",
+    )
+    .fetch_all(&mut connection)
+    .await
+    .map_err(|e| format!("Failed to list tables: {}", e))?;
+    let _ = connection.close().await;
+
+    let existing: Vec<String> = rows.iter().map(|row| row.get("table_name")).collect();
+    let missing: Vec<&str> = REQUIRED_TABLES
+        .iter()
+        .copied()
+        .filter(|table| !existing.iter().any(|t| t == table))
+        .collect();
+    if missing.is_empty() {
+        Ok(format!("All {} tables present", REQUIRED_TABLES.len()))
+    } else {
+        Err(format!("Missing tables: {}", missing.join(", ")))
+    }
+}
+
+async fn declare_queues(connection: &Connection, config: &GcsConfig) -> Result<String, String> {
+    let channel = connection
+        .create_channel()
+        .await
+        .map_err(|e| format!("Failed to open channel: {}", e))?;
+
+    let telemetry_queues = topology::telemetry_queues(config, &config.vehicles);
+    for queue in &telemetry_queues {
+        topology::declare_telemetry_queue(&channel, config, queue)
+            .await
+            .map_err(|e| format!("Failed to declare queue {}: {}", queue.queue, e))?;
+    }
+    // Commands to every vehicle share a route unless the routing key names the vehicle
+    for vehicle in &config.vehicles {
+        topology::declare_command_route(&channel, config, vehicle)
+            .await
+            .map_err(|e| format!("Failed to declare the command route for {}: {}", vehicle, e))?;
+    }
+    channel
+        .queue_declare(
+            DETECTION_QUEUE,
+            QueueDeclareOptions {
+                durable: true,
+                ..Default::default()
+            },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| format!("Failed to declare queue {}: {}", DETECTION_QUEUE, e))?;
+    Ok(format!("Declared {} telemetry queues", telemetry_queues.len()))
+}
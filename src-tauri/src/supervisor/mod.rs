@@ -0,0 +1,136 @@
+/*
+Background task supervisor. Every long-running task (telemetry consumers, heartbeat monitor,
+command queue worker, health monitor) is spawned through here so a task that fails or panics
+is restarted with exponential backoff instead of silently dying. Task status is reported
+through the health endpoint.
+*/
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tokio::task::AbortHandle;
+
+use crate::clock;
+use crate::logs;
+
+const RESTART_BACKOFF_BASE: Duration = Duration::from_secs(1);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(60);
+// A task that ran this long before failing starts over from the base backoff
+const HEALTHY_RUN_RESET: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum TaskState {
+    Running,
+    Restarting,
+    Finished,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    // Restart only when the task returns an error or panics
+    OnFailure,
+    // Also restart when the task returns normally (e.g. a consumer stream that ended)
+    Always,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct TaskStatus {
+    pub name: String,
+    pub state: TaskState,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    pub started_at: String,
+}
+
+lazy_static! {
+    static ref TASKS: Mutex<HashMap<String, TaskStatus>> = Mutex::new(HashMap::new());
+    // Current run of every task, so it can be killed for fault injection
+    static ref RUNS: Mutex<HashMap<String, AbortHandle>> = Mutex::new(HashMap::new());
+}
+
+fn set_status(name: &str, update: impl FnOnce(&mut TaskStatus)) {
+    let mut tasks = TASKS.lock().unwrap();
+    let status = tasks.entry(name.to_string()).or_insert_with(|| TaskStatus {
+        name: name.to_string(),
+        state: TaskState::Running,
+        restarts: 0,
+        last_error: None,
+        started_at: chrono::Utc::now().to_rfc3339(),
+    });
+    update(status);
+}
+
+// Spawn a supervised task. `factory` builds a fresh future for every (re)start.
+pub fn spawn<F, Fut>(name: &str, policy: RestartPolicy, factory: F)
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+{
+    let name = name.to_string();
+    tauri::async_runtime::spawn(async move {
+        let mut backoff = RESTART_BACKOFF_BASE;
+        loop {
+            set_status(&name, |status| {
+                status.state = TaskState::Running;
+                status.started_at = chrono::Utc::now().to_rfc3339();
+            });
+            let started = clock::now();
+
+            // Run in its own task so a panic is caught as a JoinError
+            let run = tokio::spawn(factory());
+            RUNS.lock().unwrap().insert(name.clone(), run.abort_handle());
+            let outcome = match run.await {
+                Ok(Ok(())) => None,
+                Ok(Err(e)) => Some(e),
+                Err(e) if e.is_cancelled() => Some("task killed".to_string()),
+                Err(e) => Some(format!("task panicked: {}", e)),
+            };
+            RUNS.lock().unwrap().remove(&name);
+
+            if outcome.is_none() && policy == RestartPolicy::OnFailure {
+                set_status(&name, |status| status.state = TaskState::Finished);
+                logs::info("supervisor", format!("Task {} finished", name));
+                return;
+            }
+
+            if started.elapsed() >= HEALTHY_RUN_RESET {
+                backoff = RESTART_BACKOFF_BASE;
+            }
+            let reason = outcome.unwrap_or_else(|| "task exited".to_string());
+            logs::error(
+                "supervisor",
+                format!("Task {} stopped ({}); restarting in {:?}", name, reason, backoff),
+            );
+            set_status(&name, |status| {
+                status.state = TaskState::Restarting;
+                status.restarts = status.restarts.saturating_add(1);
+                status.last_error = Some(reason);
+            });
+
+            clock::sleep(backoff).await;
+            backoff = (backoff * 2).min(RESTART_BACKOFF_MAX);
+        }
+    });
+}
+
+// Abort the current run of a task as if it had crashed; its restart policy then applies
+pub fn kill(name: &str) -> Result<(), String> {
+    let run = RUNS.lock().unwrap().remove(name);
+    match run {
+        Some(run) => {
+            run.abort();
+            Ok(())
+        }
+        None => Err(format!("Task {} is not running", name)),
+    }
+}
+
+// Status of every supervised task, sorted by name
+pub fn statuses() -> Vec<TaskStatus> {
+    let mut tasks: Vec<TaskStatus> = TASKS.lock().unwrap().values().cloned().collect();
+    tasks.sort_by(|a, b| a.name.cmp(&b.name));
+    tasks
+}
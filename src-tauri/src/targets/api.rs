@@ -0,0 +1,197 @@
+/*
+Define the targets API: casualty / target records, their status progression and stage links.
+*/
+use sqlx::PgPool;
+use tauri::{AppHandle, Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, current_operator, OperatorRole};
+use crate::init_db::lazy_pool;
+use crate::logs;
+use super::sql::{delete_target, insert_target, select_target, select_targets, update_target, update_target_status};
+use super::{Target, TargetAction, TargetChange, TargetInput, TargetStatus};
+
+#[procedures(
+    event_trigger = TargetsEventTrigger,
+    export_to = "../src/lib/bindings.ts",
+    path = "targets"
+)]
+pub trait TargetsApi {
+    #[taurpc(event)]
+    async fn on_target_changed(change: TargetChange);
+
+    async fn list_targets(mission_id: Option<i32>) -> Result<Vec<Target>, String>;
+    async fn create_target(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        input: TargetInput,
+    ) -> Result<Target, String>;
+    async fn update_target(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        target_id: i32,
+        input: TargetInput,
+    ) -> Result<Target, String>;
+    // Status only moves forward: Detected -> Confirmed -> Secured -> Delivered
+    async fn set_target_status(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        target_id: i32,
+        status: TargetStatus,
+    ) -> Result<Target, String>;
+    async fn link_target_stage(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        target_id: i32,
+        stage_id: Option<i32>,
+    ) -> Result<Target, String>;
+    async fn delete_target(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        target_id: i32,
+    ) -> Result<(), String>;
+}
+
+#[derive(Clone)]
+pub struct TargetsApiImpl {
+    db: PgPool,
+}
+
+impl TargetsApiImpl {
+    pub async fn new() -> Self {
+        Self { db: lazy_pool(2) }
+    }
+
+    async fn find(&self, target_id: i32) -> Result<Target, String> {
+        select_target(self.db.clone(), target_id)
+            .await
+            .map_err(|e| format!("Failed to load target: {}", e))?
+            .ok_or(format!("Target {} not found", target_id))
+    }
+
+    fn emit(&self, app_handle: AppHandle<impl Runtime>, action: TargetAction, target: &Target) {
+        let change = TargetChange {
+            action,
+            target: target.clone(),
+        };
+        if let Err(e) = TargetsEventTrigger::new(app_handle).on_target_changed(change) {
+            logs::warn("targets", format!("Failed to emit target change: {}", e));
+        }
+    }
+
+    async fn save(
+        &self,
+        app_handle: AppHandle<impl Runtime>,
+        target_id: i32,
+        input: TargetInput,
+    ) -> Result<Target, String> {
+        input.validate()?;
+        let target = update_target(self.db.clone(), target_id, &input)
+            .await
+            .map_err(|e| format!("Failed to update target: {}", e))?
+            .ok_or(format!("Target {} not found", target_id))?;
+        self.emit(app_handle, TargetAction::Updated, &target);
+        Ok(target)
+    }
+}
+
+#[resolvers]
+impl TargetsApi for TargetsApiImpl {
+    async fn list_targets(self, mission_id: Option<i32>) -> Result<Vec<Target>, String> {
+        select_targets(self.db.clone(), mission_id)
+            .await
+            .map_err(|e| format!("Failed to load targets: {}", e))
+    }
+
+    async fn create_target(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        input: TargetInput,
+    ) -> Result<Target, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            input.validate()?;
+            let target = insert_target(self.db.clone(), &input, &current_operator())
+                .await
+                .map_err(|e| format!("Failed to save target: {}", e))?;
+            self.emit(app_handle, TargetAction::Created, &target);
+            Ok(target)
+        })
+        .await
+    }
+
+    async fn update_target(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        target_id: i32,
+        input: TargetInput,
+    ) -> Result<Target, String> {
+        authorized(&window, OperatorRole::Operator, self.save(app_handle, target_id, input)).await
+    }
+
+    async fn set_target_status(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        target_id: i32,
+        status: TargetStatus,
+    ) -> Result<Target, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let current = self.find(target_id).await?;
+            if status < current.status {
+                return Err(format!(
+                    "Target {} is already {}; status cannot go back to {}",
+                    target_id,
+                    current.status.name(),
+                    status.name()
+                ));
+            }
+            let target = update_target_status(self.db.clone(), target_id, status)
+                .await
+                .map_err(|e| format!("Failed to update target status: {}", e))?
+                .ok_or(format!("Target {} not found", target_id))?;
+            self.emit(app_handle, TargetAction::Updated, &target);
+            Ok(target)
+        })
+        .await
+    }
+
+    async fn link_target_stage(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        target_id: i32,
+        stage_id: Option<i32>,
+    ) -> Result<Target, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let current = self.find(target_id).await?;
+            let input = TargetInput {
+                mission_id: current.mission_id,
+                stage_id,
+                label: current.label,
+                lat: current.lat,
+                long: current.long,
+            };
+            self.save(app_handle, target_id, input).await
+        })
+        .await
+    }
+
+    async fn delete_target(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        target_id: i32,
+    ) -> Result<(), String> {
+        authorized(&window, OperatorRole::MissionCommander, async move {
+            let target = delete_target(self.db.clone(), target_id)
+                .await
+                .map_err(|e| format!("Failed to delete target: {}", e))?
+                .ok_or(format!("Target {} not found", target_id))?;
+            self.emit(app_handle, TargetAction::Deleted, &target);
+            Ok(())
+        })
+        .await
+    }
+}
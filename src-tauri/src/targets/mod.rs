@@ -0,0 +1,194 @@
+/*
+Casualty / target records. A target is created when a vehicle reports a detection on the
+vehicle_detections queue (or manually by an operator) and then moves through
+Detected -> Confirmed -> Secured -> Delivered as the mission progresses. Records can be
+linked to the mission stage that handles them.
+*/
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::PgPool;
+use tauri::AppHandle;
+
+use crate::logs;
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use crate::telemetry::geos::{harversine_distance, Coordinate};
+
+pub mod api;
+pub mod sql;
+
+pub use api::{TargetsApi, TargetsApiImpl, TargetsEventTrigger};
+
+// Queue vehicles publish detections to
+pub const DETECTION_QUEUE: &str = "vehicle_detections";
+// Detections this close (m) to an open target of the same mission update it instead of
+// creating a duplicate record
+const DETECTION_MERGE_DISTANCE_M: f64 = 15.0;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Type)]
+pub enum TargetStatus {
+    Detected,
+    Confirmed,
+    Secured,
+    Delivered,
+}
+
+impl TargetStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TargetStatus::Detected => "Detected",
+            TargetStatus::Confirmed => "Confirmed",
+            TargetStatus::Secured => "Secured",
+            TargetStatus::Delivered => "Delivered",
+        }
+    }
+
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "Confirmed" => TargetStatus::Confirmed,
+            "Secured" => TargetStatus::Secured,
+            "Delivered" => TargetStatus::Delivered,
+            _ => TargetStatus::Detected,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct Target {
+    pub target_id: i32,
+    pub mission_id: Option<i32>,
+    pub stage_id: Option<i32>,
+    pub label: String,
+    pub lat: f64,
+    pub long: f64,
+    pub status: TargetStatus,
+    // Uppercase vehicle id, or the operator for manually created records
+    pub discovered_by: String,
+    pub discovered_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct TargetInput {
+    pub mission_id: Option<i32>,
+    pub stage_id: Option<i32>,
+    pub label: String,
+    pub lat: f64,
+    pub long: f64,
+}
+
+impl TargetInput {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-90.0..=90.0).contains(&self.lat) || !(-180.0..=180.0).contains(&self.long) {
+            return Err("Target position must be a valid latitude/longitude".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum TargetAction {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct TargetChange {
+    pub action: TargetAction,
+    pub target: Target,
+}
+
+// Detection message published by a vehicle's onboard classifier
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DetectionMessage {
+    pub vehicle_id: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+pub fn emit_change(app_handle: &Option<AppHandle>, action: TargetAction, target: &Target) {
+    let Some(app_handle) = app_handle else { return };
+    let change = TargetChange {
+        action,
+        target: target.clone(),
+    };
+    if let Err(e) = TargetsEventTrigger::new(app_handle.clone()).on_target_changed(change) {
+        logs::warn("targets", format!("Failed to emit target change: {}", e));
+    }
+}
+
+// Turn a detection into a target record, merging it into a nearby open target if one exists
+pub async fn record_detection(
+    db: PgPool,
+    app_handle: &Option<AppHandle>,
+    detection: DetectionMessage,
+    mission_id: Option<i32>,
+) -> Result<Target, String> {
+    let input = TargetInput {
+        mission_id,
+        stage_id: None,
+        label: detection
+            .label
+            .clone()
+            .unwrap_or_else(|| "Casualty".to_string()),
+        lat: detection.latitude,
+        long: detection.longitude,
+    };
+    input.validate()?;
+
+    let point = Coordinate {
+        latitude: detection.latitude,
+        longitude: detection.longitude,
+    };
+    let open_targets = sql::select_targets(db.clone(), mission_id)
+        .await
+        .map_err(|e| format!("Failed to load targets: {}", e))?;
+    let nearby = open_targets.into_iter().find(|t| {
+        t.mission_id == mission_id
+            && t.status < TargetStatus::Delivered
+            && harversine_distance(&point, &Coordinate { latitude: t.lat, longitude: t.long })
+                <= DETECTION_MERGE_DISTANCE_M
+    });
+
+    if let Some(existing) = nearby {
+        // Repeated sightings refine the position of an unconfirmed detection only
+        if existing.status != TargetStatus::Detected {
+            return Ok(existing);
+        }
+        let refined = TargetInput {
+            stage_id: existing.stage_id,
+            label: existing.label.clone(),
+            ..input
+        };
+        let target = sql::update_target(db, existing.target_id, &refined)
+            .await
+            .map_err(|e| format!("Failed to update target: {}", e))?
+            .unwrap_or(existing);
+        emit_change(app_handle, TargetAction::Updated, &target);
+        return Ok(target);
+    }
+
+    let target = sql::insert_target(db, &input, &detection.vehicle_id.to_uppercase())
+        .await
+        .map_err(|e| format!("Failed to save target: {}", e))?;
+    logs::info(
+        "targets",
+        format!(
+            "{} detected target {} at ({}, {})",
+            target.discovered_by, target.target_id, target.lat, target.long
+        ),
+    );
+    emit_change(app_handle, TargetAction::Created, &target);
+    notifications::notify(
+        NotificationCategory::TargetDetected,
+        NotificationSeverity::Info,
+        Some(&detection.vehicle_id),
+        format!(
+            "{} detected a possible target at ({:.6}, {:.6})",
+            target.discovered_by, target.lat, target.long
+        ),
+    );
+    Ok(target)
+}
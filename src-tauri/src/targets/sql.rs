@@ -0,0 +1,137 @@
+/*
+Define all target record database functions.
+*/
+use sqlx::postgres::PgRow;
+use sqlx::{query, PgPool, Row};
+
+use super::{Target, TargetInput, TargetStatus};
+
+const TARGET_COLUMNS: &str = "
+    target_id, mission_id, stage_id, label, lat, long, status, discovered_by,
+    to_char(discovered_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS discovered_at,
+    to_char(updated_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS updated_at
+";
+
+fn to_target(row: PgRow) -> Target {
+    Target {
+        target_id: row.get("target_id"),
+        mission_id: row.get("mission_id"),
+        stage_id: row.get("stage_id"),
+        label: row.get("label"),
+        lat: row.get("lat"),
+        long: row.get("long"),
+        status: TargetStatus::parse(row.get("status")),
+        discovered_by: row.get("discovered_by"),
+        discovered_at: row.get("discovered_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+pub async fn insert_target(
+    db_conn: PgPool,
+    input: &TargetInput,
+    discovered_by: &str,
+) -> Result<Target, sqlx::Error> {
+    let row = query(&format!(
+        "INSERT INTO targets(mission_id, stage_id, label, lat, long, status, discovered_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {}",
+        TARGET_COLUMNS
+    ))
+    .bind(input.mission_id)
+    .bind(input.stage_id)
+    .bind(input.label.trim())
+    .bind(input.lat)
+    .bind(input.long)
+    .bind(TargetStatus::Detected.name())
+    .bind(discovered_by)
+    .fetch_one(&db_conn)
+    .await?;
+
+    Ok(to_target(row))
+}
+
+pub async fn update_target(
+    db_conn: PgPool,
+    target_id: i32,
+    input: &TargetInput,
+) -> Result<Option<Target>, sqlx::Error> {
+    let row = query(&format!(
+        "UPDATE targets
+        SET mission_id = $2, stage_id = $3, label = $4, lat = $5, long = $6, updated_at = NOW()
+        WHERE target_id = $1
+        RETURNING {}",
+        TARGET_COLUMNS
+    ))
+    .bind(target_id)
+    .bind(input.mission_id)
+    .bind(input.stage_id)
+    .bind(input.label.trim())
+    .bind(input.lat)
+    .bind(input.long)
+    .fetch_optional(&db_conn)
+    .await?;
+
+    Ok(row.map(to_target))
+}
+
+pub async fn update_target_status(
+    db_conn: PgPool,
+    target_id: i32,
+    status: TargetStatus,
+) -> Result<Option<Target>, sqlx::Error> {
+    let row = query(&format!(
+        "UPDATE targets SET status = $2, updated_at = NOW(),
+            secured_at = CASE WHEN $2 = 'Secured' THEN COALESCE(secured_at, NOW()) ELSE secured_at END,
+            delivered_at = CASE WHEN $2 = 'Delivered' THEN COALESCE(delivered_at, NOW()) ELSE delivered_at END
+        WHERE target_id = $1
+        RETURNING {}",
+        TARGET_COLUMNS
+    ))
+    .bind(target_id)
+    .bind(status.name())
+    .fetch_optional(&db_conn)
+    .await?;
+
+    Ok(row.map(to_target))
+}
+
+pub async fn select_target(db_conn: PgPool, target_id: i32) -> Result<Option<Target>, sqlx::Error> {
+    let row = query(&format!("SELECT {} FROM targets WHERE target_id = $1", TARGET_COLUMNS))
+        .bind(target_id)
+        .fetch_optional(&db_conn)
+        .await?;
+
+    Ok(row.map(to_target))
+}
+
+pub async fn delete_target(db_conn: PgPool, target_id: i32) -> Result<Option<Target>, sqlx::Error> {
+    let row = query(&format!(
+        "DELETE FROM targets WHERE target_id = $1 RETURNING {}",
+        TARGET_COLUMNS
+    ))
+    .bind(target_id)
+    .fetch_optional(&db_conn)
+    .await?;
+
+    Ok(row.map(to_target))
+}
+
+// All targets when mission_id is None, otherwise that mission's targets
+pub async fn select_targets(
+    db_conn: PgPool,
+    mission_id: Option<i32>,
+) -> Result<Vec<Target>, sqlx::Error> {
+    let rows = query(&format!(
+        "SELECT {}
+        FROM targets
+        WHERE $1::INTEGER IS NULL OR mission_id = $1
+        ORDER BY target_id",
+        TARGET_COLUMNS
+    ))
+    .bind(mission_id)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_target).collect())
+}
@@ -0,0 +1,83 @@
+/*
+Per-vehicle arm state as reported in telemetry. Vehicles that report an `armed` flag are
+tracked here, and a notification is raised when the state changes. Missions and stage
+transitions are interlocked on it: a vehicle that reports itself disarmed cannot start a
+mission or move to its next stage. Vehicles that never report the flag are not blocked, since
+older firmware does not send it.
+*/
+use std::collections::HashMap;
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use crate::vehicles;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct ArmState {
+    pub vehicle_id: String,
+    pub armed: bool,
+    pub changed_at: String,
+}
+
+lazy_static! {
+    // Keyed by lowercase vehicle id
+    static ref ARM_STATES: RwLock<HashMap<String, ArmState>> = RwLock::new(HashMap::new());
+}
+
+// Record the arm flag from a telemetry report, notifying when it changes
+pub fn record(vehicle_id: &str, armed: bool) {
+    let vehicle_id = vehicle_id.to_lowercase();
+    let previous = {
+        let mut states = ARM_STATES.write().unwrap();
+        let previous = states.get(&vehicle_id).map(|state| state.armed);
+        if previous != Some(armed) {
+            states.insert(
+                vehicle_id.clone(),
+                ArmState {
+                    vehicle_id: vehicle_id.clone(),
+                    armed,
+                    changed_at: chrono::Utc::now().to_rfc3339(),
+                },
+            );
+        }
+        previous
+    };
+    // The first report only establishes the state
+    if previous.is_some_and(|was_armed| was_armed != armed) {
+        notifications::notify(
+            NotificationCategory::Arming,
+            NotificationSeverity::Info,
+            Some(&vehicle_id),
+            format!(
+                "{} {}",
+                vehicles::display_name(&vehicle_id),
+                if armed { "armed" } else { "disarmed" }
+            ),
+        );
+    }
+}
+
+// None when the vehicle has not reported an arm state
+pub fn is_armed(vehicle_id: &str) -> Option<bool> {
+    ARM_STATES
+        .read()
+        .unwrap()
+        .get(&vehicle_id.to_lowercase())
+        .map(|state| state.armed)
+}
+
+pub fn arm_states() -> Vec<ArmState> {
+    let mut states: Vec<ArmState> = ARM_STATES.read().unwrap().values().cloned().collect();
+    states.sort_by(|a, b| a.vehicle_id.cmp(&b.vehicle_id));
+    states
+}
+
+// Interlock for mission starts and stage transitions
+pub fn require_armed(vehicle_id: &str) -> Result<(), String> {
+    match is_armed(vehicle_id) {
+        Some(false) => Err(format!("{} is disarmed", vehicles::display_name(vehicle_id))),
+        _ => Ok(()),
+    }
+}
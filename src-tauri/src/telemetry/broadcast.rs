@@ -0,0 +1,48 @@
+/*
+In-process fan-out of processed telemetry. Every VehicleTelemetryData update emitted to the
+frontend is also published here so other consumers (the WebSocket server) receive the same
+stream without touching the RabbitMQ consumer. Publishing is a no-op while nobody listens.
+*/
+use std::sync::Arc;
+use lazy_static::lazy_static;
+use tokio::sync::broadcast;
+
+use crate::telemetry::types::VehicleTelemetryData;
+
+// Updates buffered per subscriber before a slow one starts skipping
+const CHANNEL_CAPACITY: usize = 64;
+
+// Vehicles carried in VehicleTelemetryData
+pub const TELEMETRY_VEHICLES: [&str; 3] = ["eru", "mea", "mra"];
+
+#[derive(Debug, Clone)]
+pub struct TelemetryUpdate {
+    // Vehicle whose telemetry changed; None when every vehicle may have changed
+    pub vehicle_id: Option<String>,
+    pub telemetry: Arc<VehicleTelemetryData>,
+}
+
+impl TelemetryUpdate {
+    pub fn affects(&self, vehicle_id: &str) -> bool {
+        self.vehicle_id.as_deref().is_none_or(|id| id == vehicle_id)
+    }
+}
+
+lazy_static! {
+    static ref CHANNEL: broadcast::Sender<TelemetryUpdate> = broadcast::channel(CHANNEL_CAPACITY).0;
+}
+
+pub fn publish(vehicle_id: Option<&str>, telemetry: Arc<VehicleTelemetryData>) {
+    if CHANNEL.receiver_count() == 0 {
+        return;
+    }
+    // Only fails when the last receiver went away in the meantime
+    let _ = CHANNEL.send(TelemetryUpdate {
+        vehicle_id: vehicle_id.map(|id| id.to_string()),
+        telemetry,
+    });
+}
+
+pub fn subscribe() -> broadcast::Receiver<TelemetryUpdate> {
+    CHANNEL.subscribe()
+}
@@ -1,6 +1,14 @@
+pub mod arming;
+pub mod broadcast;
 pub mod geos;
+pub mod patient;
 pub mod publisher;
 pub mod rabbitmq;
+pub mod separation;
+pub mod signal;
+pub mod staleness;
+pub mod state;
+pub mod tagging;
 pub mod test_rabbitmq;
 pub mod types;
 pub mod sql;
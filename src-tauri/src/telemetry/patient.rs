@@ -0,0 +1,34 @@
+/*
+Patient sensor flag reported by the ERU and MEA payloads (request_coordinate.patient_secured).
+The latest reading of each vehicle is tracked here so the active mission's patient status is
+only updated when the flag changes, not on every telemetry report.
+*/
+use std::collections::HashMap;
+use std::sync::RwLock;
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+
+use crate::events::EventSink;
+
+// Vehicles that carry a patient payload
+pub const PATIENT_VEHICLES: [&str; 2] = ["eru", "mea"];
+
+lazy_static! {
+    // Keyed by lowercase vehicle id
+    static ref READINGS: RwLock<HashMap<String, bool>> = RwLock::new(HashMap::new());
+}
+
+// Mission update run when a vehicle's patient sensor flag changes
+#[async_trait]
+pub trait PatientSensorHandler: Send + Sync {
+    async fn patient_sensor_changed(&self, events: &dyn EventSink, vehicle_id: &str, secured: bool);
+}
+
+// Record a reading; true when it is the vehicle's first or differs from its last one
+pub fn record(vehicle_id: &str, secured: bool) -> bool {
+    let vehicle_id = vehicle_id.to_lowercase();
+    if !PATIENT_VEHICLES.contains(&vehicle_id.as_str()) {
+        return false;
+    }
+    READINGS.write().unwrap().insert(vehicle_id, secured) != Some(secured)
+}
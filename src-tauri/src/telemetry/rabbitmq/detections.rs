@@ -0,0 +1,41 @@
+use futures_util::stream::StreamExt;
+use lapin::{options::*, Consumer, Result as LapinResult};
+use sqlx::PgPool;
+use tauri::AppHandle;
+
+use crate::commands::CommandsApiImpl;
+use crate::faults;
+use crate::logs;
+use crate::targets::{self, DetectionMessage};
+
+// Process detection messages; each becomes (or refines) a target record of the active mission
+pub async fn process_detections(
+    mut consumer: Consumer,
+    db: PgPool,
+    app_handle: Option<AppHandle>,
+    commands: Option<CommandsApiImpl>,
+) -> LapinResult<()> {
+    while let Some(delivery) = consumer.next().await {
+        let Ok(delivery) = delivery else { continue };
+        if faults::drop_message() {
+            delivery.ack(BasicAckOptions::default()).await?;
+            continue;
+        }
+        match serde_json::from_slice::<DetectionMessage>(&faults::corrupt(&delivery.data)) {
+            Ok(detection) => {
+                let vehicle_id = detection.vehicle_id.to_uppercase();
+                let mission_id = commands.as_ref().and_then(|c| c.active_mission());
+                if let Err(e) = targets::record_detection(db.clone(), &app_handle, detection, mission_id).await {
+                    logs::error("targets", format!("Failed to record detection from {}: {}", vehicle_id, e));
+                }
+                delivery.ack(BasicAckOptions::default()).await?;
+            }
+            Err(e) => {
+                logs::warn("targets", format!("Failed to parse detection message: {}", e));
+                delivery.reject(BasicRejectOptions::default()).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
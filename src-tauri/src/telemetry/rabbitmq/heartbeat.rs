@@ -1,34 +1,54 @@
-use crate::telemetry::types::VehicleTelemetryData;
+use crate::telemetry::broadcast;
+use crate::telemetry::state::TelemetryState;
 use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
-use tauri::{AppHandle, Emitter};
+use std::time::Duration;
+use async_trait::async_trait;
 use tokio::sync::Mutex;
-use tokio::time::interval;
 
-use super::TelemetryEventTrigger;
+use crate::clock::{self, Instant};
+use crate::commands::{capabilities, ping};
+use crate::config;
+use crate::events::EventSink;
+use crate::logs;
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use crate::resync;
+use crate::vehicles;
 
 #[derive(Clone, Debug)]
 pub struct VehicleHeartbeat {
     pub last_seen: Instant,
     pub is_connected: bool,
     pub consecutive_failures: u32,
+    // When the current disconnection started; the lost-link countdown runs from here
+    pub disconnected_at: Option<Instant>,
+    pub failsafe_triggered: bool,
+}
+
+// Lost-link procedure for a vehicle that stayed disconnected past lost_link_failsafe_secs
+#[async_trait]
+pub trait LostLinkHandler: Send + Sync {
+    async fn lost_link(&self, events: &dyn EventSink, vehicle_id: &str);
 }
 
 impl VehicleHeartbeat {
     pub fn new() -> Self {
         Self {
-            last_seen: Instant::now(),
+            last_seen: clock::now(),
             is_connected: true,
             consecutive_failures: 0,
+            disconnected_at: None,
+            failsafe_triggered: false,
         }
     }
 
     pub fn update(&mut self) {
-        self.last_seen = Instant::now();
+        self.last_seen = clock::now();
         self.is_connected = true;
         self.consecutive_failures = 0;
+        self.disconnected_at = None;
+        self.failsafe_triggered = false;
     }
 
     pub fn is_timeout(&self, timeout_duration: Duration) -> bool {
@@ -38,102 +58,169 @@ impl VehicleHeartbeat {
     pub fn mark_disconnected(&mut self) {
         self.is_connected = false;
         self.consecutive_failures += 1;
+        self.disconnected_at = Some(clock::now());
+    }
+
+    // Disconnected for longer than the failsafe timeout, and the failsafe has not run yet
+    fn failsafe_due(&self, failsafe_after: Duration) -> bool {
+        !self.is_connected
+            && !self.failsafe_triggered
+            && self.disconnected_at.is_some_and(|at| at.elapsed() >= failsafe_after)
+    }
+}
+
+// Cloneable view of the heartbeat state for modules outside telemetry (e.g. commands)
+#[derive(Clone)]
+pub struct HeartbeatHandle {
+    heartbeats: Arc<Mutex<HashMap<String, VehicleHeartbeat>>>,
+    timeout: Duration,
+}
+
+impl HeartbeatHandle {
+    pub fn new(heartbeats: Arc<Mutex<HashMap<String, VehicleHeartbeat>>>, timeout: Duration) -> Self {
+        Self { heartbeats, timeout }
+    }
+
+    // Vehicle ids are matched case-insensitively ("MEA" and "mea" are the same vehicle)
+    pub async fn is_connected(&self, vehicle_id: &str) -> bool {
+        is_vehicle_connected(&vehicle_id.to_lowercase(), self.heartbeats.clone(), self.timeout).await
+    }
+
+    pub async fn any_connected(&self) -> bool {
+        let heartbeats_guard = self.heartbeats.lock().await;
+        heartbeats_guard
+            .values()
+            .any(|h| h.is_connected && !h.is_timeout(self.timeout))
     }
 }
 
-// Start the heartbeat monitoring task
-pub async fn start_heartbeat_monitor(
+// Heartbeat monitoring loop; runs until the task is stopped (spawned by the supervisor)
+pub async fn run_heartbeat_monitor(
     heartbeats: Arc<Mutex<HashMap<String, VehicleHeartbeat>>>,
-    state: Arc<Mutex<VehicleTelemetryData>>,
-    app_handle: Option<AppHandle>,
+    state: Arc<TelemetryState>,
+    events: Arc<dyn EventSink>,
     timeout: Duration,
     check_interval: Duration,
-) {
-    tokio::spawn(async move {
-        let mut interval_timer = interval(check_interval);
-
-        loop {
-            interval_timer.tick().await;
-
-            let mut heartbeats_guard = heartbeats.lock().await;
-            let mut state_guard = state.lock().await;
-            let mut status_changed = false;
-
-            for (vehicle_id, heartbeat) in heartbeats_guard.iter_mut() {
-                if heartbeat.is_timeout(timeout) && heartbeat.is_connected {
-                    println!("Vehicle {} heartbeat timeout detected", vehicle_id);
-                    heartbeat.mark_disconnected();
-
-                    // Update vehicle status in telemetry data based on vehicle_id
-                    match vehicle_id.as_str() {
-                        "eru" => {
-                            state_guard.ERU.vehicle_status = "Disconnected".to_string();
-                            status_changed = true;
-                        }
-                        "mea" => {
-                            state_guard.MEA.vehicle_status = "Disconnected".to_string();
-                            status_changed = true;
-                        }
-                        "mra" => {
-                            state_guard.MRA.vehicle_status = "Disconnected".to_string();
-                            status_changed = true;
-                        }
-                        _ => {
-                            println!("Unknown vehicle_id: {}", vehicle_id);
-                        }
-                    }
+    last_tick: Arc<Mutex<Option<Instant>>>,
+    lost_link: Option<Arc<dyn LostLinkHandler>>,
+) -> Result<(), String> {
+    let mut interval_timer = clock::interval(check_interval);
 
-                    if status_changed {
-                        println!(
-                            "Vehicle {} marked as disconnected after {} seconds of no data",
-                            vehicle_id,
-                            timeout.as_secs()
-                        );
-                    }
+    loop {
+        interval_timer.tick().await;
+        // Liveness marker reported by the health endpoint
+        *last_tick.lock().await = Some(clock::now());
+
+        let mut heartbeats_guard = heartbeats.lock().await;
+        let mut status_changed = false;
+        // Read every tick so the timeout can be changed at runtime
+        let failsafe_secs = config::get().lost_link_failsafe_secs;
+        let mut lost_links = vec![];
+
+        for (vehicle_id, heartbeat) in heartbeats_guard.iter_mut() {
+            if heartbeat.is_timeout(timeout) && heartbeat.is_connected {
+                println!("Vehicle {} heartbeat timeout detected", vehicle_id);
+                heartbeat.mark_disconnected();
+                ping::reset(vehicle_id);
+                capabilities::forget(vehicle_id);
+
+                // Update vehicle status in telemetry data based on vehicle_id
+                if state.update(vehicle_id, |t| {
+                    t.vehicle_status = "Disconnected".to_string();
+                    t.link_latency_ms = None;
+                }) {
+                    status_changed = true;
+                } else {
+                    println!("Unknown vehicle_id: {}", vehicle_id);
                 }
+
+                if status_changed {
+                    println!(
+                        "Vehicle {} marked as disconnected after {} seconds of no data",
+                        vehicle_id,
+                        timeout.as_secs()
+                    );
+                    notifications::raise(
+                        NotificationCategory::Disconnect,
+                        NotificationSeverity::Critical,
+                        vehicle_id,
+                        match failsafe_secs {
+                            0 => format!(
+                                "{} disconnected: no telemetry for {} seconds",
+                                vehicles::display_name(vehicle_id),
+                                timeout.as_secs()
+                            ),
+                            secs => format!(
+                                "{} disconnected: no telemetry for {} seconds; lost-link failsafe in {} seconds",
+                                vehicles::display_name(vehicle_id),
+                                timeout.as_secs(),
+                                secs
+                            ),
+                        },
+                    );
+                }
+            }
+
+            if failsafe_secs > 0 && heartbeat.failsafe_due(Duration::from_secs(failsafe_secs.into())) {
+                heartbeat.failsafe_triggered = true;
+                lost_links.push(vehicle_id.clone());
+            }
+        }
+        drop(heartbeats_guard); // Release the lock before emitting
+
+        for vehicle_id in lost_links {
+            logs::warn("telemetry", format!("Lost-link failsafe triggered for {}", vehicle_id));
+            notifications::raise(
+                NotificationCategory::LostLink,
+                NotificationSeverity::Critical,
+                &vehicle_id,
+                format!(
+                    "{} lost link for {} seconds; failsafe procedure started",
+                    vehicles::display_name(&vehicle_id),
+                    failsafe_secs
+                ),
+            );
+            if let Some(handler) = &lost_link {
+                handler.lost_link(events.as_ref(), &vehicle_id).await;
             }
+        }
 
-            // If any status changed, emit update
-            if status_changed {
-                if let Some(app_handle) = &app_handle {
-                    let vehicle_telemetry = state_guard.clone();
-                    drop(state_guard); // Release the lock before emitting
-                    drop(heartbeats_guard); // Release the lock before emitting
-
-                    // Try to emit via TelemetryEventTrigger first
-                    match TelemetryEventTrigger::new(app_handle.clone())
-                        .on_updated(vehicle_telemetry.clone())
-                    {
-                        Ok(_) => {
-                            println!("Successfully emitted heartbeat status update via event trigger");
-                        }
-                        Err(e) => {
-                            println!(
-                                "Failed to emit heartbeat status update via event trigger: {}",
-                                e
-                            );
-
-                            // Fallback to regular app_handle emit
-                            let payload = json!({
-                                "type": "heartbeat_update",
-                                "telemetry": vehicle_telemetry
-                            });
-                            if let Err(e) = app_handle.emit("telemetry_update", &payload) {
-                                println!("Failed to emit heartbeat status update: {}", e);
-                            }
-                        }
+        // If any status changed, emit update
+        if status_changed {
+            let vehicle_telemetry = state.snapshot();
+            broadcast::publish(None, vehicle_telemetry.clone());
+            resync::record_telemetry();
+
+            // Try to emit via TelemetryEventTrigger first
+            match events.telemetry_updated(vehicle_telemetry.clone()) {
+                Ok(_) => {
+                    println!("Successfully emitted heartbeat status update via event trigger");
+                }
+                Err(e) => {
+                    println!(
+                        "Failed to emit heartbeat status update via event trigger: {}",
+                        e
+                    );
+
+                    // Fallback to a plain telemetry_update event
+                    let payload = json!({
+                        "type": "heartbeat_update",
+                        "telemetry": vehicle_telemetry
+                    });
+                    if let Err(e) = events.emit_json("telemetry_update", payload) {
+                        println!("Failed to emit heartbeat status update: {}", e);
                     }
                 }
             }
         }
-    });
+    }
 }
 
 // Update heartbeat for a vehicle
 pub async fn update_vehicle_heartbeat(
     vehicle_id: &str,
     heartbeats: Arc<Mutex<HashMap<String, VehicleHeartbeat>>>,
-    state: Arc<Mutex<VehicleTelemetryData>>,
+    state: Arc<TelemetryState>,
 ) {
     let mut heartbeats_guard = heartbeats.lock().await;
     if let Some(heartbeat) = heartbeats_guard.get_mut(vehicle_id) {
@@ -145,28 +232,23 @@ pub async fn update_vehicle_heartbeat(
                 "Vehicle {} reconnected after being disconnected",
                 vehicle_id
             );
+            notifications::clear(NotificationCategory::Disconnect, vehicle_id);
+            notifications::clear(NotificationCategory::LostLink, vehicle_id);
+            notifications::notify(
+                NotificationCategory::Disconnect,
+                NotificationSeverity::Info,
+                Some(vehicle_id),
+                format!("{} reconnected", vehicles::display_name(vehicle_id)),
+            );
 
             // Update vehicle status back to normal if it was disconnected
-            let mut state_guard = state.lock().await;
-            match vehicle_id {
-                "eru" => {
-                    if state_guard.ERU.vehicle_status == "Disconnected" {
-                        state_guard.ERU.vehicle_status = "Connected".to_string();
-                    }
-                }
-                "mea" => {
-                    if state_guard.MEA.vehicle_status == "Disconnected" {
-                        state_guard.MEA.vehicle_status = "Connected".to_string();
-                    }
-                }
-                "mra" => {
-                    if state_guard.MRA.vehicle_status == "Disconnected" {
-                        state_guard.MRA.vehicle_status = "Connected".to_string();
-                    }
-                }
-                _ => {
-                    println!("Unknown vehicle_id for reconnection: {}", vehicle_id);
+            let known = state.update(vehicle_id, |t| {
+                if t.vehicle_status == "Disconnected" {
+                    t.vehicle_status = "Connected".to_string();
                 }
+            });
+            if !known {
+                println!("Unknown vehicle_id for reconnection: {}", vehicle_id);
             }
         }
     }
@@ -191,3 +273,63 @@ pub async fn is_vehicle_connected(
         .map(|h| h.is_connected && !h.is_timeout(timeout))
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::advance;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const FAILSAFE_AFTER: Duration = Duration::from_secs(60);
+
+    #[tokio::test(start_paused = true)]
+    async fn heartbeat_times_out_after_silence() {
+        let mut heartbeat = VehicleHeartbeat::new();
+        advance(TIMEOUT).await;
+        assert!(!heartbeat.is_timeout(TIMEOUT));
+        advance(Duration::from_millis(1)).await;
+        assert!(heartbeat.is_timeout(TIMEOUT));
+
+        heartbeat.update();
+        assert!(!heartbeat.is_timeout(TIMEOUT));
+        advance(TIMEOUT / 2).await;
+        assert!(!heartbeat.is_timeout(TIMEOUT));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lost_link_failsafe_runs_from_the_disconnection() {
+        let mut heartbeat = VehicleHeartbeat::new();
+        // The countdown starts when the vehicle is marked disconnected, not at its last report
+        advance(TIMEOUT).await;
+        heartbeat.mark_disconnected();
+        assert!(!heartbeat.failsafe_due(FAILSAFE_AFTER));
+
+        advance(FAILSAFE_AFTER - Duration::from_millis(1)).await;
+        assert!(!heartbeat.failsafe_due(FAILSAFE_AFTER));
+        advance(Duration::from_millis(1)).await;
+        assert!(heartbeat.failsafe_due(FAILSAFE_AFTER));
+
+        // Runs once per disconnection
+        heartbeat.failsafe_triggered = true;
+        advance(FAILSAFE_AFTER).await;
+        assert!(!heartbeat.failsafe_due(FAILSAFE_AFTER));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reconnecting_cancels_the_lost_link_countdown() {
+        let mut heartbeat = VehicleHeartbeat::new();
+        heartbeat.mark_disconnected();
+        advance(FAILSAFE_AFTER / 2).await;
+        heartbeat.update();
+        advance(FAILSAFE_AFTER).await;
+        assert!(!heartbeat.failsafe_due(FAILSAFE_AFTER));
+        assert_eq!(heartbeat.consecutive_failures, 0);
+
+        // A new disconnection starts a fresh countdown
+        heartbeat.mark_disconnected();
+        advance(FAILSAFE_AFTER - Duration::from_secs(1)).await;
+        assert!(!heartbeat.failsafe_due(FAILSAFE_AFTER));
+        advance(Duration::from_secs(1)).await;
+        assert!(heartbeat.failsafe_due(FAILSAFE_AFTER));
+    }
+}
@@ -1,7 +1,76 @@
+/*
+AMQP consumer setup and payload decoding. Over the bandwidth-constrained radio link a vehicle may
+compress its telemetry with zstd or gzip, naming the encoding in the content-encoding property
+(or a content-encoding header, for clients that can't set properties); uncompressed messages
+need neither. Every command the GCS publishes carries an accept-encoding header listing the
+encodings it decompresses, so vehicles only compress once they have heard from a GCS that can
+read it.
+*/
+use std::borrow::Cow;
+use std::io::Read;
 use lapin::{
-    options::*, types::FieldTable, Channel, Consumer, Queue, Result as LapinResult,
+    options::*,
+    types::{AMQPValue, FieldTable},
+    BasicProperties, Channel, Consumer, Queue, Result as LapinResult,
 };
 
+// Unacked deliveries per consumer; the rest stays in the broker while the pipeline is full
+const PREFETCH: u16 = 32;
+
+pub const ACCEPTED_ENCODINGS: &str = "zstd, gzip";
+
+// Bound on a decompressed message, so a corrupt or hostile payload can't inflate without limit
+const MAX_DECOMPRESSED_BYTES: u64 = 4 * 1024 * 1024;
+
+// Headers advertising the accepted encodings, for outgoing commands
+pub fn accept_encoding_headers() -> FieldTable {
+    let mut headers = FieldTable::default();
+    headers.insert("accept-encoding".into(), AMQPValue::LongString(ACCEPTED_ENCODINGS.into()));
+    headers
+}
+
+fn content_encoding(properties: &BasicProperties) -> Option<String> {
+    if let Some(encoding) = properties.content_encoding() {
+        return Some(encoding.as_str().trim().to_lowercase());
+    }
+    let headers = properties.headers().as_ref()?;
+    match headers.inner().get("content-encoding")? {
+        AMQPValue::LongString(encoding) => Some(encoding.to_string().trim().to_lowercase()),
+        AMQPValue::ShortString(encoding) => Some(encoding.as_str().trim().to_lowercase()),
+        _ => None,
+    }
+}
+
+fn read_bounded(mut reader: impl Read, encoding: &str) -> Result<Vec<u8>, String> {
+    let mut decoded = vec![];
+    reader
+        .by_ref()
+        .take(MAX_DECOMPRESSED_BYTES + 1)
+        .read_to_end(&mut decoded)
+        .map_err(|e| format!("Failed to decompress {} payload: {}", encoding, e))?;
+    if decoded.len() as u64 > MAX_DECOMPRESSED_BYTES {
+        return Err(format!(
+            "Decompressed {} payload exceeds {} bytes",
+            encoding, MAX_DECOMPRESSED_BYTES
+        ));
+    }
+    Ok(decoded)
+}
+
+// Message body with its content encoding removed
+pub fn decode_payload<'a>(properties: &BasicProperties, data: &'a [u8]) -> Result<Cow<'a, [u8]>, String> {
+    match content_encoding(properties).as_deref() {
+        None | Some("") | Some("identity") => Ok(Cow::Borrowed(data)),
+        Some("gzip") => read_bounded(flate2::read::GzDecoder::new(data), "gzip").map(Cow::Owned),
+        Some("zstd") => {
+            let decoder = zstd::stream::read::Decoder::new(data)
+                .map_err(|e| format!("Failed to decompress zstd payload: {}", e))?;
+            read_bounded(decoder, "zstd").map(Cow::Owned)
+        }
+        Some(other) => Err(format!("Unsupported content encoding {}", other)),
+    }
+}
+
 // Declare a queue for the consumer
 pub async fn queue_declare(channel: &Channel, queue_name: &str) -> LapinResult<Queue> {
     channel
@@ -29,6 +98,7 @@ pub async fn create_consumer(channel: &Channel, queue_name: &str) -> LapinResult
 
     println!("Creating consumer with tag: {}", consumer_tag);
 
+    channel.basic_qos(PREFETCH, BasicQosOptions::default()).await?;
     channel
         .basic_consume(
             queue_name,
@@ -38,3 +108,10 @@ pub async fn create_consumer(channel: &Channel, queue_name: &str) -> LapinResult
         )
         .await
 }
+
+// Close a consumer's channel so its unacked deliveries go back to the queue; already closed is fine
+pub async fn close_channel(channel: &Channel) {
+    if channel.status().connected() {
+        let _ = channel.close(200, "Recreating consumer").await;
+    }
+}
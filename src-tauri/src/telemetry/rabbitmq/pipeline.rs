@@ -0,0 +1,133 @@
+/*
+Telemetry pipeline stages connected by bounded channels:
+
+    parse (AMQP consumers, MQTT bridge, simulator) → enrich → persist → emit
+
+Each stage runs as its own supervised task. A full channel makes the stage before it wait, so a
+slow database or frontend holds the consumers back and the backlog stays in the broker past the
+consumer prefetch, instead of growing memory without bound. Queue depths are reported in the
+metrics, and in trace mode each message's time to every stage (metrics::trace).
+*/
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, Mutex};
+
+use crate::logs;
+use crate::metrics;
+use crate::metrics::trace::{MessageTrace, TraceStep};
+use crate::supervisor::{self, RestartPolicy};
+use crate::telemetry::types::{TelemetryData, VehicleTelemetryData};
+use super::{process, RabbitMQAPIImpl};
+
+// Messages each stage can queue; at 10 Hz per vehicle this is several seconds of telemetry
+pub const STAGE_CAPACITY: usize = 256;
+
+// A report after the enrich stage, with the telemetry state it produced
+pub struct Enriched {
+    pub data: TelemetryData,
+    pub snapshot: Arc<VehicleTelemetryData>,
+    pub trace: Option<MessageTrace>,
+}
+
+struct Stage<T> {
+    name: &'static str,
+    sender: mpsc::Sender<T>,
+    // Held by the stage task; a restarted task picks up where the previous one stopped
+    receiver: Mutex<mpsc::Receiver<T>>,
+}
+
+impl<T> Stage<T> {
+    fn new(name: &'static str) -> Arc<Self> {
+        let (sender, receiver) = mpsc::channel(STAGE_CAPACITY);
+        Arc::new(Self { name, sender, receiver: Mutex::new(receiver) })
+    }
+
+    // Waits while the stage is full
+    async fn send(&self, item: T) {
+        if self.sender.send(item).await.is_err() {
+            logs::error("telemetry", format!("Telemetry {} stage is closed", self.name));
+        }
+    }
+}
+
+// Next item for a stage task, recording the backlog left behind it
+async fn next<T>(receiver: &mut mpsc::Receiver<T>, stage: &'static str) -> Option<T> {
+    let item = receiver.recv().await;
+    metrics::record_queue_depth(stage, receiver.len(), receiver.max_capacity());
+    item
+}
+
+#[derive(Clone)]
+pub struct Pipeline {
+    enrich: Arc<Stage<(TelemetryData, Option<MessageTrace>)>>,
+    persist: Arc<Stage<Enriched>>,
+    emit: Arc<Stage<Enriched>>,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self {
+            enrich: Stage::new("enrich"),
+            persist: Stage::new("persist"),
+            emit: Stage::new("emit"),
+        }
+    }
+}
+
+impl Pipeline {
+    // Parsed telemetry from any source; `received` is when its payload arrived
+    pub async fn submit(&self, data: TelemetryData, received: Instant) {
+        let trace = MessageTrace::start(received).map(|trace| trace.mark(TraceStep::Parsed));
+        self.enrich.send((data, trace)).await;
+    }
+}
+
+// Spawn the enrich, persist and emit tasks
+pub fn start(telemetry: &RabbitMQAPIImpl) {
+    let task = telemetry.clone();
+    supervisor::spawn("telemetry_enrich", RestartPolicy::OnFailure, move || {
+        let telemetry = task.clone();
+        async move {
+            let pipeline = &telemetry.pipeline;
+            let mut receiver = pipeline.enrich.receiver.lock().await;
+            while let Some((data, trace)) = next(&mut receiver, pipeline.enrich.name).await {
+                let mut enriched = process::enrich(&telemetry, data).await;
+                enriched.trace = trace.map(|trace| trace.mark(TraceStep::Enriched));
+                pipeline.persist.send(enriched).await;
+            }
+            Ok(())
+        }
+    });
+
+    let task = telemetry.clone();
+    supervisor::spawn("telemetry_persist", RestartPolicy::OnFailure, move || {
+        let telemetry = task.clone();
+        async move {
+            let pipeline = &telemetry.pipeline;
+            let mut receiver = pipeline.persist.receiver.lock().await;
+            while let Some(mut enriched) = next(&mut receiver, pipeline.persist.name).await {
+                process::persist(&telemetry, &enriched).await;
+                enriched.trace = enriched.trace.map(|trace| trace.mark(TraceStep::Persisted));
+                pipeline.emit.send(enriched).await;
+            }
+            Ok(())
+        }
+    });
+
+    let task = telemetry.clone();
+    supervisor::spawn("telemetry_emit", RestartPolicy::OnFailure, move || {
+        let telemetry = task.clone();
+        async move {
+            let pipeline = &telemetry.pipeline;
+            let mut receiver = pipeline.emit.receiver.lock().await;
+            while let Some(mut enriched) = next(&mut receiver, pipeline.emit.name).await {
+                let trace = enriched.trace.take();
+                process::emit(&telemetry, enriched);
+                if let Some(trace) = trace {
+                    trace.mark(TraceStep::Emitted).finish();
+                }
+            }
+            Ok(())
+        }
+    });
+}
@@ -1,164 +1,89 @@
+use crate::telemetry::arming;
+use crate::telemetry::broadcast;
 use crate::telemetry::geos;
-use crate::telemetry::geos::*;
+use crate::telemetry::patient;
+use crate::telemetry::separation;
+use crate::telemetry::signal;
+use crate::telemetry::staleness;
+use crate::telemetry::tagging;
 use crate::telemetry::sql::*;
-use crate::telemetry::types::{TelemetryData, VehicleTelemetryData};
+use crate::telemetry::types::{DisplayTelemetry, TelemetryData};
 use futures_util::stream::StreamExt;
 use lapin::{options::*, Consumer, Result as LapinResult};
 use serde_json::json;
-use sqlx::PgPool;
-use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Duration;
-use tauri::{AppHandle, Emitter};
-use tokio::sync::Mutex;
+use std::time::Instant;
 
-use super::heartbeat::{is_vehicle_connected, update_vehicle_heartbeat, VehicleHeartbeat};
-use super::TelemetryEventTrigger;
+use super::heartbeat::{is_vehicle_connected, update_vehicle_heartbeat};
+use super::listen;
+use crate::altitude_bands;
+use crate::commands::capabilities;
+use crate::commands::ping;
+use crate::debug_feed;
+use crate::faults;
+use crate::geofence;
+use crate::logs;
+use crate::metrics;
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use crate::protocols;
+use crate::resync;
+use crate::settings;
+use crate::terrain;
+use crate::units::DisplayUnits;
+use crate::vehicles;
+use super::pipeline::Enriched;
+use super::RabbitMQAPIImpl;
 
 // Process telemetry data from the consumer
 pub async fn process_telemetry(
     mut consumer: Consumer,
-    state: Arc<Mutex<VehicleTelemetryData>>,
-    db: PgPool,
-    app_handle: Option<AppHandle>,
-    vehicle_heartbeats: Arc<Mutex<HashMap<String, VehicleHeartbeat>>>,
-    heartbeat_timeout: Duration,
+    queue: &str,
+    telemetry: &RabbitMQAPIImpl,
 ) -> LapinResult<()> {
     let mut failure_count = 0;
 
     while let Some(delivery) = consumer.next().await {
         if let Ok(delivery) = delivery {
-            match serde_json::from_slice::<TelemetryData>(&delivery.data) {
+            let received = Instant::now();
+            if faults::drop_message() {
+                delivery.ack(BasicAckOptions::default()).await?;
+                continue;
+            }
+            let parsed = listen::decode_payload(&delivery.properties, &delivery.data)
+                .and_then(|payload| protocols::parse_telemetry(None, &faults::corrupt(&payload)));
+            match parsed {
                 Ok(mut data) => {
                     failure_count = 0; // reset on success
-
-                    // Update heartbeat for this vehicle
-                    update_vehicle_heartbeat(
-                        &data.vehicle_id,
-                        vehicle_heartbeats.clone(),
-                        state.clone(),
-                    )
-                    .await;
-
-                    // Existing signal strength check
-                    if data.signal_strength < -70 {
-                        data.vehicle_status = "Bad Connection".to_string();
-                    }
-
-                    // Existing geo-fencing check
-                    let point = geos::Coordinate {
-                        latitude: data.current_position.latitude,
-                        longitude: data.current_position.longitude,
-                    };
-
-                    if is_near_keep_out_zone(&data.vehicle_id, &point, 1000.0) {
-                        data.vehicle_status = "Approaching restricted area".to_string();
-                    }
-
-                    // If vehicle was marked as disconnected but we're receiving data,
-                    // and no other critical status is set, mark as connected
-                    if data.vehicle_status.is_empty() || data.vehicle_status == "Disconnected" {
-                        if is_vehicle_connected(
-                            &data.vehicle_id,
-                            vehicle_heartbeats.clone(),
-                            heartbeat_timeout,
-                        )
-                        .await
-                        {
-                            data.vehicle_status = "Connected".to_string();
-                        }
+                    if data.timestamp.is_none() {
+                        // The AMQP timestamp property is in seconds
+                        data.timestamp = delivery.properties.timestamp().as_ref().map(|secs| *secs as i64 * 1000);
                     }
-
-                    let vehicle_id = data.vehicle_id.clone();
-                    state
-                        .lock()
-                        .await
-                        .update_vehicle_telemetry_state(vehicle_id.clone(), data.clone());
-
-                    // Create payload for the event
-                    let payload = json!({
-                        "vehicle_id": vehicle_id,
-                        "telemetry": data.clone(),
-                        "timestamp": std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs()
-                    });
-
-                    // Emit the telemetry update using TelemetryEventTrigger
-                    if let Some(app_handle) = &app_handle {
-                        let vehicle_telemetry: VehicleTelemetryData = state.lock().await.clone();
-                        match TelemetryEventTrigger::new(app_handle.clone())
-                            .on_updated(vehicle_telemetry)
-                        {
-                            Ok(_) => {
-                                println!(
-                                    "Successfully emitted telemetry update via event trigger for vehicle: {}",
-                                    vehicle_id
-                                );
-                            }
-                            Err(e) => {
-                                println!(
-                                    "Failed to emit telemetry update via event trigger: {}",
-                                    e
-                                );
-
-                                // Fallback to regular app_handle emit
-                                if let Err(e) = app_handle.emit("telemetry_update", &payload) {
-                                    println!("Failed to emit telemetry update: {}", e);
-                                }
-                            }
-                        }
-                    } else {
-                        println!("Warning: No app_handle available to emit telemetry updates");
+                    if let Some(timestamp) = data.timestamp {
+                        let (age_ms, stale) = staleness::check(timestamp);
+                        metrics::record_message_age(queue, age_ms, stale);
                     }
-
-                    println!("Received telemetry data from {}: {:?}", vehicle_id, payload);
-                    println!("Vehicle {} status: {:?}", vehicle_id, data.vehicle_status);
+                    // Acked once queued; waits here while the pipeline is full
+                    telemetry.pipeline.submit(data, received).await;
                     delivery.ack(BasicAckOptions::default()).await?;
-
-                    // Insert telemetry data into the database
-                    let current_position_str = serde_json::to_string(&data.current_position).unwrap();
-                    let request_coordinate_str =
-                        serde_json::to_string(&data.request_coordinate).unwrap();
-
-                    if let Err(e) = insert_telemetry(
-                        db.clone(),
-                        data.vehicle_id.clone(),
-                        data.signal_strength,
-                        data.pitch,
-                        data.yaw,
-                        data.roll,
-                        data.speed,
-                        data.altitude,
-                        data.battery_life,
-                        current_position_str,
-                        data.vehicle_status.clone(),
-                        request_coordinate_str,
-                    )
-                    .await
-                    {
-                        eprintln!("Failed to insert telemetry data: {}", e);
-                    }
                 }
                 Err(e) => {
                     failure_count += 1;
-                    println!(
-                        "Failed to parse Telemetry data (attempt {}): {}",
-                        failure_count, e
+                    metrics::record_parse_failure();
+                    logs::warn(
+                        "telemetry",
+                        format!("Failed to parse Telemetry data (attempt {}): {}", failure_count, e),
                     );
-                    println!("Raw payload: {:?}", String::from_utf8_lossy(&delivery.data));
+                    debug_feed::record("telemetry", || {
+                        format!("Raw payload: {:?}", String::from_utf8_lossy(&delivery.data))
+                    });
                     delivery.reject(BasicRejectOptions::default()).await?;
 
                     if failure_count >= 3 {
                         let error_payload = json!({
-                            "error": "Failed to establish a connection after 3 invalid messages",
+                            "error": "Recreating the telemetry consumer after 3 invalid messages",
                             "consecutive_failures": failure_count
                         });
 
-                        if let Some(app_handle) = &app_handle {
-                            app_handle.emit("telemetry_error", error_payload).ok();
-                        }
+                        telemetry.events.emit_json("telemetry_error", error_payload).ok();
 
                         return Err(lapin::Error::InvalidChannelState(
                             lapin::ChannelState::Closed,
@@ -171,3 +96,222 @@ pub async fn process_telemetry(
 
     Ok(())
 }
+
+// Enrich stage: heartbeat, alerts and vehicle status, then the new state
+pub async fn enrich(telemetry: &RabbitMQAPIImpl, mut data: TelemetryData) -> Enriched {
+    let RabbitMQAPIImpl {
+        state,
+        events,
+        vehicle_heartbeats,
+        heartbeat_timeout,
+        coordinate_requests,
+        commands,
+        patient_sensor,
+        ..
+    } = telemetry;
+    metrics::record_telemetry_message();
+
+    // Update heartbeat for this vehicle
+    update_vehicle_heartbeat(
+        &data.vehicle_id,
+        vehicle_heartbeats.clone(),
+        state.clone(),
+    )
+    .await;
+
+    let settings = settings::current();
+    let thresholds = &settings.alerts;
+    let alias = vehicles::alias(&data.vehicle_id);
+
+    // Existing signal strength check
+    let weak_signal = data.signal_strength <= thresholds.weak_signal_strength;
+    if weak_signal {
+        data.vehicle_status = "Bad Connection".to_string();
+    }
+    notifications::track(
+        weak_signal,
+        NotificationCategory::WeakSignal,
+        NotificationSeverity::Warning,
+        &data.vehicle_id,
+        || format!("{} signal strength is {} dBm", alias.display_name, data.signal_strength),
+    );
+    if let Some(change) = signal::record(&data.vehicle_id, data.signal_strength) {
+        if let Err(e) = events.signal_trend(change) {
+            logs::error("telemetry", format!("Failed to emit signal trend: {}", e));
+        }
+    }
+
+    notifications::track(
+        data.battery_life <= thresholds.low_battery_percent,
+        NotificationCategory::LowBattery,
+        NotificationSeverity::Warning,
+        &data.vehicle_id,
+        || format!("{} battery is at {}%", alias.display_name, data.battery_life),
+    );
+
+    if let Some(armed) = data.armed {
+        arming::record(&data.vehicle_id, armed);
+    }
+
+    if let Some(sequence) = data.ping_reply {
+        ping::replied(&data.vehicle_id, sequence);
+    }
+
+    // Only the announcement's first arrival matters; it isn't passed on with the telemetry
+    if let Some(announcement) = data.capabilities.take() {
+        capabilities::announce(&data.vehicle_id, announcement);
+    }
+
+    if let Some(secured) = data.request_coordinate.patient_secured {
+        if patient::record(&data.vehicle_id, secured) {
+            if let Some(handler) = patient_sensor {
+                handler.patient_sensor_changed(events.as_ref(), &data.vehicle_id, secured).await;
+            }
+        }
+    }
+
+    // Existing geo-fencing check
+    let point = geos::Coordinate {
+        latitude: data.current_position.latitude,
+        longitude: data.current_position.longitude,
+    };
+
+    let active_mission = commands.as_ref().and_then(|c| c.active_mission());
+    if geofence::check_position(&data.vehicle_id, &point, data.altitude as f64) {
+        data.vehicle_status = "Approaching restricted area".to_string();
+    }
+    terrain::record_altitude(
+        &data.vehicle_id,
+        point.latitude,
+        point.longitude,
+        data.altitude as f64,
+    );
+    separation::record_position(&data.vehicle_id, point, data.altitude as f64);
+    altitude_bands::check_altitude(
+        active_mission,
+        &data.vehicle_id,
+        data.altitude as f64,
+    );
+
+    // If vehicle was marked as disconnected but we're receiving data,
+    // and no other critical status is set, mark as connected
+    if data.vehicle_status.is_empty() || data.vehicle_status == "Disconnected" {
+        if is_vehicle_connected(
+            &data.vehicle_id,
+            vehicle_heartbeats.clone(),
+            *heartbeat_timeout,
+        )
+        .await
+        {
+            data.vehicle_status = "Connected".to_string();
+        }
+    }
+
+    // Surface new coordinate requests to the operator
+    if let Some(request) = coordinate_requests.lock().await.detect(&data) {
+        println!(
+            "Vehicle {} requested coordinate approval (request {})",
+            request.vehicle_id, request.request_id
+        );
+        if let Err(e) = events.coordinate_request(request) {
+            println!("Failed to emit coordinate request: {}", e);
+        }
+    }
+
+    let units = DisplayUnits::resolve(settings.units, &settings.unit_overrides);
+    data.display = Some(DisplayTelemetry {
+        altitude: units.distance(data.altitude as f64),
+        speed: units.speed(data.speed as f64),
+    });
+    data.alias = Some(alias);
+    data.link_latency_ms = ping::latency_ms(&data.vehicle_id);
+    (data.mission_id, data.stage_id) = tagging::lookup(&data.vehicle_id);
+    // Aged as of now, so time spent waiting in the pipeline counts too
+    (data.data_age_ms, data.stale) = match data.timestamp.map(staleness::check) {
+        Some((age_ms, stale)) => (Some(age_ms), Some(stale)),
+        None => (None, None),
+    };
+
+    state.set(data.clone());
+    let snapshot = state.snapshot();
+    Enriched { data, snapshot, trace: None }
+}
+
+// Persist stage
+pub async fn persist(telemetry: &RabbitMQAPIImpl, enriched: &Enriched) {
+    let data = &enriched.data;
+    let current_position_str = serde_json::to_string(&data.current_position).unwrap();
+    let request_coordinate_str =
+        serde_json::to_string(&data.request_coordinate).unwrap();
+
+    let insert_started = Instant::now();
+    faults::delay_db_write().await;
+    let inserted = insert_telemetry(
+        telemetry.db.clone(),
+        data.vehicle_id.clone(),
+        data.signal_strength,
+        data.pitch,
+        data.yaw,
+        data.roll,
+        data.speed,
+        data.altitude,
+        data.battery_life,
+        current_position_str,
+        data.vehicle_status.clone(),
+        request_coordinate_str,
+        data.mission_id,
+        data.stage_id,
+    )
+    .await;
+    metrics::record_db_insert(insert_started.elapsed());
+    if let Err(e) = inserted {
+        logs::error("telemetry::db", format!("Failed to insert telemetry data: {}", e));
+    }
+}
+
+// Emit stage: in-process broadcast and frontend events
+pub fn emit(telemetry: &RabbitMQAPIImpl, enriched: Enriched) {
+    let Enriched { data, snapshot, .. } = enriched;
+    let events = &telemetry.events;
+    let vehicle_id = data.vehicle_id.clone();
+
+    broadcast::publish(Some(&vehicle_id), snapshot.clone());
+    resync::record_telemetry();
+
+    // Emit the telemetry update using TelemetryEventTrigger
+    let emit_started = Instant::now();
+    let emitted = events.telemetry_updated(snapshot);
+    metrics::record_event_emit(emit_started.elapsed());
+    if let Err(e) = emitted {
+        logs::warn(
+            "telemetry",
+            format!("Failed to emit telemetry update via event trigger: {}", e),
+        );
+
+        // Fallback to a plain telemetry_update event
+        let payload = json!({
+            "vehicle_id": vehicle_id,
+            "telemetry": data.clone(),
+            "timestamp": std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        });
+        if let Err(e) = events.emit_json("telemetry_update", payload) {
+            logs::warn("telemetry", format!("Failed to emit telemetry update: {}", e));
+        }
+    }
+
+    debug_feed::record(&vehicle_id, || {
+        format!(
+            "{}: alt {:.1} m, speed {:.1} m/s, battery {}%, signal {}, at ({:.6}, {:.6})",
+            data.vehicle_status,
+            data.altitude,
+            data.speed,
+            data.battery_life,
+            data.signal_strength,
+            data.current_position.latitude,
+            data.current_position.longitude
+        )
+    });
+}
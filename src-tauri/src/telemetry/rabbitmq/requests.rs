@@ -0,0 +1,66 @@
+use crate::telemetry::types::{CoordinateRequest, CoordinateRequestStatus, TelemetryData};
+use std::collections::HashMap;
+
+// Tracks coordinate requests raised by vehicles through the `request_coordinate` telemetry field.
+// A request is "new" when a vehicle reports a non-zero message_flag different from the last one seen.
+#[derive(Debug, Default)]
+pub struct CoordinateRequests {
+    next_id: i32,
+    last_flags: HashMap<String, i32>,
+    requests: Vec<CoordinateRequest>,
+}
+
+impl CoordinateRequests {
+    // Returns the newly created request, if this telemetry message raised one
+    pub fn detect(&mut self, data: &TelemetryData) -> Option<CoordinateRequest> {
+        let flag = data.request_coordinate.message_flag;
+        let previous = self.last_flags.insert(data.vehicle_id.clone(), flag);
+        if flag == 0 || previous == Some(flag) {
+            return None;
+        }
+
+        // A newer request from the same vehicle supersedes any it still has pending
+        self.requests.retain(|r| {
+            r.vehicle_id != data.vehicle_id || !matches!(r.status, CoordinateRequestStatus::Pending)
+        });
+
+        self.next_id += 1;
+        let request = CoordinateRequest {
+            request_id: self.next_id,
+            vehicle_id: data.vehicle_id.clone(),
+            message_flag: flag,
+            request_location: data.request_coordinate.request_location.clone(),
+            patient_secured: data.request_coordinate.patient_secured,
+            received_at: chrono::Utc::now().to_rfc3339(),
+            status: CoordinateRequestStatus::Pending,
+        };
+        self.requests.push(request.clone());
+        Some(request)
+    }
+
+    pub fn pending(&self) -> Vec<CoordinateRequest> {
+        self.requests
+            .iter()
+            .filter(|r| matches!(r.status, CoordinateRequestStatus::Pending))
+            .cloned()
+            .collect()
+    }
+
+    // Resolve a pending request; resolved requests are removed from the store
+    pub fn resolve(
+        &mut self,
+        request_id: i32,
+        status: CoordinateRequestStatus,
+    ) -> Result<CoordinateRequest, String> {
+        let index = self
+            .requests
+            .iter()
+            .position(|r| {
+                r.request_id == request_id && matches!(r.status, CoordinateRequestStatus::Pending)
+            })
+            .ok_or("Pending request not found")?;
+        let mut request = self.requests.remove(index);
+        request.status = status;
+        Ok(request)
+    }
+}
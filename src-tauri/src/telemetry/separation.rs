@@ -0,0 +1,145 @@
+/*
+Inter-vehicle separation monitoring. The latest position and altitude of every vehicle is kept
+from live telemetry; each new report is checked against the other vehicles, and a Separation
+alert is raised for a pair when both the horizontal and the vertical distance fall below the
+operator's minima. Positions older than the heartbeat timeout are ignored.
+*/
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::config;
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use crate::settings;
+use crate::vehicles;
+use super::geos::{harversine_distance, Coordinate};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct VehicleSeparation {
+    pub vehicle_a: String,
+    pub vehicle_b: String,
+    pub horizontal_m: f64,
+    pub vertical_m: f64,
+    pub below_minimum: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct SeparationMatrix {
+    // Vehicles with a recent position, sorted
+    pub vehicles: Vec<String>,
+    // One entry per pair, vehicle_a < vehicle_b
+    pub pairs: Vec<VehicleSeparation>,
+    pub min_horizontal_m: f64,
+    pub min_vertical_m: f64,
+    pub computed_at: String,
+}
+
+struct VehiclePosition {
+    position: Coordinate,
+    altitude: f64,
+    updated: Instant,
+}
+
+lazy_static! {
+    static ref POSITIONS: Mutex<HashMap<String, VehiclePosition>> = Mutex::new(HashMap::new());
+}
+
+fn pair_key(a: &str, b: &str) -> String {
+    format!("{}-{}", a, b)
+}
+
+fn max_age() -> Duration {
+    Duration::from_secs(config::get().heartbeat_timeout_secs as u64)
+}
+
+fn separation(a: (&String, &VehiclePosition), b: (&String, &VehiclePosition)) -> VehicleSeparation {
+    let thresholds = settings::current().alerts;
+    let ((a_id, a_pos), (b_id, b_pos)) = if a.0 <= b.0 { (a, b) } else { (b, a) };
+    let horizontal_m = harversine_distance(&a_pos.position, &b_pos.position);
+    let vertical_m = (a_pos.altitude - b_pos.altitude).abs();
+    VehicleSeparation {
+        vehicle_a: a_id.clone(),
+        vehicle_b: b_id.clone(),
+        horizontal_m,
+        vertical_m,
+        below_minimum: horizontal_m < thresholds.min_horizontal_separation_m
+            && vertical_m < thresholds.min_vertical_separation_m,
+    }
+}
+
+// Record a telemetry position and re-check this vehicle against every other one
+pub fn record_position(vehicle_id: &str, position: Coordinate, altitude: f64) {
+    let vehicle_id = vehicle_id.to_lowercase();
+    let max_age = max_age();
+    let pairs: Vec<VehicleSeparation> = {
+        let mut positions = POSITIONS.lock().unwrap();
+        positions.insert(
+            vehicle_id.clone(),
+            VehiclePosition { position, altitude, updated: Instant::now() },
+        );
+        let own = positions.get_key_value(&vehicle_id).unwrap();
+        positions
+            .iter()
+            .filter(|(id, p)| **id != vehicle_id && p.updated.elapsed() <= max_age)
+            .map(|other| separation(own, other))
+            .collect()
+    };
+
+    for pair in pairs {
+        notifications::track(
+            pair.below_minimum,
+            NotificationCategory::Separation,
+            NotificationSeverity::Critical,
+            &pair_key(&pair.vehicle_a, &pair.vehicle_b),
+            || {
+                format!(
+                    "{} and {} are {:.0} m apart horizontally and {:.0} m vertically",
+                    vehicles::display_name(&pair.vehicle_a),
+                    vehicles::display_name(&pair.vehicle_b),
+                    pair.horizontal_m,
+                    pair.vertical_m
+                )
+            },
+        );
+    }
+}
+
+// Vehicles with a recent position: (id, position, altitude)
+pub fn recent_positions() -> Vec<(String, Coordinate, f64)> {
+    let max_age = max_age();
+    POSITIONS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, p)| p.updated.elapsed() <= max_age)
+        .map(|(id, p)| (id.clone(), p.position.clone(), p.altitude))
+        .collect()
+}
+
+pub fn separation_matrix() -> SeparationMatrix {
+    let thresholds = settings::current().alerts;
+    let max_age = max_age();
+    let positions = POSITIONS.lock().unwrap();
+    let mut recent: Vec<(&String, &VehiclePosition)> = positions
+        .iter()
+        .filter(|(_, p)| p.updated.elapsed() <= max_age)
+        .collect();
+    recent.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut pairs = vec![];
+    for (i, a) in recent.iter().enumerate() {
+        for b in &recent[i + 1..] {
+            pairs.push(separation(*a, *b));
+        }
+    }
+    SeparationMatrix {
+        vehicles: recent.iter().map(|(id, _)| (*id).clone()).collect(),
+        pairs,
+        min_horizontal_m: thresholds.min_horizontal_separation_m,
+        min_vertical_m: thresholds.min_vertical_separation_m,
+        computed_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
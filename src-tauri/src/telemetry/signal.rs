@@ -0,0 +1,192 @@
+/*
+Signal-strength history. The signal strength of every report is kept per vehicle for the last
+minute, so the frontend can draw a sparkline and a fading link is spotted before it crosses the
+weak-signal threshold. The trend is the least-squares change over the window: a drop of at
+least the operator's signal_trend_drop_dbm raises a SignalTrend alert, and every change of
+trend is emitted to the frontend.
+*/
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::clock::{self, Instant};
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use crate::settings;
+use crate::vehicles;
+
+// Reports kept per vehicle
+const WINDOW: Duration = Duration::from_secs(60);
+// Less history than this gives no trend
+const MIN_TREND_SPAN: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum SignalTrend {
+    Improving,
+    Stable,
+    Degrading,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct SignalSample {
+    pub recorded_at: String,
+    pub signal_strength: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct SignalHistory {
+    pub vehicle_id: String,
+    pub window_secs: u32,
+    // Oldest first
+    pub samples: Vec<SignalSample>,
+    pub trend: SignalTrend,
+    // Change over the window (dBm); negative while the signal weakens
+    pub change_dbm: f64,
+}
+
+// Emitted when a vehicle's trend changes
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct SignalTrendChange {
+    pub vehicle_id: String,
+    pub trend: SignalTrend,
+    pub change_dbm: f64,
+    pub window_secs: u32,
+}
+
+struct Sample {
+    received: Instant,
+    recorded_at: String,
+    signal_strength: i32,
+}
+
+struct VehicleHistory {
+    samples: VecDeque<Sample>,
+    trend: SignalTrend,
+}
+
+lazy_static! {
+    // Keyed by lowercase vehicle id
+    static ref HISTORIES: Mutex<HashMap<String, VehicleHistory>> = Mutex::new(HashMap::new());
+}
+
+// Least-squares slope over the window, scaled to the time the samples span
+fn change_dbm(samples: &VecDeque<Sample>) -> f64 {
+    let (Some(first), Some(last)) = (samples.front(), samples.back()) else {
+        return 0.0;
+    };
+    let span = last.received.duration_since(first.received);
+    if span < MIN_TREND_SPAN {
+        return 0.0;
+    }
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|s| {
+            (
+                s.received.duration_since(first.received).as_secs_f64(),
+                s.signal_strength as f64,
+            )
+        })
+        .collect();
+    let n = points.len() as f64;
+    let mean_t = points.iter().map(|(t, _)| t).sum::<f64>() / n;
+    let mean_dbm = points.iter().map(|(_, dbm)| dbm).sum::<f64>() / n;
+    let (covariance, variance) = points.iter().fold((0.0, 0.0), |(cov, var), (t, dbm)| {
+        (cov + (t - mean_t) * (dbm - mean_dbm), var + (t - mean_t).powi(2))
+    });
+    if variance == 0.0 {
+        return 0.0;
+    }
+    covariance / variance * span.as_secs_f64()
+}
+
+fn classify(change_dbm: f64, drop_dbm: f64) -> SignalTrend {
+    if change_dbm <= -drop_dbm {
+        SignalTrend::Degrading
+    } else if change_dbm >= drop_dbm {
+        SignalTrend::Improving
+    } else {
+        SignalTrend::Stable
+    }
+}
+
+// Record a report's signal strength; Some when the vehicle's trend changed
+pub fn record(vehicle_id: &str, signal_strength: i32) -> Option<SignalTrendChange> {
+    let vehicle_id = vehicle_id.to_lowercase();
+    let now = clock::now();
+    let drop_dbm = settings::current().alerts.signal_trend_drop_dbm;
+
+    let (trend, change, changed) = {
+        let mut histories = HISTORIES.lock().unwrap();
+        let history = histories.entry(vehicle_id.clone()).or_insert(VehicleHistory {
+            samples: VecDeque::new(),
+            trend: SignalTrend::Stable,
+        });
+        history.samples.push_back(Sample {
+            received: now,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            signal_strength,
+        });
+        while history
+            .samples
+            .front()
+            .is_some_and(|s| now.duration_since(s.received) > WINDOW)
+        {
+            history.samples.pop_front();
+        }
+
+        let change = change_dbm(&history.samples);
+        let trend = classify(change, drop_dbm);
+        let changed = trend != history.trend;
+        history.trend = trend;
+        (trend, change, changed)
+    };
+
+    notifications::track(
+        trend == SignalTrend::Degrading,
+        NotificationCategory::SignalTrend,
+        NotificationSeverity::Warning,
+        &vehicle_id,
+        || {
+            format!(
+                "{} signal degrading over last {} s ({:.0} dBm, now {} dBm)",
+                vehicles::display_name(&vehicle_id),
+                WINDOW.as_secs(),
+                change,
+                signal_strength
+            )
+        },
+    );
+
+    changed.then(|| SignalTrendChange {
+        vehicle_id,
+        trend,
+        change_dbm: change,
+        window_secs: WINDOW.as_secs() as u32,
+    })
+}
+
+// Samples from the last window for a sparkline; empty for a vehicle without recent reports
+pub fn history(vehicle_id: &str) -> SignalHistory {
+    let vehicle_id = vehicle_id.to_lowercase();
+    let now = clock::now();
+    let histories = HISTORIES.lock().unwrap();
+    let history = histories.get(&vehicle_id);
+    let samples = history.map(|h| &h.samples);
+    SignalHistory {
+        samples: samples
+            .into_iter()
+            .flatten()
+            .filter(|s| now.duration_since(s.received) <= WINDOW)
+            .map(|s| SignalSample {
+                recorded_at: s.recorded_at.clone(),
+                signal_strength: s.signal_strength,
+            })
+            .collect(),
+        trend: history.map_or(SignalTrend::Stable, |h| h.trend),
+        change_dbm: samples.map_or(0.0, change_dbm),
+        window_secs: WINDOW.as_secs() as u32,
+        vehicle_id,
+    }
+}
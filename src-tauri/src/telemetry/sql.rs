@@ -1,4 +1,6 @@
-use sqlx::{query, PgPool};
+use sqlx::{query, PgPool, Row};
+
+use crate::telemetry::types::{ChartPoint, Coordinate, TelemetryField};
 
 pub async fn insert_telemetry(
     db_conn: PgPool,
@@ -13,10 +15,12 @@ pub async fn insert_telemetry(
     current_position: String,
     status: String,
     request_coordinate: String,
+    mission_id: Option<i32>,
+    stage_id: Option<i32>,
 ) -> Result<(), sqlx::Error> {
     query("
-        INSERT INTO telemetry(vehicle_id, signal_strength, pitch, yaw, roll, speed, altitude, battery_life, current_position, vehicle_status, request_coordinate)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        INSERT INTO telemetry(vehicle_id, signal_strength, pitch, yaw, roll, speed, altitude, battery_life, current_position, vehicle_status, request_coordinate, mission_id, stage_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
     ")
     .bind(vehicle_id)
     .bind(signal_strength)
@@ -29,9 +33,145 @@ pub async fn insert_telemetry(
     .bind(current_position)
     .bind(status)
     .bind(request_coordinate)
+    .bind(mission_id)
+    .bind(stage_id)
     .execute(&db_conn)
     .await
     .expect("Failed to update vehicle status");
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TelemetryRecord {
+    pub vehicle_id: String,
+    pub recorded_at: String,
+    pub position: Coordinate,
+    pub altitude: f32,
+    pub speed: f32,
+    pub pitch: f32,
+    pub yaw: f32,
+    pub roll: f32,
+    pub battery_life: i32,
+    pub signal_strength: i32,
+    pub vehicle_status: String,
+    // Stage the vehicle was on; None for rows recorded before telemetry was tagged
+    #[serde(default)]
+    pub stage_id: Option<i32>,
+}
+
+// Telemetry recorded while the mission was active, oldest first
+pub async fn select_mission_telemetry(
+    db_conn: PgPool,
+    mission_id: i32,
+) -> Result<Vec<TelemetryRecord>, sqlx::Error> {
+    let rows = query("
+        SELECT vehicle_id, signal_strength, pitch::REAL AS pitch, yaw::REAL AS yaw,
+            roll::REAL AS roll, speed::REAL AS speed, altitude::REAL AS altitude, battery_life,
+            current_position, vehicle_status, stage_id,
+            to_char(recorded_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS.MS\"Z\"') AS recorded_at
+        FROM telemetry
+        WHERE mission_id = $1
+        ORDER BY telemetry.recorded_at
+    ")
+    .bind(mission_id)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            // Rows with an unreadable position cannot be placed on a track
+            let position: Coordinate =
+                serde_json::from_str(&row.get::<String, _>("current_position")).ok()?;
+            Some(TelemetryRecord {
+                vehicle_id: row.get("vehicle_id"),
+                recorded_at: row.get("recorded_at"),
+                position,
+                altitude: row.get("altitude"),
+                speed: row.get("speed"),
+                pitch: row.get("pitch"),
+                yaw: row.get("yaw"),
+                roll: row.get("roll"),
+                battery_life: row.get("battery_life"),
+                signal_strength: row.get("signal_strength"),
+                vehicle_status: row.get("vehicle_status"),
+                stage_id: row.get("stage_id"),
+            })
+        })
+        .collect())
+}
+
+// Re-insert a record from a mission bundle, keeping its original timestamp
+pub async fn insert_telemetry_record(
+    db_conn: PgPool,
+    mission_id: i32,
+    record: &TelemetryRecord,
+) -> Result<(), sqlx::Error> {
+    query("
+        INSERT INTO telemetry(vehicle_id, signal_strength, pitch, yaw, roll, speed, altitude, battery_life, current_position, vehicle_status, mission_id, recorded_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12::TIMESTAMPTZ)
+    ")
+    .bind(&record.vehicle_id)
+    .bind(record.signal_strength)
+    .bind(record.pitch)
+    .bind(record.yaw)
+    .bind(record.roll)
+    .bind(record.speed)
+    .bind(record.altitude)
+    .bind(record.battery_life)
+    .bind(serde_json::to_string(&record.position).unwrap_or_default())
+    .bind(&record.vehicle_status)
+    .bind(mission_id)
+    .bind(&record.recorded_at)
+    .execute(&db_conn)
+    .await?;
+
+    Ok(())
+}
+
+// Time-bucketed avg/min/max of one field, aggregated in the database
+pub async fn select_chart_series(
+    db_conn: PgPool,
+    vehicle_id: &str,
+    field: TelemetryField,
+    from: &str,
+    to: &str,
+    bucket_secs: u32,
+) -> Result<Vec<ChartPoint>, sqlx::Error> {
+    let column = field.column();
+    let rows = query(&format!("
+        SELECT
+            to_char(
+                to_timestamp(floor(extract(epoch FROM recorded_at) / $4) * $4) AT TIME ZONE 'UTC',
+                'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"'
+            ) AS bucket_start,
+            AVG({0})::FLOAT8 AS avg_value,
+            MIN({0})::FLOAT8 AS min_value,
+            MAX({0})::FLOAT8 AS max_value,
+            COUNT(*)::INTEGER AS samples
+        FROM telemetry
+        WHERE LOWER(vehicle_id) = LOWER($1)
+          AND recorded_at >= $2::TIMESTAMPTZ
+          AND recorded_at <= $3::TIMESTAMPTZ
+        GROUP BY floor(extract(epoch FROM recorded_at) / $4)
+        ORDER BY floor(extract(epoch FROM recorded_at) / $4)
+    ", column))
+    .bind(vehicle_id)
+    .bind(from)
+    .bind(to)
+    .bind(bucket_secs as f64)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ChartPoint {
+            bucket_start: row.get("bucket_start"),
+            avg: row.get("avg_value"),
+            min: row.get("min_value"),
+            max: row.get("max_value"),
+            samples: row.get("samples"),
+        })
+        .collect())
+}
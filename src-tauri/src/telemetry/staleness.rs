@@ -0,0 +1,14 @@
+/*
+Stale telemetry detection. A vehicle that keeps sending is not necessarily current: after a
+reconnect the broker may replay a backlog of old reports, and a congested pipeline delays them.
+Each report's age is its vehicle timestamp against the GCS clock when it is processed, and a
+report older than stale_telemetry_secs is flagged stale in the emitted state even though
+messages are arriving. Ages rely on the vehicle and GCS clocks agreeing.
+*/
+use crate::config;
+
+// (age_ms, stale) of a report the vehicle timestamped at `timestamp_ms`
+pub fn check(timestamp_ms: i64) -> (f64, bool) {
+    let age_ms = (chrono::Utc::now().timestamp_millis() - timestamp_ms).max(0) as f64;
+    (age_ms, age_ms > config::get().stale_telemetry_secs * 1000.0)
+}
@@ -0,0 +1,63 @@
+/*
+Latest processed telemetry, sharded per vehicle. Each vehicle sits behind its own RwLock, so the
+consumers for different vehicles and the heartbeat monitor do not wait on each other, and
+readers (REST, WebSocket clients, get_telemetry) only take read locks. Locks are never held
+across an await. A snapshot of all vehicles is assembled from the shards' Arcs without copying
+any telemetry; it is consistent per vehicle, not across vehicles.
+*/
+use std::sync::{Arc, RwLock};
+
+use crate::telemetry::types::{TelemetryData, VehicleTelemetryData};
+
+pub struct TelemetryState {
+    eru: RwLock<Arc<TelemetryData>>,
+    mea: RwLock<Arc<TelemetryData>>,
+    mra: RwLock<Arc<TelemetryData>>,
+}
+
+impl Default for TelemetryState {
+    fn default() -> Self {
+        let VehicleTelemetryData { ERU: eru, MEA: mea, MRA: mra } = VehicleTelemetryData::default();
+        Self {
+            eru: RwLock::new(eru),
+            mea: RwLock::new(mea),
+            mra: RwLock::new(mra),
+        }
+    }
+}
+
+impl TelemetryState {
+    fn shard(&self, vehicle_id: &str) -> Option<&RwLock<Arc<TelemetryData>>> {
+        match vehicle_id {
+            "eru" => Some(&self.eru),
+            "mea" => Some(&self.mea),
+            "mra" => Some(&self.mra),
+            _ => None,
+        }
+    }
+
+    // Replace the vehicle's telemetry; unknown vehicles are ignored
+    pub fn set(&self, data: TelemetryData) {
+        if let Some(shard) = self.shard(&data.vehicle_id) {
+            *shard.write().unwrap() = Arc::new(data);
+        }
+    }
+
+    // Edit the vehicle's telemetry in place (copied first if a snapshot still holds it);
+    // returns false for an unknown vehicle
+    pub fn update(&self, vehicle_id: &str, edit: impl FnOnce(&mut TelemetryData)) -> bool {
+        let Some(shard) = self.shard(vehicle_id) else {
+            return false;
+        };
+        edit(Arc::make_mut(&mut shard.write().unwrap()));
+        true
+    }
+
+    pub fn snapshot(&self) -> Arc<VehicleTelemetryData> {
+        Arc::new(VehicleTelemetryData {
+            ERU: self.eru.read().unwrap().clone(),
+            MEA: self.mea.read().unwrap().clone(),
+            MRA: self.mra.read().unwrap().clone(),
+        })
+    }
+}
@@ -0,0 +1,48 @@
+/*
+Mission tags for telemetry: the running (active or paused) mission and each of its vehicles'
+current stage, kept in step with the mission state by missions::api::events. Every report is
+stamped with them in the enrich stage, so persisted rows and emitted telemetry carry mission_id
+and stage_id and can be filtered by mission without matching timestamps against the mission.
+*/
+use std::collections::HashMap;
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+
+use crate::missions::types::{MissionStageStatusEnum, MissionsStruct};
+
+#[derive(Debug, Default)]
+struct MissionTags {
+    mission_id: Option<i32>,
+    // Current stage id by lowercase vehicle name
+    stages: HashMap<String, i32>,
+}
+
+lazy_static! {
+    static ref TAGS: RwLock<MissionTags> = RwLock::new(MissionTags::default());
+}
+
+pub fn set_missions(state: &MissionsStruct) {
+    let running = state.missions.iter().find(|m| {
+        m.mission_id == state.current_mission
+            && matches!(m.mission_status, MissionStageStatusEnum::Active | MissionStageStatusEnum::Paused)
+    });
+    let tags = match running {
+        Some(mission) => MissionTags {
+            mission_id: Some(mission.mission_id),
+            stages: [&mission.vehicles.MEA, &mission.vehicles.ERU, &mission.vehicles.MRA]
+                .into_iter()
+                .filter(|vehicle| vehicle.stages.iter().any(|s| s.stage_id == vehicle.current_stage))
+                .map(|vehicle| (vehicle.vehicle_name.to_string().to_lowercase(), vehicle.current_stage))
+                .collect(),
+        },
+        None => MissionTags::default(),
+    };
+    *TAGS.write().unwrap() = tags;
+}
+
+// (mission_id, stage_id) for a report from `vehicle_id`; the stage is None for vehicles that
+// aren't part of the mission or have no current stage
+pub fn lookup(vehicle_id: &str) -> (Option<i32>, Option<i32>) {
+    let tags = TAGS.read().unwrap();
+    (tags.mission_id, tags.stages.get(&vehicle_id.to_lowercase()).copied())
+}
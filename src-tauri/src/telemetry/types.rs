@@ -1,14 +1,20 @@
 
 use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::commands::capabilities::CapabilityAnnouncement;
+use crate::units::Measurement;
+use crate::vehicles::VehicleAlias;
 
 #[taurpc::ipc_type]
 #[derive(Debug)]
 #[allow(non_snake_case)]
 
+// Shared with the per-vehicle shards of TelemetryState
 pub struct VehicleTelemetryData {
-    pub ERU: TelemetryData,
-    pub MEA: TelemetryData,
-    pub MRA: TelemetryData,
+    pub ERU: Arc<TelemetryData>,
+    pub MEA: Arc<TelemetryData>,
+    pub MRA: Arc<TelemetryData>,
 }
 
 impl Default for VehicleTelemetryData {
@@ -19,7 +25,7 @@ impl Default for VehicleTelemetryData {
         };
         
         Self {
-            ERU: TelemetryData {
+            ERU: Arc::new(TelemetryData {
                 vehicle_id: "eru".to_string(),
                 signal_strength: 0,
                 pitch: 0.0,
@@ -35,8 +41,19 @@ impl Default for VehicleTelemetryData {
                     request_location: default_coords.clone(),
                     patient_secured: None,
                 },
-            },
-            MEA: TelemetryData {
+                timestamp: None,
+                armed: None,
+                ping_reply: None,
+                display: None,
+                alias: None,
+                link_latency_ms: None,
+                mission_id: None,
+                stage_id: None,
+                capabilities: None,
+                data_age_ms: None,
+                stale: None,
+            }),
+            MEA: Arc::new(TelemetryData {
                 vehicle_id: "mea".to_string(),
                 signal_strength: 0,
                 pitch: 0.0,
@@ -52,8 +69,19 @@ impl Default for VehicleTelemetryData {
                     request_location: default_coords.clone(),
                     patient_secured: None,
                 },
-            },
-            MRA: TelemetryData {
+                timestamp: None,
+                armed: None,
+                ping_reply: None,
+                display: None,
+                alias: None,
+                link_latency_ms: None,
+                mission_id: None,
+                stage_id: None,
+                capabilities: None,
+                data_age_ms: None,
+                stale: None,
+            }),
+            MRA: Arc::new(TelemetryData {
                 vehicle_id: "mra".to_string(),
                 signal_strength: 0,
                 pitch: 0.0,
@@ -69,22 +97,29 @@ impl Default for VehicleTelemetryData {
                     request_location: default_coords.clone(),
                     patient_secured: None,
                 },
-            },
+                timestamp: None,
+                armed: None,
+                ping_reply: None,
+                display: None,
+                alias: None,
+                link_latency_ms: None,
+                mission_id: None,
+                stage_id: None,
+                capabilities: None,
+                data_age_ms: None,
+                stale: None,
+            }),
         }
     }
 }
 
 impl VehicleTelemetryData {
-    pub fn update_vehicle_telemetry_state(
-        &mut self,
-        vehicle_id: String,
-        telemetry_data: TelemetryData,
-    ) {
-        match vehicle_id.as_str() {
-            "eru" => self.ERU = telemetry_data,
-            "mea" => self.MEA = telemetry_data,
-            "mra" => self.MRA = telemetry_data,
-            _ => {}
+    pub fn vehicle(&self, vehicle_id: &str) -> Option<&TelemetryData> {
+        match vehicle_id {
+            "eru" => Some(self.ERU.as_ref()),
+            "mea" => Some(self.MEA.as_ref()),
+            "mra" => Some(self.MRA.as_ref()),
+            _ => None,
         }
     }
 }
@@ -103,6 +138,46 @@ pub struct TelemetryData {
     pub current_position: Coordinate,
     pub vehicle_status: String,
     pub request_coordinate: RequestCoordinate,
+    // Unix time (ms) the vehicle sampled the report; over AMQP the message's timestamp property
+    // stands in when it is missing. None from vehicles that send neither
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    // Reported by vehicles that support arming; None from older firmware
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub armed: Option<bool>,
+    // Sequence of the last Ping the vehicle received; see commands/ping.rs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ping_reply: Option<u32>,
+    // Display values, vehicle alias and command link round trip, filled in by the backend before
+    // telemetry is emitted; ignored in incoming reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<DisplayTelemetry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<VehicleAlias>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_latency_ms: Option<f64>,
+    // Running mission and the vehicle's current stage when the report arrived, stamped by the
+    // backend (telemetry/tagging.rs); ignored in incoming reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mission_id: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage_id: Option<i32>,
+    // Sent by vehicles in their first report after connecting; see commands/capabilities.rs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<CapabilityAnnouncement>,
+    // Age of the report when it was processed and whether that is past stale_telemetry_secs,
+    // filled in by the backend (telemetry/staleness.rs); None without a timestamp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_age_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale: Option<bool>,
+}
+// Altitude and speed in the operator's display units
+#[taurpc::ipc_type]
+#[derive(Debug, Default)]
+pub struct DisplayTelemetry {
+    pub altitude: Measurement,
+    pub speed: Measurement,
 }
 #[taurpc::ipc_type]
 //Change vehicleStatus : i8 1 byte 0 - 255
@@ -125,3 +200,69 @@ pub struct Coordinate {
 pub struct AppData {
     pub telemetryx: HashMap<String, TelemetryData>,
 }
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone, specta::Type)]
+pub enum CoordinateRequestStatus {
+    Pending,
+    Approved,
+    Denied,
+}
+
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct CoordinateRequest {
+    pub request_id: i32,
+    pub vehicle_id: String,
+    pub message_flag: i32,
+    pub request_location: Coordinate,
+    pub patient_secured: Option<bool>,
+    pub received_at: String,
+    pub status: CoordinateRequestStatus,
+}
+
+// Numeric telemetry fields that can be charted
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone, Copy, PartialEq, Eq, specta::Type)]
+pub enum TelemetryField {
+    SignalStrength,
+    Pitch,
+    Yaw,
+    Roll,
+    Speed,
+    Altitude,
+    BatteryLife,
+}
+
+impl TelemetryField {
+    // Column name in the telemetry table; never built from user input
+    pub fn column(&self) -> &'static str {
+        match self {
+            TelemetryField::SignalStrength => "signal_strength",
+            TelemetryField::Pitch => "pitch",
+            TelemetryField::Yaw => "yaw",
+            TelemetryField::Roll => "roll",
+            TelemetryField::Speed => "speed",
+            TelemetryField::Altitude => "altitude",
+            TelemetryField::BatteryLife => "battery_life",
+        }
+    }
+}
+
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct ChartPoint {
+    pub bucket_start: String,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+    pub samples: i32,
+}
+
+#[taurpc::ipc_type]
+#[derive(Debug)]
+pub struct ChartSeries {
+    pub vehicle_id: String,
+    pub field: TelemetryField,
+    // May be wider than requested so the series stays under MAX_CHART_POINTS
+    pub bucket_secs: u32,
+    pub points: Vec<ChartPoint>,
+}
@@ -0,0 +1,34 @@
+/*
+Define the terrain API: ground elevation lookups and each vehicle's height above ground.
+*/
+use taurpc::{procedures, resolvers};
+
+use crate::telemetry::types::Coordinate;
+use super::TerrainClearance;
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "terrain")]
+pub trait TerrainApi {
+    // Metres above mean sea level; None where no elevation tile is installed
+    async fn get_elevation(coordinate: Coordinate) -> Option<f64>;
+    async fn get_terrain_clearance() -> Vec<TerrainClearance>;
+    // Pick up tiles added to the terrain directory while the app is running
+    async fn reload_tiles();
+}
+
+#[derive(Clone, Default)]
+pub struct TerrainApiImpl;
+
+#[resolvers]
+impl TerrainApi for TerrainApiImpl {
+    async fn get_elevation(self, coordinate: Coordinate) -> Option<f64> {
+        super::elevation_at(coordinate.latitude, coordinate.longitude)
+    }
+
+    async fn get_terrain_clearance(self) -> Vec<TerrainClearance> {
+        super::clearances()
+    }
+
+    async fn reload_tiles(self) {
+        super::reload_tiles()
+    }
+}
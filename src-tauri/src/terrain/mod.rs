@@ -0,0 +1,120 @@
+/*
+Terrain elevation from local SRTM tiles, so height above ground can be reported without an
+internet connection in the field. Tiles are read from the configured directory on first use
+and kept in memory. Telemetry altitude is treated as metres above mean sea level (GPS
+altitude); a vehicle below the configured terrain clearance raises a warning, as does a
+waypoint upload whose altitudes would put it there.
+*/
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::config;
+use crate::logs;
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+
+pub mod api;
+pub mod srtm;
+
+pub use api::{TerrainApi, TerrainApiImpl};
+
+use srtm::{tile_name, Tile};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct TerrainClearance {
+    pub vehicle_id: String,
+    pub altitude_m: f64,
+    // None when no tile covers the vehicle's position
+    pub ground_elevation_m: Option<f64>,
+    pub height_above_ground_m: Option<f64>,
+    pub updated_at: String,
+}
+
+lazy_static! {
+    // None marks a tile that is missing or unreadable, so it is not retried on every fix
+    static ref TILES: Mutex<HashMap<String, Option<Arc<Tile>>>> = Mutex::new(HashMap::new());
+    static ref CLEARANCES: Mutex<HashMap<String, TerrainClearance>> = Mutex::new(HashMap::new());
+}
+
+fn tile_for(latitude: f64, longitude: f64) -> Option<Arc<Tile>> {
+    let name = tile_name(latitude, longitude);
+    let mut tiles = TILES.lock().unwrap();
+    if let Some(tile) = tiles.get(&name) {
+        return tile.clone();
+    }
+    let path = Path::new(&config::get().terrain_tiles_dir).join(format!("{}.hgt", name));
+    let tile = match Tile::load(&path) {
+        Ok(tile) => {
+            logs::info("terrain", format!("Loaded elevation tile {}", name));
+            Some(Arc::new(tile))
+        }
+        Err(e) => {
+            logs::warn("terrain", format!("No elevation data for {}: {}", name, e));
+            None
+        }
+    };
+    tiles.insert(name, tile.clone());
+    tile
+}
+
+// Ground elevation (m above mean sea level), or None without terrain data
+pub fn elevation_at(latitude: f64, longitude: f64) -> Option<f64> {
+    if !latitude.is_finite() || !longitude.is_finite() || latitude.abs() >= 90.0 || longitude.abs() > 180.0 {
+        return None;
+    }
+    tile_for(latitude, longitude)?.elevation(latitude, longitude)
+}
+
+// Forget cached tiles, e.g. after new tiles were copied into the directory
+pub fn reload_tiles() {
+    TILES.lock().unwrap().clear();
+}
+
+// Update a vehicle's height above ground from a telemetry fix
+pub fn record_altitude(vehicle_id: &str, latitude: f64, longitude: f64, altitude_m: f64) {
+    let ground = elevation_at(latitude, longitude);
+    let height = ground.map(|g| altitude_m - g);
+    let minimum = config::get().min_terrain_clearance_m;
+
+    notifications::track(
+        height.is_some_and(|h| h < minimum),
+        NotificationCategory::TerrainClearance,
+        NotificationSeverity::Critical,
+        vehicle_id,
+        || format!("{} is {:.0} m above ground", vehicle_id.to_uppercase(), height.unwrap_or_default()),
+    );
+
+    CLEARANCES.lock().unwrap().insert(
+        vehicle_id.to_lowercase(),
+        TerrainClearance {
+            vehicle_id: vehicle_id.to_lowercase(),
+            altitude_m,
+            ground_elevation_m: ground,
+            height_above_ground_m: height,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        },
+    );
+}
+
+pub fn clearances() -> Vec<TerrainClearance> {
+    let mut clearances: Vec<TerrainClearance> = CLEARANCES.lock().unwrap().values().cloned().collect();
+    clearances.sort_by(|a, b| a.vehicle_id.cmp(&b.vehicle_id));
+    clearances
+}
+
+// Indices of waypoints whose altitude leaves less than the configured clearance over terrain
+pub fn low_clearance_waypoints(waypoints: &[(f64, f64)], altitudes: &[f64]) -> Vec<usize> {
+    let minimum = config::get().min_terrain_clearance_m;
+    waypoints
+        .iter()
+        .zip(altitudes)
+        .enumerate()
+        .filter(|(_, ((lat, long), altitude))| {
+            elevation_at(*lat, *long).is_some_and(|ground| **altitude - ground < minimum)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
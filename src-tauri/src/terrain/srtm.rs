@@ -0,0 +1,61 @@
+/*
+SRTM .hgt tile reader. A tile covers one degree square, is named after its south-west corner
+(e.g. N33W118.hgt) and holds a square grid of big-endian i16 elevations in metres, north row
+first: 1201 samples per side for 3" data, 3601 for 1" data.
+*/
+use std::fs;
+use std::path::Path;
+
+const VOID: i16 = -32768;
+
+pub struct Tile {
+    size: usize,
+    samples: Vec<i16>,
+}
+
+// Tile name for the square containing (latitude, longitude)
+pub fn tile_name(latitude: f64, longitude: f64) -> String {
+    let lat = latitude.floor() as i32;
+    let lon = longitude.floor() as i32;
+    format!(
+        "{}{:02}{}{:03}",
+        if lat < 0 { 'S' } else { 'N' },
+        lat.abs(),
+        if lon < 0 { 'W' } else { 'E' },
+        lon.abs()
+    )
+}
+
+impl Tile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let size = match bytes.len() {
+            2884802 => 1201,
+            25934402 => 3601,
+            len => return Err(format!("{} has an unexpected size ({} bytes)", path.display(), len)),
+        };
+        let samples = bytes
+            .chunks_exact(2)
+            .map(|pair| i16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        Ok(Self { size, samples })
+    }
+
+    fn sample(&self, row: usize, col: usize) -> Option<f64> {
+        let value = self.samples[row * self.size + col];
+        (value != VOID).then_some(value as f64)
+    }
+
+    // Bilinear interpolation between the four surrounding samples; None over voids
+    pub fn elevation(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        let last = (self.size - 1) as f64;
+        let x = (longitude - longitude.floor()) * last;
+        let y = (latitude.floor() + 1.0 - latitude) * last;
+        let (col, row) = ((x.floor() as usize).min(self.size - 2), (y.floor() as usize).min(self.size - 2));
+        let (fx, fy) = (x - col as f64, y - row as f64);
+
+        let top = self.sample(row, col)? * (1.0 - fx) + self.sample(row, col + 1)? * fx;
+        let bottom = self.sample(row + 1, col)? * (1.0 - fx) + self.sample(row + 1, col + 1)? * fx;
+        Some(top * (1.0 - fy) + bottom * fy)
+    }
+}
@@ -0,0 +1,184 @@
+/*
+Define the tiles API: prefetch an area into the offline cache, report progress, and manage
+the cache size. Tiles themselves are served through the `tiles://` URI scheme.
+*/
+use std::sync::atomic::{AtomicBool, Ordering};
+use futures_util::stream::{self, StreamExt};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, OperatorRole};
+use crate::logs;
+use super::{TileBounds, TileCacheStats, MAX_PREFETCH_TILES, MAX_ZOOM};
+
+// Parallel downloads while prefetching; kept low out of courtesy to public tile servers
+const PREFETCH_CONCURRENCY: usize = 4;
+// Progress is emitted every this many tiles
+const PROGRESS_INTERVAL: u32 = 50;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct PrefetchProgress {
+    pub total: u32,
+    pub downloaded: u32,
+    pub already_cached: u32,
+    pub failed: u32,
+    pub finished: bool,
+    pub cancelled: bool,
+}
+
+lazy_static! {
+    static ref PREFETCHING: AtomicBool = AtomicBool::new(false);
+    static ref CANCEL: AtomicBool = AtomicBool::new(false);
+}
+
+#[procedures(event_trigger = TilesEventTrigger, export_to = "../src/lib/bindings.ts", path = "tiles")]
+pub trait TilesApi {
+    #[taurpc(event)]
+    async fn on_prefetch_progress(progress: PrefetchProgress);
+
+    // Starts a background download of every tile in the bounds; returns the tile count
+    async fn prefetch_tiles(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        bounds: TileBounds,
+        min_zoom: u8,
+        max_zoom: u8,
+    ) -> Result<u32, String>;
+    async fn cancel_prefetch();
+    async fn get_cache_stats() -> TileCacheStats;
+    // Evict least recently used tiles down to the configured limit; returns tiles removed
+    async fn trim_tile_cache(window: Window<impl Runtime>) -> Result<u32, String>;
+    async fn clear_tile_cache(window: Window<impl Runtime>) -> Result<(), String>;
+}
+
+#[derive(Clone, Default)]
+pub struct TilesApiImpl;
+
+fn emit_progress(app_handle: &AppHandle<impl Runtime>, progress: &PrefetchProgress) {
+    if let Err(e) = TilesEventTrigger::new(app_handle.clone()).on_prefetch_progress(progress.clone()) {
+        logs::error("tiles", format!("Failed to emit prefetch progress: {}", e));
+    }
+}
+
+#[resolvers]
+impl TilesApi for TilesApiImpl {
+    async fn prefetch_tiles(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        bounds: TileBounds,
+        min_zoom: u8,
+        max_zoom: u8,
+    ) -> Result<u32, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            bounds.validate()?;
+            if min_zoom > max_zoom || max_zoom > MAX_ZOOM {
+                return Err(format!("Zoom range must be within 0 to {}", MAX_ZOOM));
+            }
+            let total = super::count_tiles(&bounds, min_zoom, max_zoom);
+            if total > MAX_PREFETCH_TILES {
+                return Err(format!(
+                    "{} tiles requested; at most {} can be prefetched at once",
+                    total, MAX_PREFETCH_TILES
+                ));
+            }
+            if PREFETCHING.swap(true, Ordering::SeqCst) {
+                return Err("A prefetch is already running".into());
+            }
+            CANCEL.store(false, Ordering::SeqCst);
+
+            let tiles = super::tiles_in(&bounds, min_zoom, max_zoom);
+            let total = tiles.len() as u32;
+            logs::info(
+                "tiles",
+                format!("Prefetching {} tiles at zoom {}-{}", total, min_zoom, max_zoom),
+            );
+            tauri::async_runtime::spawn(async move {
+                let mut progress = PrefetchProgress {
+                    total,
+                    downloaded: 0,
+                    already_cached: 0,
+                    failed: 0,
+                    finished: false,
+                    cancelled: false,
+                };
+                let mut results = stream::iter(tiles)
+                    .take_while(|_| std::future::ready(!CANCEL.load(Ordering::SeqCst)))
+                    .map(super::fetch)
+                    .buffer_unordered(PREFETCH_CONCURRENCY);
+                let mut last_error = None;
+                while let Some(result) = results.next().await {
+                    match result {
+                        Ok(true) => progress.downloaded += 1,
+                        Ok(false) => progress.already_cached += 1,
+                        Err(e) => {
+                            progress.failed += 1;
+                            last_error = Some(e);
+                        }
+                    }
+                    let done = progress.downloaded + progress.already_cached + progress.failed;
+                    if done % PROGRESS_INTERVAL == 0 {
+                        emit_progress(&app_handle, &progress);
+                    }
+                }
+
+                progress.finished = true;
+                progress.cancelled = CANCEL.load(Ordering::SeqCst);
+                if let Some(e) = last_error {
+                    logs::warn("tiles", format!("{} tiles failed to download; last error: {}", progress.failed, e));
+                }
+                logs::info(
+                    "tiles",
+                    format!(
+                        "Prefetch {}: {} downloaded, {} already cached, {} failed",
+                        if progress.cancelled { "cancelled" } else { "finished" },
+                        progress.downloaded,
+                        progress.already_cached,
+                        progress.failed
+                    ),
+                );
+                if let Err(e) = tauri::async_runtime::spawn_blocking(super::trim_cache)
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|r| r)
+                {
+                    logs::warn("tiles", e);
+                }
+                emit_progress(&app_handle, &progress);
+                PREFETCHING.store(false, Ordering::SeqCst);
+            });
+            Ok(total)
+        })
+        .await
+    }
+
+    async fn cancel_prefetch(self) {
+        CANCEL.store(true, Ordering::SeqCst);
+    }
+
+    async fn get_cache_stats(self) -> TileCacheStats {
+        super::stats()
+    }
+
+    async fn trim_tile_cache(self, window: Window<impl Runtime>) -> Result<u32, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            tauri::async_runtime::spawn_blocking(super::trim_cache)
+                .await
+                .map_err(|e| format!("Failed to trim tile cache: {}", e))?
+        })
+        .await
+    }
+
+    async fn clear_tile_cache(self, window: Window<impl Runtime>) -> Result<(), String> {
+        authorized(&window, OperatorRole::MissionCommander, async move {
+            if PREFETCHING.load(Ordering::SeqCst) {
+                return Err("Cancel the running prefetch before clearing the cache".into());
+            }
+            super::clear_cache()
+        })
+        .await
+    }
+}
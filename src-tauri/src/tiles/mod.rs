@@ -0,0 +1,314 @@
+/*
+Offline map tile cache. Tiles are stored on disk as {tile_cache_dir}/{z}/{x}/{y}.{ext} and
+served to the map through the `tiles://` URI scheme (http://tiles.localhost on Windows).
+A tile missing from the cache is downloaded from `tile_source_url` when the network allows
+and stored for next time; `prefetch_tiles` fills the cache for an area ahead of a field day.
+The cache is trimmed to `tile_cache_max_mb`, evicting the least recently served tiles first.
+*/
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, SystemTime};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::config;
+use crate::logs;
+
+pub mod api;
+
+pub use api::{TilesApi, TilesApiImpl, TilesEventTrigger};
+
+pub const MAX_ZOOM: u8 = 19;
+// Largest prefetch accepted in one request; zoom 19 over a few km² is already tens of thousands
+pub const MAX_PREFETCH_TILES: u64 = 100000;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(15);
+// Check the cache size after this many downloads
+const TRIM_INTERVAL: u32 = 200;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct TileBounds {
+    pub north: f64,
+    pub south: f64,
+    pub east: f64,
+    pub west: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct TileCacheStats {
+    pub tile_count: u32,
+    pub size_bytes: f64,
+    pub max_bytes: f64,
+    pub cache_dir: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Type)]
+pub struct TileId {
+    pub z: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+lazy_static! {
+    static ref CLIENT: reqwest::Client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        // Public tile servers reject requests without an identifying user agent
+        .user_agent(concat!("ngcp-gcs/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .expect("Failed to build tile HTTP client");
+    static ref DOWNLOADS: AtomicU32 = AtomicU32::new(0);
+}
+
+impl TileBounds {
+    pub fn validate(&self) -> Result<(), String> {
+        let lat_ok = |v: f64| v.is_finite() && (-85.0511..=85.0511).contains(&v);
+        let lon_ok = |v: f64| v.is_finite() && (-180.0..=180.0).contains(&v);
+        if !lat_ok(self.north) || !lat_ok(self.south) || !lon_ok(self.east) || !lon_ok(self.west) {
+            return Err("Bounding box is outside the web map extent".into());
+        }
+        if self.south > self.north || self.west > self.east {
+            return Err("Bounding box must have south <= north and west <= east".into());
+        }
+        Ok(())
+    }
+}
+
+// Slippy-map tile containing (latitude, longitude) at zoom z
+fn tile_xy(latitude: f64, longitude: f64, z: u8) -> (u32, u32) {
+    let n = 2f64.powi(z as i32);
+    let x = ((longitude + 180.0) / 360.0 * n).floor();
+    let lat = latitude.to_radians();
+    let y = ((1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / std::f64::consts::PI) / 2.0 * n).floor();
+    let max = n as u32 - 1;
+    ((x.max(0.0) as u32).min(max), (y.max(0.0) as u32).min(max))
+}
+
+// Inclusive x and y ranges covering the bounds at zoom z
+fn tile_range(bounds: &TileBounds, z: u8) -> ((u32, u32), (u32, u32)) {
+    let (x0, y0) = tile_xy(bounds.north, bounds.west, z);
+    let (x1, y1) = tile_xy(bounds.south, bounds.east, z);
+    ((x0, x1), (y0, y1))
+}
+
+pub fn count_tiles(bounds: &TileBounds, min_zoom: u8, max_zoom: u8) -> u64 {
+    (min_zoom..=max_zoom)
+        .map(|z| {
+            let ((x0, x1), (y0, y1)) = tile_range(bounds, z);
+            (x1 - x0 + 1) as u64 * (y1 - y0 + 1) as u64
+        })
+        .sum()
+}
+
+pub fn tiles_in(bounds: &TileBounds, min_zoom: u8, max_zoom: u8) -> Vec<TileId> {
+    let mut tiles = vec![];
+    for z in min_zoom..=max_zoom {
+        let ((x0, x1), (y0, y1)) = tile_range(bounds, z);
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                tiles.push(TileId { z, x, y });
+            }
+        }
+    }
+    tiles
+}
+
+// File extension of the upstream tiles, taken from the source URL template
+fn tile_extension() -> String {
+    let url = config::get().tile_source_url;
+    let path = url.split('?').next().unwrap_or_default();
+    path.rsplit('.')
+        .next()
+        .filter(|ext| ext.len() <= 4 && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or("png")
+        .to_string()
+}
+
+pub fn content_type() -> &'static str {
+    match tile_extension().as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "pbf" | "mvt" => "application/x-protobuf",
+        _ => "image/png",
+    }
+}
+
+fn cache_dir() -> PathBuf {
+    PathBuf::from(config::get().tile_cache_dir)
+}
+
+fn tile_path(tile: TileId) -> PathBuf {
+    cache_dir()
+        .join(tile.z.to_string())
+        .join(tile.x.to_string())
+        .join(format!("{}.{}", tile.y, tile_extension()))
+}
+
+pub fn is_cached(tile: TileId) -> bool {
+    tile_path(tile).is_file()
+}
+
+async fn download(tile: TileId) -> Result<Vec<u8>, String> {
+    let url = config::get()
+        .tile_source_url
+        .replace("{z}", &tile.z.to_string())
+        .replace("{x}", &tile.x.to_string())
+        .replace("{y}", &tile.y.to_string());
+    let response = CLIENT
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download tile {}/{}/{}: {}", tile.z, tile.x, tile.y, e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Tile server returned {} for {}/{}/{}",
+            response.status(),
+            tile.z,
+            tile.x,
+            tile.y
+        ));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read tile {}/{}/{}: {}", tile.z, tile.x, tile.y, e))?;
+    Ok(bytes.to_vec())
+}
+
+fn store(tile: TileId, bytes: &[u8]) -> Result<(), String> {
+    let path = tile_path(tile);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    // Write then rename so a half-written tile is never served
+    let partial = path.with_extension("part");
+    fs::write(&partial, bytes).map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+    fs::rename(&partial, &path).map_err(|e| format!("Failed to store {}: {}", path.display(), e))?;
+
+    if DOWNLOADS.fetch_add(1, Ordering::Relaxed) % TRIM_INTERVAL == TRIM_INTERVAL - 1 {
+        tauri::async_runtime::spawn_blocking(|| {
+            if let Err(e) = trim_cache() {
+                logs::warn("tiles", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+// Download a tile into the cache; already cached tiles are left alone
+pub async fn fetch(tile: TileId) -> Result<bool, String> {
+    if is_cached(tile) {
+        return Ok(false);
+    }
+    let bytes = download(tile).await?;
+    store(tile, &bytes)?;
+    Ok(true)
+}
+
+// A tile for the map: from the cache, else from the network (and then cached)
+pub async fn get_tile(tile: TileId) -> Result<Vec<u8>, String> {
+    if tile.z > MAX_ZOOM || tile.x >= 1 << tile.z || tile.y >= 1 << tile.z {
+        return Err(format!("Tile {}/{}/{} does not exist", tile.z, tile.x, tile.y));
+    }
+    let path = tile_path(tile);
+    if let Ok(bytes) = fs::read(&path) {
+        // Served tiles count as recently used when the cache is trimmed
+        if let Ok(file) = fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        return Ok(bytes);
+    }
+    let bytes = download(tile).await?;
+    if let Err(e) = store(tile, &bytes) {
+        logs::warn("tiles", e);
+    }
+    Ok(bytes)
+}
+
+fn cached_files(dir: &Path, files: &mut Vec<(PathBuf, u64, SystemTime)>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.metadata() {
+            Ok(meta) if meta.is_dir() => cached_files(&path, files),
+            Ok(meta) => files.push((path, meta.len(), meta.modified().unwrap_or(SystemTime::UNIX_EPOCH))),
+            Err(_) => {}
+        }
+    }
+}
+
+fn max_bytes() -> u64 {
+    config::get().tile_cache_max_mb as u64 * 1024 * 1024
+}
+
+pub fn stats() -> TileCacheStats {
+    let mut files = vec![];
+    cached_files(&cache_dir(), &mut files);
+    TileCacheStats {
+        tile_count: files.len() as u32,
+        size_bytes: files.iter().map(|f| f.1).sum::<u64>() as f64,
+        max_bytes: max_bytes() as f64,
+        cache_dir: cache_dir().display().to_string(),
+    }
+}
+
+// Delete least recently used tiles until the cache fits; returns the number removed
+pub fn trim_cache() -> Result<u32, String> {
+    let mut files = vec![];
+    cached_files(&cache_dir(), &mut files);
+    let mut size: u64 = files.iter().map(|f| f.1).sum();
+    let limit = max_bytes();
+    if size <= limit {
+        return Ok(0);
+    }
+
+    files.sort_by_key(|f| f.2);
+    let mut removed = 0;
+    for (path, len, _) in files {
+        if size <= limit {
+            break;
+        }
+        fs::remove_file(&path).map_err(|e| format!("Failed to evict {}: {}", path.display(), e))?;
+        size -= len;
+        removed += 1;
+    }
+    logs::info("tiles", format!("Evicted {} tiles to keep the cache under {} bytes", removed, limit));
+    Ok(removed)
+}
+
+pub fn clear_cache() -> Result<(), String> {
+    let dir = cache_dir();
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("Failed to clear {}: {}", dir.display(), e))?;
+    }
+    Ok(())
+}
+
+fn parse_tile_path(path: &str) -> Option<TileId> {
+    let mut parts = path.trim_matches('/').split('/');
+    let z = parts.next()?.parse().ok()?;
+    let x = parts.next()?.parse().ok()?;
+    // Accept both /z/x/y and /z/x/y.png
+    let y = parts.next()?.split('.').next()?.parse().ok()?;
+    parts.next().is_none().then_some(TileId { z, x, y })
+}
+
+// Response for the `tiles://` URI scheme
+pub async fn serve(path: &str) -> tauri::http::Response<Vec<u8>> {
+    let response = tauri::http::Response::builder().header("Access-Control-Allow-Origin", "*");
+    let Some(tile) = parse_tile_path(path) else {
+        return response
+            .status(400)
+            .body(format!("Invalid tile path: {}", path).into_bytes())
+            .unwrap();
+    };
+    match get_tile(tile).await {
+        Ok(bytes) => response
+            .status(200)
+            .header("Content-Type", content_type())
+            .body(bytes)
+            .unwrap(),
+        // Offline and not cached; the map shows an empty tile
+        Err(e) => response.status(404).body(e.into_bytes()).unwrap(),
+    }
+}
@@ -0,0 +1,151 @@
+/*
+Conversion of the backend's SI values (metres, metres per second, degrees Celsius) into the
+operator's display units. The units follow the settings' unit system, with optional
+per-quantity overrides (e.g. metric distances with speeds in knots). Values are only converted
+on the way out, in telemetry events, weather status and CSV flight logs; everything stored or
+compared against thresholds stays SI.
+*/
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::settings::{self, UnitSystem};
+
+const FEET_PER_METRE: f64 = 3.280839895;
+const KNOTS_PER_METRE_PER_SECOND: f64 = 1.943844492;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum DistanceUnit {
+    Meters,
+    Feet,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum SpeedUnit {
+    MetersPerSecond,
+    Knots,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+impl DistanceUnit {
+    pub fn from_si(self, metres: f64) -> f64 {
+        match self {
+            DistanceUnit::Meters => metres,
+            DistanceUnit::Feet => metres * FEET_PER_METRE,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            DistanceUnit::Meters => "m",
+            DistanceUnit::Feet => "ft",
+        }
+    }
+}
+
+impl SpeedUnit {
+    pub fn from_si(self, metres_per_second: f64) -> f64 {
+        match self {
+            SpeedUnit::MetersPerSecond => metres_per_second,
+            SpeedUnit::Knots => metres_per_second * KNOTS_PER_METRE_PER_SECOND,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            SpeedUnit::MetersPerSecond => "m/s",
+            SpeedUnit::Knots => "kn",
+        }
+    }
+}
+
+impl TemperatureUnit {
+    pub fn from_si(self, celsius: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+}
+
+// Per-quantity units; None follows the unit system
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Type)]
+#[serde(default)]
+pub struct UnitOverrides {
+    pub distance: Option<DistanceUnit>,
+    pub speed: Option<SpeedUnit>,
+    pub temperature: Option<TemperatureUnit>,
+}
+
+// A converted value with the symbol of its unit
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Type)]
+pub struct Measurement {
+    pub value: f64,
+    pub unit: String,
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1} {}", self.value, self.unit)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayUnits {
+    pub distance: DistanceUnit,
+    pub speed: SpeedUnit,
+    pub temperature: TemperatureUnit,
+}
+
+impl DisplayUnits {
+    pub fn resolve(system: UnitSystem, overrides: &UnitOverrides) -> Self {
+        let (distance, speed, temperature) = match system {
+            UnitSystem::Metric => (DistanceUnit::Meters, SpeedUnit::MetersPerSecond, TemperatureUnit::Celsius),
+            UnitSystem::Imperial => (DistanceUnit::Feet, SpeedUnit::Knots, TemperatureUnit::Fahrenheit),
+        };
+        Self {
+            distance: overrides.distance.unwrap_or(distance),
+            speed: overrides.speed.unwrap_or(speed),
+            temperature: overrides.temperature.unwrap_or(temperature),
+        }
+    }
+
+    // Units from the cached operator settings
+    pub fn current() -> Self {
+        let settings = settings::current();
+        Self::resolve(settings.units, &settings.unit_overrides)
+    }
+
+    pub fn distance(&self, metres: f64) -> Measurement {
+        Measurement {
+            value: self.distance.from_si(metres),
+            unit: self.distance.symbol().to_string(),
+        }
+    }
+
+    pub fn speed(&self, metres_per_second: f64) -> Measurement {
+        Measurement {
+            value: self.speed.from_si(metres_per_second),
+            unit: self.speed.symbol().to_string(),
+        }
+    }
+
+    pub fn temperature(&self, celsius: f64) -> Measurement {
+        Measurement {
+            value: self.temperature.from_si(celsius),
+            unit: self.temperature.symbol().to_string(),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+/*
+Define the vehicle registry API: read the aliases and edit one vehicle's display name, callsign
+and color, notifying every window when they change. Also serves the vehicle detail snapshot,
+assembled from the telemetry pipeline, the heartbeat monitor, the mission state, the offline
+command queue and the notification log.
+*/
+use sqlx::PgPool;
+use tauri::{AppHandle, Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, current_operator, OperatorRole};
+use crate::commands::CommandsApiImpl;
+use crate::init_db::lazy_pool;
+use crate::logs;
+use crate::missions::api::MissionApiImpl;
+use crate::missions::types::MissionsStruct;
+use crate::notifications::sql::select_vehicle_notifications;
+use crate::settings::sql::{select_setting, upsert_setting};
+use crate::telemetry::rabbitmq::{RabbitMQAPIImpl, VehicleHeartbeat};
+use super::{
+    alias, merge, set_registry, with_alias, VehicleAlias, VehicleAssignment, VehicleLink,
+    VehicleSnapshot, VEHICLE_IDS,
+};
+
+const REGISTRY_KEY: &str = "vehicle_registry";
+// Alerts included in a vehicle snapshot
+const RECENT_ALERTS: i64 = 20;
+
+fn to_link(heartbeat: &VehicleHeartbeat) -> VehicleLink {
+    VehicleLink {
+        connected: heartbeat.is_connected,
+        last_seen_secs: heartbeat.last_seen.elapsed().as_secs_f64(),
+        consecutive_failures: heartbeat.consecutive_failures,
+        disconnected_secs: heartbeat.disconnected_at.map(|at| at.elapsed().as_secs_f64()),
+        failsafe_triggered: heartbeat.failsafe_triggered,
+    }
+}
+
+fn assignment(missions: &MissionsStruct, vehicle_id: &str) -> Option<VehicleAssignment> {
+    let mission = missions
+        .missions
+        .iter()
+        .find(|m| m.mission_id == missions.current_mission)?;
+    let vehicle = match vehicle_id {
+        "mea" => &mission.vehicles.MEA,
+        "eru" => &mission.vehicles.ERU,
+        "mra" => &mission.vehicles.MRA,
+        _ => return None,
+    };
+    if vehicle.stages.is_empty() {
+        return None;
+    }
+    Some(VehicleAssignment {
+        mission_id: mission.mission_id,
+        mission_name: mission.mission_name.clone(),
+        mission_status: mission.mission_status.clone(),
+        stage: vehicle
+            .stages
+            .iter()
+            .find(|s| s.stage_id == vehicle.current_stage)
+            .cloned(),
+        is_auto: vehicle.is_auto,
+        patient_status: vehicle.patient_status.clone(),
+    })
+}
+
+#[procedures(
+    event_trigger = VehiclesEventTrigger,
+    export_to = "../src/lib/bindings.ts",
+    path = "vehicles"
+)]
+pub trait VehiclesApi {
+    #[taurpc(event)]
+    async fn on_aliases_changed(aliases: Vec<VehicleAlias>);
+
+    async fn get_vehicle_aliases() -> Result<Vec<VehicleAlias>, String>;
+    async fn update_vehicle_alias(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        alias: VehicleAlias,
+    ) -> Result<Vec<VehicleAlias>, String>;
+    // Everything the vehicle detail panel shows, read at once
+    async fn get_vehicle_snapshot(vehicle_id: String) -> Result<VehicleSnapshot, String>;
+}
+
+#[derive(Clone)]
+pub struct VehiclesApiImpl {
+    db: PgPool,
+    missions: MissionApiImpl,
+    telemetry: RabbitMQAPIImpl,
+    commands: CommandsApiImpl,
+}
+
+impl VehiclesApiImpl {
+    pub async fn new(missions: MissionApiImpl, telemetry: RabbitMQAPIImpl, commands: CommandsApiImpl) -> Self {
+        let api = Self { db: lazy_pool(2), missions, telemetry, commands };
+        // Warm the cache; default aliases stay in place if the database is unavailable
+        if let Err(e) = api.load().await {
+            logs::warn("vehicles", format!("Using default vehicle aliases: {}", e));
+        }
+        api
+    }
+
+    async fn load(&self) -> Result<Vec<VehicleAlias>, String> {
+        let stored = select_setting(self.db.clone(), REGISTRY_KEY)
+            .await
+            .map_err(|e| format!("Failed to load vehicle aliases: {}", e))?;
+        let stored = match stored {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Stored vehicle aliases are invalid: {}", e))?,
+            None => vec![],
+        };
+        let registry = merge(stored);
+        set_registry(registry.clone());
+        Ok(registry)
+    }
+}
+
+#[resolvers]
+impl VehiclesApi for VehiclesApiImpl {
+    async fn get_vehicle_aliases(self) -> Result<Vec<VehicleAlias>, String> {
+        self.load().await
+    }
+
+    async fn update_vehicle_alias(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        alias: VehicleAlias,
+    ) -> Result<Vec<VehicleAlias>, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let registry = with_alias(&self.load().await?, alias.clone())?;
+
+            let value = serde_json::to_string(&registry).map_err(|e| e.to_string())?;
+            upsert_setting(self.db.clone(), REGISTRY_KEY, &value, &current_operator())
+                .await
+                .map_err(|e| format!("Failed to save vehicle aliases: {}", e))?;
+            set_registry(registry.clone());
+            logs::info(
+                "vehicles",
+                format!("{} renamed to {} by {}", alias.vehicle_id.to_uppercase(), alias.display_name.trim(), current_operator()),
+            );
+
+            if let Err(e) = VehiclesEventTrigger::new(app_handle).on_aliases_changed(registry.clone()) {
+                logs::warn("vehicles", format!("Failed to emit vehicle alias change: {}", e));
+            }
+            Ok(registry)
+        })
+        .await
+    }
+    async fn get_vehicle_snapshot(self, vehicle_id: String) -> Result<VehicleSnapshot, String> {
+        let vehicle_id = vehicle_id.trim().to_lowercase();
+        if !VEHICLE_IDS.contains(&vehicle_id.as_str()) {
+            return Err(format!("Unknown vehicle {}", vehicle_id));
+        }
+        let recent_alerts = select_vehicle_notifications(self.db.clone(), &vehicle_id, RECENT_ALERTS)
+            .await
+            .map_err(|e| format!("Failed to load vehicle alerts: {}", e))?;
+        let link = self
+            .telemetry
+            .get_heartbeat_status()
+            .await
+            .get(&vehicle_id)
+            .map(to_link);
+        Ok(VehicleSnapshot {
+            alias: alias(&vehicle_id),
+            telemetry: self.telemetry.telemetry_snapshot().vehicle(&vehicle_id).cloned(),
+            link,
+            assignment: assignment(&self.missions.snapshot().await, &vehicle_id),
+            pending_commands: self.commands.pending_commands(&vehicle_id).await,
+            recent_alerts,
+            taken_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+}
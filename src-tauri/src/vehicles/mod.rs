@@ -0,0 +1,174 @@
+/*
+Vehicle registry: display names, callsigns and map colors for the internal vehicle ids. The
+registry is stored as one document in the settings table and cached here, so telemetry events,
+mission events, notifications and reports all name a vehicle the same way. The vehicle detail
+panel reads everything it shows about one vehicle as a single VehicleSnapshot.
+*/
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::commands::types::PendingCommand;
+use crate::missions::types::{MissionStageStatusEnum, PatientStatusEnum, StageStruct};
+use crate::notifications::Notification;
+use crate::telemetry::types::TelemetryData;
+
+pub mod api;
+
+pub use api::{VehiclesApi, VehiclesApiImpl};
+
+pub const VEHICLE_IDS: [&str; 4] = ["eru", "mea", "mra", "fra"];
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Type)]
+pub struct VehicleAlias {
+    pub vehicle_id: String,
+    pub display_name: String,
+    pub callsign: String,
+    // Map and chart color as #RRGGBB
+    pub color: String,
+}
+
+impl VehicleAlias {
+    fn default_for(vehicle_id: &str) -> Self {
+        let color = match vehicle_id {
+            "eru" => "#e53935",
+            "mea" => "#1e88e5",
+            "mra" => "#43a047",
+            "fra" => "#fb8c00",
+            _ => "#757575",
+        };
+        Self {
+            vehicle_id: vehicle_id.to_string(),
+            display_name: vehicle_id.to_uppercase(),
+            callsign: vehicle_id.to_uppercase(),
+            color: color.to_string(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !VEHICLE_IDS.contains(&self.vehicle_id.as_str()) {
+            return Err(format!("Unknown vehicle {}", self.vehicle_id));
+        }
+        let display_name = self.display_name.trim();
+        if display_name.is_empty() || display_name.chars().count() > 32 {
+            return Err("Display name must be between 1 and 32 characters".into());
+        }
+        if self.callsign.is_empty()
+            || self.callsign.len() > 16
+            || !self.callsign.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err("Callsign must be 1 to 16 letters, digits or dashes".into());
+        }
+        let hex = self.color.strip_prefix('#').unwrap_or_default();
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err("Color must be a #RRGGBB value".into());
+        }
+        Ok(())
+    }
+}
+
+// Heartbeat state of a vehicle the monitor has heard from
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct VehicleLink {
+    pub connected: bool,
+    pub last_seen_secs: f64,
+    pub consecutive_failures: u32,
+    // Length of the current disconnection
+    pub disconnected_secs: Option<f64>,
+    pub failsafe_triggered: bool,
+}
+
+// The vehicle's part in the current mission
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct VehicleAssignment {
+    pub mission_id: i32,
+    pub mission_name: String,
+    pub mission_status: MissionStageStatusEnum,
+    // None when the current stage id matches no stage, e.g. after the last one
+    pub stage: Option<StageStruct>,
+    pub is_auto: Option<bool>,
+    pub patient_status: Option<PatientStatusEnum>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct VehicleSnapshot {
+    pub alias: VehicleAlias,
+    pub telemetry: Option<TelemetryData>,
+    // None until the heartbeat monitor has heard from the vehicle
+    pub link: Option<VehicleLink>,
+    // None when the vehicle has no stages in the current mission
+    pub assignment: Option<VehicleAssignment>,
+    pub pending_commands: Vec<PendingCommand>,
+    // Newest first
+    pub recent_alerts: Vec<Notification>,
+    pub taken_at: String,
+}
+
+pub fn default_registry() -> Vec<VehicleAlias> {
+    VEHICLE_IDS.iter().map(|id| VehicleAlias::default_for(id)).collect()
+}
+
+lazy_static! {
+    static ref REGISTRY: RwLock<Vec<VehicleAlias>> = RwLock::new(default_registry());
+}
+
+// Latest saved registry, one entry per known vehicle
+pub fn registry() -> Vec<VehicleAlias> {
+    REGISTRY.read().unwrap().clone()
+}
+
+// Alias for a vehicle id in any case (eru, ERU); unknown ids get a default named after the id
+pub fn alias(vehicle_id: &str) -> VehicleAlias {
+    let vehicle_id = vehicle_id.to_lowercase();
+    REGISTRY
+        .read()
+        .unwrap()
+        .iter()
+        .find(|alias| alias.vehicle_id == vehicle_id)
+        .cloned()
+        .unwrap_or_else(|| VehicleAlias::default_for(&vehicle_id))
+}
+
+pub fn display_name(vehicle_id: &str) -> String {
+    alias(vehicle_id).display_name
+}
+
+// Stored entries over the defaults, so a vehicle missing from an older document keeps its default
+fn merge(stored: Vec<VehicleAlias>) -> Vec<VehicleAlias> {
+    default_registry()
+        .into_iter()
+        .map(|default| {
+            stored
+                .iter()
+                .find(|alias| alias.vehicle_id == default.vehicle_id)
+                .cloned()
+                .unwrap_or(default)
+        })
+        .collect()
+}
+
+// Replace one entry; display names and callsigns must stay unique across vehicles
+fn with_alias(registry: &[VehicleAlias], alias: VehicleAlias) -> Result<Vec<VehicleAlias>, String> {
+    alias.validate()?;
+    for other in registry.iter().filter(|other| other.vehicle_id != alias.vehicle_id) {
+        if other.display_name.eq_ignore_ascii_case(alias.display_name.trim()) {
+            return Err(format!("{} already uses the name {}", other.vehicle_id.to_uppercase(), other.display_name));
+        }
+        if other.callsign.eq_ignore_ascii_case(&alias.callsign) {
+            return Err(format!("{} already uses the callsign {}", other.vehicle_id.to_uppercase(), other.callsign));
+        }
+    }
+    let alias = VehicleAlias {
+        display_name: alias.display_name.trim().to_string(),
+        ..alias
+    };
+    Ok(registry
+        .iter()
+        .map(|entry| if entry.vehicle_id == alias.vehicle_id { alias.clone() } else { entry.clone() })
+        .collect())
+}
+
+fn set_registry(registry: Vec<VehicleAlias>) {
+    *REGISTRY.write().unwrap() = registry;
+}
@@ -0,0 +1,146 @@
+/*
+Define the video API: register per-vehicle stream URLs, check stream health, and record the
+start/stop events reported by the frontend player.
+*/
+use sqlx::PgPool;
+use tauri::{AppHandle, Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::auth::{authorized, current_operator, OperatorRole};
+use crate::init_db::lazy_pool;
+use crate::logs;
+use super::sql::{delete_stream, insert_event, select_events, select_stream, select_streams, upsert_stream};
+use super::{vehicle_key, StreamEvent, StreamEventKind, StreamHealth, StreamInfo, StreamInput};
+
+const DEFAULT_EVENT_LIMIT: u32 = 100;
+
+#[procedures(event_trigger = VideoEventTrigger, export_to = "../src/lib/bindings.ts", path = "video")]
+pub trait VideoApi {
+    #[taurpc(event)]
+    async fn on_stream_event(event: StreamEvent);
+
+    async fn list_streams() -> Result<Vec<StreamInfo>, String>;
+    async fn get_stream_info(vehicle_id: String) -> Result<StreamInfo, String>;
+    async fn set_stream(
+        window: Window<impl Runtime>,
+        vehicle_id: String,
+        input: StreamInput,
+    ) -> Result<StreamInfo, String>;
+    async fn remove_stream(window: Window<impl Runtime>, vehicle_id: String) -> Result<(), String>;
+    async fn check_stream_health(vehicle_id: String) -> Result<StreamHealth, String>;
+    async fn record_stream_event(
+        app_handle: AppHandle<impl Runtime>,
+        vehicle_id: String,
+        kind: StreamEventKind,
+        detail: String,
+    ) -> Result<StreamEvent, String>;
+    async fn list_stream_events(
+        vehicle_id: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<Vec<StreamEvent>, String>;
+}
+
+#[derive(Clone)]
+pub struct VideoApiImpl {
+    db: PgPool,
+}
+
+impl VideoApiImpl {
+    pub async fn new() -> Self {
+        Self { db: lazy_pool(2) }
+    }
+
+    async fn stream(&self, vehicle_id: &str) -> Result<StreamInfo, String> {
+        let key = vehicle_key(vehicle_id)?;
+        select_stream(self.db.clone(), &key)
+            .await
+            .map_err(|e| format!("Failed to load video stream: {}", e))?
+            .ok_or(format!("No video stream registered for {}", vehicle_id.to_uppercase()))
+    }
+}
+
+#[resolvers]
+impl VideoApi for VideoApiImpl {
+    async fn list_streams(self) -> Result<Vec<StreamInfo>, String> {
+        select_streams(self.db.clone())
+            .await
+            .map_err(|e| format!("Failed to load video streams: {}", e))
+    }
+
+    async fn get_stream_info(self, vehicle_id: String) -> Result<StreamInfo, String> {
+        self.stream(&vehicle_id).await
+    }
+
+    async fn set_stream(
+        self,
+        window: Window<impl Runtime>,
+        vehicle_id: String,
+        input: StreamInput,
+    ) -> Result<StreamInfo, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let key = vehicle_key(&vehicle_id)?;
+            input.validate()?;
+            let stream = upsert_stream(self.db.clone(), &key, &input)
+                .await
+                .map_err(|e| format!("Failed to save video stream: {}", e))?;
+            logs::info(
+                "video",
+                format!("{} stream set to {} ({})", key.to_uppercase(), stream.url, input.protocol.name()),
+            );
+            Ok(stream)
+        })
+        .await
+    }
+
+    async fn remove_stream(self, window: Window<impl Runtime>, vehicle_id: String) -> Result<(), String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            let key = vehicle_key(&vehicle_id)?;
+            let removed = delete_stream(self.db.clone(), &key)
+                .await
+                .map_err(|e| format!("Failed to remove video stream: {}", e))?;
+            if !removed {
+                return Err(format!("No video stream registered for {}", key.to_uppercase()));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn check_stream_health(self, vehicle_id: String) -> Result<StreamHealth, String> {
+        let stream = self.stream(&vehicle_id).await?;
+        Ok(super::check_health(&stream).await)
+    }
+
+    async fn record_stream_event(
+        self,
+        app_handle: AppHandle<impl Runtime>,
+        vehicle_id: String,
+        kind: StreamEventKind,
+        detail: String,
+    ) -> Result<StreamEvent, String> {
+        let stream = self.stream(&vehicle_id).await?;
+        let event = insert_event(self.db.clone(), &stream.vehicle_id, kind, detail.trim(), &current_operator())
+            .await
+            .map_err(|e| format!("Failed to record stream event: {}", e))?;
+        let summary = format!("{} stream {}", stream.vehicle_id.to_uppercase(), kind.name().to_lowercase());
+        match kind {
+            StreamEventKind::Error => logs::warn("video", format!("{}: {}", summary, event.detail)),
+            _ => logs::info("video", summary),
+        }
+        if let Err(e) = VideoEventTrigger::new(app_handle).on_stream_event(event.clone()) {
+            logs::warn("video", format!("Failed to emit stream event: {}", e));
+        }
+        Ok(event)
+    }
+
+    async fn list_stream_events(
+        self,
+        vehicle_id: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<Vec<StreamEvent>, String> {
+        let key = vehicle_id.as_deref().map(vehicle_key).transpose()?;
+        select_events(self.db.clone(), key.as_deref(), limit.unwrap_or(DEFAULT_EVENT_LIMIT) as i64)
+            .await
+            .map_err(|e| format!("Failed to load stream events: {}", e))
+    }
+}
@@ -0,0 +1,174 @@
+/*
+Video stream registry. Each vehicle's camera feed (RTSP, WebRTC or HTTP) is registered here
+instead of being hardcoded in the frontend, with a reachability check for the stream server
+and a log of stream start/stop events reported by the video player.
+*/
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::config;
+
+pub mod api;
+pub mod sql;
+
+pub use api::{VideoApi, VideoApiImpl};
+
+const MAX_LABEL_LENGTH: usize = 60;
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum StreamProtocol {
+    Rtsp,
+    // URL of the WebRTC signalling endpoint (e.g. a WHEP URL)
+    WebRtc,
+    // MJPEG or HLS over HTTP
+    Http,
+}
+
+impl StreamProtocol {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StreamProtocol::Rtsp => "Rtsp",
+            StreamProtocol::WebRtc => "WebRtc",
+            StreamProtocol::Http => "Http",
+        }
+    }
+
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "WebRtc" => StreamProtocol::WebRtc,
+            "Http" => StreamProtocol::Http,
+            _ => StreamProtocol::Rtsp,
+        }
+    }
+
+    fn schemes(&self) -> &'static [&'static str] {
+        match self {
+            StreamProtocol::Rtsp => &["rtsp", "rtsps"],
+            StreamProtocol::WebRtc => &["http", "https", "ws", "wss"],
+            StreamProtocol::Http => &["http", "https"],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct StreamInfo {
+    pub vehicle_id: String,
+    pub url: String,
+    pub protocol: StreamProtocol,
+    pub label: String,
+    // Whether the player last reported the stream as started
+    pub active: bool,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct StreamInput {
+    pub url: String,
+    pub protocol: StreamProtocol,
+    pub label: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct StreamHealth {
+    pub vehicle_id: String,
+    pub reachable: bool,
+    pub latency_ms: Option<f64>,
+    pub error: Option<String>,
+    pub checked_at: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum StreamEventKind {
+    Started,
+    Stopped,
+    Error,
+}
+
+impl StreamEventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StreamEventKind::Started => "Started",
+            StreamEventKind::Stopped => "Stopped",
+            StreamEventKind::Error => "Error",
+        }
+    }
+
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "Started" => StreamEventKind::Started,
+            "Error" => StreamEventKind::Error,
+            _ => StreamEventKind::Stopped,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct StreamEvent {
+    pub event_id: i32,
+    pub vehicle_id: String,
+    pub kind: StreamEventKind,
+    pub detail: String,
+    pub created_by: String,
+    pub created_at: String,
+}
+
+// Lowercase roster id for a vehicle, or an error for vehicles not in the config
+pub fn vehicle_key(vehicle_id: &str) -> Result<String, String> {
+    let key = vehicle_id.trim().to_lowercase();
+    if config::get().vehicles.contains(&key) {
+        Ok(key)
+    } else {
+        Err(format!("Unknown vehicle: {}", vehicle_id))
+    }
+}
+
+impl StreamInput {
+    pub fn validate(&self) -> Result<(), String> {
+        let url = reqwest::Url::parse(self.url.trim()).map_err(|e| format!("Invalid stream URL: {}", e))?;
+        if !self.protocol.schemes().contains(&url.scheme()) {
+            return Err(format!(
+                "{} streams need a {} URL",
+                self.protocol.name(),
+                self.protocol.schemes().join("/")
+            ));
+        }
+        if url.host_str().is_none() {
+            return Err("Stream URL has no host".into());
+        }
+        if self.label.trim().chars().count() > MAX_LABEL_LENGTH {
+            return Err(format!("Stream labels are limited to {} characters", MAX_LABEL_LENGTH));
+        }
+        Ok(())
+    }
+}
+
+// Whether the stream server accepts a TCP connection; does not decode any video
+pub async fn check_health(stream: &StreamInfo) -> StreamHealth {
+    let result = async {
+        let url = reqwest::Url::parse(&stream.url).map_err(|e| format!("Invalid stream URL: {}", e))?;
+        let host = url.host_str().ok_or("Stream URL has no host")?.to_string();
+        let port = url.port_or_known_default().unwrap_or(match url.scheme() {
+            "rtsps" => 322,
+            _ => 554,
+        });
+        let started = Instant::now();
+        timeout(HEALTH_CHECK_TIMEOUT, TcpStream::connect((host.as_str(), port)))
+            .await
+            .map_err(|_| format!("{}:{} did not respond", host, port))?
+            .map_err(|e| format!("Cannot reach {}:{}: {}", host, port, e))?;
+        Ok::<f64, String>(started.elapsed().as_secs_f64() * 1000.0)
+    }
+    .await;
+
+    StreamHealth {
+        vehicle_id: stream.vehicle_id.clone(),
+        reachable: result.is_ok(),
+        latency_ms: result.as_ref().ok().copied(),
+        error: result.err(),
+        checked_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
@@ -0,0 +1,143 @@
+/*
+Define all video stream registry database functions.
+*/
+use sqlx::postgres::PgRow;
+use sqlx::{query, PgPool, Row};
+
+use super::{StreamEvent, StreamEventKind, StreamInfo, StreamInput, StreamProtocol};
+
+const STREAM_COLUMNS: &str = "
+    vehicle_id, url, protocol, label, active,
+    to_char(updated_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS updated_at
+";
+
+const EVENT_COLUMNS: &str = "
+    event_id, vehicle_id, kind, detail, created_by,
+    to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS created_at
+";
+
+fn to_stream(row: PgRow) -> StreamInfo {
+    StreamInfo {
+        vehicle_id: row.get("vehicle_id"),
+        url: row.get("url"),
+        protocol: StreamProtocol::parse(row.get("protocol")),
+        label: row.get("label"),
+        active: row.get("active"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn to_event(row: PgRow) -> StreamEvent {
+    StreamEvent {
+        event_id: row.get("event_id"),
+        vehicle_id: row.get("vehicle_id"),
+        kind: StreamEventKind::parse(row.get("kind")),
+        detail: row.get("detail"),
+        created_by: row.get("created_by"),
+        created_at: row.get("created_at"),
+    }
+}
+
+pub async fn select_streams(db_conn: PgPool) -> Result<Vec<StreamInfo>, sqlx::Error> {
+    let rows = query(&format!("SELECT {} FROM video_streams ORDER BY vehicle_id", STREAM_COLUMNS))
+        .fetch_all(&db_conn)
+        .await?;
+
+    Ok(rows.into_iter().map(to_stream).collect())
+}
+
+pub async fn select_stream(db_conn: PgPool, vehicle_id: &str) -> Result<Option<StreamInfo>, sqlx::Error> {
+    let row = query(&format!("SELECT {} FROM video_streams WHERE vehicle_id = $1", STREAM_COLUMNS))
+        .bind(vehicle_id)
+        .fetch_optional(&db_conn)
+        .await?;
+
+    Ok(row.map(to_stream))
+}
+
+// Register or replace a vehicle's stream; a new URL starts out inactive
+pub async fn upsert_stream(
+    db_conn: PgPool,
+    vehicle_id: &str,
+    input: &StreamInput,
+) -> Result<StreamInfo, sqlx::Error> {
+    let row = query(&format!(
+        "INSERT INTO video_streams(vehicle_id, url, protocol, label)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (vehicle_id) DO UPDATE
+        SET url = EXCLUDED.url,
+            protocol = EXCLUDED.protocol,
+            label = EXCLUDED.label,
+            active = video_streams.active AND video_streams.url = EXCLUDED.url,
+            updated_at = NOW()
+        RETURNING {}",
+        STREAM_COLUMNS
+    ))
+    .bind(vehicle_id)
+    .bind(input.url.trim())
+    .bind(input.protocol.name())
+    .bind(input.label.trim())
+    .fetch_one(&db_conn)
+    .await?;
+
+    Ok(to_stream(row))
+}
+
+pub async fn delete_stream(db_conn: PgPool, vehicle_id: &str) -> Result<bool, sqlx::Error> {
+    let result = query("DELETE FROM video_streams WHERE vehicle_id = $1")
+        .bind(vehicle_id)
+        .execute(&db_conn)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Log a start/stop event and update the stream's active flag
+pub async fn insert_event(
+    db_conn: PgPool,
+    vehicle_id: &str,
+    kind: StreamEventKind,
+    detail: &str,
+    created_by: &str,
+) -> Result<StreamEvent, sqlx::Error> {
+    query("UPDATE video_streams SET active = $2 WHERE vehicle_id = $1")
+        .bind(vehicle_id)
+        .bind(kind == StreamEventKind::Started)
+        .execute(&db_conn)
+        .await?;
+    let row = query(&format!(
+        "INSERT INTO video_stream_events(vehicle_id, kind, detail, created_by)
+        VALUES ($1, $2, $3, $4)
+        RETURNING {}",
+        EVENT_COLUMNS
+    ))
+    .bind(vehicle_id)
+    .bind(kind.name())
+    .bind(detail)
+    .bind(created_by)
+    .fetch_one(&db_conn)
+    .await?;
+
+    Ok(to_event(row))
+}
+
+// Most recent events first, optionally for one vehicle
+pub async fn select_events(
+    db_conn: PgPool,
+    vehicle_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<StreamEvent>, sqlx::Error> {
+    let rows = query(&format!(
+        "SELECT {} FROM video_stream_events
+        WHERE $1::TEXT IS NULL OR vehicle_id = $1
+        ORDER BY event_id DESC
+        LIMIT $2",
+        EVENT_COLUMNS
+    ))
+    .bind(vehicle_id)
+    .bind(limit)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_event).collect())
+}
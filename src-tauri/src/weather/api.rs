@@ -0,0 +1,124 @@
+/*
+Define the weather API: current hold recommendation, reading history, manual readings, and
+the provider polling task.
+*/
+use std::time::Duration;
+use sqlx::PgPool;
+use tauri::{AppHandle, Runtime, Window};
+use taurpc::{procedures, resolvers};
+use tokio::time::interval;
+
+use crate::auth::{authorized, OperatorRole};
+use crate::config;
+use crate::init_db::lazy_pool;
+use crate::logs;
+use crate::supervisor::{self, RestartPolicy};
+use super::provider::{self, Provider};
+use super::{WeatherObservation, WeatherReading, WeatherStatus};
+
+#[procedures(event_trigger = WeatherEventTrigger, export_to = "../src/lib/bindings.ts", path = "weather")]
+pub trait WeatherApi {
+    #[taurpc(event)]
+    async fn on_weather_updated(status: WeatherStatus);
+
+    async fn get_weather_status() -> WeatherStatus;
+    // RFC 3339 timestamps, oldest reading first
+    async fn get_weather_history(from: String, to: String) -> Result<Vec<WeatherReading>, String>;
+    // Record a reading taken by hand or from a station that cannot be polled
+    async fn record_weather_reading(
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        observation: WeatherObservation,
+    ) -> Result<WeatherStatus, String>;
+}
+
+#[derive(Clone)]
+pub struct WeatherApiImpl {
+    db: PgPool,
+}
+
+impl WeatherApiImpl {
+    pub async fn new() -> Self {
+        Self { db: lazy_pool(2) }
+    }
+
+    async fn record(
+        &self,
+        app_handle: &AppHandle<impl Runtime>,
+        source: &str,
+        observation: &WeatherObservation,
+    ) -> Result<WeatherStatus, String> {
+        let reading = super::sql::insert_reading(self.db.clone(), source, observation)
+            .await
+            .map_err(|e| format!("Failed to save weather reading: {}", e))?;
+        let status = super::evaluate(reading);
+        if let Err(e) = WeatherEventTrigger::new(app_handle.clone()).on_weather_updated(status.clone()) {
+            logs::error("weather", format!("Failed to emit weather update: {}", e));
+        }
+        Ok(status)
+    }
+
+    // Poll the configured provider every weather_poll_interval_secs
+    pub fn start_poller(&self, app_handle: AppHandle) {
+        let provider = match Provider::from_config() {
+            Ok(Provider::Disabled) => return,
+            Ok(provider) => provider,
+            Err(e) => {
+                logs::error("weather", e);
+                return;
+            }
+        };
+        logs::info("weather", format!("Polling weather from {}", provider.name()));
+        let api = self.clone();
+        supervisor::spawn("weather_poller", RestartPolicy::OnFailure, move || {
+            api.clone().run_poller(app_handle.clone(), provider)
+        });
+    }
+
+    async fn run_poller(self, app_handle: AppHandle, provider: Provider) -> Result<(), String> {
+        // Restore the last reading so the status is known before the first poll returns
+        if let Ok(Some(reading)) = super::sql::select_latest_reading(self.db.clone()).await {
+            super::evaluate(reading);
+        }
+        let mut ticker = interval(Duration::from_secs(config::get().weather_poll_interval_secs));
+        loop {
+            ticker.tick().await;
+            match provider::fetch(provider).await {
+                Ok(Some(observation)) => {
+                    if let Err(e) = self.record(&app_handle, provider.name(), &observation).await {
+                        logs::error("weather", e);
+                    }
+                }
+                Ok(None) => {}
+                // Network failures are expected in the field; keep the last reading
+                Err(e) => logs::warn("weather", e),
+            }
+        }
+    }
+}
+
+#[resolvers]
+impl WeatherApi for WeatherApiImpl {
+    async fn get_weather_status(self) -> WeatherStatus {
+        super::current_status()
+    }
+
+    async fn get_weather_history(self, from: String, to: String) -> Result<Vec<WeatherReading>, String> {
+        super::sql::select_readings_between(self.db, &from, &to)
+            .await
+            .map_err(|e| format!("Failed to load weather history: {}", e))
+    }
+
+    async fn record_weather_reading(
+        self,
+        window: Window<impl Runtime>,
+        app_handle: AppHandle<impl Runtime>,
+        observation: WeatherObservation,
+    ) -> Result<WeatherStatus, String> {
+        authorized(&window, OperatorRole::Operator, async move {
+            observation.validate()?;
+            self.record(&app_handle, "manual", &observation).await
+        })
+        .await
+    }
+}
@@ -0,0 +1,157 @@
+/*
+Weather monitoring for launch and hold decisions. Readings come from a polled provider
+(Open-Meteo or a local weather station's JSON feed, chosen with `weather_provider`) or are
+pushed through the API, and are stored in the weather_readings table. Each reading is checked
+against the wind, gust and precipitation limits in the config; when any is exceeded a mission
+hold is recommended and a notification raised. Readings stay SI; the status carries the
+latest values in the operator's display units.
+*/
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::config;
+use crate::notifications::{self, NotificationCategory, NotificationSeverity};
+use crate::units::{DisplayUnits, Measurement};
+
+pub mod api;
+pub mod provider;
+pub mod sql;
+
+pub use api::{WeatherApi, WeatherApiImpl, WeatherEventTrigger};
+
+// One observation as reported by a provider or station; only wind speed is required
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct WeatherObservation {
+    pub wind_speed_ms: f64,
+    pub wind_gust_ms: Option<f64>,
+    pub wind_direction_deg: Option<f64>,
+    // Precipitation over the provider's last reporting interval
+    pub precipitation_mm: Option<f64>,
+    pub temperature_c: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct WeatherReading {
+    pub reading_id: i32,
+    pub source: String,
+    pub wind_speed_ms: f64,
+    pub wind_gust_ms: Option<f64>,
+    pub wind_direction_deg: Option<f64>,
+    pub precipitation_mm: Option<f64>,
+    pub temperature_c: Option<f64>,
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, Type)]
+pub struct WeatherStatus {
+    pub latest: Option<WeatherReading>,
+    pub hold_recommended: bool,
+    // Limits the latest reading exceeds
+    pub reasons: Vec<String>,
+    pub display: Option<WeatherDisplay>,
+}
+
+// Latest reading in the operator's display units
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct WeatherDisplay {
+    pub wind_speed: Measurement,
+    pub wind_gust: Option<Measurement>,
+    pub temperature: Option<Measurement>,
+}
+
+impl WeatherDisplay {
+    fn new(reading: &WeatherReading, units: &DisplayUnits) -> Self {
+        Self {
+            wind_speed: units.speed(reading.wind_speed_ms),
+            wind_gust: reading.wind_gust_ms.map(|g| units.speed(g)),
+            temperature: reading.temperature_c.map(|t| units.temperature(t)),
+        }
+    }
+}
+
+lazy_static! {
+    static ref STATUS: RwLock<WeatherStatus> = RwLock::new(WeatherStatus::default());
+}
+
+impl WeatherObservation {
+    pub fn validate(&self) -> Result<(), String> {
+        let non_negative = |v: f64| v.is_finite() && v >= 0.0;
+        if !non_negative(self.wind_speed_ms)
+            || !self.wind_gust_ms.map_or(true, non_negative)
+            || !self.precipitation_mm.map_or(true, non_negative)
+        {
+            return Err("Wind and precipitation values must be non-negative numbers".into());
+        }
+        if !self.wind_direction_deg.map_or(true, |d| (0.0..=360.0).contains(&d)) {
+            return Err("Wind direction must be between 0 and 360 degrees".into());
+        }
+        if !self.temperature_c.map_or(true, f64::is_finite) {
+            return Err("Temperature must be a number".into());
+        }
+        Ok(())
+    }
+}
+
+// Limits from the safety config that the reading exceeds, worded in the display units
+pub fn exceeded_limits(reading: &WeatherReading, units: &DisplayUnits) -> Vec<String> {
+    let config = config::get();
+    let mut reasons = vec![];
+    if reading.wind_speed_ms > config.max_wind_speed_ms {
+        reasons.push(format!(
+            "Wind {} exceeds {}",
+            units.speed(reading.wind_speed_ms),
+            units.speed(config.max_wind_speed_ms)
+        ));
+    }
+    if let Some(gust) = reading.wind_gust_ms.filter(|g| *g > config.max_wind_gust_ms) {
+        reasons.push(format!(
+            "Gusts {} exceed {}",
+            units.speed(gust),
+            units.speed(config.max_wind_gust_ms)
+        ));
+    }
+    if let Some(rain) = reading.precipitation_mm.filter(|p| *p > config.max_precipitation_mm) {
+        reasons.push(format!(
+            "Precipitation {:.1} mm exceeds {:.1} mm",
+            rain, config.max_precipitation_mm
+        ));
+    }
+    reasons
+}
+
+// Re-evaluate the hold recommendation for a new reading; notifies when it changes
+pub fn evaluate(reading: WeatherReading) -> WeatherStatus {
+    let units = DisplayUnits::current();
+    let reasons = exceeded_limits(&reading, &units);
+    let status = WeatherStatus {
+        hold_recommended: !reasons.is_empty(),
+        reasons,
+        display: Some(WeatherDisplay::new(&reading, &units)),
+        latest: Some(reading),
+    };
+
+    let was_holding = STATUS.read().unwrap().hold_recommended;
+    if status.hold_recommended && !was_holding {
+        notifications::notify(
+            NotificationCategory::Weather,
+            NotificationSeverity::Critical,
+            None,
+            format!("Mission hold recommended: {}", status.reasons.join("; ")),
+        );
+    } else if !status.hold_recommended && was_holding {
+        notifications::notify(
+            NotificationCategory::Weather,
+            NotificationSeverity::Info,
+            None,
+            "Weather is back within limits",
+        );
+    }
+    *STATUS.write().unwrap() = status.clone();
+    status
+}
+
+pub fn current_status() -> WeatherStatus {
+    STATUS.read().unwrap().clone()
+}
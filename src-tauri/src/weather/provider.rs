@@ -0,0 +1,102 @@
+/*
+Weather providers polled by the weather task. `open-meteo` queries the Open-Meteo current
+conditions API at the map's default centre; `station` reads a local weather station that
+serves a WeatherObservation as JSON; `disabled` turns polling off (readings can still be
+pushed through the API).
+*/
+use std::time::Duration;
+use lazy_static::lazy_static;
+use serde_json::Value;
+
+use crate::config;
+use crate::settings;
+use super::WeatherObservation;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    static ref CLIENT: reqwest::Client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("Failed to build weather HTTP client");
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    Disabled,
+    OpenMeteo,
+    Station,
+}
+
+impl Provider {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_lowercase().as_str() {
+            "" | "disabled" | "none" => Ok(Provider::Disabled),
+            "open-meteo" => Ok(Provider::OpenMeteo),
+            "station" => Ok(Provider::Station),
+            other => Err(format!("Unknown weather provider: {}", other)),
+        }
+    }
+
+    pub fn from_config() -> Result<Self, String> {
+        Self::parse(&config::get().weather_provider)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Provider::Disabled => "disabled",
+            Provider::OpenMeteo => "open-meteo",
+            Provider::Station => "station",
+        }
+    }
+}
+
+async fn get_json(url: &str) -> Result<Value, String> {
+    let response = CLIENT
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Weather request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Weather provider returned {}", response.status()));
+    }
+    response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Invalid weather response: {}", e))
+}
+
+async fn fetch_open_meteo() -> Result<WeatherObservation, String> {
+    let map = settings::current().map;
+    let url = format!(
+        "{}?latitude={}&longitude={}&current=wind_speed_10m,wind_gusts_10m,wind_direction_10m,precipitation,temperature_2m&wind_speed_unit=ms",
+        config::get().weather_url,
+        map.center_lat,
+        map.center_long
+    );
+    let body = get_json(&url).await?;
+    let current = body.get("current").ok_or("Weather response has no current conditions")?;
+    let field = |name: &str| current.get(name).and_then(Value::as_f64);
+    Ok(WeatherObservation {
+        wind_speed_ms: field("wind_speed_10m").ok_or("Weather response has no wind speed")?,
+        wind_gust_ms: field("wind_gusts_10m"),
+        wind_direction_deg: field("wind_direction_10m"),
+        precipitation_mm: field("precipitation"),
+        temperature_c: field("temperature_2m"),
+    })
+}
+
+async fn fetch_station() -> Result<WeatherObservation, String> {
+    let body = get_json(&config::get().weather_url).await?;
+    serde_json::from_value(body).map_err(|e| format!("Invalid weather station reading: {}", e))
+}
+
+pub async fn fetch(provider: Provider) -> Result<Option<WeatherObservation>, String> {
+    let observation = match provider {
+        Provider::Disabled => return Ok(None),
+        Provider::OpenMeteo => fetch_open_meteo().await?,
+        Provider::Station => fetch_station().await?,
+    };
+    observation.validate()?;
+    Ok(Some(observation))
+}
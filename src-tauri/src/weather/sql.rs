@@ -0,0 +1,79 @@
+/*
+Define all weather reading database functions.
+*/
+use sqlx::postgres::PgRow;
+use sqlx::{query, PgPool, Row};
+
+use super::{WeatherObservation, WeatherReading};
+
+const READING_COLUMNS: &str = "
+    reading_id, source, wind_speed, wind_gust, wind_direction, precipitation, temperature,
+    to_char(recorded_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS recorded_at
+";
+
+fn to_reading(row: PgRow) -> WeatherReading {
+    WeatherReading {
+        reading_id: row.get("reading_id"),
+        source: row.get("source"),
+        wind_speed_ms: row.get("wind_speed"),
+        wind_gust_ms: row.get("wind_gust"),
+        wind_direction_deg: row.get("wind_direction"),
+        precipitation_mm: row.get("precipitation"),
+        temperature_c: row.get("temperature"),
+        recorded_at: row.get("recorded_at"),
+    }
+}
+
+pub async fn insert_reading(
+    db_conn: PgPool,
+    source: &str,
+    observation: &WeatherObservation,
+) -> Result<WeatherReading, sqlx::Error> {
+    let row = query(&format!(
+        "INSERT INTO weather_readings(source, wind_speed, wind_gust, wind_direction, precipitation, temperature)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING {}",
+        READING_COLUMNS
+    ))
+    .bind(source)
+    .bind(observation.wind_speed_ms)
+    .bind(observation.wind_gust_ms)
+    .bind(observation.wind_direction_deg)
+    .bind(observation.precipitation_mm)
+    .bind(observation.temperature_c)
+    .fetch_one(&db_conn)
+    .await?;
+
+    Ok(to_reading(row))
+}
+
+pub async fn select_latest_reading(db_conn: PgPool) -> Result<Option<WeatherReading>, sqlx::Error> {
+    let row = query(&format!(
+        "SELECT {} FROM weather_readings ORDER BY reading_id DESC LIMIT 1",
+        READING_COLUMNS
+    ))
+    .fetch_optional(&db_conn)
+    .await?;
+
+    Ok(row.map(to_reading))
+}
+
+// Readings between two RFC 3339 timestamps, oldest first
+pub async fn select_readings_between(
+    db_conn: PgPool,
+    from: &str,
+    to: &str,
+) -> Result<Vec<WeatherReading>, sqlx::Error> {
+    let rows = query(&format!(
+        "SELECT {} FROM weather_readings
+        WHERE recorded_at >= $1::TIMESTAMPTZ AND recorded_at <= $2::TIMESTAMPTZ
+        ORDER BY weather_readings.recorded_at",
+        READING_COLUMNS
+    ))
+    .bind(from)
+    .bind(to)
+    .fetch_all(&db_conn)
+    .await?;
+
+    Ok(rows.into_iter().map(to_reading).collect())
+}
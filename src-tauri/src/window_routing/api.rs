@@ -0,0 +1,46 @@
+/*
+Define the window routing API: a window picks the event channels it receives and lists what
+each open window is subscribed to.
+*/
+use tauri::{AppHandle, Manager, Runtime, Window};
+use taurpc::{procedures, resolvers};
+
+use crate::logs;
+use super::{EventChannel, WindowRoute};
+
+#[procedures(export_to = "../src/lib/bindings.ts", path = "window_routing")]
+pub trait WindowRoutingApi {
+    // Limit the calling window to `channels`; None receives every channel again
+    async fn set_window_channels(
+        window: Window<impl Runtime>,
+        channels: Option<Vec<EventChannel>>,
+    ) -> WindowRoute;
+    async fn get_window_routes(app_handle: AppHandle<impl Runtime>) -> Vec<WindowRoute>;
+}
+
+#[derive(Clone, Default)]
+pub struct WindowRoutingApiImpl;
+
+#[resolvers]
+impl WindowRoutingApi for WindowRoutingApiImpl {
+    async fn set_window_channels(
+        self,
+        window: Window<impl Runtime>,
+        channels: Option<Vec<EventChannel>>,
+    ) -> WindowRoute {
+        let label = window.label();
+        match channels {
+            Some(channels) => super::subscribe(label, channels),
+            None => super::unsubscribe(label),
+        }
+        let route = super::route(label);
+        logs::info("window_routing", format!("Window {} receives {:?}", label, route.channels));
+        route
+    }
+
+    async fn get_window_routes(self, app_handle: AppHandle<impl Runtime>) -> Vec<WindowRoute> {
+        let mut labels: Vec<String> = app_handle.webview_windows().into_keys().collect();
+        labels.sort();
+        labels.iter().map(|label| super::route(label)).collect()
+    }
+}
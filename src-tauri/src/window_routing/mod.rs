@@ -0,0 +1,104 @@
+/*
+Per-window event routing. The app can run several windows at once (map, telemetry wall, mission
+editor), and a window can subscribe to the channels it renders so that, e.g., the telemetry
+wall gets only telemetry events. A window that never subscribed receives every channel.
+Emergency stops and critical notifications ignore subscriptions and reach every window.
+Event triggers take their scope from here through `scope`; plain Tauri events use `emit`.
+*/
+use std::collections::HashMap;
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use taurpc::Windows;
+
+pub mod api;
+
+pub use api::{WindowRoutingApi, WindowRoutingApiImpl};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Type)]
+pub enum EventChannel {
+    // Telemetry updates, coordinate requests, signal trends and telemetry errors
+    Telemetry,
+    // Mission state, stages, notes, holds and patient status
+    Missions,
+    Notifications,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Type)]
+pub struct WindowRoute {
+    pub label: String,
+    // Every channel for a window without subscriptions
+    pub channels: Vec<EventChannel>,
+    pub subscribed: bool,
+}
+
+pub const ALL_CHANNELS: [EventChannel; 3] =
+    [EventChannel::Telemetry, EventChannel::Missions, EventChannel::Notifications];
+
+lazy_static! {
+    // Channels by window label, only for windows that subscribed
+    static ref SUBSCRIPTIONS: RwLock<HashMap<String, Vec<EventChannel>>> = RwLock::new(HashMap::new());
+}
+
+pub fn subscribe(label: &str, channels: Vec<EventChannel>) {
+    let mut deduped = vec![];
+    for channel in channels {
+        if !deduped.contains(&channel) {
+            deduped.push(channel);
+        }
+    }
+    SUBSCRIPTIONS.write().unwrap().insert(label.to_string(), deduped);
+}
+
+// Back to every channel; also called when the window closes so a reused label starts fresh
+pub fn unsubscribe(label: &str) {
+    SUBSCRIPTIONS.write().unwrap().remove(label);
+}
+
+pub fn route(label: &str) -> WindowRoute {
+    match SUBSCRIPTIONS.read().unwrap().get(label) {
+        Some(channels) => WindowRoute { label: label.to_string(), channels: channels.clone(), subscribed: true },
+        None => WindowRoute { label: label.to_string(), channels: ALL_CHANNELS.to_vec(), subscribed: false },
+    }
+}
+
+// Windows an event on `channel` goes to; All whenever no open window filters it out
+pub fn scope<R: Runtime>(app_handle: &AppHandle<R>, channel: EventChannel) -> Windows {
+    let subscriptions = SUBSCRIPTIONS.read().unwrap();
+    if subscriptions.is_empty() {
+        return Windows::All;
+    }
+    let labels: Vec<String> = app_handle.webview_windows().into_keys().collect();
+    let receiving: Vec<String> = labels
+        .iter()
+        .filter(|label| subscriptions.get(*label).is_none_or(|channels| channels.contains(&channel)))
+        .cloned()
+        .collect();
+    if receiving.len() == labels.len() {
+        Windows::All
+    } else {
+        Windows::N(receiving)
+    }
+}
+
+// Plain Tauri event to the windows receiving `channel`
+pub fn emit<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    channel: EventChannel,
+    event: &str,
+    payload: Value,
+) -> tauri::Result<()> {
+    match scope(app_handle, channel) {
+        Windows::All => app_handle.emit(event, payload),
+        Windows::One(label) => app_handle.emit_to(label.as_str(), event, payload),
+        Windows::N(labels) => {
+            for label in labels {
+                app_handle.emit_to(label.as_str(), event, payload.clone())?;
+            }
+            Ok(())
+        }
+    }
+}
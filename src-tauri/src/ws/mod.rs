@@ -0,0 +1,192 @@
+/*
+WebSocket stream of processed telemetry for secondary displays (e.g. a big screen at the ground
+station). When `telemetry_ws_addr` is set, clients connect to ws://<addr>/telemetry and receive
+every telemetry update the frontend receives, one frame per vehicle:
+
+    {"vehicle_id": "eru", "telemetry": { ...TelemetryData... }, "timestamp": <unix seconds>}
+
+Clients receive all vehicles unless they subscribe to a subset, either when connecting
+(ws://<addr>/telemetry?vehicles=eru,mra) or at any time by sending {"subscribe": ["mea"]}; an
+empty list subscribes to every vehicle again. The current telemetry of the subscribed vehicles
+is sent right after connecting and after each subscription change. The stream is read-only;
+invalid messages are answered with {"error": "..."}.
+*/
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, Query, State};
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::config;
+use crate::logs;
+use crate::supervisor::{self, RestartPolicy};
+use crate::telemetry::broadcast::{self, TELEMETRY_VEHICLES};
+use crate::telemetry::rabbitmq::RabbitMQAPIImpl;
+use crate::telemetry::types::VehicleTelemetryData;
+
+#[derive(Deserialize)]
+struct ConnectParams {
+    vehicles: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ClientMessage {
+    subscribe: Vec<String>,
+}
+
+// Subscribed vehicles; empty means every vehicle
+#[derive(Default)]
+struct Subscription(HashSet<String>);
+
+impl Subscription {
+    fn parse<'a>(vehicles: impl IntoIterator<Item = &'a str>) -> Result<Self, String> {
+        let mut subscribed = HashSet::new();
+        for vehicle in vehicles.into_iter().map(|v| v.trim().to_lowercase()) {
+            if vehicle.is_empty() {
+                continue;
+            }
+            if !TELEMETRY_VEHICLES.contains(&vehicle.as_str()) {
+                return Err(format!("No telemetry for vehicle {}", vehicle));
+            }
+            subscribed.insert(vehicle);
+        }
+        Ok(Self(subscribed))
+    }
+
+    fn includes(&self, vehicle_id: &str) -> bool {
+        self.0.is_empty() || self.0.contains(vehicle_id)
+    }
+}
+
+fn error_frame(message: String) -> Message {
+    Message::Text(json!({ "error": message }).to_string())
+}
+
+// Frames for the subscribed vehicles accepted by `changed`
+fn telemetry_frames(
+    telemetry: &VehicleTelemetryData,
+    subscription: &Subscription,
+    changed: impl Fn(&str) -> bool,
+) -> Vec<Message> {
+    let timestamp = chrono::Utc::now().timestamp();
+    TELEMETRY_VEHICLES
+        .iter()
+        .filter(|vehicle_id| subscription.includes(vehicle_id) && changed(vehicle_id))
+        .filter_map(|vehicle_id| {
+            let payload = json!({
+                "vehicle_id": vehicle_id,
+                "telemetry": telemetry.vehicle(vehicle_id)?,
+                "timestamp": timestamp,
+            });
+            Some(Message::Text(payload.to_string()))
+        })
+        .collect()
+}
+
+async fn send_all(socket: &mut WebSocket, frames: Vec<Message>) -> Result<(), axum::Error> {
+    for frame in frames {
+        socket.send(frame).await?;
+    }
+    Ok(())
+}
+
+async fn run_client(
+    mut socket: WebSocket,
+    telemetry: RabbitMQAPIImpl,
+    mut subscription: Subscription,
+    peer: SocketAddr,
+) {
+    // Subscribe before the snapshot so no update falls between the two
+    let mut updates = broadcast::subscribe();
+    let snapshot = telemetry.telemetry_snapshot();
+    if send_all(&mut socket, telemetry_frames(&snapshot, &subscription, |_| true)).await.is_err() {
+        return;
+    }
+
+    loop {
+        let sent = tokio::select! {
+            update = updates.recv() => match update {
+                Ok(update) => {
+                    let frames = telemetry_frames(&update.telemetry, &subscription, |v| update.affects(v));
+                    send_all(&mut socket, frames).await
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    logs::warn("ws", format!("Telemetry client {} fell behind, skipped {} updates", peer, skipped));
+                    Ok(())
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let parsed = serde_json::from_str::<ClientMessage>(&text)
+                        .map_err(|e| format!("Invalid message: {}", e))
+                        .and_then(|message| Subscription::parse(message.subscribe.iter().map(String::as_str)));
+                    match parsed {
+                        Ok(parsed) => {
+                            subscription = parsed;
+                            let snapshot = telemetry.telemetry_snapshot();
+                            send_all(&mut socket, telemetry_frames(&snapshot, &subscription, |_| true)).await
+                        }
+                        Err(e) => socket.send(error_frame(e)).await,
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by the WebSocket layer
+                Some(Ok(_)) => Ok(()),
+            },
+        };
+        if sent.is_err() {
+            break;
+        }
+    }
+    logs::info("ws", format!("Telemetry client {} disconnected", peer));
+}
+
+async fn connect(
+    upgrade: WebSocketUpgrade,
+    State(telemetry): State<RabbitMQAPIImpl>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    Query(params): Query<ConnectParams>,
+) -> Response {
+    let subscription = Subscription::parse(params.vehicles.as_deref().unwrap_or("").split(','));
+    upgrade.on_upgrade(move |mut socket| async move {
+        match subscription {
+            Ok(subscription) => {
+                logs::info("ws", format!("Telemetry client {} connected", peer));
+                run_client(socket, telemetry, subscription, peer).await;
+            }
+            Err(e) => {
+                let _ = socket.send(error_frame(e)).await;
+            }
+        }
+    })
+}
+
+async fn serve(telemetry: RabbitMQAPIImpl, addr: String) -> Result<(), String> {
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| format!("Failed to bind telemetry WebSocket to {}: {}", addr, e))?;
+    logs::info("ws", format!("Streaming telemetry on ws://{}/telemetry", addr));
+    let router = Router::new()
+        .route("/telemetry", get(connect))
+        .with_state(telemetry);
+    axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .map_err(|e| format!("Telemetry WebSocket server failed: {}", e))
+}
+
+// Start the server when telemetry_ws_addr is configured
+pub fn start(telemetry: RabbitMQAPIImpl) {
+    let addr = config::get().telemetry_ws_addr;
+    if addr.is_empty() {
+        return;
+    }
+    supervisor::spawn("telemetry_ws", RestartPolicy::OnFailure, move || {
+        serve(telemetry.clone(), addr.clone())
+    });
+}
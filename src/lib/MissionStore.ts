@@ -124,11 +124,17 @@ export const missionPiniaStore = defineStore("mission", () => {
       .find((mission) => mission.mission_id === missionId)
       ?.vehicles[vehicleName].stages.find((s) => s.stage_id === stageId);
   };
-  const addStage = async (missionId: number, vehicleName: VehicleEnum) => {
-    return await taurpc.mission.add_stage(missionId, vehicleName, "New Stage");
+  const addStage = async (missionId: number, vehicleName: VehicleEnum, template: string | null = null) => {
+    // A template names the stage itself
+    return await taurpc.mission.add_stage(missionId, vehicleName, template ? "" : "New Stage", template);
   };
-  const deleteStage = async (missionId: number, vehicleName: VehicleEnum, stageId: number) => {
-    return await taurpc.mission.delete_stage(missionId, vehicleName, stageId);
+  const deleteStage = async (
+    missionId: number,
+    vehicleName: VehicleEnum,
+    stageId: number,
+    justification: string | null = null
+  ) => {
+    return await taurpc.mission.delete_stage(missionId, vehicleName, stageId, justification);
   };
   const renameStage = async (
     missionId: number,
@@ -145,9 +151,10 @@ export const missionPiniaStore = defineStore("mission", () => {
     missionId: number,
     vehicleName: VehicleEnum,
     stageId: number,
-    area: GeoCoordinateStruct[]
+    area: GeoCoordinateStruct[],
+    simplify: boolean | null = null
   ) => {
-    return await taurpc.mission.update_stage_area(missionId, vehicleName, stageId, area);
+    return await taurpc.mission.update_stage_area(missionId, vehicleName, stageId, area, simplify);
   };
 
   // --------------------------
@@ -166,15 +173,22 @@ export const missionPiniaStore = defineStore("mission", () => {
     missionId: number,
     zoneType: ZoneType,
     zoneIndex: number,
-    zoneCoords: GeoCoordinateStruct[]
+    zoneCoords: GeoCoordinateStruct[],
+    simplify: boolean | null = null
   ) => {
-    return await taurpc.mission.update_zone(missionId, zoneType, zoneIndex, zoneCoords);
+    return await taurpc.mission.update_zone(missionId, zoneType, zoneIndex, zoneCoords, simplify);
   };
   const addZone = async (missionId: number, zoneType: ZoneType) => {
     return await taurpc.mission.add_zone(missionId, zoneType);
   };
-  const deleteZone = async (missionId: number, zoneType: ZoneType, zoneIndex: number) => {
-    return await taurpc.mission.delete_zone(missionId, zoneType, zoneIndex);
+  const deleteZone = async (
+    missionId: number,
+    zoneType: ZoneType,
+    zoneIndex: number,
+    confirmation: string | null = null,
+    justification: string | null = null
+  ) => {
+    return await taurpc.mission.delete_zone(missionId, zoneType, zoneIndex, confirmation, justification);
   };
 
   return {
@@ -3,6 +3,7 @@ import { missionPiniaStore } from "./MissionStore";
 import { mapPiniaStore } from "./MapStore";
 import { telemetryPiniaStore } from "./TelemetryStore";
 import { VehicleTelemetryData } from "./bindings";
+import { BINDINGS_VERSION } from "./bindingsVersion";
 
 //Declare store variables:
 let missionStore: ReturnType<typeof missionPiniaStore>;
@@ -20,6 +21,11 @@ export const establishTaurpcConnection = () => {
 // Backend Event Listeners
 // ===============================================
   const taurpc = createTauRPCProxy();
+  // Bindings generated for a different backend fail in confusing ways; report it up front
+  taurpc.startup.check_bindings(BINDINGS_VERSION).catch((error) => {
+    console.error("TAURPC: Bindings version mismatch:", error);
+  });
+
   taurpc.mission.get_all_missions().then((data) => {
     console.log("PINIA: Mission data fetched:", data);
     missionStore!.syncRustState(data);